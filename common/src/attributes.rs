@@ -11,14 +11,26 @@ pub struct Attributes {
     pub stack_size: u64,
     pub max_input_size: u64,
     pub max_output_size: u64,
+    /// Path of the codec of the function's arguments and return value, see
+    /// `jolt::codec`
+    pub codec: Option<String>,
 }
 
 pub fn parse_attributes(attr: &Vec<NestedMeta>) -> Attributes {
     let mut attributes = HashMap::<_, u64>::new();
     let mut wasm = false;
+    let mut codec = None;
 
     for attr in attr {
         match attr {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. }))
+                if path.is_ident("codec") =>
+            {
+                match lit {
+                    Lit::Str(lit) => codec = Some(lit.value()),
+                    _ => panic!("expected string literal"),
+                }
+            }
             NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, lit, .. })) => {
                 let value: u64 = match lit {
                     Lit::Int(lit) => lit.base10_parse().unwrap(),
@@ -57,5 +69,6 @@ pub fn parse_attributes(attr: &Vec<NestedMeta>) -> Attributes {
         stack_size,
         max_input_size,
         max_output_size,
+        codec,
     }
 }
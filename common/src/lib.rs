@@ -1,3 +1,7 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
 pub mod attributes;
 pub mod constants;
+#[cfg(feature = "std")]
 pub mod rv_trace;
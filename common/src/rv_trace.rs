@@ -598,6 +598,23 @@ impl JoltDevice {
         self.inputs.len() + self.outputs.len()
     }
 
+    /// If the guest panicked, returns the panic message it wrote to the output
+    /// region (NUL-terminated UTF-8), if any.
+    pub fn panic_message(&self) -> Option<String> {
+        if !self.panic {
+            return None;
+        }
+        let len = self
+            .outputs
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(self.outputs.len());
+        if len == 0 {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&self.outputs[..len]).into_owned())
+    }
+
     pub fn is_input(&self, address: u64) -> bool {
         address >= self.memory_layout.input_start && address < self.memory_layout.input_end
     }
@@ -669,4 +686,25 @@ impl MemoryLayout {
             termination,
         }
     }
+
+    /// Renders the layout as C preprocessor definitions, for programs built
+    /// outside of the Jolt SDK.
+    pub fn to_c_header(&self) -> String {
+        format!(
+            "#define JOLT_INPUT_START {:#x}\n\
+             #define JOLT_INPUT_END {:#x}\n\
+             #define JOLT_OUTPUT_START {:#x}\n\
+             #define JOLT_OUTPUT_END {:#x}\n\
+             #define JOLT_PANIC {:#x}\n\
+             #define JOLT_TERMINATION {:#x}\n\
+             #define JOLT_RAM_START {:#x}\n",
+            self.input_start,
+            self.input_end,
+            self.output_start,
+            self.output_end,
+            self.panic,
+            self.termination,
+            RAM_START_ADDRESS,
+        )
+    }
 }
@@ -6,6 +6,7 @@ pub fn main() {
 
     let now = Instant::now();
     let (output, proof) = prove_collatz_single(19);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_collatz_single(proof);
 
@@ -20,6 +21,7 @@ pub fn main() {
     let start: u128 = 1 << 68;
     let now = Instant::now();
     let (output, proof) = prove_collatz_convergence(start, start + 100);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_collatz_convergence(proof);
 
@@ -13,3 +13,11 @@ fn fib(n: u32) -> u128 {
 
     b
 }
+
+#[cfg(test)]
+mod tests {
+    #[jolt::test]
+    fn fib_small() {
+        assert_eq!(super::fib(10), 55);
+    }
+}
@@ -9,6 +9,7 @@ pub fn main() {
 
     let now = Instant::now();
     let (output, proof) = prove_fib(50);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_fib(proof);
 
@@ -5,6 +5,7 @@ pub fn main() {
 
     let now = Instant::now();
     let (output, proof) = prove();
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify(proof);
 
@@ -1,11 +1,14 @@
 use std::time::Instant;
 
+use jolt_sdk::{Jolt, RV32IJoltVM};
+
 pub fn main() {
     let (prove_add, verify_add) = guest::build_add();
     let (prove_mul, verify_mul) = guest::build_mul();
 
     let now = Instant::now();
     let (output, proof) = prove_add(5, 10);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_add(proof);
 
@@ -13,8 +16,22 @@ pub fn main() {
     println!("add valid: {}", is_valid);
 
     let (output, proof) = prove_mul(5, 10);
+    let output = output.expect("the guest panicked");
     let is_valid = verify_mul(proof);
 
     println!("mul output: {}", output);
     println!("mul valid: {}", is_valid);
+
+    // Both functions built into one ELF, sharing a single preprocessing
+    let (program, preprocessing) = guest::preprocess_add_shared(&["add", "mul"]);
+    let (output, proof) = guest::prove_add(program.clone(), preprocessing.clone(), 5, 10);
+    let output = output.expect("the guest panicked");
+    let is_valid =
+        RV32IJoltVM::verify(preprocessing.clone(), proof.proof, proof.commitments, None).is_ok();
+    println!("shared add output: {}, valid: {}", output, is_valid);
+
+    let (output, proof) = guest::prove_mul(program, preprocessing.clone(), 5, 10);
+    let output = output.expect("the guest panicked");
+    let is_valid = RV32IJoltVM::verify(preprocessing, proof.proof, proof.commitments, None).is_ok();
+    println!("shared mul output: {}, valid: {}", output, is_valid);
 }
@@ -27,6 +27,7 @@ pub fn main() {
 
     let now = Instant::now();
     let (output, proof) = prove_allocate_stack_with_increased_size();
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_allocate_stack_with_increased_size(proof);
 
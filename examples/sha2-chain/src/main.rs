@@ -8,6 +8,7 @@ pub fn main() {
     let native_output = guest::sha2_chain(input, iters);
     let now = Instant::now();
     let (output, proof) = prove_sha2_chain(input, iters);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_sha2_chain(proof);
 
@@ -7,6 +7,7 @@ pub fn main() {
     let iters = 100;
     let now = Instant::now();
     let (output, proof) = prove_sha3_chain(input, iters);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify_sha3_chain(proof);
 
@@ -4,6 +4,7 @@ pub fn main() {
     let (prove, verify) = guest::build_int_to_string();
 
     let (output, proof) = prove(81);
+    let output = output.expect("the guest panicked");
     let is_valid = verify(proof);
 
     println!("int to string output: {:?}", output);
@@ -13,6 +14,7 @@ pub fn main() {
 
     let now = Instant::now();
     let (output, proof) = prove(20);
+    let output = output.expect("the guest panicked");
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     let is_valid = verify(proof);
 
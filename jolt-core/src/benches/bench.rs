@@ -15,7 +15,7 @@ use rand_core::RngCore;
 use rand_distr::{Distribution, Zipf};
 use serde::Serialize;
 
-#[derive(Debug, Copy, Clone, clap::ValueEnum)]
+#[derive(Debug, Copy, Clone, Serialize, clap::ValueEnum)]
 pub enum PCSType {
     Zeromorph,
     HyperKZG,
@@ -1,2 +1,4 @@
 pub mod bench;
+#[cfg(feature = "bench")]
+pub mod runner;
 pub mod sum_timer;
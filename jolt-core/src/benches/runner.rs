@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use ark_bn254::{Bn254, Fr};
+use ark_serialize::CanonicalSerialize;
+use serde::Serialize;
+
+use crate::field::JoltField;
+use crate::host::Program;
+use crate::jolt::vm::rv32i_vm::{RV32IJoltVM, C, M};
+use crate::jolt::vm::{Jolt, JoltPreprocessing};
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::commitment::hyperkzg::HyperKZG;
+use crate::poly::commitment::zeromorph::Zeromorph;
+use crate::utils::transcript::{KeccakTranscript, Transcript};
+
+use super::bench::PCSType;
+
+/// The example guests bundled with Jolt that benchmarks can prove. Each does an
+/// adjustable amount of uniform work, so it can be run at any trace length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReferenceGuest {
+    /// `fib(n)`: arithmetic only
+    Fibonacci,
+    /// `n` chained SHA-256 hashes
+    Sha2Chain,
+    /// `n` chained Keccak-256 hashes
+    Sha3Chain,
+}
+
+impl ReferenceGuest {
+    pub fn package(&self) -> &'static str {
+        match self {
+            Self::Fibonacci => "fibonacci-guest",
+            Self::Sha2Chain => "sha2-chain-guest",
+            Self::Sha3Chain => "sha3-chain-guest",
+        }
+    }
+
+    /// The guest doing `units` units of work
+    fn program(&self, units: u32) -> Program {
+        let mut program = Program::new(self.package());
+        match self {
+            Self::Fibonacci => program.set_input(&units),
+            Self::Sha2Chain | Self::Sha3Chain => {
+                program.set_input(&[5u8; 32]);
+                program.set_input(&units);
+            }
+        }
+        program
+    }
+
+    /// The number of units of work whose trace is about `trace_length` steps
+    /// long, extrapolated from two short runs
+    fn units_for(&self, trace_length: usize) -> u32 {
+        const STEP: u32 = 8;
+        let base = self.program(1).trace().1.len();
+        let stepped = self.program(1 + STEP).trace().1.len();
+        let per_unit = (stepped.saturating_sub(base) / STEP as usize).max(1);
+        1 + (trace_length.saturating_sub(base) / per_unit) as u32
+    }
+}
+
+/// Which benchmarks `run` runs: every guest, at every trace length, with every
+/// commitment scheme
+#[derive(Clone, Debug)]
+pub struct BenchmarkConfig {
+    pub guests: Vec<ReferenceGuest>,
+    /// Approximate trace lengths to prove each guest at. The actual trace
+    /// lengths are reported with the results.
+    pub trace_lengths: Vec<usize>,
+    pub schemes: Vec<PCSType>,
+    /// Number of proofs made of each configuration, of which the fastest is
+    /// reported
+    pub repetitions: usize,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            guests: vec![
+                ReferenceGuest::Fibonacci,
+                ReferenceGuest::Sha2Chain,
+                ReferenceGuest::Sha3Chain,
+            ],
+            trace_lengths: vec![1 << 16],
+            schemes: vec![PCSType::HyperKZG],
+            repetitions: 1,
+        }
+    }
+}
+
+/// The measurements of one benchmark. Times are in seconds and sizes in bytes.
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkResult {
+    pub guest: ReferenceGuest,
+    pub pcs: PCSType,
+    /// The field the proof is over, which is determined by the PCS
+    pub field: &'static str,
+    /// The requested trace length, see `BenchmarkConfig::trace_lengths`
+    pub target_trace_length: usize,
+    /// The units of work done by the guest to reach it
+    pub input: u32,
+    #[serde(flatten)]
+    pub measurements: Measurements,
+}
+
+/// See `BenchmarkResult`
+#[derive(Clone, Debug, Serialize)]
+pub struct Measurements {
+    pub trace_length: usize,
+    pub padded_trace_length: usize,
+    pub tracing_time: f64,
+    pub preprocessing_time: f64,
+    pub proving_time: f64,
+    pub verification_time: f64,
+    /// Compressed size of the proof and its commitments
+    pub proof_size: usize,
+    pub verified: bool,
+}
+
+/// The results of `run`, with the version of Jolt that produced them
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkReport {
+    pub jolt_version: &'static str,
+    pub results: Vec<BenchmarkResult>,
+}
+
+impl BenchmarkReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Builds and proves the reference guests in every configuration of `config`,
+/// one after the other. The guests are built from the examples of the Jolt
+/// workspace, so this must be run from it.
+pub fn run(config: &BenchmarkConfig) -> BenchmarkReport {
+    let mut results = vec![];
+    for guest in &config.guests {
+        for &target_trace_length in &config.trace_lengths {
+            let input = guest.units_for(target_trace_length);
+            for &pcs in &config.schemes {
+                let mut program = guest.program(input);
+                let measurements = match pcs {
+                    PCSType::HyperKZG => measure::<Fr, HyperKZG<Bn254, KeccakTranscript>, _>(
+                        &mut program,
+                        config.repetitions,
+                    ),
+                    PCSType::Zeromorph => measure::<Fr, Zeromorph<Bn254, KeccakTranscript>, _>(
+                        &mut program,
+                        config.repetitions,
+                    ),
+                };
+                results.push(BenchmarkResult {
+                    guest: *guest,
+                    pcs,
+                    field: "bn254",
+                    target_trace_length,
+                    input,
+                    measurements,
+                });
+            }
+        }
+    }
+    BenchmarkReport {
+        jolt_version: env!("CARGO_PKG_VERSION"),
+        results,
+    }
+}
+
+/// Proves and verifies `program`, proving it `repetitions` times
+fn measure<F, PCS, ProofTranscript>(program: &mut Program, repetitions: usize) -> Measurements
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let (bytecode, memory_init) = program.decode();
+    let start = Instant::now();
+    let (io_device, trace) = program.trace();
+    let tracing_time = start.elapsed();
+    let trace_length = trace.len();
+    let padded_trace_length = trace_length.next_power_of_two();
+
+    // Sized to the benchmark, like `Program::profile`, so that the PCS setup
+    // does not dominate
+    let start = Instant::now();
+    let preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript> = RV32IJoltVM::preprocess(
+        bytecode.clone(),
+        io_device.memory_layout.clone(),
+        memory_init,
+        bytecode.len().next_power_of_two(),
+        program.max_memory_words(),
+        padded_trace_length,
+    );
+    let preprocessing_time = start.elapsed();
+
+    let mut proving_time = Duration::MAX;
+    let mut proof = None;
+    for _ in 0..repetitions.max(1) {
+        let start = Instant::now();
+        let (jolt_proof, commitments, _) =
+            <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::prove(
+                io_device.clone(),
+                trace.clone(),
+                preprocessing.clone(),
+            );
+        proving_time = proving_time.min(start.elapsed());
+        proof = Some((jolt_proof, commitments));
+    }
+    let (jolt_proof, commitments) = proof.unwrap();
+    let proof_size = jolt_proof.compressed_size() + commitments.compressed_size();
+
+    let start = Instant::now();
+    let verified = RV32IJoltVM::verify(preprocessing, jolt_proof, commitments, None).is_ok();
+    let verification_time = start.elapsed();
+
+    Measurements {
+        trace_length,
+        padded_trace_length,
+        tracing_time: tracing_time.as_secs_f64(),
+        preprocessing_time: preprocessing_time.as_secs_f64(),
+        proving_time: proving_time.as_secs_f64(),
+        verification_time: verification_time.as_secs_f64(),
+        proof_size,
+        verified,
+    }
+}
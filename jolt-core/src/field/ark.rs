@@ -1,5 +1,5 @@
+use crate::utils::parallel::prelude::*;
 use ark_ff::{prelude::*, BigInt, PrimeField, UniformRand};
-use rayon::prelude::*;
 
 use crate::utils::thread::unsafe_allocate_zero_vec;
 
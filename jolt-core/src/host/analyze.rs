@@ -1,11 +1,11 @@
-use std::{collections::HashMap, fs::File, io, path::PathBuf};
+use std::{collections::HashMap, fmt, fs::File, io, path::PathBuf, time::Duration};
 
 use serde::{Deserialize, Serialize};
 use tracer::{ELFInstruction, JoltDevice, RVTraceRow, RV32IM};
 
 use crate::{
     field::JoltField,
-    jolt::vm::{rv32i_vm::RV32I, JoltTraceStep},
+    jolt::vm::{cost::CostReport, rv32i_vm::RV32I, JoltTraceStep},
 };
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -49,3 +49,45 @@ impl ProgramSummary {
         Ok(())
     }
 }
+
+/// Cycle counts of an execution and the projected cost of proving it, see
+/// `Program::profile`
+#[derive(Clone, Debug)]
+pub struct ProfileReport {
+    /// Executed RISC-V instructions
+    pub cycles: usize,
+    /// Steps of the Jolt trace, i.e. cycles with virtual sequences expanded
+    pub trace_length: usize,
+    /// Cycles spent in each function of the guest, most first (see
+    /// `tracer::cycles_by_function`)
+    pub functions: Vec<(Option<String>, usize)>,
+    pub cost: CostReport,
+}
+
+impl ProfileReport {
+    /// Steps added to pad the trace to a power of two
+    pub fn padding(&self) -> usize {
+        self.cost.padded_trace_length - self.trace_length
+    }
+
+    /// Projected proving time for a prover proving `steps_per_second` steps per
+    /// second, e.g. as measured for another proof on the same machine. Proving
+    /// time is roughly linear in the padded trace length.
+    pub fn proving_time(&self, steps_per_second: usize) -> Duration {
+        Duration::from_secs_f64(self.cost.padded_trace_length as f64 / steps_per_second as f64)
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cycles:                 {}", self.cycles)?;
+        writeln!(f, "steps:                  {}", self.trace_length)?;
+        writeln!(
+            f,
+            "padding:                {} steps ({:.1}%)",
+            self.padding(),
+            100.0 * self.padding() as f64 / self.cost.padded_trace_length as f64
+        )?;
+        write!(f, "{}", self.cost)
+    }
+}
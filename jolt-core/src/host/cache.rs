@@ -0,0 +1,148 @@
+//! Cache of guest builds, so that hosts running the same unchanged guest again,
+//! e.g. across test or bench runs, skip compiling and decoding it. Entries are
+//! keyed by a hash of everything the build depends on: the sources of the guest
+//! package and of its local (path) dependencies, the lockfile, the toolchain and
+//! the build's configuration. Dependencies from registries or git are pinned by
+//! the lockfile.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use alloy_primitives::hex;
+use serde_json::Value;
+use sha3::{Digest, Sha3_256};
+
+/// Directory of the cache, with one subdirectory per build key
+const CACHE_DIR: &str = "/tmp/jolt-guest-cache";
+
+/// Set to disable the cache, e.g. when the guest's build depends on files or
+/// environment variables the key does not cover
+const DISABLE_CACHE_ENV: &str = "JOLT_NO_BUILD_CACHE";
+
+/// The key of a build of `guest` with the given environment variables and
+/// `config`, or `None` if the cache is disabled or the guest's sources cannot be
+/// located (in which case it is always rebuilt).
+pub(crate) fn build_key(
+    guest: &str,
+    envs: &[(&str, String)],
+    config: &[&[u8]],
+) -> Option<[u8; 32]> {
+    if std::env::var_os(DISABLE_CACHE_ENV).is_some() {
+        return None;
+    }
+
+    let output = Command::new("cargo")
+        .args(["metadata", "--format-version", "1", "--no-deps"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout).ok()?;
+    let workspace_root = PathBuf::from(metadata["workspace_root"].as_str()?);
+
+    let mut hasher = Sha3_256::new();
+    for dir in package_dirs(&metadata, guest)? {
+        hash_dir(&mut hasher, &dir, &dir).ok()?;
+    }
+    hasher.update(fs::read(workspace_root.join("Cargo.lock")).unwrap_or_default());
+
+    let toolchain = Command::new("rustc")
+        .envs(envs.iter().cloned())
+        .args(["--version", "--verbose"])
+        .output()
+        .ok()?;
+    hasher.update(toolchain.stdout);
+    for (key, value) in envs {
+        hasher.update(key.as_bytes());
+        hasher.update(value.as_bytes());
+    }
+    for bytes in config {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    Some(hasher.finalize().into())
+}
+
+/// The path of the cached file `name` of the build `key`
+pub(crate) fn entry(key: &[u8; 32], name: &str) -> PathBuf {
+    Path::new(CACHE_DIR).join(hex::encode(key)).join(name)
+}
+
+/// Caches `contents` as `path` (see `entry`). Writes to a temporary file first,
+/// so that concurrent hosts never read a partially written entry.
+pub(crate) fn store(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::create_dir_all(path.parent().unwrap())?;
+    let temp = path.with_extension(format!("tmp-{}", std::process::id()));
+    fs::write(&temp, contents)?;
+    fs::rename(temp, path)
+}
+
+/// The directories of `guest` and of its path dependencies, transitively, as
+/// far as they are described by `metadata`
+fn package_dirs(metadata: &Value, guest: &str) -> Option<Vec<PathBuf>> {
+    let packages = metadata["packages"].as_array()?;
+    let manifest_dir = |package: &Value| {
+        Path::new(package["manifest_path"].as_str()?)
+            .parent()
+            .map(Path::to_path_buf)
+    };
+
+    let guest = packages
+        .iter()
+        .find(|package| package["name"].as_str() == Some(guest))?;
+    let mut dirs = vec![manifest_dir(guest)?];
+    let mut seen: HashSet<PathBuf> = dirs.iter().cloned().collect();
+    let mut pending = vec![guest];
+    while let Some(package) = pending.pop() {
+        for dependency in package["dependencies"].as_array()? {
+            if dependency["kind"].as_str() == Some("dev") {
+                continue;
+            }
+            let Some(path) = dependency["path"].as_str() else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            if let Some(package) = packages
+                .iter()
+                .find(|package| manifest_dir(package).as_ref() == Some(&path))
+            {
+                pending.push(package);
+            }
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Some(dirs)
+}
+
+/// Hashes the paths (relative to `root`) and contents of the files in `dir`,
+/// skipping build outputs and hidden files
+fn hash_dir(hasher: &mut Sha3_256, root: &Path, dir: &Path) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        if name == "target" || name.to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            hash_dir(hasher, root, &path)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            let contents = fs::read(&path)?;
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(contents);
+        }
+    }
+    Ok(())
+}
@@ -4,21 +4,24 @@ use core::str::FromStr;
 use std::{
     fs::{self, File},
     io::{self, Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::Command,
 };
 
+use crate::utils::parallel::prelude::*;
 use postcard;
-use rayon::prelude::*;
 use serde::Serialize;
 
 use common::{
     constants::{
         DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
+        RAM_START_ADDRESS, REGISTER_COUNT, RNG_SEED_SIZE,
     },
-    rv_trace::JoltDevice,
+    rv_trace::{JoltDevice, MemoryLayout, RVTraceRow},
+};
+pub use tracer::{
+    ELFInstruction, GuestPanic, Hints, LogRecord, ReferenceDivergence, TraceDivergence,
 };
-pub use tracer::ELFInstruction;
 
 use crate::{
     field::JoltField,
@@ -29,52 +32,151 @@ use crate::{
             mulhsu::MULHSUInstruction, rem::REMInstruction, remu::REMUInstruction,
             sb::SBInstruction, sh::SHInstruction, VirtualInstructionSequence,
         },
-        vm::{bytecode::BytecodeRow, rv32i_vm::RV32I, JoltTraceStep},
+        vm::{
+            bytecode::BytecodeRow,
+            config::ProverConfig,
+            kat::{self, KnownAnswerVectors, RecordingTranscript},
+            progress::ProverStage,
+            read_write_memory::MemoryState,
+            rv32i_vm::{RV32IChainedProof, RV32IJoltVM, Serializable, C, M, RV32I},
+            stage_summary::STAGE_SPAN,
+            Jolt, JoltPreprocessing, JoltTraceStep, ProgramPreprocessing,
+        },
+    },
+    poly::commitment::commitment_scheme::CommitmentScheme,
+    utils::{
+        errors::{ConfigError, ProofVerifyError},
+        transcript::Transcript,
     },
 };
 
-use self::analyze::ProgramSummary;
+use self::analyze::{ProfileReport, ProgramSummary};
+pub use self::remote::{RemoteProver, REMOTE_PROVER_ENV};
 #[cfg(not(target_arch = "wasm32"))]
-use self::toolchain::{install_no_std_toolchain, install_toolchain};
+use self::toolchain::{
+    build_failure_hint, install_no_std_toolchain, install_target_spec_support, install_toolchain,
+};
 
 pub mod analyze;
+mod cache;
+mod remote;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod toolchain;
 
 #[derive(Clone)]
 pub struct Program {
     guest: String,
-    func: Option<String>,
+    funcs: Vec<String>,
+    /// Index in `funcs` of the function to run, for a shared build
+    entry: usize,
     input: Vec<u8>,
+    raw_input: Vec<u8>,
+    advice: Vec<u8>,
+    hints: Hints,
+    seed: [u8; RNG_SEED_SIZE],
     memory_size: u64,
     stack_size: u64,
     max_input_size: u64,
     max_output_size: u64,
     std: bool,
     pub elf: Option<PathBuf>,
+    guest_panic: Option<GuestPanic>,
+    raw: bool,
+    /// Whether `funcs` are the guest's `#[jolt::test]` functions (see `set_tests`)
+    test: bool,
+    /// Target specification to build the guest for instead of the Jolt targets
+    target_spec: Option<PathBuf>,
+    /// Key of the guest's build in the build cache, if it was built and can be cached
+    build_key: Option<[u8; 32]>,
 }
 
 impl Program {
     pub fn new(guest: &str) -> Self {
         Self {
             guest: guest.to_string(),
-            func: None,
+            funcs: vec![],
+            entry: 0,
             input: Vec::new(),
+            raw_input: Vec::new(),
+            advice: Vec::new(),
+            hints: Hints::new(),
+            seed: [0; RNG_SEED_SIZE],
             memory_size: DEFAULT_MEMORY_SIZE,
             stack_size: DEFAULT_STACK_SIZE,
             max_input_size: DEFAULT_MAX_INPUT_SIZE,
             max_output_size: DEFAULT_MAX_OUTPUT_SIZE,
             std: false,
             elf: None,
+            guest_panic: None,
+            raw: false,
+            test: false,
+            target_spec: None,
+            build_key: None,
         }
     }
 
+    /// Creates a program from a prebuilt rv32im ELF, e.g. compiled from C/C++,
+    /// instead of building a guest crate with the Jolt SDK.
+    ///
+    /// Such programs must follow Jolt's I/O conventions (see `MemoryLayout`):
+    /// they are loaded at `RAM_START_ADDRESS`, read their inputs from
+    /// `input_start`, write their outputs to `output_start`, and terminate by
+    /// storing 1 to `termination` and then spinning in place. Inputs are passed
+    /// to the program verbatim, see `set_input_bytes`.
+    pub fn from_elf(elf: PathBuf) -> Self {
+        let guest = elf
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut program = Self::new(&guest);
+        program.elf = Some(elf);
+        program.raw = true;
+        program
+    }
+
     pub fn set_std(&mut self, std: bool) {
         self.std = std;
     }
 
+    /// Builds the guest for the custom target described by the target JSON
+    /// `spec`, e.g. one enabling an ISA extension, rather than for the Jolt targets.
+    /// The standard library is built from source for it (`-Zbuild-std`), which
+    /// needs a nightly toolchain with the `rust-src` component, installed if
+    /// missing.
+    pub fn set_target_spec(&mut self, spec: PathBuf) {
+        self.target_spec = Some(spec);
+    }
+
     pub fn set_func(&mut self, func: &str) {
-        self.func = Some(func.to_string())
+        self.set_funcs(&[func])
+    }
+
+    /// Builds a single ELF exporting all of the given provable functions, so that
+    /// they share one preprocessing. The function to run is chosen with
+    /// `select_func`, and passed to the guest ahead of its inputs.
+    pub fn set_funcs(&mut self, funcs: &[&str]) {
+        self.funcs = funcs.iter().map(|func| func.to_string()).collect();
+        self.entry = 0;
+    }
+
+    /// Builds the guest's `#[jolt::test]` functions `tests`, including those in
+    /// `#[cfg(test)]` modules, into a single ELF instead of its provable functions.
+    /// Each test is then run in the emulator with `run_test`.
+    pub fn set_tests(&mut self, tests: &[&str]) {
+        self.set_funcs(tests);
+        self.test = true;
+    }
+
+    /// Selects which of the functions of a shared build (see `set_funcs`) to run.
+    pub fn select_func(&mut self, func: &str) {
+        if self.funcs.is_empty() {
+            return;
+        }
+        self.entry = self
+            .funcs
+            .iter()
+            .position(|f| f == func)
+            .unwrap_or_else(|| panic!("{func} is not built into this program"));
     }
 
     pub fn set_input<T: Serialize>(&mut self, input: &T) {
@@ -82,6 +184,55 @@ impl Program {
         self.input.append(&mut serialized);
     }
 
+    /// Appends raw bytes to the inputs, without serializing them.
+    pub fn set_input_bytes(&mut self, bytes: &[u8]) {
+        self.input.extend_from_slice(bytes);
+    }
+
+    /// Appends bytes to the guest's raw input, which is loaded into its input
+    /// region with the other inputs and read via `jolt::raw_input()`. Unlike
+    /// `set_input`, the bytes are not serialized, and they count towards the
+    /// guest's `max_input_size`.
+    pub fn set_raw_input(&mut self, bytes: &[u8]) {
+        self.raw_input.extend_from_slice(bytes);
+    }
+
+    /// Appends untrusted advice (hints), which the guest can read via
+    /// `jolt::advice`. Advice is not part of the public inputs and is not
+    /// constrained by the proof, so the guest must check anything it reads.
+    pub fn set_advice(&mut self, bytes: &[u8]) {
+        self.advice.extend_from_slice(bytes);
+    }
+
+    /// Answers the guest's `jolt::hint::request`s with id `id` by calling
+    /// `handler` on the request. Like advice, responses are not constrained by
+    /// the proof. They are recorded on first execution and replayed afterwards
+    /// (see `tracer::Hints`), so tracing the program again, e.g. for proving,
+    /// sees the same responses.
+    pub fn set_hint(
+        &mut self,
+        id: u32,
+        handler: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) {
+        self.hints.register(id, handler);
+    }
+
+    /// The messages the guest logged with `jolt::log!` during its most recent
+    /// execution, with the cycles they were logged at. `trace` also emits them
+    /// as `tracing` events with target `guest`.
+    pub fn logs(&self) -> Vec<LogRecord> {
+        self.hints.logs()
+    }
+
+    /// Sets the seed of the guest's prover-seeded RNG (see `jolt::prover_rand`).
+    /// The seed is passed to the guest as part of its public inputs, so the
+    /// proof shows which seed was used, but it is not derived from the
+    /// transcript: whoever proves picks it, and can pick it to influence the
+    /// guest's "random" bytes. Defaults to all zeros.
+    pub fn set_prover_seed(&mut self, seed: [u8; RNG_SEED_SIZE]) {
+        self.seed = seed;
+    }
+
     pub fn set_memory_size(&mut self, len: u64) {
         self.memory_size = len;
     }
@@ -98,19 +249,47 @@ impl Program {
         self.max_output_size = size;
     }
 
+    /// Layout of the program's I/O region, which prebuilt ELFs (see
+    /// `from_elf`) must follow.
+    pub fn memory_layout(&self) -> MemoryLayout {
+        MemoryLayout::new(self.max_input_size, self.max_output_size)
+    }
+
+    /// Upper bound on the number of words of memory covered by a proof of the
+    /// program (see `Jolt::cost_report`): the registers, the I/O region and all
+    /// of the guest's RAM.
+    pub fn max_memory_words(&self) -> usize {
+        let memory_layout = self.memory_layout();
+        let ram_end = RAM_START_ADDRESS + self.memory_size;
+        (REGISTER_COUNT + (ram_end - memory_layout.input_start) / 4).next_power_of_two() as usize
+    }
+
+    /// The panic message and backtrace of the guest, if it panicked during
+    /// the most recent call to `trace` or `profile`.
+    pub fn guest_panic(&self) -> Option<&GuestPanic> {
+        self.guest_panic.as_ref()
+    }
+
     #[tracing::instrument(skip_all, name = "Program::build")]
     pub fn build(&mut self) {
         if self.elf.is_none() {
             #[cfg(not(target_arch = "wasm32"))]
-            install_toolchain().unwrap();
-            #[cfg(not(target_arch = "wasm32"))]
-            install_no_std_toolchain().unwrap();
+            match &self.target_spec {
+                Some(spec) => install_target_spec_support(spec),
+                None if self.std => install_toolchain(),
+                None => install_no_std_toolchain(),
+            }
+            .unwrap_or_else(|err| panic!("could not install the guest toolchain: {}", err));
 
             self.save_linker();
 
-            let rust_flags = [
+            // The flags replace the user's RUSTFLAGS and override the guest's profile,
+            // so that the ELF, and with it the program hash, is the same on every
+            // machine with the same toolchain
+            let linker_arg = format!("link-arg=-T{}", self.linker_path());
+            let mut rust_flags: Vec<String> = [
                 "-C",
-                &format!("link-arg=-T{}", self.linker_path()),
+                &linker_arg,
                 "-C",
                 "passes=lower-atomic",
                 "-C",
@@ -119,57 +298,175 @@ impl Program {
                 "strip=symbols",
                 "-C",
                 "opt-level=z",
-            ];
+                "-C",
+                "codegen-units=1",
+                "-C",
+                "link-arg=--build-id=none",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect();
+            rust_flags.extend(remap_path_prefixes());
 
             let toolchain = if self.std {
                 "riscv32im-jolt-zkvm-elf"
             } else {
                 "riscv32im-unknown-none-elf"
             };
+            // Cargo names the output directory of a custom target after its spec file
+            let (target_triple, target_name) = match &self.target_spec {
+                Some(spec) => (
+                    spec.to_str().unwrap().to_string(),
+                    spec.file_stem().unwrap().to_string_lossy().into_owned(),
+                ),
+                None => (toolchain.to_string(), toolchain.to_string()),
+            };
 
-            let mut envs = vec![("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))];
+            let mut envs = vec![
+                ("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f")),
+                ("CARGO_INCREMENTAL", "0".to_string()),
+            ];
 
-            if self.std {
+            if self.std && self.target_spec.is_none() {
                 envs.push(("RUSTUP_TOOLCHAIN", toolchain.to_string()));
             }
 
-            if let Some(func) = &self.func {
-                envs.push(("JOLT_FUNC_NAME", func.to_string()));
+            if self.test {
+                envs.push(("JOLT_TEST_NAMES", self.funcs.join(",")));
+            } else if !self.funcs.is_empty() {
+                envs.push(("JOLT_FUNC_NAME", self.funcs.join(",")));
             }
 
-            let target = format!(
-                "/tmp/jolt-guest-target-{}-{}",
-                self.guest,
-                self.func.as_ref().unwrap_or(&"".to_string())
+            // The flags only name the linker script, so its contents are keyed separately
+            let linker_script = self.linker_script();
+            let target_spec = match &self.target_spec {
+                Some(spec) => fs::read(spec).unwrap_or_default(),
+                None => vec![],
+            };
+            self.build_key = cache::build_key(
+                &self.guest,
+                &envs,
+                &[
+                    target_triple.as_bytes(),
+                    target_spec.as_slice(),
+                    linker_script.as_bytes(),
+                ],
             );
+            if let Some(key) = &self.build_key {
+                let cached = cache::entry(key, "guest.elf");
+                if cached.exists() {
+                    self.elf = Some(cached);
+                    return;
+                }
+            }
 
-            let output = Command::new("cargo")
-                .envs(envs)
-                .args([
-                    "build",
-                    "--release",
-                    "--features",
-                    "guest",
-                    "-p",
-                    &self.guest,
-                    "--target-dir",
-                    &target,
-                    "--target",
-                    toolchain,
-                ])
-                .output()
-                .expect("failed to build guest");
+            let target = if self.test {
+                format!("/tmp/jolt-guest-target-{}-test", self.guest)
+            } else {
+                format!(
+                    "/tmp/jolt-guest-target-{}-{}",
+                    self.guest,
+                    self.funcs.join("-")
+                )
+            };
+
+            let mut command = Command::new("cargo");
+            command.envs(envs.clone()).args([
+                if self.test { "rustc" } else { "build" },
+                "--release",
+                "--features",
+                "guest",
+                "-p",
+                &self.guest,
+                "--target-dir",
+                &target,
+                "--target",
+                &target_triple,
+            ]);
+            if self.target_spec.is_some() {
+                // There is no prebuilt standard library for custom targets
+                let crates = if self.std {
+                    "std,panic_abort"
+                } else {
+                    "core,alloc"
+                };
+                command.args([
+                    &format!("-Zbuild-std={}", crates),
+                    "-Zbuild-std-features=compiler-builtins-mem",
+                ]);
+            }
+            if self.test {
+                // Only the guest crate is compiled with `cfg(test)`, into a static
+                // library linked below, as its dependencies may not build with it
+                command.args(["--lib", "--crate-type", "staticlib", "--", "--cfg", "test"]);
+            }
+            let output = command.output().expect("failed to build guest");
 
             if !output.status.success() {
                 io::stderr().write_all(&output.stderr).unwrap();
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(hint) = build_failure_hint(&String::from_utf8_lossy(&output.stderr)) {
+                    eprintln!("{}", hint);
+                }
                 panic!("failed to compile guest");
             }
 
-            let elf = format!("{}/{}/release/{}", target, toolchain, self.guest);
+            let mut elf = format!("{}/{}/release/{}", target, target_name, self.guest);
+            if self.test {
+                let archive = format!(
+                    "{}/{}/release/lib{}.a",
+                    target,
+                    target_name,
+                    self.guest.replace('-', "_")
+                );
+                elf.push_str("-tests");
+                self.link(&archive, &elf, &envs);
+            }
+            if let Some(key) = &self.build_key {
+                // Failing to cache the build only means it is not reused
+                let _ = fs::read(&elf)
+                    .and_then(|elf| cache::store(&cache::entry(key, "guest.elf"), &elf));
+            }
             self.elf = Some(PathBuf::from_str(&elf).unwrap());
         }
     }
 
+    /// The hash that identifies the program in verifier keys and proofs (see
+    /// `ProgramPreprocessing`). Guest builds are reproducible, so it is the same
+    /// wherever the guest is built from the same sources and toolchain.
+    pub fn program_hash(&mut self) -> [u8; 32] {
+        self.program_preprocessing().program_hash
+    }
+
+    /// The program-dependent part of the guest's preprocessing, for
+    /// `Jolt::preprocess_program`. Like the ELF, it is cached with the guest's build,
+    /// so that preprocessing an unchanged guest again skips decoding it and
+    /// expanding its bytecode.
+    pub fn program_preprocessing(&mut self) -> ProgramPreprocessing {
+        self.build();
+        let cached = self.build_key.map(|key| {
+            let name = format!(
+                "program-{}-{}.bin",
+                self.max_input_size, self.max_output_size
+            );
+            cache::entry(&key, &name)
+        });
+        if let Some(program) = cached
+            .as_deref()
+            .and_then(|path| ProgramPreprocessing::from_file(path).ok())
+        {
+            return program;
+        }
+
+        let (bytecode, memory_init) = self.decode();
+        let program =
+            ProgramPreprocessing::new::<RV32I>(bytecode, self.memory_layout(), memory_init);
+        if let Some(path) = cached {
+            let _ = cache::store(&path, &program.serialize_to_bytes().unwrap());
+        }
+        program
+    }
+
     pub fn decode(&mut self) -> (Vec<ELFInstruction>, Vec<(u64, u8)>) {
         self.build();
         let elf = self.elf.as_ref().unwrap();
@@ -184,52 +481,293 @@ impl Program {
     #[tracing::instrument(skip_all, name = "Program::trace")]
     pub fn trace(&mut self) -> (JoltDevice, Vec<JoltTraceStep<RV32I>>) {
         self.build();
+        let stage = tracing::info_span!(
+            STAGE_SPAN,
+            stage = %ProverStage::Tracing,
+            trace_length = tracing::field::Empty
+        )
+        .entered();
         let elf = self.elf.clone().unwrap();
-        let (raw_trace, io_device) =
-            tracer::trace(&elf, &self.input, self.max_input_size, self.max_output_size);
-
-        let trace: Vec<_> = raw_trace
-            .into_par_iter()
-            .flat_map(|row| match row.instruction.opcode {
-                tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::REM => REMInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::SH => SHInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::SB => SBInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LB => LBInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LH => LHInstruction::<32>::virtual_trace(row),
-                _ => vec![row],
-            })
-            .map(|row| {
-                let instruction_lookup = if let Ok(jolt_instruction) = RV32I::try_from(&row) {
-                    Some(jolt_instruction)
-                } else {
-                    // Instruction does not use lookups
-                    None
-                };
+        let (raw_trace, io_device) = tracer::trace(
+            &elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+        );
 
-                JoltTraceStep {
-                    instruction_lookup,
-                    bytecode_row: BytecodeRow::from_instruction::<RV32I>(&row.instruction),
-                    memory_ops: (&row).into(),
-                    circuit_flags: row.instruction.to_circuit_flags(),
-                }
-            })
-            .collect();
+        self.guest_panic = if io_device.panic {
+            let elf_contents = fs::read(&elf).unwrap();
+            tracer::guest_panic(&elf_contents, &raw_trace, &io_device)
+        } else {
+            None
+        };
+        for log in self.hints.logs() {
+            tracing::info!(target: "guest", cycle = log.cycle, "{}", log.message);
+        }
 
+        let trace = process_trace(raw_trace);
+        stage.record("trace_length", trace.len());
         (io_device, trace)
     }
 
-    pub fn trace_analyze<F: JoltField>(mut self) -> ProgramSummary {
+    /// Traces the program starting from the final memory state of a previously
+    /// proven execution, for proving with `Jolt::prove_continuation`.
+    #[tracing::instrument(skip_all, name = "Program::trace_from_state")]
+    pub fn trace_from_state(
+        &mut self,
+        state: &MemoryState,
+    ) -> (JoltDevice, Vec<JoltTraceStep<RV32I>>) {
+        self.build();
+        let elf = self.elf.clone().unwrap();
+        let (raw_trace, io_device) = tracer::trace_from_state(
+            &elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+            &state.nonzero_words(),
+        );
+        (io_device, process_trace(raw_trace))
+    }
+
+    /// Proves the program's execution in chunks of `chunk_size` steps, each a
+    /// continuation of the previous one (see `Jolt::prove_chunks`). The program
+    /// is traced one chunk at a time, so executions much longer than the
+    /// maximum trace length of `preprocessing` can be proven without ever
+    /// holding their whole trace in memory. `chunk_size` must be a power of two.
+    #[tracing::instrument(skip_all, name = "Program::prove_continuations")]
+    pub fn prove_continuations<F, PCS, ProofTranscript>(
+        &mut self,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        chunk_size: usize,
+    ) -> RV32IChainedProof<F, PCS, ProofTranscript>
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        assert!(chunk_size.is_power_of_two());
+        // Every segment's proof includes the I/O of the whole execution, which
+        // is much cheaper to compute up front than the trace
+        let (io_device, _) = self.execute();
+
+        let elf = self.elf.clone().unwrap();
+        let mut raw_chunks = tracer::trace_chunks(
+            &elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+            chunk_size,
+        );
+        // Virtual sequences expand rows into several steps, so chunks of rows
+        // are regrouped into chunks of exactly `chunk_size` steps
+        let mut steps: Vec<JoltTraceStep<RV32I>> = vec![];
+        let chunks = std::iter::from_fn(move || {
+            while steps.len() < chunk_size {
+                match raw_chunks.next() {
+                    Some(rows) => steps.extend(process_trace(rows)),
+                    None => break,
+                }
+            }
+            let rest = steps.split_off(steps.len().min(chunk_size));
+            let chunk = std::mem::replace(&mut steps, rest);
+            (!chunk.is_empty()).then_some(chunk)
+        });
+        RV32IJoltVM::prove_chunks(io_device, chunks, preprocessing.clone())
+    }
+
+    /// Like `prove_continuations`, but picks the largest chunk size for which
+    /// the prover is estimated to stay within `max_memory` bytes (see
+    /// `Jolt::chunk_size_for_memory`), assuming the guest may touch all of its
+    /// RAM. `max_trace_length` is the one `preprocessing` was created with.
+    /// Fails with `ConfigError::NoChunkFits` if the budget is too small for
+    /// any chunk size.
+    pub fn prove_within_memory<F, PCS, ProofTranscript>(
+        &mut self,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        max_trace_length: usize,
+        max_memory: usize,
+    ) -> Result<RV32IChainedProof<F, PCS, ProofTranscript>, ConfigError>
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let chunk_size =
+            <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::chunk_size_for_memory(
+                preprocessing,
+                self.max_memory_words(),
+                max_trace_length.next_power_of_two(),
+                max_memory,
+            )
+            .ok_or(ConfigError::NoChunkFits(max_memory))?;
+        Ok(self.prove_continuations(preprocessing, chunk_size))
+    }
+
+    /// Proves the program's execution with `prove_continuations`, as configured
+    /// by `config`: in chunks of its chunk size, or the largest chunk size
+    /// within its memory budget (or, if calibrated, the memory of the machine),
+    /// or otherwise its maximum trace length, and on its threads.
+    /// `preprocessing` must be from `Jolt::preprocess_with_config` with the
+    /// same config.
+    pub fn prove_with_config<F, PCS, ProofTranscript>(
+        &mut self,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        config: &ProverConfig,
+    ) -> Result<RV32IChainedProof<F, PCS, ProofTranscript>, ConfigError>
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        config.check_pcs::<PCS, ProofTranscript>()?;
+        let memory_size = self.max_memory_words();
+        let chunk_size = match (config.chunk_size(), config.memory_budget()) {
+            (Some(chunk_size), _) => chunk_size,
+            (None, Some(memory_budget)) => {
+                <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::chunk_size_for_memory(
+                    preprocessing,
+                    memory_size,
+                    config.max_trace_length().next_power_of_two(),
+                    memory_budget,
+                )
+                .ok_or(ConfigError::NoChunkFits(memory_budget))?
+            }
+            // Calibrated to this machine, the largest chunk size within the
+            // memory it has to spare
+            (None, None) => config
+                .calibration()
+                .and_then(|calibration| calibration.memory_budget())
+                .and_then(|memory_budget| {
+                    <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::chunk_size_for_memory(
+                        preprocessing,
+                        memory_size,
+                        config.max_trace_length().next_power_of_two(),
+                        memory_budget,
+                    )
+                })
+                .unwrap_or(config.max_trace_length().next_power_of_two()),
+        };
+        <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::check_security(
+            preprocessing,
+            chunk_size,
+            memory_size,
+            config.security().clone(),
+        )?;
+        Ok(config.install(|| self.prove_continuations(preprocessing, chunk_size)))
+    }
+
+    /// Traces the program and writes the raw trace to `path` in the compressed
+    /// trace format (see `tracer::trace_format`), so that it can be proven
+    /// later, or elsewhere, via `load_trace` without re-executing the guest.
+    pub fn save_trace(&mut self, path: &PathBuf) -> io::Result<()> {
+        let (raw_trace, io_device) = self.raw_trace();
+        let file = io::BufWriter::new(File::create(path)?);
+        tracer::trace_format::write_trace(file, &raw_trace, &io_device)
+    }
+
+    /// Runs the program without recording its trace, returning the final I/O
+    /// device state and the number of executed cycles, for when only the
+    /// program's outputs or cycle count are needed. Instructions are still
+    /// emulated one by one as in `trace`; only the trace rows are not kept.
+    #[tracing::instrument(skip_all, name = "Program::execute")]
+    pub fn execute(&mut self) -> (JoltDevice, usize) {
         self.build();
         let elf = self.elf.as_ref().unwrap();
-        let (raw_trace, _) =
-            tracer::trace(elf, &self.input, self.max_input_size, self.max_output_size);
+        let (cycles, io_device) = tracer::execute(
+            elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+        );
+        (io_device, cycles)
+    }
+
+    /// Runs the test `test` of a test build (see `set_tests`) in the emulator,
+    /// without proving it.
+    pub fn run_test(&mut self, test: &str) -> TestOutcome {
+        self.select_func(test);
+        let (raw_trace, io_device) = self.raw_trace();
+        let elf_contents = fs::read(self.elf.as_ref().unwrap()).unwrap();
+        self.guest_panic = tracer::guest_panic(&elf_contents, &raw_trace, &io_device);
+        TestOutcome {
+            cycles: raw_trace.len(),
+            panic: self.guest_panic.clone(),
+        }
+    }
+
+    /// Runs this program and `other` (e.g. the same guest built with a different
+    /// toolchain, or the same binary on different inputs) and returns the first
+    /// step at which their executions diverge, if any.
+    pub fn diff(&mut self, other: &mut Program) -> Option<TraceDivergence> {
+        let (left, _) = self.raw_trace();
+        let (right, _) = other.raw_trace();
+        tracer::first_divergence(&left, &right)
+    }
+
+    /// Runs the program in the emulator and, in lockstep, in an independent
+    /// reference interpreter, returning the first instruction after which their
+    /// architectural states (PC, registers, stored memory) differ, if any.
+    pub fn diff_against_reference(&mut self) -> Result<JoltDevice, ReferenceDivergence> {
+        self.build();
+        let elf = self.elf.as_ref().unwrap();
+        let (_, io_device) = tracer::trace_differential(
+            elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+        )?;
+        Ok(io_device)
+    }
+
+    fn raw_trace(&mut self) -> (Vec<RVTraceRow>, JoltDevice) {
+        self.build();
+        let elf = self.elf.as_ref().unwrap();
+        tracer::trace(
+            elf,
+            &self.inputs(),
+            &self.advice,
+            &self.hints,
+            self.max_input_size,
+            self.max_output_size,
+        )
+    }
+
+    /// The guest's input region: the RNG seed, the serialized inputs, and the
+    /// length-prefixed raw input. Prebuilt ELFs receive their inputs as is.
+    fn inputs(&self) -> Vec<u8> {
+        if self.raw {
+            return self.input.clone();
+        }
+        let raw_input_len = (self.raw_input.len() as u32).to_le_bytes();
+        // Shared and test builds read the index of the function to run after the seed
+        let entry = if self.funcs.len() > 1 || self.test {
+            (self.entry as u32).to_le_bytes().to_vec()
+        } else {
+            vec![]
+        };
+        [
+            self.seed.as_slice(),
+            &entry,
+            &self.input,
+            &raw_input_len,
+            &self.raw_input,
+        ]
+        .concat()
+    }
+
+    pub fn trace_analyze<F: JoltField>(mut self) -> ProgramSummary {
+        self.build();
+        let (raw_trace, _) = self.raw_trace();
 
         let (bytecode, memory_init) = self.decode();
         let (io_device, processed_trace) = self.trace();
@@ -243,26 +781,236 @@ impl Program {
         }
     }
 
+    /// Runs the program and reports its cycle count, the cycles spent in each
+    /// function, and the projected size and prover memory of a proof of it with
+    /// `PCS` (see `Jolt::cost_report`), without proving it. The memory estimate
+    /// assumes the guest may touch all of its RAM.
+    #[tracing::instrument(skip_all, name = "Program::profile")]
+    pub fn profile<F, PCS, ProofTranscript>(&mut self) -> ProfileReport
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let (raw_trace, io_device) = self.raw_trace();
+        let elf_contents = fs::read(self.elf.as_ref().unwrap()).unwrap();
+        self.guest_panic = if io_device.panic {
+            tracer::guest_panic(&elf_contents, &raw_trace, &io_device)
+        } else {
+            None
+        };
+        let functions = tracer::cycles_by_function(&elf_contents, &raw_trace);
+        let cycles = raw_trace.len();
+        let trace_length = process_trace(raw_trace).len();
+
+        // The preprocessing is only as large as this execution needs, since the
+        // PCS setup dominates its cost
+        let (bytecode, memory_init) = self.decode();
+        let padded_trace_length = trace_length.next_power_of_two();
+        let preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript> = RV32IJoltVM::preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            bytecode.len().next_power_of_two(),
+            self.memory_size as usize,
+            padded_trace_length,
+        );
+        let cost = <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::cost_report(
+            &preprocessing,
+            padded_trace_length,
+            self.max_memory_words(),
+        );
+
+        ProfileReport {
+            cycles,
+            trace_length,
+            functions,
+            cost,
+        }
+    }
+
+    /// Proves the program and records the intermediate values of the proof (see
+    /// `kat::known_answer_vectors`), with preprocessing only as large as the
+    /// execution needs. For validating other verifier implementations against a
+    /// tiny guest.
+    pub fn known_answer_vectors<F, PCS>(&mut self) -> Result<KnownAnswerVectors, ProofVerifyError>
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<RecordingTranscript, Field = F>,
+    {
+        let (io_device, trace) = self.trace();
+        let (bytecode, memory_init) = self.decode();
+        let preprocessing: JoltPreprocessing<C, F, PCS, RecordingTranscript> =
+            RV32IJoltVM::preprocess(
+                bytecode.clone(),
+                io_device.memory_layout.clone(),
+                memory_init,
+                bytecode.len().next_power_of_two(),
+                self.memory_size as usize,
+                trace.len().next_power_of_two(),
+            );
+        kat::known_answer_vectors::<C, M, F, PCS, RV32IJoltVM>(io_device, trace, preprocessing)
+    }
+
     fn save_linker(&self) {
         let linker_path = PathBuf::from_str(&self.linker_path()).unwrap();
         if let Some(parent) = linker_path.parent() {
             fs::create_dir_all(parent).expect("could not create linker file");
         }
 
-        let linker_script = LINKER_SCRIPT_TEMPLATE
-            .replace("{MEMORY_SIZE}", &self.memory_size.to_string())
-            .replace("{STACK_SIZE}", &self.stack_size.to_string());
-
         let mut file = File::create(linker_path).expect("could not create linker file");
-        file.write_all(linker_script.as_bytes())
+        file.write_all(self.linker_script().as_bytes())
             .expect("could not save linker");
     }
 
+    /// Links the static library `archive` of a test build into the ELF `elf`, with
+    /// the linker of the toolchain selected by `envs`. Unlike guest binaries, test
+    /// ELFs keep their symbols, for backtraces of failed tests.
+    fn link(&self, archive: &str, elf: &str, envs: &[(&str, String)]) {
+        let rustc = |args: &[&str]| {
+            let output = Command::new("rustc")
+                .envs(envs.iter().cloned())
+                .args(args)
+                .output()
+                .expect("failed to run rustc");
+            String::from_utf8(output.stdout).unwrap()
+        };
+        let sysroot = rustc(&["--print", "sysroot"]);
+        let version = rustc(&["--version", "--verbose"]);
+        let host = version
+            .lines()
+            .find_map(|line| line.strip_prefix("host: "))
+            .expect("could not determine the host target");
+        let lld = PathBuf::from(sysroot.trim())
+            .join("lib/rustlib")
+            .join(host)
+            .join("bin/rust-lld");
+
+        let output = Command::new(lld)
+            .args([
+                "-flavor",
+                "gnu",
+                "-T",
+                &self.linker_path(),
+                "--gc-sections",
+                "-u",
+                "_start",
+                "-e",
+                "_start",
+                "-o",
+                elf,
+                archive,
+            ])
+            .output()
+            .expect("failed to link guest tests");
+        if !output.status.success() {
+            io::stderr().write_all(&output.stderr).unwrap();
+            panic!("failed to link guest tests");
+        }
+    }
+
+    fn linker_script(&self) -> String {
+        LINKER_SCRIPT_TEMPLATE
+            .replace("{MEMORY_SIZE}", &self.memory_size.to_string())
+            .replace("{STACK_SIZE}", &self.stack_size.to_string())
+    }
+
     fn linker_path(&self) -> String {
         format!("/tmp/jolt-guest-linkers/{}.ld", self.guest)
     }
 }
 
+/// Result of running a guest test with `Program::run_test`
+#[derive(Clone, Debug)]
+pub struct TestOutcome {
+    pub cycles: usize,
+    /// The test's panic, if it failed
+    pub panic: Option<GuestPanic>,
+}
+
+impl TestOutcome {
+    pub fn passed(&self) -> bool {
+        self.panic.is_none()
+    }
+}
+
+/// Loads a trace written by `Program::save_trace`.
+pub fn load_trace(path: &PathBuf) -> io::Result<(JoltDevice, Vec<JoltTraceStep<RV32I>>)> {
+    let file = io::BufReader::new(File::open(path)?);
+    let (raw_trace, io_device) = tracer::trace_format::read_trace(file)?;
+    Ok((io_device, process_trace(raw_trace)))
+}
+
+/// Expands virtual sequences and converts raw trace rows into Jolt trace steps
+fn process_trace(raw_trace: Vec<RVTraceRow>) -> Vec<JoltTraceStep<RV32I>> {
+    raw_trace
+        .into_par_iter()
+        .flat_map(|row| match row.instruction.opcode {
+            tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::REM => REMInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::SH => SHInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::SB => SBInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::LB => LBInstruction::<32>::virtual_trace(row),
+            tracer::RV32IM::LH => LHInstruction::<32>::virtual_trace(row),
+            _ => vec![row],
+        })
+        .map(|row| {
+            let instruction_lookup = if let Ok(jolt_instruction) = RV32I::try_from(&row) {
+                Some(jolt_instruction)
+            } else {
+                // Instruction does not use lookups
+                None
+            };
+
+            JoltTraceStep {
+                instruction_lookup,
+                bytecode_row: BytecodeRow::from_instruction::<RV32I>(&row.instruction),
+                memory_ops: (&row).into(),
+                circuit_flags: row.instruction.to_circuit_flags(),
+                precompile_input: row.precompile_input.map(Box::new),
+            }
+        })
+        .collect()
+}
+
+/// `--remap-path-prefix` flags replacing the paths of the building machine that
+/// end up in the guest's ELF (e.g. in panic locations) with fixed ones: those of
+/// the workspace, of the sources of its dependencies and of the toolchains.
+fn remap_path_prefixes() -> Vec<String> {
+    let workspace = Command::new("cargo")
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            let manifest = PathBuf::from(String::from_utf8(output.stdout).ok()?.trim());
+            manifest.parent().map(Path::to_path_buf)
+        });
+    let home = dirs::home_dir();
+    let home_or = |var: &str, dir: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(dir)))
+    };
+    [
+        (workspace, "/guest"),
+        (home_or("CARGO_HOME", ".cargo"), "/cargo"),
+        (home_or("RUSTUP_HOME", ".rustup"), "/rustup"),
+        (home.as_ref().map(|home| home.join(".jolt")), "/jolt"),
+    ]
+    .into_iter()
+    .filter_map(|(path, to)| Some(format!("--remap-path-prefix={}={}", path?.display(), to)))
+    .collect()
+}
+
+// Input sections are sorted by name, so that the layout does not depend on the
+// order in which the linker receives them
 const LINKER_SCRIPT_TEMPLATE: &str = r#"
 MEMORY {
   program (rwx) : ORIGIN = 0x80000000, LENGTH = {MEMORY_SIZE}
@@ -275,14 +1023,22 @@ SECTIONS {
 
   .text : {
     *(.text)
+    *(SORT_BY_NAME(.text.*))
+  } > program
+
+  .rodata : {
+    *(.rodata)
+    *(SORT_BY_NAME(.rodata.*))
   } > program
 
   .data : {
     *(.data)
+    *(SORT_BY_NAME(.data.*))
   } > program
 
   .bss : {
     *(.bss)
+    *(SORT_BY_NAME(.bss.*))
   } > program
 
   . = ALIGN(8);
@@ -0,0 +1,154 @@
+//! Client for remote provers served by `jolt serve`.
+
+use std::{fs, thread, time::Duration};
+
+use alloy_primitives::hex;
+use eyre::{bail, eyre, Result};
+use reqwest::{
+    blocking::{Client, Response},
+    StatusCode,
+};
+use serde_json::{json, Value};
+
+use super::Program;
+use crate::{
+    field::JoltField, jolt::vm::rv32i_vm::RV32IProofEnvelope,
+    poly::commitment::commitment_scheme::CommitmentScheme, utils::transcript::Transcript,
+};
+
+/// Set to the URL of a `jolt serve` server to prove remotely from the generated
+/// `prove_*` functions (see `RemoteProver::from_env`)
+pub const REMOTE_PROVER_ENV: &str = "JOLT_REMOTE_PROVER";
+
+/// Proves programs on a remote `jolt serve` server instead of locally. The
+/// server is sent the program's hash, inputs and advice, and the ELF and memory
+/// configuration the first time it proves the program. Hints (see
+/// `Program::set_hint`) are not available to remotely proven programs.
+///
+/// Remote proofs are made without committing to the program, like local ones,
+/// so they verify with the program's usual preprocessing.
+#[derive(Clone, Debug)]
+pub struct RemoteProver {
+    url: String,
+    client: Client,
+    poll_interval: Duration,
+}
+
+impl RemoteProver {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: Client::new(),
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// The remote prover at `JOLT_REMOTE_PROVER`, if set. The generated
+    /// `prove_*` functions use it when it is, so switching an application between
+    /// local and remote proving needs no code change.
+    pub fn from_env() -> Option<Self> {
+        std::env::var(REMOTE_PROVER_ENV)
+            .ok()
+            .filter(|url| !url.is_empty())
+            .map(|url| Self::new(&url))
+    }
+
+    /// How often to poll the server for the status of a job
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Proves the execution of `program` on its inputs remotely, blocking until
+    /// the proof is done.
+    pub fn prove<F, PCS, ProofTranscript>(
+        &self,
+        program: &mut Program,
+    ) -> Result<RV32IProofEnvelope<F, PCS, ProofTranscript>>
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let program_hash = program.program_preprocessing().program_hash;
+        let job = json!({
+            "program": hex::encode(program_hash),
+            "input": hex::encode(program.inputs()),
+            "advice": hex::encode(&program.advice),
+        });
+
+        let mut response = self.client.post(self.endpoint("jobs")).json(&job).send()?;
+        if response.status() == StatusCode::NOT_FOUND {
+            // The server does not know the program yet
+            let elf = fs::read(program.elf.as_ref().unwrap())?;
+            let query = format!(
+                "max_input_size={}&max_output_size={}&memory_size={}",
+                program.max_input_size, program.max_output_size, program.memory_size
+            );
+            check(
+                self.client
+                    .post(self.endpoint(&format!("programs?{}", query)))
+                    .body(elf)
+                    .send()?,
+            )?;
+            response = self.client.post(self.endpoint("jobs")).json(&job).send()?;
+        }
+        let id = check(response)?.json::<Value>()?["id"]
+            .as_u64()
+            .ok_or_else(|| eyre!("the remote prover returned no job id"))?;
+
+        loop {
+            let status: Value = check(
+                self.client
+                    .get(self.endpoint(&format!("jobs/{}", id)))
+                    .send()?,
+            )?
+            .json()?;
+            match status["status"].as_str() {
+                Some("done") => break,
+                Some("failed") => bail!(
+                    "remote proving failed: {}",
+                    status["error"].as_str().unwrap_or("unknown error")
+                ),
+                Some(_) => thread::sleep(self.poll_interval),
+                None => bail!("unexpected job status from the remote prover: {}", status),
+            }
+        }
+        let proof = check(
+            self.client
+                .get(self.endpoint(&format!("jobs/{}/proof", id)))
+                .send()?,
+        )?
+        .bytes()?;
+        // The server keeps finished jobs until they are discarded
+        let _ = self
+            .client
+            .delete(self.endpoint(&format!("jobs/{}", id)))
+            .send();
+
+        let envelope =
+            RV32IProofEnvelope::<F, PCS, ProofTranscript>::deserialize_from_bytes(&proof)?;
+        if envelope.header.program_hash != program_hash {
+            bail!("the remote prover proved a different program");
+        }
+        Ok(envelope)
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.url, path)
+    }
+}
+
+/// `response`, if it succeeded, otherwise the server's error
+fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let error = response
+        .json::<Value>()
+        .ok()
+        .and_then(|body| body["error"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    bail!("the remote prover returned {}: {}", status, error)
+}
@@ -2,7 +2,8 @@ use std::{
     fs::{self, read_to_string, File},
     future::Future,
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    process::Command,
 };
 
 use dirs::home_dir;
@@ -16,23 +17,51 @@ const TOOLCHAIN_TAG: &str = include_str!("../../../guest-toolchain-tag");
 const DOWNLOAD_RETRIES: usize = 5;
 const DELAY_BASE_MS: u64 = 500;
 
+/// Target (and rustup toolchain) of guests built with the standard library
+pub const STD_TARGET: &str = "riscv32im-jolt-zkvm-elf";
+/// Target of guests built without the standard library
+pub const NO_STD_TARGET: &str = "riscv32im-unknown-none-elf";
+
+/// Pins the release of the std toolchain to install, instead of the one this
+/// version of Jolt was released with (see `guest-toolchain-tag`)
+pub const TOOLCHAIN_TAG_ENV: &str = "JOLT_TOOLCHAIN_TAG";
+/// Path of a toolchain archive (as downloaded from the release) to install
+/// instead of downloading it, e.g. on hosts without network access
+pub const TOOLCHAIN_ARCHIVE_ENV: &str = "JOLT_TOOLCHAIN_ARCHIVE";
+/// If set, missing toolchains are an error rather than downloaded
+pub const OFFLINE_ENV: &str = "JOLT_OFFLINE";
+
 #[cfg(not(target_arch = "wasm32"))]
 /// Installs the toolchain if it is not already
 pub fn install_toolchain() -> Result<()> {
     if !has_toolchain() {
-        let client = Client::builder().user_agent("Mozilla/5.0").build()?;
-        let toolchain_url = toolchain_url();
-
-        let rt = Runtime::new().unwrap();
-        rt.block_on(retry_times(DOWNLOAD_RETRIES, DELAY_BASE_MS, || {
-            download_toolchain(&client, &toolchain_url)
-        }))?;
+        let tag = toolchain_tag();
+        if let Some(archive) = std::env::var_os(TOOLCHAIN_ARCHIVE_ENV) {
+            fs::create_dir_all(jolt_dir())?;
+            fs::copy(&archive, jolt_dir().join("rust-toolchain.tar.gz"))
+                .map_err(|err| eyre!("could not read {:?}: {}", archive, err))?;
+        } else if offline() {
+            bail!(
+                "the \"{STD_TARGET}\" toolchain ({tag}) is not installed, and {OFFLINE_ENV} is \
+                 set. Download {} and point {TOOLCHAIN_ARCHIVE_ENV} at it to install it offline.",
+                toolchain_url(&tag)
+            );
+        } else {
+            let client = Client::builder().user_agent("Mozilla/5.0").build()?;
+            let toolchain_url = toolchain_url(&tag);
+
+            let rt = Runtime::new().unwrap();
+            rt.block_on(retry_times(DOWNLOAD_RETRIES, DELAY_BASE_MS, || {
+                download_toolchain(&client, &toolchain_url)
+            }))?;
+        }
         unpack_toolchain()?;
         remove_archive()?;
         link_toolchain()?;
-        write_tag_file()?;
+        write_tag_file(&tag)?;
         println!(
-            "\"riscv32im-jolt-zkvm-elf\" toolchain installed successfully at {:?}",
+            "\"{STD_TARGET}\" toolchain {} installed successfully at {:?}",
+            tag,
             jolt_dir()
         );
     }
@@ -40,13 +69,109 @@ pub fn install_toolchain() -> Result<()> {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
+/// Installs the no-std target for the active toolchain if it is not already
 pub fn install_no_std_toolchain() -> Result<()> {
-    std::process::Command::new("rustup")
-        .args(["target", "add", "riscv32im-unknown-none-elf"])
-        .output()?;
+    if sysroot()?.join("lib/rustlib").join(NO_STD_TARGET).exists() {
+        return Ok(());
+    }
+    if offline() {
+        bail!(
+            "the \"{NO_STD_TARGET}\" target is not installed, and {OFFLINE_ENV} is set. \
+             Install it with `rustup target add {NO_STD_TARGET}`."
+        );
+    }
+    rustup(&["target", "add", NO_STD_TARGET])
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Installs what building guests for custom targets (see `Program::set_target_spec`)
+/// needs on top of the active toolchain: the sources of the standard library, which
+/// is built for the target with `-Zbuild-std`.
+pub fn install_target_spec_support(spec: &Path) -> Result<()> {
+    if !spec.exists() {
+        bail!("target specification {:?} does not exist", spec);
+    }
+    if sysroot()?.join("lib/rustlib/src/rust/library").exists() {
+        return Ok(());
+    }
+    if offline() {
+        bail!(
+            "building for {:?} needs the rust-src component, which is not installed, and \
+             {OFFLINE_ENV} is set. Install it with `rustup component add rust-src`.",
+            spec
+        );
+    }
+    rustup(&["component", "add", "rust-src"])
+}
+
+/// A hint on how to fix the failed guest build that printed `stderr`, if it
+/// failed because of a missing or mismatched toolchain.
+pub fn build_failure_hint(stderr: &str) -> Option<String> {
+    if stderr.contains(&format!("toolchain '{STD_TARGET}' is not installed"))
+        || stderr.contains("custom toolchain")
+    {
+        Some(format!(
+            "The \"{STD_TARGET}\" toolchain is missing or broken. Reinstall it with \
+             `jolt install-toolchain`."
+        ))
+    } else if stderr.contains("can't find crate for `core`")
+        || stderr.contains("target may not be installed")
+    {
+        Some(format!(
+            "The guest's target is not installed for the active toolchain. Run \
+             `rustup target add {NO_STD_TARGET}` (or `jolt install-toolchain` for std guests)."
+        ))
+    } else if stderr.contains("only accepted on the nightly") {
+        Some("Building for a custom target specification needs a nightly toolchain.".to_string())
+    } else if stderr.contains("linker `rust-lld` not found")
+        || stderr.contains("error: linking with")
+    {
+        Some(
+            "The guest failed to link. Check that the installed toolchains match this version \
+             of Jolt (`jolt install-toolchain`), and that the guest's memory and stack sizes \
+             fit its program."
+                .to_string(),
+        )
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn rustup(args: &[&str]) -> Result<()> {
+    let output = Command::new("rustup")
+        .args(args)
+        .output()
+        .map_err(|err| eyre!("could not run `rustup {}`: {}", args.join(" "), err))?;
+    if !output.status.success() {
+        bail!(
+            "`rustup {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
     Ok(())
 }
 
+/// Sysroot of the active toolchain
+fn sysroot() -> Result<PathBuf> {
+    let output = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+fn offline() -> bool {
+    std::env::var_os(OFFLINE_ENV).is_some()
+}
+
+fn toolchain_tag() -> String {
+    std::env::var(TOOLCHAIN_TAG_ENV).unwrap_or_else(|_| TOOLCHAIN_TAG.trim().to_string())
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 async fn retry_times<F, T, E>(times: usize, base_ms: u64, f: F) -> Result<T>
 where
@@ -72,22 +197,17 @@ fn delay_timeout(i: usize, base_ms: u64) -> u64 {
     rand::random::<u64>() % timeout
 }
 
-fn write_tag_file() -> Result<()> {
+fn write_tag_file(tag: &str) -> Result<()> {
     let tag_path = toolchain_tag_file();
     let mut tag_file = File::create(tag_path)?;
-    tag_file.write_all(TOOLCHAIN_TAG.as_bytes())?;
+    tag_file.write_all(tag.as_bytes())?;
     Ok(())
 }
 
 fn link_toolchain() -> Result<()> {
     let link_path = jolt_dir().join("rust/build/host/stage2");
-    let output = std::process::Command::new("rustup")
-        .args([
-            "toolchain",
-            "link",
-            "riscv32im-jolt-zkvm-elf",
-            link_path.to_str().unwrap(),
-        ])
+    let output = Command::new("rustup")
+        .args(["toolchain", "link", STD_TARGET, link_path.to_str().unwrap()])
         .output()?;
 
     if !output.status.success() {
@@ -98,7 +218,7 @@ fn link_toolchain() -> Result<()> {
 }
 
 fn unpack_toolchain() -> Result<()> {
-    let output = std::process::Command::new("tar")
+    let output = Command::new("tar")
         .args(["-xzf", "rust-toolchain.tar.gz"])
         .current_dir(jolt_dir())
         .output()?;
@@ -157,35 +277,35 @@ fn remove_archive() -> Result<()> {
     Ok(())
 }
 
-fn toolchain_url() -> String {
+fn toolchain_url(tag: &str) -> String {
     let target = target_lexicon::HOST;
     format!(
         "https://github.com/a16z/rust/releases/download/{}/rust-toolchain-{}.tar.gz",
-        TOOLCHAIN_TAG, target,
+        tag, target,
     )
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 pub fn uninstall_no_std_toolchain() -> Result<()> {
-    std::process::Command::new("rustup")
-        .args(["target", "remove", "riscv32im-unknown-none-elf"])
+    Command::new("rustup")
+        .args(["target", "remove", NO_STD_TARGET])
         .output()?;
 
-    println!("\"riscv32im-unknown-none-elf\" toolchain uninstalled successfully");
+    println!("\"{NO_STD_TARGET}\" toolchain uninstalled successfully");
     Ok(())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 /// Uninstalls the toolchain if it is already installed
 pub fn uninstall_toolchain() -> Result<()> {
-    if !has_toolchain() {
+    if !toolchain_tag_file().exists() {
         println!("Toolchain is not installed");
         return Ok(());
     }
 
     // Remove the linked toolchain from rustup
-    let output = std::process::Command::new("rustup")
-        .args(["toolchain", "remove", "riscv32im-jolt-zkvm-elf"])
+    let output = Command::new("rustup")
+        .args(["toolchain", "remove", STD_TARGET])
         .output()?;
 
     if !output.status.success() {
@@ -210,17 +330,22 @@ pub fn uninstall_toolchain() -> Result<()> {
         fs::remove_file(&tag_file)?;
     }
 
-    println!("\"riscv32im-jolt-zkvm-elf\" toolchain uninstalled successfully");
+    println!("\"{STD_TARGET}\" toolchain uninstalled successfully");
     Ok(())
 }
 
+/// Whether the pinned release of the std toolchain is installed and still linked
 fn has_toolchain() -> bool {
     let tag_path = toolchain_tag_file();
-    if let Ok(tag) = read_to_string(tag_path) {
-        tag == TOOLCHAIN_TAG
-    } else {
-        false
-    }
+    let Ok(tag) = read_to_string(tag_path) else {
+        return false;
+    };
+    tag.trim() == toolchain_tag()
+        && jolt_dir().join("rust/build/host/stage2").exists()
+        && Command::new("rustup")
+            .args(["toolchain", "list"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(STD_TARGET))
 }
 
 fn jolt_dir() -> PathBuf {
@@ -1,5 +1,7 @@
 use ark_ff::Zero;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use rand::rngs::StdRng;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
@@ -19,9 +21,10 @@ use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvalu
 use common::constants::{BYTES_PER_INSTRUCTION, RAM_START_ADDRESS};
 use common::rv_trace::ELFInstruction;
 
-use rayon::prelude::*;
+use crate::utils::parallel::prelude::*;
 
 use super::{JoltPolynomials, JoltTraceStep};
+use crate::utils::errors::ProofVerifyError;
 use crate::utils::transcript::Transcript;
 
 use crate::{
@@ -46,6 +49,10 @@ pub struct BytecodeStuff<T: CanonicalSerialize + CanonicalDeserialize> {
     pub(crate) t_final: T,
     a_init_final: VerifierComputedOpening<T>,
     v_init_final: VerifierComputedOpening<[T; 6]>,
+    /// Committed copy of `v_init_final`, if the program is committed to (see
+    /// `ProgramCommitment`). Verifiers that only know the program by its
+    /// commitment check these openings instead of computing `v_init_final`.
+    pub(crate) v_program: Option<[T; 6]>,
 }
 
 /// Note –– F: JoltField bound is not enforced.
@@ -69,6 +76,12 @@ pub type BytecodeCommitments<PCS: CommitmentScheme<ProofTranscript>, ProofTransc
 impl<F: JoltField, T: CanonicalSerialize + CanonicalDeserialize + Default>
     Initializable<T, BytecodePreprocessing<F>> for BytecodeStuff<T>
 {
+    fn initialize(preprocessing: &BytecodePreprocessing<F>) -> Self {
+        Self {
+            v_program: preprocessing.commit_program.then(Default::default),
+            ..Default::default()
+        }
+    }
 }
 
 impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
@@ -82,7 +95,9 @@ impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
     }
 
     fn init_final_values(&self) -> Vec<&T> {
-        vec![&self.t_final]
+        let mut values = vec![&self.t_final];
+        values.extend(self.v_program.iter().flatten());
+        values
     }
 
     fn read_write_values_mut(&mut self) -> Vec<&mut T> {
@@ -93,7 +108,9 @@ impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
     }
 
     fn init_final_values_mut(&mut self) -> Vec<&mut T> {
-        vec![&mut self.t_final]
+        let mut values = vec![&mut self.t_final];
+        values.extend(self.v_program.iter_mut().flatten());
+        values
     }
 }
 
@@ -122,6 +139,63 @@ pub struct BytecodeRow {
     virtual_sequence_remaining: Option<usize>,
 }
 
+// `imm` is signed, which ark-serialize has no encoding for, so it is serialized as
+// its two's complement `u64`
+impl CanonicalSerialize for BytecodeRow {
+    fn serialize_with_mode<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.address.serialize_with_mode(&mut writer, compress)?;
+        self.bitflags.serialize_with_mode(&mut writer, compress)?;
+        self.rd.serialize_with_mode(&mut writer, compress)?;
+        self.rs1.serialize_with_mode(&mut writer, compress)?;
+        self.rs2.serialize_with_mode(&mut writer, compress)?;
+        (self.imm as u64).serialize_with_mode(&mut writer, compress)?;
+        self.virtual_sequence_remaining
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.address.serialized_size(compress)
+            + self.bitflags.serialized_size(compress)
+            + self.rd.serialized_size(compress)
+            + self.rs1.serialized_size(compress)
+            + self.rs2.serialized_size(compress)
+            + (self.imm as u64).serialized_size(compress)
+            + self.virtual_sequence_remaining.serialized_size(compress)
+    }
+}
+
+impl Valid for BytecodeRow {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for BytecodeRow {
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            address: usize::deserialize_with_mode(&mut reader, compress, validate)?,
+            bitflags: u64::deserialize_with_mode(&mut reader, compress, validate)?,
+            rd: u8::deserialize_with_mode(&mut reader, compress, validate)?,
+            rs1: u8::deserialize_with_mode(&mut reader, compress, validate)?,
+            rs2: u8::deserialize_with_mode(&mut reader, compress, validate)?,
+            imm: u64::deserialize_with_mode(&mut reader, compress, validate)? as i64,
+            virtual_sequence_remaining: Option::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+        })
+    }
+}
+
 impl BytecodeRow {
     pub fn new(address: usize, bitflags: u64, rd: u8, rs1: u8, rs2: u8, imm: i64) -> Self {
         Self {
@@ -232,6 +306,8 @@ pub struct BytecodePreprocessing<F: JoltField> {
     /// is the one used to keep track of the next (potentially virtual) instruction to execute.
     /// Key: (ELF address, virtual sequence index or 0)
     virtual_address_map: BTreeMap<(usize, usize), usize>,
+    /// Whether proofs also commit to `v_init_final` (see `ProgramCommitment`).
+    pub(crate) commit_program: bool,
 }
 
 impl<F: JoltField> BytecodePreprocessing<F> {
@@ -295,8 +371,30 @@ impl<F: JoltField> BytecodePreprocessing<F> {
             v_init_final,
             code_size,
             virtual_address_map,
+            commit_program: false,
         }
     }
+
+    /// Preprocessing for a verifier that only knows the program by its
+    /// `ProgramCommitment`: it holds no bytecode, and `v_init_final` is opened
+    /// from its commitment instead.
+    pub fn committed(code_size: usize) -> Self {
+        Self {
+            v_init_final: Default::default(),
+            code_size,
+            virtual_address_map: BTreeMap::new(),
+            commit_program: true,
+        }
+    }
+
+    /// The polynomials committed to by `ProgramCommitment`.
+    pub(crate) fn v_init_final(&self) -> &[MultilinearPolynomial<F>; 6] {
+        &self.v_init_final
+    }
+
+    pub(crate) fn code_size(&self) -> usize {
+        self.code_size
+    }
 }
 
 impl<F, PCS, ProofTranscript> BytecodeProof<F, PCS, ProofTranscript>
@@ -450,6 +548,9 @@ where
             t_final,
             a_init_final: None,
             v_init_final: None,
+            v_program: preprocessing
+                .commit_program
+                .then(|| preprocessing.v_init_final.clone()),
         }
     }
 
@@ -623,19 +724,24 @@ where
         preprocessing: &Self::Preprocessing,
         _r_read_write: &[F],
         r_init_final: &[F],
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         openings.a_init_final =
             Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
 
-        openings.v_init_final = Some(
-            MultilinearPolynomial::batch_evaluate(
-                &preprocessing.v_init_final.iter().collect::<Vec<_>>(),
-                r_init_final,
-            )
-            .0
-            .try_into()
-            .unwrap(),
-        );
+        openings.v_init_final = match openings.v_program {
+            // Already checked against the program commitment by the opening proof
+            Some(v_program) => Some(v_program),
+            None => Some(
+                MultilinearPolynomial::batch_evaluate(
+                    &preprocessing.v_init_final.iter().collect::<Vec<_>>(),
+                    r_init_final,
+                )
+                .0
+                .try_into()
+                .unwrap(),
+            ),
+        };
+        Ok(())
     }
 
     fn read_tuples(
@@ -730,6 +836,7 @@ mod tests {
             memory_ops: [MemoryOp::noop_read(); MEMORY_OPS_PER_INSTRUCTION],
             bytecode_row,
             circuit_flags: [false; NUM_CIRCUIT_FLAGS],
+            precompile_input: None,
         }
     }
 
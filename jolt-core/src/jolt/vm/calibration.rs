@@ -0,0 +1,282 @@
+//! Tuning the prover to the machine it runs on. The MSM window sizes, how finely
+//! parallel loops are chunked and the chunk length of continuations were tuned
+//! on one reference machine; `CalibrationProfile::measure` times the prover's
+//! kernels on this one instead, and the profile it returns picks them from the
+//! measurements. Measuring takes a few seconds, so profiles are saved and
+//! loaded again, by default from the user's cache directory.
+
+use std::hint::black_box;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_std::{test_rng, UniformRand, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::msm::{self, VariableBaseMSM};
+use crate::utils::parallel::{self, prelude::*};
+use crate::utils::thread::{self, num_parallel_chunks};
+
+/// Number of bases of the MSMs timed
+const MSM_SIZE: usize = 1 << 16;
+/// Window offsets tried, see `msm::set_window_offset`
+const WINDOW_OFFSETS: [usize; 5] = [0, 1, 2, 3, 4];
+/// Chunks per thread tried, see `thread::set_chunks_per_thread`
+const CHUNKS_PER_THREAD: [usize; 5] = [1, 2, 4, 8, 16];
+
+/// Measurements of the prover's kernels on one machine, and the settings
+/// picked from them. Applied by `install`, or by a `ProverConfig` built
+/// `with_calibration`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationProfile {
+    /// Threads the parallel kernels were timed on
+    pub threads: usize,
+    /// Nanoseconds per BN254 scalar field multiplication, on one thread
+    pub field_mul_ns: f64,
+    /// Nanoseconds per base of a 2^16-base G1 MSM with the fastest window size
+    pub msm_base_ns: f64,
+    /// Nanoseconds per BN254 pairing
+    pub pairing_ns: f64,
+    /// The fastest MSM window offset, see `msm::set_window_offset`
+    pub msm_window_offset: usize,
+    /// The fastest chunking of parallel loops, see `thread::set_chunks_per_thread`
+    pub chunks_per_thread: usize,
+    /// Physical memory of the machine in bytes, if known
+    pub memory: Option<usize>,
+}
+
+impl CalibrationProfile {
+    /// Times the prover's kernels on the current thread pool. Leaves the
+    /// process's settings as they were.
+    #[tracing::instrument(skip_all, name = "CalibrationProfile::measure")]
+    pub fn measure() -> Self {
+        let (msm_window_offset, msm_time) = fastest(&WINDOW_OFFSETS, time_msm);
+        let (chunks_per_thread, _) = fastest(&CHUNKS_PER_THREAD, time_sparse_loop);
+        Self {
+            threads: parallel::current_num_threads(),
+            field_mul_ns: time_field_muls(),
+            msm_base_ns: nanos(msm_time) / MSM_SIZE as f64,
+            pairing_ns: time_pairings(),
+            msm_window_offset,
+            chunks_per_thread,
+            memory: physical_memory(),
+        }
+    }
+
+    /// Applies the profile's settings to this process
+    pub fn install(&self) {
+        msm::set_window_offset(self.msm_window_offset);
+        thread::set_chunks_per_thread(self.chunks_per_thread);
+    }
+
+    /// The memory a proof may use on this machine, leaving a quarter of it to the
+    /// rest of the system. Picks the chunk length of continuations proven with
+    /// a calibrated `ProverConfig` that sets neither a chunk size nor a budget.
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory.map(|memory| memory / 4 * 3)
+    }
+
+    /// `calibration.json` in the user's cache directory, if there is one
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("jolt").join("calibration.json"))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    /// The profile saved at `default_path`, or a new one, measured and saved
+    /// there. Profiles measured on another number of threads are measured again.
+    pub fn load_or_measure() -> Self {
+        let path = Self::default_path();
+        if let Some(profile) = path.as_deref().and_then(|path| Self::load(path).ok()) {
+            if profile.threads == parallel::current_num_threads() {
+                return profile;
+            }
+        }
+        let profile = Self::measure();
+        if let Some(path) = path {
+            if let Err(err) = profile.save(&path) {
+                tracing::warn!("failed to save the calibration to {:?}: {}", path, err);
+            }
+        }
+        profile
+    }
+}
+
+/// The setting among `settings` for which `time` is the shortest, and that time
+fn fastest(settings: &[usize], time: impl Fn(usize) -> Duration) -> (usize, Duration) {
+    settings
+        .iter()
+        .map(|&setting| (setting, time(setting)))
+        .min_by_key(|(_, duration)| *duration)
+        .unwrap()
+}
+
+/// The shortest of three runs of `op`, which discounts one-off noise such as
+/// page faults on first use
+fn best_of_three(mut op: impl FnMut()) -> Duration {
+    (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            op();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn nanos(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e9
+}
+
+fn time_field_muls() -> f64 {
+    const MULS: usize = 1 << 20;
+    let mut rng = test_rng();
+    let x = Fr::rand(&mut rng);
+    let time = best_of_three(|| {
+        let mut acc = black_box(x);
+        for _ in 0..MULS {
+            acc *= x;
+        }
+        black_box(acc);
+    });
+    nanos(time) / MULS as f64
+}
+
+fn time_msm(window_offset: usize) -> Duration {
+    let mut rng = test_rng();
+    // Multiples of one point, which are much quicker to generate than random
+    // points and as good for timing
+    let point = G1Projective::rand(&mut rng);
+    let bases: Vec<G1Affine> = {
+        let mut multiples = Vec::with_capacity(MSM_SIZE);
+        let mut multiple = point;
+        for _ in 0..MSM_SIZE {
+            multiples.push(multiple);
+            multiple += point;
+        }
+        G1Projective::normalize_batch(&multiples)
+    };
+    let scalars: Vec<Fr> = (0..MSM_SIZE).map(|_| Fr::rand(&mut rng)).collect();
+
+    let previous = msm::window_offset();
+    msm::set_window_offset(window_offset);
+    let time = best_of_three(|| {
+        black_box(G1Projective::msm_field_elements(&bases, None, &scalars, None, false).unwrap());
+    });
+    msm::set_window_offset(previous);
+    time
+}
+
+fn time_pairings() -> f64 {
+    const PAIRINGS: usize = 8;
+    let g1 = G1Affine::generator();
+    let g2 = G2Affine::generator();
+    let time = best_of_three(|| {
+        for _ in 0..PAIRINGS {
+            black_box(Bn254::pairing(black_box(g1), black_box(g2)));
+        }
+    });
+    nanos(time) / PAIRINGS as f64
+}
+
+/// Times a parallel loop over rows of uneven density, like the R1CS witness's,
+/// split into `chunks_per_thread` chunks per thread
+fn time_sparse_loop(chunks_per_thread: usize) -> Duration {
+    const ROWS: usize = 1 << 20;
+    let mut rng = test_rng();
+    let x = Fr::rand(&mut rng);
+    // Dense and empty runs of rows, as in traces alternating between kinds of
+    // instructions
+    let rows: Vec<Fr> = (0..ROWS)
+        .map(|i| {
+            if (i >> 12).count_ones() % 3 == 0 {
+                x
+            } else {
+                Fr::zero()
+            }
+        })
+        .collect();
+
+    let previous = thread::chunks_per_thread();
+    thread::set_chunks_per_thread(chunks_per_thread);
+    let time = best_of_three(|| {
+        let chunk_size = ROWS.div_ceil(num_parallel_chunks());
+        let sum: Fr = rows
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .filter(|row| !row.is_zero())
+                    .map(|row| row.square() * row)
+                    .sum::<Fr>()
+            })
+            .sum();
+        black_box(sum);
+    });
+    thread::set_chunks_per_thread(previous);
+    time
+}
+
+/// `MemTotal` of `/proc/meminfo`
+#[cfg(target_os = "linux")]
+fn physical_memory() -> Option<usize> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kilobytes = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<usize>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn physical_memory() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_profile() {
+        let dir = std::env::temp_dir().join(format!("jolt-calibration-{}", std::process::id()));
+        let profile = CalibrationProfile {
+            threads: 8,
+            field_mul_ns: 20.0,
+            msm_base_ns: 700.0,
+            pairing_ns: 1e6,
+            msm_window_offset: 3,
+            chunks_per_thread: 8,
+            memory: Some(1 << 30),
+        };
+        let path = dir.join("calibration.json");
+        profile.save(&path).unwrap();
+        assert_eq!(CalibrationProfile::load(&path).unwrap(), profile);
+        assert_eq!(profile.memory_budget(), Some(3 << 28));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn fastest_setting() {
+        let (setting, _) = fastest(&[1, 2, 3], |setting| {
+            Duration::from_millis(setting.abs_diff(2) as u64 + 1)
+        });
+        assert_eq!(setting, 2);
+    }
+}
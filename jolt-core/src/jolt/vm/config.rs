@@ -0,0 +1,454 @@
+#[cfg(feature = "parallel")]
+use std::collections::HashMap;
+use std::path::PathBuf;
+#[cfg(feature = "parallel")]
+use std::sync::{Arc, LazyLock, Mutex};
+
+#[cfg(feature = "parallel")]
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::commitment::setup_cache::SetupCache;
+use crate::utils::errors::{ConfigError, SecurityError};
+use crate::utils::transcript::Transcript;
+
+use super::calibration::CalibrationProfile;
+use super::security::SecurityConfig;
+
+/// A polynomial commitment scheme a `ProverConfig` can require. The scheme is
+/// still chosen by the `PCS` type parameter; the config only checks it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PcsBackend {
+    HyperKZG,
+    Zeromorph,
+    Mock,
+}
+
+impl PcsBackend {
+    /// `CommitmentScheme::protocol_name` of the scheme
+    pub fn protocol_name(&self) -> &'static [u8] {
+        match self {
+            Self::HyperKZG => b"hyperkzg",
+            Self::Zeromorph => b"zeromorph",
+            Self::Mock => b"mock_commit",
+        }
+    }
+}
+
+/// Scheduling priority of the prover's threads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ThreadPriority {
+    #[default]
+    Normal,
+    /// Lower than the embedding application's own threads (a nice value of 10
+    /// on Linux), so that they are not starved by proving. Has no effect on
+    /// other platforms.
+    Low,
+}
+
+/// The prover's thread pools, by size and priority. Pools are kept for the
+/// life of the process, so that proofs with the same config share one and the
+/// threads are not spawned again for each proof.
+#[cfg(feature = "parallel")]
+static THREAD_POOLS: LazyLock<Mutex<HashMap<(usize, ThreadPriority), Arc<ThreadPool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How to preprocess and prove: the sizes the PCS setup supports, the
+/// resources the prover may use and the security its proofs must meet. Built
+/// with `ProverConfig::builder`, which validates it, and passed to
+/// `Jolt::preprocess_with_config`, `Jolt::verifier_key_with_config`,
+/// `Jolt::prove_with_config` and `Program::prove_with_config`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProverConfig {
+    max_bytecode_size: usize,
+    max_memory_address: usize,
+    max_trace_length: usize,
+    setup_cache: Option<SetupCache>,
+    threads: Option<usize>,
+    priority: ThreadPriority,
+    memory_budget: Option<usize>,
+    chunk_size: Option<usize>,
+    pcs: Option<PcsBackend>,
+    zk: bool,
+    security: SecurityConfig,
+    calibration: Option<CalibrationProfile>,
+}
+
+impl Default for ProverConfig {
+    fn default() -> Self {
+        Self {
+            max_bytecode_size: 1 << 20,
+            max_memory_address: 1 << 20,
+            max_trace_length: 1 << 24,
+            setup_cache: SetupCache::from_env(),
+            threads: None,
+            priority: ThreadPriority::Normal,
+            memory_budget: None,
+            chunk_size: None,
+            pcs: None,
+            zk: false,
+            security: SecurityConfig::default(),
+            calibration: None,
+        }
+    }
+}
+
+impl ProverConfig {
+    /// A builder starting from the default config: a PCS setup for up to 2^20
+    /// instructions and words of memory and 2^24 steps, cached in
+    /// `JOLT_SETUP_CACHE` if set, all cores, no memory budget and the default
+    /// `SecurityConfig`
+    pub fn builder() -> ProverConfigBuilder {
+        ProverConfigBuilder(Self::default())
+    }
+
+    pub fn max_bytecode_size(&self) -> usize {
+        self.max_bytecode_size
+    }
+
+    pub fn max_memory_address(&self) -> usize {
+        self.max_memory_address
+    }
+
+    pub fn max_trace_length(&self) -> usize {
+        self.max_trace_length
+    }
+
+    /// Where the PCS setup is loaded from, or computed and stored to, by
+    /// `Jolt::preprocess_with_config`
+    pub fn setup_cache(&self) -> Option<&SetupCache> {
+        self.setup_cache.as_ref()
+    }
+
+    /// The number of threads the prover runs on. If neither this nor the
+    /// priority is set, the prover runs on rayon's global pool. Without the
+    /// `parallel` feature, the prover runs on the caller's thread whatever this is.
+    pub fn threads(&self) -> Option<usize> {
+        self.threads
+    }
+
+    pub fn priority(&self) -> ThreadPriority {
+        self.priority
+    }
+
+    /// The most memory, in bytes, the prover is estimated to use (see
+    /// `CostReport::prover_memory`)
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    /// The size of the chunks `Program::prove_with_config` proves the trace in
+    pub fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    pub fn pcs(&self) -> Option<PcsBackend> {
+        self.pcs
+    }
+
+    pub fn zk(&self) -> bool {
+        self.zk
+    }
+
+    pub fn security(&self) -> &SecurityConfig {
+        &self.security
+    }
+
+    /// The measurements of this machine the prover is tuned to, if any
+    pub fn calibration(&self) -> Option<&CalibrationProfile> {
+        self.calibration.as_ref()
+    }
+
+    /// Runs `op` on the prover's own thread pool of `threads` threads (or one
+    /// per core) at `priority`, unless both are left as the default, in which
+    /// case it runs on the caller's pool. Rayon work spawned by `op`, i.e. all
+    /// of the prover's, stays on that pool, so the global pool remains free for
+    /// the embedding application. Applies the calibration first, if set.
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        if let Some(calibration) = &self.calibration {
+            calibration.install();
+        }
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = self.thread_pool() {
+            return pool.install(op);
+        }
+        op()
+    }
+
+    /// The thread pool `install` runs on, built on first use
+    #[cfg(feature = "parallel")]
+    pub fn thread_pool(&self) -> Option<Arc<ThreadPool>> {
+        if self.threads.is_none() && self.priority == ThreadPriority::Normal {
+            return None;
+        }
+        let threads = self.threads.unwrap_or_else(num_cpus);
+        let priority = self.priority;
+        let mut pools = THREAD_POOLS.lock().unwrap();
+        let pool = pools.entry((threads, priority)).or_insert_with(|| {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name(|i| format!("jolt-prover-{}", i))
+                .start_handler(move |_| set_thread_priority(priority))
+                .build()
+                .expect("failed to build the prover's thread pool");
+            Arc::new(pool)
+        });
+        Some(pool.clone())
+    }
+
+    /// Fails if the config requires a different commitment scheme than `PCS`
+    pub fn check_pcs<PCS, ProofTranscript>(&self) -> Result<(), ConfigError>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        match self.pcs {
+            Some(pcs) if pcs.protocol_name() != PCS::protocol_name() => {
+                Err(ConfigError::PcsMismatch(
+                    String::from_utf8_lossy(pcs.protocol_name()).into_owned(),
+                    String::from_utf8_lossy(PCS::protocol_name()).into_owned(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn num_cpus() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Sets the scheduling priority of the current thread
+#[cfg(all(feature = "parallel", target_os = "linux"))]
+fn set_thread_priority(priority: ThreadPriority) {
+    extern "C" {
+        fn setpriority(which: i32, who: u32, prio: i32) -> i32;
+    }
+    const PRIO_PROCESS: i32 = 0;
+    if priority == ThreadPriority::Low {
+        // On Linux, the nice value is per thread, and `who = 0` is the caller.
+        // Failing to lower the priority is harmless, so the result is ignored.
+        unsafe { setpriority(PRIO_PROCESS, 0, 10) };
+    }
+}
+
+#[cfg(all(feature = "parallel", not(target_os = "linux")))]
+fn set_thread_priority(_priority: ThreadPriority) {}
+
+/// See `ProverConfig::builder`
+#[derive(Clone, Debug)]
+pub struct ProverConfigBuilder(ProverConfig);
+
+impl ProverConfigBuilder {
+    pub fn with_max_bytecode_size(mut self, max_bytecode_size: usize) -> Self {
+        self.0.max_bytecode_size = max_bytecode_size;
+        self
+    }
+
+    pub fn with_max_memory_address(mut self, max_memory_address: usize) -> Self {
+        self.0.max_memory_address = max_memory_address;
+        self
+    }
+
+    pub fn with_max_trace_length(mut self, max_trace_length: usize) -> Self {
+        self.0.max_trace_length = max_trace_length;
+        self
+    }
+
+    /// Caches the PCS setup in `dir` (see `SetupCache`)
+    pub fn with_setup_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.0.setup_cache = Some(SetupCache::new(dir));
+        self
+    }
+
+    /// Always computes the PCS setup, even if `JOLT_SETUP_CACHE` is set
+    pub fn without_setup_cache(mut self) -> Self {
+        self.0.setup_cache = None;
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.0.threads = Some(threads);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: ThreadPriority) -> Self {
+        self.0.priority = priority;
+        self
+    }
+
+    /// Fails proofs estimated to need more memory, and for chunked proving
+    /// (see `Program::prove_with_config`) picks the largest chunk size within it
+    pub fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.0.memory_budget = Some(memory_budget);
+        self
+    }
+
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.0.chunk_size = Some(chunk_size);
+        self
+    }
+
+    pub fn with_pcs(mut self, pcs: PcsBackend) -> Self {
+        self.0.pcs = Some(pcs);
+        self
+    }
+
+    /// Zero-knowledge proofs. Not supported yet, so `build` fails if set.
+    pub fn with_zk(mut self, zk: bool) -> Self {
+        self.0.zk = zk;
+        self
+    }
+
+    pub fn with_security(mut self, security: SecurityConfig) -> Self {
+        self.0.security = security;
+        self
+    }
+
+    /// Tunes the prover to the machine `calibration` was measured on: its MSM
+    /// window sizes and chunking of parallel loops, and, unless a chunk size or
+    /// memory budget is set, the chunk size of `Program::prove_with_config`
+    pub fn with_calibration(mut self, calibration: CalibrationProfile) -> Self {
+        self.0.calibration = Some(calibration);
+        self
+    }
+
+    /// Validates the config, so that invalid settings are caught before any
+    /// preprocessing or proving is done
+    pub fn build(self) -> Result<ProverConfig, ConfigError> {
+        let config = self.0;
+        for (name, size) in [
+            ("max_bytecode_size", config.max_bytecode_size),
+            ("max_memory_address", config.max_memory_address),
+            ("max_trace_length", config.max_trace_length),
+        ] {
+            if size == 0 {
+                return Err(ConfigError::ZeroSize(name));
+            }
+        }
+        if config.threads == Some(0) {
+            return Err(ConfigError::ZeroSize("threads"));
+        }
+        if let Some(chunk_size) = config.chunk_size {
+            if !chunk_size.is_power_of_two() {
+                return Err(ConfigError::ChunkSizeNotPowerOfTwo(chunk_size));
+            }
+            if chunk_size > config.max_trace_length.next_power_of_two() {
+                return Err(ConfigError::ChunkTooLarge(
+                    chunk_size,
+                    config.max_trace_length,
+                ));
+            }
+            if config.memory_budget.is_some() {
+                return Err(ConfigError::ChunkSizeAndMemoryBudget);
+            }
+        }
+        if config.zk {
+            return Err(ConfigError::ZkUnsupported);
+        }
+        // As checked by `Jolt::check_security`, which also needs the trace length
+        if config.security.extension_degree != 1 {
+            return Err(SecurityError::UnsupportedExtensionDegree(
+                config.security.extension_degree,
+            )
+            .into());
+        }
+        if config.security.repetitions != 1 {
+            return Err(SecurityError::UnsupportedRepetitions(config.security.repetitions).into());
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::commitment::hyperkzg::HyperKZG;
+    use crate::utils::transcript::KeccakTranscript;
+    use ark_bn254::Bn254;
+
+    #[test]
+    fn build_validates() {
+        assert_eq!(ProverConfig::builder().build(), Ok(ProverConfig::default()));
+        assert_eq!(
+            ProverConfig::builder().with_max_trace_length(0).build(),
+            Err(ConfigError::ZeroSize("max_trace_length"))
+        );
+        assert_eq!(
+            ProverConfig::builder().with_threads(0).build(),
+            Err(ConfigError::ZeroSize("threads"))
+        );
+        assert_eq!(
+            ProverConfig::builder().with_chunk_size(1000).build(),
+            Err(ConfigError::ChunkSizeNotPowerOfTwo(1000))
+        );
+        assert_eq!(
+            ProverConfig::builder()
+                .with_max_trace_length(1 << 10)
+                .with_chunk_size(1 << 11)
+                .build(),
+            Err(ConfigError::ChunkTooLarge(1 << 11, 1 << 10))
+        );
+        assert_eq!(
+            ProverConfig::builder()
+                .with_chunk_size(1 << 10)
+                .with_memory_budget(1 << 30)
+                .build(),
+            Err(ConfigError::ChunkSizeAndMemoryBudget)
+        );
+        assert_eq!(
+            ProverConfig::builder().with_zk(true).build(),
+            Err(ConfigError::ZkUnsupported)
+        );
+        assert_eq!(
+            ProverConfig::builder()
+                .with_security(SecurityConfig {
+                    repetitions: 2,
+                    ..Default::default()
+                })
+                .build(),
+            Err(ConfigError::Security(
+                SecurityError::UnsupportedRepetitions(2)
+            ))
+        );
+    }
+
+    #[test]
+    fn check_pcs() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+        let config = ProverConfig::builder()
+            .with_pcs(PcsBackend::HyperKZG)
+            .build()
+            .unwrap();
+        assert!(config.check_pcs::<PCS, KeccakTranscript>().is_ok());
+
+        let config = ProverConfig::builder()
+            .with_pcs(PcsBackend::Zeromorph)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            config.check_pcs::<PCS, KeccakTranscript>(),
+            Err(ConfigError::PcsMismatch(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn install() {
+        let config = ProverConfig::builder().with_threads(2).build().unwrap();
+        assert_eq!(config.install(rayon::current_num_threads), 2);
+        let name = config.install(|| std::thread::current().name().map(String::from));
+        assert!(name.unwrap().starts_with("jolt-prover-"));
+        // The pool is reused
+        let pool = config.thread_pool().unwrap();
+        assert!(Arc::ptr_eq(&pool, &config.thread_pool().unwrap()));
+
+        assert!(ProverConfig::default().thread_pool().is_none());
+        let config = ProverConfig::builder()
+            .with_priority(ThreadPriority::Low)
+            .build()
+            .unwrap();
+        assert_eq!(config.install(rayon::current_num_threads), num_cpus());
+    }
+}
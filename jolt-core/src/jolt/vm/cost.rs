@@ -0,0 +1,207 @@
+use std::fmt;
+
+use crate::field::JoltField;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::r1cs::key::UniformSpartanKey;
+use crate::utils::math::Math;
+
+/// Witness, constraint and proof sizes for proving a given configuration,
+/// computed without running the prover (see `Jolt::cost_report`). Useful for
+/// evaluating the cost of e.g. custom constraints or precompiles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostReport {
+    /// Trace length padded to a power of two, i.e. the number of steps proven
+    pub padded_trace_length: usize,
+    /// Committed polynomials with one entry per step (see `read_write_values`)
+    pub read_write_columns: usize,
+    /// Committed polynomials sized by the bytecode or memory (see `init_final_values`)
+    pub init_final_columns: usize,
+    /// Inputs to the R1CS per step, including aux variables
+    pub r1cs_inputs: usize,
+    pub uniform_constraints: usize,
+    pub cross_step_constraints: usize,
+    /// Constraints of degree greater than 2, see `ProductConstraint`
+    pub product_constraints: usize,
+    /// Uniform and cross-step constraints across all steps, padded to a power of two
+    pub constraint_rows: usize,
+    /// Size in bytes of the commitments to all polynomials
+    pub commitments_size: usize,
+    /// Size in bytes of the R1CS (Spartan) proof
+    pub r1cs_proof_size: usize,
+    /// Estimated size in bytes of the rest of the proof: memory-checking and
+    /// lookup sumchecks, evaluation claims, and the batched opening proof
+    pub other_proof_size: usize,
+    /// Estimated peak memory of the prover in bytes: the trace, the witness
+    /// polynomials, the Spartan matrix-vector products and the grand product trees
+    pub prover_memory: usize,
+}
+
+impl CostReport {
+    /// Total number of committed polynomials
+    pub fn num_commitments(&self) -> usize {
+        self.read_write_columns + self.init_final_columns
+    }
+
+    /// Estimated size in bytes of the proof and commitments sent to the verifier
+    pub fn total_size(&self) -> usize {
+        self.commitments_size + self.r1cs_proof_size + self.other_proof_size
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "steps (padded):         {}", self.padded_trace_length)?;
+        writeln!(
+            f,
+            "committed columns:      {} ({} read/write, {} init/final)",
+            self.num_commitments(),
+            self.read_write_columns,
+            self.init_final_columns
+        )?;
+        writeln!(f, "R1CS inputs per step:   {}", self.r1cs_inputs)?;
+        writeln!(
+            f,
+            "constraints per step:   {} uniform, {} cross-step, {} product",
+            self.uniform_constraints, self.cross_step_constraints, self.product_constraints
+        )?;
+        writeln!(f, "constraint rows:        {}", self.constraint_rows)?;
+        writeln!(f, "commitments:            {} bytes", self.commitments_size)?;
+        writeln!(f, "R1CS proof:             {} bytes", self.r1cs_proof_size)?;
+        writeln!(f, "rest of proof (est.):   {} bytes", self.other_proof_size)?;
+        writeln!(f, "total (est.):           {} bytes", self.total_size())?;
+        write!(f, "prover memory (est.):   {} bytes", self.prover_memory)
+    }
+}
+
+/// Gas prices used by `VerificationCost::evm_gas`: the BN254 precompiles as of
+/// EIP-1108, Keccak-256 of two words, and calldata
+const PAIRING_BASE_GAS: usize = 45_000;
+const PAIRING_GAS: usize = 34_000;
+/// An `ecMul`, plus the `ecAdd` accumulating its result
+const SCALAR_MUL_GAS: usize = 6_150;
+const KECCAK_GAS: usize = 42;
+/// A `mulmod` with the surrounding stack and memory operations
+const FIELD_MUL_GAS: usize = 20;
+const CALLDATA_BYTE_GAS: usize = 16;
+const TRANSACTION_GAS: usize = 21_000;
+
+/// Estimated work for verifying a single proof of a given configuration,
+/// computed without a proof (see `Jolt::verification_cost`). Useful for budgeting,
+/// e.g. on-chain verification, before committing to a configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationCost {
+    /// Pairings of the final PCS opening check; none for schemes without pairings
+    pub pairings: usize,
+    /// Scalar multiplications in G1, from combining the commitments of the batched
+    /// opening and the PCS opening check
+    pub scalar_muls: usize,
+    /// Field multiplications, other than those in group operations: evaluating
+    /// sumcheck round polynomials and eq polynomials, combining evaluation claims,
+    /// and evaluating the R1CS matrices
+    pub field_muls: usize,
+    /// Hash invocations of the Fiat-Shamir transcript: one per absorbed element
+    /// and one per challenge
+    pub hashes: usize,
+    /// Size in bytes of the proof and commitments, see `CostReport::total_size`
+    pub proof_size: usize,
+}
+
+impl VerificationCost {
+    /// Estimated gas for verifying the proof with the Solidity verifier (see
+    /// `jolt-evm-verifier`), including the base transaction cost and calldata.
+    /// Only meaningful for BN254 and a pairing-based commitment scheme.
+    pub fn evm_gas(&self) -> usize {
+        let pairing_gas = if self.pairings == 0 {
+            0
+        } else {
+            PAIRING_BASE_GAS + self.pairings * PAIRING_GAS
+        };
+        TRANSACTION_GAS
+            + pairing_gas
+            + self.scalar_muls * SCALAR_MUL_GAS
+            + self.field_muls * FIELD_MUL_GAS
+            + self.hashes * KECCAK_GAS
+            + self.proof_size * CALLDATA_BYTE_GAS
+    }
+}
+
+impl fmt::Display for VerificationCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "pairings:               {}", self.pairings)?;
+        writeln!(f, "G1 scalar mults:        {}", self.scalar_muls)?;
+        writeln!(f, "field mults:            {}", self.field_muls)?;
+        writeln!(f, "transcript hashes:      {}", self.hashes)?;
+        writeln!(f, "proof size (est.):      {} bytes", self.proof_size)?;
+        write!(f, "EVM gas (est.):         {}", self.evm_gas())
+    }
+}
+
+/// Number of field elements in a sumcheck proof: one compressed univariate
+/// polynomial (all coefficients but the linear term) per round.
+pub(crate) fn sumcheck_size(num_rounds: usize, degree: usize) -> usize {
+    num_rounds * degree
+}
+
+/// Number of field elements in a GKR grand product proof over `num_leaves`
+/// leaves: a degree-3 sumcheck per layer plus the two claims it reduces to.
+pub(crate) fn grand_product_size(num_leaves: usize) -> usize {
+    (1..num_leaves.next_power_of_two().log_2())
+        .map(|layer| sumcheck_size(layer, 3) + 2)
+        .sum()
+}
+
+/// Number of sumcheck rounds in a GKR grand product proof over `num_leaves` leaves
+pub(crate) fn grand_product_rounds(num_leaves: usize) -> usize {
+    (1..num_leaves.next_power_of_two().log_2()).sum()
+}
+
+/// Number of sumcheck rounds in the Spartan proof for `key`
+pub(crate) fn spartan_rounds<const C: usize, I: ConstraintInput, F: JoltField>(
+    key: &UniformSpartanKey<C, I, F>,
+) -> usize {
+    let num_step_bits = key.num_steps.log_2();
+    let product = if key.product_constraints.is_empty() {
+        0
+    } else {
+        num_step_bits
+    };
+    key.num_rows_bits() + (2 * key.num_vars_uniform_padded()).log_2() + 1 + num_step_bits + product
+}
+
+/// Number of field multiplications for evaluating the R1CS matrix MLEs of `key`,
+/// see `UniformSpartanKey::evaluate_matrix_mle_full`: one per non-zero entry and
+/// per entry of the row and column eq tables.
+pub(crate) fn matrix_evaluation_muls<const C: usize, I: ConstraintInput, F: JoltField>(
+    key: &UniformSpartanKey<C, I, F>,
+) -> usize {
+    let r1cs = &key.uniform_r1cs;
+    let uniform_entries = r1cs.a.vars.len() + r1cs.b.vars.len() + r1cs.c.vars.len();
+    let cross_step_entries: usize = key
+        .offset_eq_r1cs
+        .constraints
+        .iter()
+        .map(|constraint| constraint.eq.offset_vars.len() + constraint.condition.offset_vars.len())
+        .sum();
+    uniform_entries
+        + cross_step_entries
+        + (r1cs.num_rows + 1).next_power_of_two()
+        + 2 * key.num_vars_uniform_padded()
+}
+
+/// Number of field elements in the Spartan proof for `key`, see `UniformSpartanProof`.
+pub(crate) fn spartan_proof_size<const C: usize, I: ConstraintInput, F: JoltField>(
+    key: &UniformSpartanKey<C, I, F>,
+) -> usize {
+    let num_step_bits = key.num_steps.log_2();
+    let outer = sumcheck_size(key.num_rows_bits(), 3) + 3;
+    let inner = sumcheck_size((2 * key.num_vars_uniform_padded()).log_2() + 1, 2);
+    let shift = sumcheck_size(num_step_bits, 2) + 1;
+    let witness_evals = 2 * key.uniform_r1cs.num_vars;
+    let product = if key.product_constraints.is_empty() {
+        0
+    } else {
+        sumcheck_size(num_step_bits, key.product_constraint_degree() + 1)
+            + key.product_constraint_columns().len()
+    };
+    outer + inner + shift + witness_evals + product
+}
@@ -0,0 +1,391 @@
+//! Proving a long execution on several machines: a coordinator traces the
+//! execution in chunks and sends each chunk to a worker, which proves it as one
+//! segment of a `ChainedProof` (see `Jolt::prove_chunks`).
+//!
+//! Coordinator and worker talk over any byte stream, e.g. a TCP connection, in
+//! frames of a one-byte tag, the length of the payload as a little-endian `u64`,
+//! and the payload:
+//!
+//! - `JOB` (coordinator to worker): the index of the chunk (`u64`), whether it is
+//!   the first and the last segment (`bool`s), the I/O of the whole execution
+//!   and the memory state the chunk starts from (`Option<MemoryState>`), all
+//!   compressed with `CanonicalSerialize`, followed by the chunk's trace steps
+//!   serialized with `bincode`.
+//! - `PROOF` (worker to coordinator): the chunk's `JoltProof` followed by its
+//!   `JoltCommitments`, compressed.
+//! - `FAILURE` (worker to coordinator): a UTF-8 message saying why proving the
+//!   chunk failed.
+//!
+//! A worker answers each job with exactly one `PROOF` or `FAILURE`, and serves
+//! jobs until the coordinator closes the stream. Both sides must use the same
+//! preprocessing.
+//!
+//! Only the memory states between chunks are computed sequentially, by the
+//! coordinator, from the memory witness alone; the commitments and sumchecks of
+//! every chunk are computed by its worker.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use common::rv_trace::JoltDevice;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::Any;
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+#[cfg(feature = "parallel")]
+use std::sync::Mutex;
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::compact_polynomial::CompactPolynomial;
+#[cfg(feature = "parallel")]
+use crate::r1cs::constraints::R1CSConstraints;
+use crate::utils::transcript::Transcript;
+
+use super::read_write_memory::{
+    MemoryState, ReadWriteMemoryPolynomials, ReadWriteMemoryPreprocessing,
+};
+use super::segment::Segment;
+#[cfg(feature = "parallel")]
+use super::{ChainedProof, JoltCommitments, JoltProof};
+use super::{Jolt, JoltPreprocessing, JoltTraceStep};
+
+/// Tag of a frame carrying a `ChunkJob`
+pub const JOB: u8 = 1;
+/// Tag of a frame carrying the proof and commitments of a chunk
+pub const PROOF: u8 = 2;
+/// Tag of a frame carrying the reason a worker failed to prove a chunk
+pub const FAILURE: u8 = 3;
+
+#[cfg(feature = "parallel")]
+type ChunkProof<J, F, PCS, const C: usize, const M: usize, ProofTranscript> = JoltProof<
+    C,
+    M,
+    <<J as Jolt<F, PCS, C, M, ProofTranscript>>::Constraints as R1CSConstraints<C, F>>::Inputs,
+    F,
+    PCS,
+    <J as Jolt<F, PCS, C, M, ProofTranscript>>::InstructionSet,
+    <J as Jolt<F, PCS, C, M, ProofTranscript>>::Subtables,
+    ProofTranscript,
+    <J as Jolt<F, PCS, C, M, ProofTranscript>>::Memory,
+>;
+
+/// Writes one frame of the wire protocol
+pub fn write_frame(mut writer: impl Write, tag: u8, payload: &[u8]) -> io::Result<()> {
+    writer.write_all(&[tag])?;
+    writer.write_all(&(payload.len() as u64).to_le_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Reads one frame of the wire protocol, or `None` if the stream ends before it
+pub fn read_frame(mut reader: impl Read) -> io::Result<Option<(u8, Vec<u8>)>> {
+    let mut tag = [0u8];
+    match reader.read_exact(&mut tag) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let mut length = [0u8; 8];
+    reader.read_exact(&mut length)?;
+    let mut payload = vec![];
+    reader
+        .take(u64::from_le_bytes(length))
+        .read_to_end(&mut payload)?;
+    if payload.len() as u64 != u64::from_le_bytes(length) {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(Some((tag[0], payload)))
+}
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// One chunk of an execution to be proven by a worker
+pub struct ChunkJob<InstructionSet: JoltInstructionSet> {
+    /// Position of the chunk in the execution
+    pub index: usize,
+    pub segment: Segment,
+    /// I/O of the whole execution
+    pub program_io: JoltDevice,
+    /// Memory state at the start of the chunk, `None` for the first one
+    pub previous: Option<MemoryState>,
+    pub trace: Vec<JoltTraceStep<InstructionSet>>,
+}
+
+impl<InstructionSet> ChunkJob<InstructionSet>
+where
+    InstructionSet: JoltInstructionSet,
+    JoltTraceStep<InstructionSet>: Serialize + DeserializeOwned,
+{
+    /// Writes the job as a `JOB` frame
+    pub fn write(&self, writer: impl Write) -> io::Result<()> {
+        let mut payload = vec![];
+        (self.index as u64, self.segment.first, self.segment.last)
+            .serialize_compressed(&mut payload)
+            .map_err(invalid_data)?;
+        self.program_io
+            .serialize_compressed(&mut payload)
+            .map_err(invalid_data)?;
+        self.previous
+            .serialize_compressed(&mut payload)
+            .map_err(invalid_data)?;
+        bincode::serialize_into(&mut payload, &self.trace).map_err(invalid_data)?;
+        write_frame(writer, JOB, &payload)
+    }
+
+    /// Reads a `JOB` frame, or `None` if the stream ends before it
+    pub fn read(reader: impl Read) -> io::Result<Option<Self>> {
+        let Some((tag, payload)) = read_frame(reader)? else {
+            return Ok(None);
+        };
+        if tag != JOB {
+            return Err(invalid_data(format!(
+                "expected a job, got a frame tagged {tag}"
+            )));
+        }
+        let mut payload = payload.as_slice();
+        let (index, first, last) =
+            <(u64, bool, bool)>::deserialize_compressed(&mut payload).map_err(invalid_data)?;
+        let program_io = JoltDevice::deserialize_compressed(&mut payload).map_err(invalid_data)?;
+        let previous =
+            Option::<MemoryState>::deserialize_compressed(&mut payload).map_err(invalid_data)?;
+        let trace = bincode::deserialize(payload).map_err(invalid_data)?;
+        Ok(Some(Self {
+            index: index as usize,
+            segment: Segment { first, last },
+            program_io,
+            previous,
+            trace,
+        }))
+    }
+}
+
+/// The memory state after `trace`, starting from `previous`, as returned by
+/// `Jolt::prove_continuation` but without proving anything.
+fn final_memory_state<F: JoltField, InstructionSet: JoltInstructionSet>(
+    program_io: &JoltDevice,
+    trace: &mut Vec<JoltTraceStep<InstructionSet>>,
+    preprocessing: &ReadWriteMemoryPreprocessing,
+    segment: Segment,
+    previous: Option<MemoryState>,
+) -> MemoryState {
+    let mut preprocessing = preprocessing.clone();
+    preprocessing.segment = Some(segment);
+    preprocessing.continuation = previous;
+    let trace_length = trace.len();
+    JoltTraceStep::pad(trace);
+    preprocessing.pages = preprocessing.touched_pages(program_io, trace);
+    let polynomials =
+        ReadWriteMemoryPolynomials::<F>::generate_witness(program_io, &preprocessing, trace);
+    trace.truncate(trace_length);
+
+    let v_final: &CompactPolynomial<u32, F> = (&polynomials.v_final).try_into().unwrap();
+    MemoryState {
+        words: v_final.coeffs.clone(),
+        pages: preprocessing.pages,
+        program_io: program_io.clone(),
+    }
+}
+
+/// Splits an execution into jobs for `serve`, computing the memory state each
+/// chunk starts from as the jobs are taken. The chunks are as in
+/// `Jolt::prove_chunks`.
+pub fn chunk_jobs<'a, J, F, PCS, const C: usize, const M: usize, ProofTranscript>(
+    program_io: JoltDevice,
+    chunks: impl IntoIterator<Item = Vec<JoltTraceStep<J::InstructionSet>>> + 'a,
+    preprocessing: &'a JoltPreprocessing<C, F, PCS, ProofTranscript>,
+) -> impl Iterator<Item = ChunkJob<J::InstructionSet>> + 'a
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript>,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let memory_preprocessing = &preprocessing.read_write_memory;
+    let mut chunks = chunks.into_iter().peekable();
+    let mut previous: Option<MemoryState> = None;
+    let mut index = 0;
+    std::iter::from_fn(move || {
+        let mut trace = chunks.next()?;
+        let segment = Segment {
+            first: index == 0,
+            last: chunks.peek().is_none(),
+        };
+        assert!(
+            segment.last || trace.len().is_power_of_two(),
+            "Only the last segment may be padded"
+        );
+        let job_previous = previous.take();
+        if !segment.last {
+            previous = Some(final_memory_state::<F, _>(
+                &program_io,
+                &mut trace,
+                memory_preprocessing,
+                segment,
+                job_previous.clone(),
+            ));
+        }
+        let job = ChunkJob {
+            index,
+            segment,
+            program_io: program_io.clone(),
+            previous: job_previous,
+            trace,
+        };
+        index += 1;
+        Some(job)
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "the prover panicked"
+    }
+}
+
+/// Runs a worker: proves the jobs read from `stream` and writes back their
+/// proofs, until the coordinator closes the stream. A job that fails to prove
+/// is answered with a `FAILURE` frame, and the worker carries on.
+pub fn serve<J, F, PCS, const C: usize, const M: usize, ProofTranscript>(
+    preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    mut stream: impl Read + Write,
+) -> io::Result<()>
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript>,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+    JoltTraceStep<J::InstructionSet>: Serialize + DeserializeOwned,
+{
+    while let Some(job) = ChunkJob::<J::InstructionSet>::read(&mut stream)? {
+        let index = job.index;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut preprocessing = preprocessing.clone();
+            preprocessing.read_write_memory.segment = Some(job.segment);
+            let (proof, commitments, _, _) =
+                J::prove_continuation(job.program_io, job.trace, preprocessing, job.previous);
+            let mut payload = vec![];
+            proof.serialize_compressed(&mut payload)?;
+            commitments.serialize_compressed(&mut payload)?;
+            Ok::<_, ark_serialize::SerializationError>(payload)
+        }));
+        match result {
+            Ok(Ok(payload)) => write_frame(&mut stream, PROOF, &payload)?,
+            Ok(Err(error)) => {
+                let message = format!("chunk {index}: {error}");
+                write_frame(&mut stream, FAILURE, message.as_bytes())?
+            }
+            Err(payload) => {
+                let message = format!("chunk {index}: {}", panic_message(payload.as_ref()));
+                write_frame(&mut stream, FAILURE, message.as_bytes())?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Proves an execution, given in chunks as in `Jolt::prove_chunks`, on the
+/// workers connected through `workers` (see `serve`). Each worker is sent the
+/// next chunk as soon as it is done with the previous one. The result is the
+/// same `ChainedProof` as `Jolt::prove_chunks` would produce. Talks to the
+/// workers on a thread each, so requires the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub fn prove_distributed<J, F, PCS, const C: usize, const M: usize, ProofTranscript, Chunks>(
+    program_io: JoltDevice,
+    chunks: Chunks,
+    preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    workers: Vec<impl Read + Write + Send>,
+) -> io::Result<
+    ChainedProof<
+        C,
+        M,
+        <J::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        PCS,
+        J::InstructionSet,
+        J::Subtables,
+        ProofTranscript,
+        J::Memory,
+    >,
+>
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript>,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+    JoltTraceStep<J::InstructionSet>: Serialize + DeserializeOwned,
+    Chunks: IntoIterator<Item = Vec<JoltTraceStep<J::InstructionSet>>>,
+    Chunks::IntoIter: Send,
+{
+    assert!(!workers.is_empty(), "No workers");
+    let jobs = Mutex::new(chunk_jobs::<J, F, PCS, C, M, ProofTranscript>(
+        program_io,
+        chunks,
+        preprocessing,
+    ));
+    let results = Mutex::new(vec![]);
+
+    thread::scope(|scope| {
+        let (jobs, results) = (&jobs, &results);
+        let handles: Vec<_> = workers
+            .into_iter()
+            .map(|mut worker| {
+                scope.spawn(move || -> io::Result<()> {
+                    loop {
+                        // Computing the next chunk's initial state is sequential, so
+                        // workers take turns at it
+                        let Some(job) = jobs.lock().unwrap().next() else {
+                            return Ok(());
+                        };
+                        job.write(&mut worker)?;
+                        let payload = match read_frame(&mut worker)? {
+                            Some((PROOF, payload)) => payload,
+                            Some((FAILURE, message)) => {
+                                return Err(io::Error::other(
+                                    String::from_utf8_lossy(&message).into_owned(),
+                                ))
+                            }
+                            Some((tag, _)) => {
+                                return Err(invalid_data(format!(
+                                    "expected a proof, got a frame tagged {tag}"
+                                )))
+                            }
+                            None => return Err(io::ErrorKind::UnexpectedEof.into()),
+                        };
+                        results.lock().unwrap().push((job.index, payload));
+                    }
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle.join().unwrap())
+    })?;
+
+    let mut results = results.into_inner().unwrap();
+    assert!(!results.is_empty(), "Nothing to prove");
+    results.sort_by_key(|(index, _)| *index);
+    let mut chained_proof = ChainedProof {
+        proofs: vec![],
+        commitments: vec![],
+    };
+    for (_, payload) in results {
+        let mut payload = payload.as_slice();
+        let proof =
+            ChunkProof::<J, F, PCS, C, M, ProofTranscript>::deserialize_compressed(&mut payload)
+                .map_err(invalid_data)?;
+        let commitments =
+            JoltCommitments::deserialize_compressed(&mut payload).map_err(invalid_data)?;
+        chained_proof.proofs.push(proof);
+        chained_proof.commitments.push(commitments);
+    }
+    Ok(chained_proof)
+}
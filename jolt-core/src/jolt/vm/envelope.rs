@@ -0,0 +1,195 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use common::rv_trace::JoltDevice;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::jolt::subtable::JoltSubtableSet;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::r1cs::export::modulus_bytes;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::utils::canonical_serde::impl_canonical_serde;
+use crate::utils::errors::ProofFormatError;
+use crate::utils::transcript::Transcript;
+
+use super::read_write_memory::{MemoryArgument, ReadWriteMemoryProof};
+use super::{JoltCommitments, JoltPreprocessing, JoltProof};
+
+/// Leading bytes of a serialized `ProofEnvelope`
+pub const PROOF_MAGIC: [u8; 4] = *b"JOLT";
+
+/// Version of the format written by `ProofEnvelope::serialize_to_bytes`. Must be
+/// bumped whenever the serialization of the proof or anything it contains changes,
+/// so that older proofs are rejected up front rather than misparsed.
+pub const PROOF_FORMAT_VERSION: u32 = 4;
+
+/// Describes the proof in a `ProofEnvelope`, and can be read without decoding
+/// the proof itself.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofHeader {
+    /// Modulus of the proof's scalar field, little-endian. Identifies the curve.
+    pub field_modulus: Vec<u8>,
+    /// `CommitmentScheme::protocol_name` of the proof's commitment scheme
+    pub commitment_scheme: Vec<u8>,
+    /// Identifies the proven program, see `JoltPreprocessing::program_hash`
+    pub program_hash: [u8; 32],
+    /// The public inputs and outputs of the proven execution
+    pub program_io: JoltDevice,
+}
+
+impl ProofHeader {
+    /// Reads the header of a serialized `ProofEnvelope`, advancing `bytes` past
+    /// it, and checks that the envelope is in the current format.
+    pub fn read(bytes: &mut &[u8]) -> Result<Self, ProofFormatError> {
+        if !bytes.starts_with(&PROOF_MAGIC) {
+            return Err(ProofFormatError::InvalidMagic);
+        }
+        *bytes = &bytes[PROOF_MAGIC.len()..];
+        let version = u32::deserialize_compressed(&mut *bytes)?;
+        if version != PROOF_FORMAT_VERSION {
+            return Err(ProofFormatError::UnsupportedVersion(
+                version,
+                PROOF_FORMAT_VERSION,
+            ));
+        }
+        Ok(Self::deserialize_compressed(&mut *bytes)?)
+    }
+}
+
+/// A proof with its commitments in a stable, self-describing format: magic bytes,
+/// the format version, a `ProofHeader`, then the proof. Deserializing checks the
+/// version, field and commitment scheme before the proof is decoded, so proofs
+/// written by an incompatible version of Jolt are rejected with a clear error.
+pub struct ProofEnvelope<
+    const C: usize,
+    const M: usize,
+    I,
+    F,
+    PCS,
+    InstructionSet,
+    Subtables,
+    ProofTranscript,
+    MemoryProof = ReadWriteMemoryProof<F, PCS, ProofTranscript>,
+> where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    pub header: ProofHeader,
+    pub proof: JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>,
+    pub commitments: JoltCommitments<PCS, ProofTranscript>,
+}
+
+impl_canonical_serde!(
+    [
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    ]
+    ProofEnvelope<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+    where
+        I: ConstraintInput,
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        InstructionSet: JoltInstructionSet,
+        Subtables: JoltSubtableSet<F>,
+        ProofTranscript: Transcript,
+        MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+);
+
+impl<
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    > ProofEnvelope<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    /// Wraps a proof of the program `preprocessing` was derived from.
+    pub fn new(
+        proof: JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Self {
+        Self {
+            header: ProofHeader {
+                field_modulus: modulus_bytes::<F>(),
+                commitment_scheme: PCS::protocol_name().to_vec(),
+                program_hash: preprocessing.program_hash,
+                program_io: proof.program_io.clone(),
+            },
+            proof,
+            commitments,
+        }
+    }
+
+    pub fn serialize_to_bytes(&self) -> Result<Vec<u8>, ProofFormatError> {
+        let mut bytes = PROOF_MAGIC.to_vec();
+        PROOF_FORMAT_VERSION.serialize_compressed(&mut bytes)?;
+        self.header.serialize_compressed(&mut bytes)?;
+        self.proof.serialize_compressed(&mut bytes)?;
+        self.commitments.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn deserialize_from_bytes(mut bytes: &[u8]) -> Result<Self, ProofFormatError> {
+        let header = ProofHeader::read(&mut bytes)?;
+        if header.field_modulus != modulus_bytes::<F>() {
+            return Err(ProofFormatError::FieldMismatch);
+        }
+        if header.commitment_scheme != PCS::protocol_name() {
+            return Err(ProofFormatError::CommitmentSchemeMismatch(
+                String::from_utf8_lossy(&header.commitment_scheme).into_owned(),
+                String::from_utf8_lossy(PCS::protocol_name()).into_owned(),
+            ));
+        }
+
+        let proof = JoltProof::deserialize_compressed(&mut bytes)?;
+        let commitments =
+            JoltCommitments::<PCS, ProofTranscript>::deserialize_compressed(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(ProofFormatError::TrailingBytes(bytes.len()));
+        }
+        let envelope = Self {
+            header,
+            proof,
+            commitments,
+        };
+        if envelope.header.program_io != envelope.proof.program_io {
+            return Err(ProofFormatError::IoMismatch);
+        }
+        Ok(envelope)
+    }
+
+    /// Checks that the proof is of the program `preprocessing` was derived from.
+    pub fn check_program(
+        &self,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<(), ProofFormatError> {
+        if self.header.program_hash != preprocessing.program_hash {
+            return Err(ProofFormatError::ProgramMismatch);
+        }
+        Ok(())
+    }
+}
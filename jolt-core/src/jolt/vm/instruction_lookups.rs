@@ -5,11 +5,10 @@ use crate::poly::multilinear_polynomial::{
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::subprotocols::grand_product::BatchedGrandProduct;
 use crate::subprotocols::sparse_grand_product::ToggledBatchedGrandProduct;
+use crate::utils::parallel::prelude::*;
 use crate::utils::thread::unsafe_allocate_zero_vec;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use itertools::{interleave, Itertools};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
-use rayon::prelude::*;
 use std::marker::PhantomData;
 use tracing::trace_span;
 
@@ -397,7 +396,7 @@ where
         _preprocessing: &Self::Preprocessing,
         _r_read_write: &[F],
         r_init_final: &[F],
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         openings.a_init_final =
             Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
         openings.v_init_final = Some(
@@ -405,6 +404,7 @@ where
                 .map(|subtable| subtable.evaluate_mle(r_init_final))
                 .collect(),
         );
+        Ok(())
     }
 
     fn read_tuples(
@@ -702,6 +702,18 @@ where
     const NUM_SUBTABLES: usize = Subtables::COUNT;
     const NUM_INSTRUCTIONS: usize = InstructionSet::COUNT;
 
+    /// Number of rounds of the primary sumcheck, i.e. log2 of the padded trace length
+    pub(crate) fn primary_sumcheck_rounds(&self) -> usize {
+        self.primary_sumcheck.sumcheck_proof.compressed_polys.len()
+    }
+
+    /// The primary sumcheck, then the sumchecks of the memory-checking grand products
+    pub(crate) fn sumcheck_proofs(&self) -> Vec<&SumcheckInstanceProof<F, ProofTranscript>> {
+        let mut proofs = vec![&self.primary_sumcheck.sumcheck_proof];
+        proofs.extend(self.memory_checking.sumcheck_proofs());
+        proofs
+    }
+
     #[tracing::instrument(skip_all, name = "InstructionLookups::prove")]
     pub fn prove<'a>(
         generators: &PCS::Setup,
@@ -1214,7 +1226,7 @@ where
     /// Returns the sumcheck polynomial degree for the "primary" sumcheck. Since the primary sumcheck expression
     /// is \sum_x \tilde{eq}(r, x) * \sum_i flag_i(x) * g_i(E_1(x), ..., E_\alpha(x)), the degree is
     /// the max over all the instructions' `g_i` polynomial degrees, plus two (one for \tilde{eq}, one for flag_i)
-    fn sumcheck_poly_degree() -> usize {
+    pub(crate) fn sumcheck_poly_degree() -> usize {
         InstructionSet::iter()
             .map(|instruction| instruction.g_poly_degree(C))
             .max()
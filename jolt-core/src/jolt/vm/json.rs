@@ -0,0 +1,166 @@
+use alloy_primitives::hex;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde_json::{json, Value};
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::jolt::subtable::JoltSubtableSet;
+use crate::lasso::memory_checking::StructuredPolynomialData;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::subprotocols::grand_product::BatchedGrandProductProof;
+use crate::subprotocols::sumcheck::SumcheckInstanceProof;
+use crate::utils::errors::ProofFormatError;
+use crate::utils::transcript::Transcript;
+
+use super::envelope::PROOF_FORMAT_VERSION;
+use super::read_write_memory::MemoryArgument;
+use super::{JoltCommitments, JoltProof};
+
+fn size(value: &impl CanonicalSerialize) -> usize {
+    value.compressed_size()
+}
+
+fn to_hex(value: &impl CanonicalSerialize) -> String {
+    let mut bytes = vec![];
+    value.serialize_compressed(&mut bytes).unwrap();
+    hex::encode_prefixed(bytes)
+}
+
+fn sumcheck_rounds<F: JoltField, ProofTranscript: Transcript>(
+    proof: &SumcheckInstanceProof<F, ProofTranscript>,
+) -> usize {
+    proof.compressed_polys.len()
+}
+
+fn grand_product_layers<PCS, ProofTranscript>(
+    proof: &BatchedGrandProductProof<PCS, ProofTranscript>,
+) -> usize
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    proof.gkr_layers.len()
+}
+
+impl<
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    > JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    /// A human-readable dump of the proof and its commitments, for debugging and
+    /// auditing: the public I/O, the compressed size in bytes of each component,
+    /// sumcheck round and grand product layer counts, and every commitment in hex.
+    /// The `encoded` field holds the serialized proof and commitments, from which
+    /// `from_json` restores them.
+    pub fn to_json(&self, commitments: &JoltCommitments<PCS, ProofTranscript>) -> Value {
+        let r1cs = &self.r1cs;
+        let mut encoded = vec![];
+        self.serialize_compressed(&mut encoded).unwrap();
+        commitments.serialize_compressed(&mut encoded).unwrap();
+
+        json!({
+            "format_version": PROOF_FORMAT_VERSION,
+            "commitment_scheme": String::from_utf8_lossy(PCS::protocol_name()),
+            "metadata": {
+                "program_hash": hex::encode_prefixed(self.metadata.program_hash),
+                "verifier_key_digest": hex::encode_prefixed(self.metadata.verifier_key_digest),
+                "version": self.metadata.version,
+                "config_digest": hex::encode_prefixed(self.metadata.config_digest),
+            },
+            "trace_length": self.trace_length,
+            "program_io": {
+                "inputs": hex::encode_prefixed(&self.program_io.inputs),
+                "outputs": hex::encode_prefixed(&self.program_io.outputs),
+                "panic": self.program_io.panic,
+            },
+            "size": size(self) + size(commitments),
+            "components": {
+                "bytecode": {
+                    "size": size(&self.bytecode),
+                    "read_write_grand_product_layers":
+                        grand_product_layers(&self.bytecode.read_write_grand_product),
+                    "init_final_grand_product_layers":
+                        grand_product_layers(&self.bytecode.init_final_grand_product),
+                },
+                "read_write_memory": {
+                    "size": size(&self.read_write_memory),
+                    "pages": size(&self.memory_pages),
+                },
+                "instruction_lookups": {
+                    "size": size(&self.instruction_lookups),
+                    "primary_sumcheck_rounds": self.instruction_lookups.primary_sumcheck_rounds(),
+                },
+                "r1cs": {
+                    "size": size(r1cs),
+                    "outer_sumcheck_rounds": sumcheck_rounds(&r1cs.outer_sumcheck_proof),
+                    "inner_sumcheck_rounds": sumcheck_rounds(&r1cs.inner_sumcheck_proof),
+                    "shift_sumcheck_rounds": sumcheck_rounds(&r1cs.shift_sumcheck_proof),
+                    "product_sumcheck_rounds": r1cs
+                        .product_proof
+                        .as_ref()
+                        .map(|proof| sumcheck_rounds(&proof.sumcheck_proof)),
+                },
+                "segment_boundary": { "size": size(&self.segment_boundary) },
+                "opening_proof": { "size": size(&self.opening_proof) },
+            },
+            "commitments": {
+                "size": size(commitments),
+                "read_write": commitments
+                    .read_write_values()
+                    .into_iter()
+                    .map(to_hex)
+                    .collect::<Vec<_>>(),
+                "init_final": commitments
+                    .init_final_values()
+                    .into_iter()
+                    .map(to_hex)
+                    .collect::<Vec<_>>(),
+            },
+            "encoded": hex::encode_prefixed(encoded),
+        })
+    }
+
+    /// Restores a proof and its commitments from the output of `to_json`, e.g.
+    /// a stored test vector. Only the `encoded` field is read; the rest of the
+    /// dump is informational.
+    pub fn from_json(
+        value: &Value,
+    ) -> Result<(Self, JoltCommitments<PCS, ProofTranscript>), ProofFormatError> {
+        let version = value["format_version"].as_u64();
+        if version != Some(PROOF_FORMAT_VERSION as u64) {
+            return Err(ProofFormatError::UnsupportedVersion(
+                version.unwrap_or_default() as u32,
+                PROOF_FORMAT_VERSION,
+            ));
+        }
+        let encoded = value["encoded"]
+            .as_str()
+            .ok_or_else(|| ProofFormatError::Json("missing `encoded` field".to_string()))?;
+        let bytes = hex::decode(encoded).map_err(|e| ProofFormatError::Json(e.to_string()))?;
+
+        let mut bytes = bytes.as_slice();
+        let proof = Self::deserialize_compressed(&mut bytes)?;
+        let commitments =
+            JoltCommitments::<PCS, ProofTranscript>::deserialize_compressed(&mut bytes)?;
+        if !bytes.is_empty() {
+            return Err(ProofFormatError::TrailingBytes(bytes.len()));
+        }
+        Ok((proof, commitments))
+    }
+}
@@ -0,0 +1,278 @@
+//! Known-answer test vectors, for validating verifiers implemented independently
+//! of this crate (e.g. in other languages) stage by stage rather than only on
+//! whether they accept a proof. `known_answer_vectors` proves an execution with a
+//! `RecordingTranscript`, which gives the same challenges as `KeccakTranscript`
+//! (and hence the same proof) while logging every operation on the transcript:
+//! each absorbed label, commitment and sumcheck message, and each challenge,
+//! with the transcript's state after it. Should be run on a tiny guest, as the
+//! log grows with the trace.
+
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use alloy_primitives::hex;
+use ark_bn254::Bn254;
+use ark_ec::CurveGroup;
+use ark_serialize::CanonicalSerialize;
+use common::rv_trace::JoltDevice;
+use serde_json::{json, Value};
+
+use crate::field::JoltField;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::commitment::hyperkzg::HyperKZG;
+use crate::utils::errors::ProofVerifyError;
+use crate::utils::transcript::{KeccakTranscript, Transcript};
+
+use super::{Jolt, JoltPreprocessing, JoltTraceStep};
+
+/// Version of the format written by `KnownAnswerVectors::to_json`
+pub const KAT_FORMAT_VERSION: u32 = 1;
+
+/// The commitment scheme of `jolt prove`, over a `RecordingTranscript`
+pub type RecordingPCS = HyperKZG<Bn254, RecordingTranscript>;
+
+type TranscriptLog = Arc<Mutex<Vec<TranscriptEvent>>>;
+
+thread_local! {
+    /// The log of the transcripts created on this thread, while `record` runs
+    static RECORDING: RefCell<Option<TranscriptLog>> = const { RefCell::new(None) };
+}
+
+/// An operation on a `RecordingTranscript`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptEvent {
+    /// The `Transcript` method: `new`, `append_message`, `append_bytes`,
+    /// `append_u64`, `append_scalar`, `append_point` or `challenge_scalar`.
+    /// Vectors are absorbed as their elements, between `begin_append_vector` and
+    /// `end_append_vector` messages, as `KeccakTranscript` does.
+    pub operation: &'static str,
+    /// The label, message or bytes absorbed, the big-endian `u64`, or the scalar
+    /// or point absorbed or challenge squeezed in its compressed canonical encoding
+    pub value: Vec<u8>,
+    /// The transcript's state after the operation
+    pub state: [u8; 32],
+}
+
+/// A `KeccakTranscript` that logs its operations; see the module documentation.
+/// Clones log to the same `TranscriptEvent`s.
+#[derive(Clone)]
+pub struct RecordingTranscript {
+    inner: KeccakTranscript,
+    log: TranscriptLog,
+}
+
+impl RecordingTranscript {
+    fn record(&self, operation: &'static str, value: Vec<u8>) {
+        self.log.lock().unwrap().push(TranscriptEvent {
+            operation,
+            value,
+            state: self.inner.state,
+        });
+    }
+}
+
+fn encode(value: &impl CanonicalSerialize) -> Vec<u8> {
+    let mut bytes = vec![];
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+impl Transcript for RecordingTranscript {
+    fn new(label: &'static [u8]) -> Self {
+        let log = RECORDING.with(|recording| recording.borrow().clone());
+        let transcript = Self {
+            inner: KeccakTranscript::new(label),
+            log: log.unwrap_or_default(),
+        };
+        transcript.record("new", label.to_vec());
+        transcript
+    }
+
+    #[cfg(test)]
+    fn compare_to(&mut self, other: Self) {
+        self.inner.compare_to(other.inner);
+    }
+
+    fn append_message(&mut self, msg: &'static [u8]) {
+        self.inner.append_message(msg);
+        self.record("append_message", msg.to_vec());
+    }
+
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        self.inner.append_bytes(bytes);
+        self.record("append_bytes", bytes.to_vec());
+    }
+
+    fn append_u64(&mut self, x: u64) {
+        self.inner.append_u64(x);
+        self.record("append_u64", x.to_be_bytes().to_vec());
+    }
+
+    fn append_scalar<F: JoltField>(&mut self, scalar: &F) {
+        self.inner.append_scalar(scalar);
+        self.record("append_scalar", encode(scalar));
+    }
+
+    fn append_scalars<F: JoltField>(&mut self, scalars: &[impl Borrow<F>]) {
+        self.append_message(b"begin_append_vector");
+        for scalar in scalars {
+            self.append_scalar(scalar.borrow());
+        }
+        self.append_message(b"end_append_vector");
+    }
+
+    fn append_point<G: CurveGroup>(&mut self, point: &G) {
+        self.inner.append_point(point);
+        self.record("append_point", encode(&point.into_affine()));
+    }
+
+    fn append_points<G: CurveGroup>(&mut self, points: &[G]) {
+        self.append_message(b"begin_append_vector");
+        for point in points {
+            self.append_point(point);
+        }
+        self.append_message(b"end_append_vector");
+    }
+
+    fn challenge_scalar<F: JoltField>(&mut self) -> F {
+        let challenge: F = self.inner.challenge_scalar();
+        self.record("challenge_scalar", encode(&challenge));
+        challenge
+    }
+
+    fn challenge_vector<F: JoltField>(&mut self, len: usize) -> Vec<F> {
+        (0..len).map(|_| self.challenge_scalar()).collect()
+    }
+
+    fn challenge_scalar_powers<F: JoltField>(&mut self, len: usize) -> Vec<F> {
+        let q: F = self.challenge_scalar();
+        let mut q_powers = vec![F::one(); len];
+        for i in 1..len {
+            q_powers[i] = q_powers[i - 1] * q;
+        }
+        q_powers
+    }
+}
+
+/// Runs `f`, returning the log of the `RecordingTranscript`s it creates on this
+/// thread.
+fn record<T>(f: impl FnOnce() -> T) -> (T, Vec<TranscriptEvent>) {
+    let log = TranscriptLog::default();
+    RECORDING.with(|recording| *recording.borrow_mut() = Some(log.clone()));
+    let result = f();
+    RECORDING.with(|recording| *recording.borrow_mut() = None);
+    let events = std::mem::take(&mut *log.lock().unwrap());
+    (result, events)
+}
+
+/// The intermediate values of a proof, see `known_answer_vectors`.
+#[derive(Clone, Debug)]
+pub struct KnownAnswerVectors {
+    /// `JoltProof::to_json` of the proof: its metadata, public I/O, commitments
+    /// and encoding
+    pub proof: Value,
+    /// The operations on the prover's transcripts, in order
+    pub transcript: Vec<TranscriptEvent>,
+}
+
+impl KnownAnswerVectors {
+    pub fn to_json(&self) -> Value {
+        let transcript: Vec<Value> = self
+            .transcript
+            .iter()
+            .map(|event| {
+                json!({
+                    "operation": event.operation,
+                    "value": hex::encode_prefixed(&event.value),
+                    "state": hex::encode_prefixed(event.state),
+                })
+            })
+            .collect();
+        json!({
+            "format_version": KAT_FORMAT_VERSION,
+            "proof": self.proof,
+            "transcript": transcript,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(&self.to_json())?;
+        std::fs::write(path, json)
+    }
+}
+
+/// Proves the execution `trace` with public I/O `program_io` as `Jolt::prove`
+/// does, recording the prover's transcript, and checks that the proof verifies.
+pub fn known_answer_vectors<const C: usize, const M: usize, F, PCS, V>(
+    program_io: JoltDevice,
+    trace: Vec<JoltTraceStep<V::InstructionSet>>,
+    preprocessing: JoltPreprocessing<C, F, PCS, RecordingTranscript>,
+) -> Result<KnownAnswerVectors, ProofVerifyError>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<RecordingTranscript, Field = F>,
+    V: Jolt<F, PCS, C, M, RecordingTranscript>,
+{
+    let ((proof, commitments, _), transcript) =
+        record(|| V::prove(program_io, trace, preprocessing.clone()));
+    let vectors = KnownAnswerVectors {
+        proof: proof.to_json(&commitments),
+        transcript,
+    };
+    V::verify(preprocessing, proof, commitments, None)?;
+    Ok(vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_ec::Group;
+
+    #[test]
+    fn matches_keccak_transcript() {
+        let (keccak, events) = record(|| {
+            let mut recording = RecordingTranscript::new(b"test");
+            let mut keccak = KeccakTranscript::new(b"test");
+            recording.append_scalars(&[Fr::from(3u64), Fr::from(4u64)]);
+            keccak.append_scalars(&[Fr::from(3u64), Fr::from(4u64)]);
+            recording.append_points(&[G1Projective::generator()]);
+            keccak.append_points(&[G1Projective::generator()]);
+            recording.append_u64(7);
+            keccak.append_u64(7);
+            assert_eq!(
+                recording.challenge_scalar_powers::<Fr>(3),
+                keccak.challenge_scalar_powers::<Fr>(3)
+            );
+            keccak
+        });
+
+        let operations: Vec<_> = events.iter().map(|event| event.operation).collect();
+        assert_eq!(
+            operations,
+            [
+                "new",
+                "append_message",
+                "append_scalar",
+                "append_scalar",
+                "append_message",
+                "append_message",
+                "append_point",
+                "append_message",
+                "append_u64",
+                "challenge_scalar",
+            ]
+        );
+        assert_eq!(events[2].value, encode(&Fr::from(3u64)));
+        assert_eq!(events.last().unwrap().state, keccak.state);
+    }
+
+    #[test]
+    fn records_only_while_recording() {
+        let (transcript, _) = record(|| RecordingTranscript::new(b"test"));
+        let unrecorded = RecordingTranscript::new(b"test");
+        assert!(!Arc::ptr_eq(&transcript.log, &unrecorded.log));
+    }
+}
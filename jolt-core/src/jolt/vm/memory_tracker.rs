@@ -0,0 +1,321 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use super::stage_summary::{STAGE_SPAN, SUMCHECK_SPAN};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// A global allocator counting the bytes allocated through it, which
+/// `MemoryTracker` needs to measure anything. Opt in by installing it in the
+/// application's binary:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+/// ```
+pub struct TrackingAllocator;
+
+impl TrackingAllocator {
+    /// Bytes currently allocated
+    pub fn allocated() -> usize {
+        ALLOCATED.load(Ordering::Relaxed)
+    }
+
+    /// Whether the allocator is installed, i.e. has seen any allocations
+    pub fn is_installed() -> bool {
+        PEAK.load(Ordering::Relaxed) > 0
+    }
+
+    /// The most bytes allocated since the last call, after which the high-water
+    /// mark starts again from the bytes currently allocated
+    fn take_peak() -> usize {
+        PEAK.swap(ALLOCATED.load(Ordering::Relaxed), Ordering::Relaxed)
+    }
+
+    fn add(size: usize) {
+        let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+    }
+
+    fn sub(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                Self::add(new_size - layout.size());
+            } else {
+                Self::sub(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// The heap memory used in one `ProverStage` or sumcheck of a proof, in bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StageMemory {
+    /// The stage's name, or `sumchecks/<name>` for the sumchecks of one part of
+    /// the proof (bytecode, instruction lookups, memory or R1CS)
+    pub stage: String,
+    /// Bytes allocated when the stage was entered
+    pub start: usize,
+    /// The most bytes allocated at any point during the stage
+    pub peak: usize,
+    /// Bytes allocated when the stage was exited
+    pub end: usize,
+}
+
+/// Records the allocation high-water mark of each `prover_stage` span, and of
+/// the `prover_sumcheck` spans within them, so that a prover running out of
+/// memory can be pointed at the stage to chunk or stream. Requires
+/// `TrackingAllocator` to be the global allocator; used like `StageSummary`:
+///
+/// ```ignore
+/// let tracker = MemoryTracker::new();
+/// tracing_subscriber::registry().with(tracker.layer()).init();
+/// let (proof, commitments, _) = RV32IJoltVM::prove(io_device, trace, preprocessing);
+/// println!("{tracker}");
+/// ```
+#[derive(Clone, Default)]
+pub struct MemoryTracker {
+    stages: Arc<Mutex<Vec<StageMemory>>>,
+    /// The stages entered but not yet exited, outermost first
+    open: Arc<Mutex<Vec<StageMemory>>>,
+}
+
+impl MemoryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A `tracing` layer recording into this tracker
+    pub fn layer(&self) -> MemoryTrackerLayer {
+        MemoryTrackerLayer {
+            tracker: self.clone(),
+        }
+    }
+
+    /// The stages completed so far, in the order they finished
+    pub fn stages(&self) -> Vec<StageMemory> {
+        self.stages.lock().unwrap().clone()
+    }
+
+    /// Forgets the stages recorded so far, e.g. between proofs
+    pub fn clear(&self) {
+        self.stages.lock().unwrap().clear();
+    }
+
+    /// Attributes the high-water mark since the last stage was entered or
+    /// exited to every stage still open
+    fn flush(open: &mut [StageMemory]) {
+        let peak = TrackingAllocator::take_peak();
+        for stage in open {
+            stage.peak = stage.peak.max(peak);
+        }
+    }
+
+    fn enter(&self, stage: String) {
+        let mut open = self.open.lock().unwrap();
+        Self::flush(&mut open);
+        let start = TrackingAllocator::allocated();
+        open.push(StageMemory {
+            stage,
+            start,
+            peak: start,
+            end: start,
+        });
+    }
+
+    fn exit(&self) {
+        let mut open = self.open.lock().unwrap();
+        Self::flush(&mut open);
+        if let Some(mut stage) = open.pop() {
+            stage.end = TrackingAllocator::allocated();
+            self.stages.lock().unwrap().push(stage);
+        }
+    }
+}
+
+impl fmt::Display for MemoryTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !TrackingAllocator::is_installed() {
+            return write!(f, "memory not tracked: TrackingAllocator is not installed");
+        }
+        writeln!(f, "{:<32} {:>12} {:>12}", "stage", "peak", "growth")?;
+        for stage in self.stages() {
+            writeln!(
+                f,
+                "{:<32} {:>12} {:>12}",
+                stage.stage,
+                Bytes(stage.peak),
+                Bytes(stage.peak - stage.start)
+            )?;
+        }
+        write!(
+            f,
+            "{:<32} {:>12}",
+            "allocated now",
+            Bytes(TrackingAllocator::allocated())
+        )
+    }
+}
+
+struct Bytes(usize);
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.0 as f64;
+        let formatted = if bytes >= 1e9 {
+            format!("{:.2} GB", bytes / 1e9)
+        } else {
+            format!("{:.2} MB", bytes / 1e6)
+        };
+        f.pad(&formatted)
+    }
+}
+
+/// See `MemoryTracker::layer`
+pub struct MemoryTrackerLayer {
+    tracker: MemoryTracker,
+}
+
+/// The name of a tracked span, stored in its extensions
+struct TrackedStage(String);
+
+/// Reads the `stage` or `sumcheck` field of a span
+#[derive(Default)]
+struct StageName(Option<String>);
+
+impl Visit for StageName {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "stage" || field.name() == "sumcheck" {
+            self.0 = Some(format!("{:?}", value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "stage" || field.name() == "sumcheck" {
+            self.0 = Some(value.to_string());
+        }
+    }
+}
+
+impl<S> Layer<S> for MemoryTrackerLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if name != STAGE_SPAN && name != SUMCHECK_SPAN {
+            return;
+        }
+        let mut stage = StageName::default();
+        attrs.record(&mut stage);
+        let mut stage = stage.0.unwrap_or_default();
+        if name == SUMCHECK_SPAN {
+            stage = format!("sumchecks/{}", stage);
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(TrackedStage(stage));
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(stage) = span.extensions().get::<TrackedStage>() {
+                self.tracker.enter(stage.0.clone());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if span.extensions().get::<TrackedStage>().is_some() {
+                self.tracker.exit();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn memory_tracker() {
+        let tracker = MemoryTracker::new();
+        let subscriber = tracing_subscriber::registry().with(tracker.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let _stage = tracing::info_span!(STAGE_SPAN, stage = "sumchecks").entered();
+            let _sumcheck = tracing::info_span!(SUMCHECK_SPAN, sumcheck = "bytecode").entered();
+        });
+
+        // Inner stages finish first
+        let stages = tracker.stages();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].stage, "sumchecks/bytecode");
+        assert_eq!(stages[1].stage, "sumchecks");
+        assert!(stages[1].peak >= stages[0].peak);
+
+        tracker.clear();
+        assert!(tracker.stages().is_empty());
+    }
+
+    #[test]
+    fn peaks_propagate_to_open_stages() {
+        let mut open = vec![
+            StageMemory {
+                stage: "outer".to_string(),
+                start: 0,
+                peak: 0,
+                end: 0,
+            },
+            StageMemory {
+                stage: "inner".to_string(),
+                start: 0,
+                peak: 0,
+                end: 0,
+            },
+        ];
+        TrackingAllocator::add(1 << 20);
+        TrackingAllocator::sub(1 << 20);
+        MemoryTracker::flush(&mut open);
+        assert!(open.iter().all(|stage| stage.peak >= 1 << 20));
+    }
+}
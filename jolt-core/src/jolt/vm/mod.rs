@@ -2,16 +2,25 @@
 #![allow(dead_code)]
 
 use crate::field::JoltField;
+use crate::poly::compact_polynomial::CompactPolynomial;
 use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::poly::opening_proof::{
     ProverOpeningAccumulator, ReducedOpeningProof, VerifierOpeningAccumulator,
 };
+use crate::r1cs::builder::{CombinedUniformBuilder, ConstraintViolation};
 use crate::r1cs::constraints::R1CSConstraints;
+use crate::r1cs::export;
 use crate::r1cs::spartan::{self, UniformSpartanProof};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use crate::utils::parallel::{self, prelude::*};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use common::rv_trace::{MemoryLayout, NUM_CIRCUIT_FLAGS};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::io::Read;
 use std::marker::PhantomData;
+use std::path::Path;
 use strum::EnumCount;
 use timestamp_range_check::TimestampRangeCheckStuff;
 
@@ -29,9 +38,16 @@ use crate::lasso::memory_checking::{
     Initializable, MemoryCheckingProver, MemoryCheckingVerifier, StructuredPolynomialData,
 };
 use crate::msm::icicle;
-use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::commitment::commitment_scheme::{
+    AccumulationScheme, CommitmentScheme, OpeningInstance,
+};
+use crate::poly::commitment::setup_cache::SetupCache;
 use crate::r1cs::inputs::{ConstraintInput, R1CSPolynomials, R1CSProof, R1CSStuff};
-use crate::utils::errors::ProofVerifyError;
+use crate::r1cs::key::{SpartanKeyCache, UniformSpartanKey};
+use crate::utils::canonical_serde::impl_canonical_serde;
+use crate::utils::errors::{ConfigError, ProofVerifyError, ProvingCancelled, SecurityError};
+use crate::utils::math::Math;
+use crate::utils::pool::ScratchPool;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use common::{
@@ -40,13 +56,20 @@ use common::{
 };
 
 use self::bytecode::{BytecodePreprocessing, BytecodeProof, BytecodeRow, BytecodeStuff};
+use self::config::ProverConfig;
+use self::cost::{CostReport, VerificationCost};
 use self::instruction_lookups::{
     InstructionLookupStuff, InstructionLookupsPreprocessing, InstructionLookupsProof,
 };
+use self::progress::{ProverProgress, ProverStage};
 use self::read_write_memory::{
+    input_polynomial, io_region_size, MemoryArgument, MemoryImage, MemoryPages, MemoryState,
     ReadWriteMemoryPolynomials, ReadWriteMemoryPreprocessing, ReadWriteMemoryProof,
     ReadWriteMemoryStuff,
 };
+use self::security::{SecurityConfig, SoundnessReport};
+use self::segment::{Segment, SegmentBoundary};
+use self::stage_summary::{STAGE_SPAN, SUMCHECK_SPAN};
 
 use super::instruction::lb::LBInstruction;
 use super::instruction::lbu::LBUInstruction;
@@ -68,7 +91,271 @@ where
     pub bytecode: BytecodePreprocessing<F>,
     pub read_write_memory: ReadWriteMemoryPreprocessing,
     pub memory_layout: MemoryLayout,
+    /// Hash of the program this preprocessing was derived from, which identifies it in
+    /// verifier keys and proofs; see `ProgramPreprocessing`.
+    pub program_hash: [u8; 32],
+    /// If set, proofs commit to the program, and are checked against this commitment
+    /// rather than the bytecode and memory image (see `Jolt::commit_program`).
+    pub program_commitment: Option<ProgramCommitment<PCS, ProofTranscript>>,
+    /// Digest of the `VerifierKey` proofs made with this preprocessing are bound
+    /// to, and that the proofs it verifies must be bound to: that of the program
+    /// commitment and maximum sizes once the program is committed to
+    /// (`Jolt::commit_program`), and otherwise a digest of the program hash and
+    /// maximum sizes (see `unbound_verifier_key_digest`).
+    pub verifier_key_digest: [u8; 32],
+    /// The maximum sizes the PCS setup was created with
+    max_bytecode_size: usize,
+    max_memory_address: usize,
+    max_trace_length: usize,
     field: F::SmallValueLookupTables,
+    /// Spartan keys derived for the trace lengths proven or verified so far,
+    /// shared with clones, e.g. those of a prover proving many inputs
+    spartan_keys: SpartanKeyCache,
+}
+
+impl<const C: usize, F, PCS, ProofTranscript> JoltPreprocessing<C, F, PCS, ProofTranscript>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    /// The `VerifierKey` proofs made with this preprocessing are bound to, once
+    /// it commits to the program (see `Jolt::bind_verifier_key`)
+    pub fn verifier_key(&self) -> Option<VerifierKey<PCS, ProofTranscript>> {
+        let program_commitment = self.program_commitment.clone()?;
+        Some(VerifierKey {
+            program_commitment,
+            max_bytecode_size: self.max_bytecode_size,
+            max_memory_address: self.max_memory_address,
+            max_trace_length: self.max_trace_length,
+        })
+    }
+}
+
+/// A succinct commitment to a program: PCS commitments to its preprocessed
+/// bytecode and initial memory image. Light verifiers that only know a program
+/// by the `digest` of its commitment can verify proofs of it without the ELF or
+/// the full preprocessing (see `Jolt::preprocess_light`); the proof opens the
+/// committed polynomials wherever the verifier would otherwise evaluate them.
+/// Since guest builds are reproducible, anyone with the guest's sources can
+/// check a commitment by rebuilding the program and committing to it again.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProgramCommitment<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    /// `ProgramPreprocessing::program_hash` of the committed program
+    pub program_hash: [u8; 32],
+    pub memory_layout: MemoryLayout,
+    /// Size of the (padded) bytecode
+    pub code_size: usize,
+    /// Commitments to the six bytecode `v_init_final` polynomials
+    pub bytecode: Vec<PCS::Commitment>,
+    pub memory_image_pages: MemoryImage,
+    /// Commitment to the initial memory image, laid out as in `v_init`
+    pub memory_image: PCS::Commitment,
+}
+
+impl_canonical_serde!(
+    [PCS, ProofTranscript] ProgramCommitment<PCS, ProofTranscript>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+);
+
+impl<PCS, ProofTranscript> ProgramCommitment<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    /// Sha3-256 of the serialized commitment, which binds the program hash to the
+    /// committed polynomials.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut bytes = vec![];
+        self.serialize_compressed(&mut bytes).unwrap();
+        Sha3_256::digest(bytes).into()
+    }
+}
+
+/// A succinct commitment to a program's inputs: the PCS commitment to them, laid
+/// out as in `v_init` (see `read_write_memory::input_polynomial`). Proofs made
+/// after `Jolt::commit_inputs` carry this commitment rather than the inputs, and
+/// verifiers that know the inputs only by the commitment's `digest` check them
+/// at a cost independent of their size (see `Jolt::verify_with_input_digest`).
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct InputCommitment<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    pub commitment: PCS::Commitment,
+}
+
+impl_canonical_serde!(
+    [PCS, ProofTranscript] InputCommitment<PCS, ProofTranscript>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+);
+
+impl<PCS, ProofTranscript> InputCommitment<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    /// Commits to `inputs`, as the prover does. Costs time linear in the I/O
+    /// region, once, e.g. for whoever publishes the inputs.
+    pub fn new(inputs: &[u8], memory_layout: &MemoryLayout, generators: &PCS::Setup) -> Self {
+        let words = input_polynomial(inputs, memory_layout);
+        Self {
+            commitment: PCS::commit(&MultilinearPolynomial::from(words), generators),
+        }
+    }
+
+    /// Sha3-256 of the serialized commitment, which identifies the inputs.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut bytes = vec![];
+        self.serialize_compressed(&mut bytes).unwrap();
+        Sha3_256::digest(bytes).into()
+    }
+}
+
+/// Everything a verifier needs to check proofs of a program, distributable
+/// independently of the prover's preprocessing: the `ProgramCommitment` and the
+/// maximum sizes the PCS setup was created with. The lookup tables are fixed by
+/// the instruction set and the PCS setup is derived deterministically from the
+/// sizes (see `pcs_setup`), so neither is included. See `Jolt::bind_verifier_key`.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierKey<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    pub program_commitment: ProgramCommitment<PCS, ProofTranscript>,
+    pub max_bytecode_size: usize,
+    pub max_memory_address: usize,
+    pub max_trace_length: usize,
+}
+
+impl_canonical_serde!(
+    [PCS, ProofTranscript] VerifierKey<PCS, ProofTranscript>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+);
+
+impl<PCS, ProofTranscript> VerifierKey<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    /// Identifies the program, see `ProgramPreprocessing::program_hash`. Does not
+    /// depend on the maximum sizes.
+    pub fn program_hash(&self) -> [u8; 32] {
+        self.program_commitment.program_hash
+    }
+
+    /// Sha3-256 of the serialized key, which proofs for it are bound to (see
+    /// `ProofMetadata::verifier_key_digest`).
+    pub fn digest(&self) -> [u8; 32] {
+        let mut bytes = vec![];
+        self.serialize_compressed(&mut bytes).unwrap();
+        Sha3_256::digest(bytes).into()
+    }
+}
+
+/// The program-dependent part of Jolt preprocessing: a guest's bytecode (with
+/// virtual sequences already expanded) and initial memory, identified by a hash
+/// of the program. This can be serialized and reused across provings of the
+/// same program, skipping compilation, decoding and bytecode expansion. See
+/// `Jolt::preprocess_program`. It is `Serializable`; validated deserialization
+/// (e.g. `from_file`) recomputes the hash and rejects a tampered file.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize)]
+pub struct ProgramPreprocessing {
+    pub program_hash: [u8; 32],
+    pub memory_layout: MemoryLayout,
+    bytecode: Vec<BytecodeRow>,
+    memory_init: Vec<(u64, u8)>,
+}
+
+impl ProgramPreprocessing {
+    #[tracing::instrument(skip_all, name = "ProgramPreprocessing::new")]
+    pub fn new<InstructionSet: JoltInstructionSet>(
+        bytecode: Vec<ELFInstruction>,
+        memory_layout: MemoryLayout,
+        memory_init: Vec<(u64, u8)>,
+    ) -> Self {
+        let bytecode: Vec<BytecodeRow> = bytecode
+            .into_iter()
+            .flat_map(|instruction| match instruction.opcode {
+                tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::REM => REMInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::SH => SHInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::SB => SBInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::LB => LBInstruction::<32>::virtual_sequence(instruction),
+                tracer::RV32IM::LH => LHInstruction::<32>::virtual_sequence(instruction),
+                _ => vec![instruction],
+            })
+            .map(|instruction| BytecodeRow::from_instruction::<InstructionSet>(&instruction))
+            .collect();
+        let program_hash = Self::hash(&bytecode, &memory_layout, &memory_init);
+
+        Self {
+            program_hash,
+            memory_layout,
+            bytecode,
+            memory_init,
+        }
+    }
+
+    /// Sha3-256 of the serialized (expanded) bytecode, memory layout and
+    /// initial memory, i.e. of everything else in the `ProgramPreprocessing`.
+    fn hash(
+        bytecode: &[BytecodeRow],
+        memory_layout: &MemoryLayout,
+        memory_init: &[(u64, u8)],
+    ) -> [u8; 32] {
+        let mut bytes = vec![];
+        bytecode.serialize_compressed(&mut bytes).unwrap();
+        memory_layout.serialize_compressed(&mut bytes).unwrap();
+        memory_init.serialize_compressed(&mut bytes).unwrap();
+        Sha3_256::digest(bytes).into()
+    }
+}
+
+impl Valid for ProgramPreprocessing {
+    fn check(&self) -> Result<(), SerializationError> {
+        if Self::hash(&self.bytecode, &self.memory_layout, &self.memory_init) != self.program_hash {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for ProgramPreprocessing {
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let program = Self {
+            program_hash: <[u8; 32]>::deserialize_with_mode(&mut reader, compress, validate)?,
+            memory_layout: MemoryLayout::deserialize_with_mode(&mut reader, compress, validate)?,
+            bytecode: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            memory_init: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        if let Validate::Yes = validate {
+            program.check()?;
+        }
+        Ok(program)
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -77,6 +364,9 @@ pub struct JoltTraceStep<InstructionSet: JoltInstructionSet> {
     pub bytecode_row: BytecodeRow,
     pub memory_ops: [MemoryOp; MEMORY_OPS_PER_INSTRUCTION],
     pub circuit_flags: [bool; NUM_CIRCUIT_FLAGS],
+    /// Inputs to a precompile invoked at this step, for use by the precompile's
+    /// witness generation (see `CustomConstraints::witness`)
+    pub precompile_input: Option<Box<[u32; 16]>>,
 }
 
 pub struct ProverDebugInfo<F, ProofTranscript>
@@ -100,6 +390,7 @@ impl<InstructionSet: JoltInstructionSet> JoltTraceStep<InstructionSet> {
                 MemoryOp::noop_read(),  // RAM
             ],
             circuit_flags: [false; NUM_CIRCUIT_FLAGS],
+            precompile_input: None,
         }
     }
 
@@ -120,6 +411,7 @@ pub struct JoltProof<
     InstructionSet,
     Subtables,
     ProofTranscript,
+    MemoryProof = ReadWriteMemoryProof<F, PCS, ProofTranscript>,
 > where
     I: ConstraintInput,
     F: JoltField,
@@ -127,17 +419,213 @@ pub struct JoltProof<
     InstructionSet: JoltInstructionSet,
     Subtables: JoltSubtableSet<F>,
     ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
 {
+    pub metadata: ProofMetadata,
     pub trace_length: usize,
     pub program_io: JoltDevice,
+    pub memory_pages: MemoryPages,
     pub bytecode: BytecodeProof<F, PCS, ProofTranscript>,
-    pub read_write_memory: ReadWriteMemoryProof<F, PCS, ProofTranscript>,
+    pub read_write_memory: MemoryProof,
     pub instruction_lookups:
         InstructionLookupsProof<C, M, F, PCS, InstructionSet, Subtables, ProofTranscript>,
     pub r1cs: UniformSpartanProof<C, I, F, ProofTranscript>,
+    pub segment_boundary: SegmentBoundary<F>,
+    /// `None` for proofs in an `AggregatedProof`, whose opening proof covers them
+    pub opening_proof: Option<ReducedOpeningProof<F, PCS, ProofTranscript>>,
+}
+
+impl_canonical_serde!(
+    [
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    ]
+    JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+    where
+        I: ConstraintInput,
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        InstructionSet: JoltInstructionSet,
+        Subtables: JoltSubtableSet<F>,
+        ProofTranscript: Transcript,
+        MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+);
+
+/// Proof of an execution split into segments, each proven as a continuation of
+/// the previous one (see `Jolt::prove_segmented`).
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct ChainedProof<
+    const C: usize,
+    const M: usize,
+    I,
+    F,
+    PCS,
+    InstructionSet,
+    Subtables,
+    ProofTranscript,
+    MemoryProof = ReadWriteMemoryProof<F, PCS, ProofTranscript>,
+> where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    pub proofs:
+        Vec<JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>>,
+    /// Commitments of each segment, in order
+    pub commitments: Vec<JoltCommitments<PCS, ProofTranscript>>,
+}
+
+impl_canonical_serde!(
+    [
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    ]
+    ChainedProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+    where
+        I: ConstraintInput,
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        InstructionSet: JoltInstructionSet,
+        Subtables: JoltSubtableSet<F>,
+        ProofTranscript: Transcript,
+        MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+);
+
+/// Proofs of several executions, possibly of different programs, whose openings
+/// are all proven by one batched opening proof (see `Jolt::prove_aggregated`).
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct AggregatedProof<
+    const C: usize,
+    const M: usize,
+    I,
+    F,
+    PCS,
+    InstructionSet,
+    Subtables,
+    ProofTranscript,
+    MemoryProof = ReadWriteMemoryProof<F, PCS, ProofTranscript>,
+> where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    pub proofs:
+        Vec<JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>>,
     pub opening_proof: ReducedOpeningProof<F, PCS, ProofTranscript>,
 }
 
+impl_canonical_serde!(
+    [
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    ]
+    AggregatedProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+    where
+        I: ConstraintInput,
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        InstructionSet: JoltInstructionSet,
+        Subtables: JoltSubtableSet<F>,
+        ProofTranscript: Transcript,
+        MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+);
+
+/// What a proof was made for: the program, the verifier key, the version of Jolt
+/// and the configuration it was made with (see `Jolt::proof_metadata`). It is
+/// absorbed into the transcript before anything else, with the public I/O, so it
+/// cannot be changed without invalidating the proof, and the verifier checks it
+/// first, so that a proof of another program or key, or made with another
+/// version or configuration, is rejected with a precise error.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProofMetadata {
+    /// `JoltPreprocessing::program_hash`
+    pub program_hash: [u8; 32],
+    /// `JoltPreprocessing::verifier_key_digest`
+    pub verifier_key_digest: [u8; 32],
+    /// Version of the `jolt-core` crate that made the proof
+    pub version: String,
+    /// Hash of the commitment scheme, parameters and instruction set of the VM,
+    /// the memory layout, and the digest of the program commitment for proofs that
+    /// commit to the program
+    pub config_digest: [u8; 32],
+}
+
+impl ProofMetadata {
+    fn append_to_transcript<ProofTranscript: Transcript>(&self, transcript: &mut ProofTranscript) {
+        transcript.append_bytes(&self.program_hash);
+        transcript.append_bytes(&self.verifier_key_digest);
+        transcript.append_bytes(self.version.as_bytes());
+        transcript.append_bytes(&self.config_digest);
+    }
+
+    /// Checks that the proof with this metadata was made for `expected`
+    fn check(&self, expected: &ProofMetadata) -> Result<(), ProofVerifyError> {
+        if self.version != expected.version {
+            return Err(ProofVerifyError::VersionMismatch(
+                self.version.clone(),
+                expected.version.clone(),
+            ));
+        }
+        if self.program_hash != expected.program_hash {
+            return Err(ProofVerifyError::ProgramMismatch);
+        }
+        if self.verifier_key_digest != expected.verifier_key_digest {
+            return Err(ProofVerifyError::VerifierKeyMismatch);
+        }
+        if self.config_digest != expected.config_digest {
+            return Err(ProofVerifyError::ConfigMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// The result of `Jolt::prove_dev`: an execution whose witness was checked
+/// against the constraints but not proven. It is NOT a proof, and `verify`
+/// always rejects it; it only stands in for one while developing an
+/// application.
+#[derive(Clone, Debug)]
+pub struct DevProof {
+    pub trace_length: usize,
+    pub program_io: JoltDevice,
+    pub program_hash: [u8; 32],
+}
+
+impl DevProof {
+    /// Always fails: dev-mode proofs attest to nothing.
+    pub fn verify(&self) -> Result<(), ProofVerifyError> {
+        Err(ProofVerifyError::DevProof)
+    }
+}
+
 #[derive(Default, CanonicalSerialize, CanonicalDeserialize)]
 pub struct JoltStuff<T: CanonicalSerialize + CanonicalDeserialize + Sync> {
     pub(crate) bytecode: BytecodeStuff<T>,
@@ -147,6 +635,8 @@ pub struct JoltStuff<T: CanonicalSerialize + CanonicalDeserialize + Sync> {
     pub(crate) r1cs: R1CSStuff<T>,
 }
 
+impl_canonical_serde!([T: CanonicalSerialize + CanonicalDeserialize + Sync] JoltStuff<T>);
+
 impl<T: CanonicalSerialize + CanonicalDeserialize + Sync> StructuredPolynomialData<T>
     for JoltStuff<T>
 {
@@ -232,6 +722,31 @@ impl<
 }
 
 impl<F: JoltField> JoltPolynomials<F> {
+    /// Drops the timestamp range-check polynomials. They are as large as the
+    /// memory polynomials but only needed to commit to them and in the memory
+    /// sumchecks, and cheap to regenerate from the memory polynomials (see
+    /// `materialize_timestamp_range_check`), so the prover only keeps them
+    /// around for those steps. They are the only witness polynomials that are
+    /// materialized on demand: all the others are generated up front and kept
+    /// until the end of `prove`.
+    pub fn drop_timestamp_range_check(&mut self) {
+        drop_in_background_thread(std::mem::take(&mut self.timestamp_range_check));
+    }
+
+    /// Regenerates the timestamp range-check polynomials dropped by
+    /// `drop_timestamp_range_check`
+    #[tracing::instrument(skip_all, name = "JoltPolynomials::materialize_timestamp_range_check")]
+    pub fn materialize_timestamp_range_check<PCS, ProofTranscript>(&mut self)
+    where
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        self.timestamp_range_check =
+            TimestampValidityProof::<F, PCS, ProofTranscript>::generate_witness(
+                &self.read_write_memory,
+            );
+    }
+
     #[tracing::instrument(skip_all, name = "JoltPolynomials::commit")]
     pub fn commit<const C: usize, PCS, ProofTranscript>(
         &self,
@@ -244,6 +759,9 @@ impl<F: JoltField> JoltPolynomials<F> {
         let span = tracing::span!(tracing::Level::INFO, "commit::initialize");
         let _guard = span.enter();
         let mut commitments = JoltCommitments::<PCS, ProofTranscript>::initialize(preprocessing);
+        commitments.r1cs.aux.custom = std::iter::repeat_with(PCS::Commitment::default)
+            .take(self.r1cs.aux.custom.len())
+            .collect();
         drop(_guard);
         drop(span);
 
@@ -256,6 +774,41 @@ impl<F: JoltField> JoltPolynomials<F> {
             .zip(trace_commitments.into_iter())
             .for_each(|(dest, src)| *dest = src);
 
+        self.commit_init_final_values(&mut commitments, preprocessing);
+        commitments
+    }
+
+    /// Completes the commitments of `Jolt::generate_witness_pipelined`, which
+    /// commits to the read/write values of all but the R1CS inputs, by
+    /// committing to the R1CS inputs and the init/final values.
+    #[tracing::instrument(skip_all, name = "JoltPolynomials::commit_remaining")]
+    pub fn commit_remaining<const C: usize, PCS, ProofTranscript>(
+        &self,
+        commitments: &mut JoltCommitments<PCS, ProofTranscript>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) where
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        commitments.r1cs.aux.custom = std::iter::repeat_with(PCS::Commitment::default)
+            .take(self.r1cs.aux.custom.len())
+            .collect();
+        commit_read_write_values::<F, PCS, ProofTranscript, _, _>(
+            &self.r1cs,
+            Some(&mut commitments.r1cs),
+            &preprocessing.generators,
+        );
+        self.commit_init_final_values(commitments, preprocessing);
+    }
+
+    fn commit_init_final_values<const C: usize, PCS, ProofTranscript>(
+        &self,
+        commitments: &mut JoltCommitments<PCS, ProofTranscript>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) where
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
         let span = tracing::span!(tracing::Level::INFO, "commit::t_final");
         let _guard = span.enter();
         commitments.bytecode.t_final =
@@ -272,14 +825,81 @@ impl<F: JoltField> JoltPolynomials<F> {
             || PCS::commit(&self.read_write_memory.v_final, &preprocessing.generators),
             || PCS::commit(&self.read_write_memory.t_final, &preprocessing.generators)
         );
+        if let Some(v_final_previous) = &self.read_write_memory.v_final_previous {
+            commitments.read_write_memory.v_final_previous =
+                Some(PCS::commit(v_final_previous, &preprocessing.generators));
+        }
+        if let Some(v_inputs) = &self.read_write_memory.v_inputs {
+            // Committed without the trailing zeros beyond the I/O region, so
+            // that the commitment is the `InputCommitment` to the inputs
+            let v_inputs: &CompactPolynomial<u32, F> = v_inputs.try_into().unwrap();
+            let io_region = io_region_size(&preprocessing.memory_layout);
+            commitments.read_write_memory.v_inputs = Some(PCS::commit(
+                &MultilinearPolynomial::from(v_inputs.coeffs[..io_region].to_vec()),
+                &preprocessing.generators,
+            ));
+        }
+        // The program's polynomials are the same in every proof, so their
+        // commitments are those of the program commitment, if there is one.
+        // The memory image is laid out as committed only if the execution
+        // touches the image's pages, which `check_program_commitment` checks.
+        let program_commitment = preprocessing.program_commitment.as_ref();
+        if let Some(v_image) = &self.read_write_memory.v_image {
+            let committed_image = program_commitment.filter(|program_commitment| {
+                preprocessing
+                    .read_write_memory
+                    .pages
+                    .check_image(
+                        &program_commitment.memory_image_pages,
+                        &program_commitment.memory_layout,
+                    )
+                    .is_ok()
+            });
+            commitments.read_write_memory.v_image = Some(match committed_image {
+                Some(program_commitment) => program_commitment.memory_image.clone(),
+                None => PCS::commit(v_image, &preprocessing.generators),
+            });
+        }
+        if let Some(v_program) = &self.bytecode.v_program {
+            commitments.bytecode.v_program = Some(match program_commitment {
+                Some(program_commitment) => program_commitment.bytecode.clone().try_into().unwrap(),
+                None => {
+                    let v_program: Vec<_> = v_program.iter().collect();
+                    PCS::batch_commit(&v_program, &preprocessing.generators)
+                        .try_into()
+                        .unwrap()
+                }
+            });
+        }
         commitments.instruction_lookups.final_cts = PCS::batch_commit(
             &self.instruction_lookups.final_cts,
             &preprocessing.generators,
         );
         drop(_guard);
         drop(span);
+    }
+}
 
+/// Commits to the read/write values of `polynomials`, one part of the witness,
+/// into `commitments`, the same part of `JoltCommitments`, if it is given
+fn commit_read_write_values<F, PCS, ProofTranscript, P, S>(
+    polynomials: &P,
+    commitments: Option<&mut S>,
+    generators: &PCS::Setup,
+) where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+    P: StructuredPolynomialData<MultilinearPolynomial<F>>,
+    S: StructuredPolynomialData<PCS::Commitment>,
+{
+    if let Some(commitments) = commitments {
+        let polynomials = polynomials.read_write_values();
         commitments
+            .read_write_values_mut()
+            .into_iter()
+            .zip(PCS::batch_commit(&polynomials, generators))
+            .for_each(|(dest, src)| *dest = src);
     }
 }
 
@@ -292,6 +912,8 @@ where
     type InstructionSet: JoltInstructionSet;
     type Subtables: JoltSubtableSet<F>;
     type Constraints: R1CSConstraints<C, F>;
+    /// The memory consistency argument for registers and RAM
+    type Memory: MemoryArgument<F, PCS, ProofTranscript>;
 
     #[tracing::instrument(skip_all, name = "Jolt::preprocess")]
     fn preprocess(
@@ -301,6 +923,50 @@ where
         max_bytecode_size: usize,
         max_memory_address: usize,
         max_trace_length: usize,
+    ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
+        let program =
+            ProgramPreprocessing::new::<Self::InstructionSet>(bytecode, memory_layout, memory_init);
+        Self::preprocess_program(
+            program,
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
+        )
+    }
+
+    /// Like `preprocess`, but starting from a (possibly cached) `ProgramPreprocessing`.
+    #[tracing::instrument(skip_all, name = "Jolt::preprocess_program")]
+    fn preprocess_program(
+        program: ProgramPreprocessing,
+        max_bytecode_size: usize,
+        max_memory_address: usize,
+        max_trace_length: usize,
+    ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
+        icicle::icicle_init();
+        let generators = pcs_setup::<PCS, ProofTranscript>(
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
+            M,
+            SetupCache::from_env().as_ref(),
+        );
+        Self::preprocess_with_generators(
+            program,
+            generators,
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
+        )
+    }
+
+    /// Like `preprocess_program`, with an existing PCS setup, e.g. one loaded
+    /// from a `SetupCache`, created with the given maximum sizes
+    fn preprocess_with_generators(
+        program: ProgramPreprocessing,
+        generators: PCS::Setup,
+        max_bytecode_size: usize,
+        max_memory_address: usize,
+        max_trace_length: usize,
     ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
         let small_value_lookup_tables = F::compute_lookup_tables();
         F::initialize_lookup_tables(small_value_lookup_tables.clone());
@@ -312,55 +978,209 @@ where
             Self::Subtables,
         >();
 
-        let read_write_memory_preprocessing = ReadWriteMemoryPreprocessing::preprocess(memory_init);
-
-        let bytecode_rows: Vec<BytecodeRow> = bytecode
-            .into_iter()
-            .flat_map(|instruction| match instruction.opcode {
-                tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::REM => REMInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::SH => SHInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::SB => SBInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LB => LBInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LH => LHInstruction::<32>::virtual_sequence(instruction),
-                _ => vec![instruction],
-            })
-            .map(|instruction| BytecodeRow::from_instruction::<Self::InstructionSet>(&instruction))
-            .collect();
-        let bytecode_preprocessing = BytecodePreprocessing::<F>::preprocess(bytecode_rows);
-
-        let max_poly_len: usize = [
-            (max_bytecode_size + 1).next_power_of_two(), // Account for no-op prepended to bytecode
-            max_trace_length.next_power_of_two(),
-            max_memory_address.next_power_of_two(),
-            M,
-        ]
-        .into_iter()
-        .max()
-        .unwrap();
-        let generators = PCS::setup(max_poly_len);
+        let read_write_memory_preprocessing =
+            ReadWriteMemoryPreprocessing::preprocess(program.memory_init);
+        let bytecode_preprocessing = BytecodePreprocessing::<F>::preprocess(program.bytecode);
 
         JoltPreprocessing {
             generators,
-            memory_layout,
+            memory_layout: program.memory_layout,
+            program_hash: program.program_hash,
+            program_commitment: None,
+            verifier_key_digest: unbound_verifier_key_digest(
+                &program.program_hash,
+                max_bytecode_size,
+                max_memory_address,
+                max_trace_length,
+            ),
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
             instruction_lookups: instruction_lookups_preprocessing,
             bytecode: bytecode_preprocessing,
             read_write_memory: read_write_memory_preprocessing,
             field: small_value_lookup_tables,
+            spartan_keys: SpartanKeyCache::default(),
         }
     }
 
+    /// Like `preprocess_program`, with the maximum sizes of `config`. Fails if
+    /// `config` requires a different commitment scheme than `PCS`.
+    fn preprocess_with_config(
+        program: ProgramPreprocessing,
+        config: &ProverConfig,
+    ) -> Result<JoltPreprocessing<C, F, PCS, ProofTranscript>, ConfigError> {
+        config.check_pcs::<PCS, ProofTranscript>()?;
+        Ok(config.install(|| {
+            icicle::icicle_init();
+            let generators = pcs_setup::<PCS, ProofTranscript>(
+                config.max_bytecode_size(),
+                config.max_memory_address(),
+                config.max_trace_length(),
+                M,
+                config.setup_cache(),
+            );
+            Self::preprocess_with_generators(
+                program,
+                generators,
+                config.max_bytecode_size(),
+                config.max_memory_address(),
+                config.max_trace_length(),
+            )
+        }))
+    }
+
+    /// Commits to the program (see `ProgramCommitment`) and sets up `preprocessing`
+    /// so that subsequent proofs can be checked against the commitment by light
+    /// verifiers. Such proofs are slightly larger, are bound to the program's
+    /// `VerifierKey`, and must be verified with preprocessing from
+    /// `preprocess_light` or `preprocess_verifier`.
+    #[tracing::instrument(skip_all, name = "Jolt::commit_program")]
+    fn commit_program(
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> ProgramCommitment<PCS, ProofTranscript> {
+        let memory_layout = &preprocessing.memory_layout;
+        let v_init_final: Vec<_> = preprocessing.bytecode.v_init_final().iter().collect();
+        let bytecode = PCS::batch_commit(&v_init_final, &preprocessing.generators);
+
+        let rw_preprocessing = &preprocessing.read_write_memory;
+        let memory_image_pages = rw_preprocessing.memory_image_pages(memory_layout);
+        let memory_image = PCS::commit(
+            &MultilinearPolynomial::from(rw_preprocessing.memory_image_polynomial(memory_layout)),
+            &preprocessing.generators,
+        );
+
+        let program_commitment = ProgramCommitment {
+            program_hash: preprocessing.program_hash,
+            memory_layout: memory_layout.clone(),
+            code_size: preprocessing.bytecode.code_size(),
+            bytecode,
+            memory_image_pages,
+            memory_image,
+        };
+        preprocessing.bytecode.commit_program = true;
+        preprocessing.read_write_memory.memory_image = Some(memory_image_pages);
+        preprocessing.program_commitment = Some(program_commitment.clone());
+        preprocessing.verifier_key_digest = preprocessing.verifier_key().unwrap().digest();
+        program_commitment
+    }
+
+    /// Sets up `preprocessing` so that subsequent proofs commit to their inputs
+    /// rather than carrying them (see `InputCommitment`), for guests with large
+    /// public inputs. Such proofs are checked with `verify_with_input_digest`;
+    /// other verification methods reject them. Not supported for segmented proofs.
+    fn commit_inputs(preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>) {
+        preprocessing.read_write_memory.commit_inputs = true;
+    }
+
+    /// The `InputCommitment` that proofs made with `preprocessing` (after
+    /// `commit_inputs`) on `inputs` carry.
+    fn input_commitment(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        inputs: &[u8],
+    ) -> InputCommitment<PCS, ProofTranscript> {
+        InputCommitment::new(
+            inputs,
+            &preprocessing.memory_layout,
+            &preprocessing.generators,
+        )
+    }
+
+    /// Binds proofs made with `preprocessing` from now on to the `VerifierKey` it
+    /// returns, committing to the program first if `commit_program` has not been
+    /// called on it. Such proofs only verify with preprocessing from the key (see
+    /// `preprocess_verifier`); proofs made before the program was committed to
+    /// are bound to the program hash and maximum sizes instead, and are rejected
+    /// by it.
+    fn bind_verifier_key(
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> VerifierKey<PCS, ProofTranscript> {
+        if preprocessing.program_commitment.is_none() {
+            Self::commit_program(preprocessing);
+        }
+        preprocessing.verifier_key().unwrap()
+    }
+
+    /// `bind_verifier_key` for `preprocessing` from `preprocess_with_config(_, config)`
+    fn bind_verifier_key_with_config(
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        config: &ProverConfig,
+    ) -> VerifierKey<PCS, ProofTranscript> {
+        config.install(|| Self::bind_verifier_key(preprocessing))
+    }
+
+    /// Verifier preprocessing from a `VerifierKey`, see `preprocess_light`.
+    fn preprocess_verifier(
+        key: VerifierKey<PCS, ProofTranscript>,
+    ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
+        Self::preprocess_light(
+            key.program_commitment,
+            key.max_bytecode_size,
+            key.max_memory_address,
+            key.max_trace_length,
+        )
+    }
+
+    /// Verifier preprocessing for a program known only by its `ProgramCommitment`,
+    /// e.g. one whose `digest` matches a trusted program hash. Verifies proofs made
+    /// after `commit_program` with the same maximum sizes, i.e. those bound to the
+    /// `VerifierKey` of the commitment and sizes.
+    #[tracing::instrument(skip_all, name = "Jolt::preprocess_light")]
+    fn preprocess_light(
+        program_commitment: ProgramCommitment<PCS, ProofTranscript>,
+        max_bytecode_size: usize,
+        max_memory_address: usize,
+        max_trace_length: usize,
+    ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
+        let small_value_lookup_tables = F::compute_lookup_tables();
+        F::initialize_lookup_tables(small_value_lookup_tables.clone());
+        icicle::icicle_init();
+
+        let instruction_lookups_preprocessing = InstructionLookupsPreprocessing::preprocess::<
+            M,
+            Self::InstructionSet,
+            Self::Subtables,
+        >();
+        let generators = pcs_setup::<PCS, ProofTranscript>(
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
+            M,
+            SetupCache::from_env().as_ref(),
+        );
+
+        JoltPreprocessing {
+            generators,
+            memory_layout: program_commitment.memory_layout.clone(),
+            program_hash: program_commitment.program_hash,
+            instruction_lookups: instruction_lookups_preprocessing,
+            bytecode: BytecodePreprocessing::committed(program_commitment.code_size),
+            read_write_memory: ReadWriteMemoryPreprocessing::committed(
+                program_commitment.memory_image_pages,
+            ),
+            verifier_key_digest: VerifierKey {
+                program_commitment: program_commitment.clone(),
+                max_bytecode_size,
+                max_memory_address,
+                max_trace_length,
+            }
+            .digest(),
+            program_commitment: Some(program_commitment),
+            max_bytecode_size,
+            max_memory_address,
+            max_trace_length,
+            field: small_value_lookup_tables,
+            spartan_keys: SpartanKeyCache::default(),
+        }
+    }
+
+    /// Proves the execution `trace` with public I/O `program_io`. Proving is
+    /// deterministic: the same trace and preprocessing always give the same proof.
     #[tracing::instrument(skip_all, name = "Jolt::prove")]
     fn prove(
         program_io: JoltDevice,
-        mut trace: Vec<JoltTraceStep<Self::InstructionSet>>,
-        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
     ) -> (
         JoltProof<
             C,
@@ -371,11 +1191,241 @@ where
             Self::InstructionSet,
             Self::Subtables,
             ProofTranscript,
+            Self::Memory,
         >,
         JoltCommitments<PCS, ProofTranscript>,
         Option<ProverDebugInfo<F, ProofTranscript>>,
     ) {
+        let (proof, commitments, debug_info, _) =
+            Self::prove_continuation(program_io, trace, preprocessing, None);
+        (proof, commitments, debug_info)
+    }
+
+    /// Like `prove`, with `preprocessing` from `preprocess_with_config(_, config)`,
+    /// on `config`'s threads. Before proving, checks that the trace fits the
+    /// PCS setup, that it is estimated to be provable within `config`'s memory
+    /// budget and that the proof meets `config`'s security level.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_with_config")]
+    fn prove_with_config(
+        program_io: JoltDevice,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        config: &ProverConfig,
+    ) -> Result<
+        (
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+                Self::Memory,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+            Option<ProverDebugInfo<F, ProofTranscript>>,
+        ),
+        ConfigError,
+    > {
+        config.check_pcs::<PCS, ProofTranscript>()?;
+        let trace_length = trace.len();
+        if trace_length > config.max_trace_length() {
+            return Err(ConfigError::TraceTooLong(
+                trace_length,
+                config.max_trace_length(),
+            ));
+        }
+        let memory_size = preprocessing
+            .read_write_memory
+            .touched_pages(&program_io, &trace)
+            .memory_size();
+        if let Some(memory_budget) = config.memory_budget() {
+            let prover_memory =
+                Self::cost_report(&preprocessing, trace_length, memory_size).prover_memory;
+            if prover_memory > memory_budget {
+                return Err(ConfigError::OverMemoryBudget(prover_memory, memory_budget));
+            }
+        }
+        Self::check_security(
+            &preprocessing,
+            trace_length,
+            memory_size,
+            config.security().clone(),
+        )?;
+        Ok(config.install(|| Self::prove(program_io, trace, preprocessing)))
+    }
+
+    /// Like `prove`, but if `previous` is given, the execution is proven to start
+    /// from that memory state (see `MemoryState`), e.g. as traced by
+    /// `Program::trace_from_state`. Also returns the final memory state, from
+    /// which the next proof in the chain can continue.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_continuation")]
+    fn prove_continuation(
+        program_io: JoltDevice,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        previous: Option<MemoryState>,
+    ) -> (
+        JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        JoltCommitments<PCS, ProofTranscript>,
+        Option<ProverDebugInfo<F, ProofTranscript>>,
+        MemoryState,
+    ) {
+        Self::prove_with_progress(
+            program_io,
+            trace,
+            preprocessing,
+            previous,
+            &ProverProgress::default(),
+        )
+        .expect("proving without a cancellation token cannot be cancelled")
+    }
+
+    /// Like `prove_continuation`, but reports each stage of proving to `progress`
+    /// as it is entered, and stops with `ProvingCancelled` once the cancellation
+    /// token of `progress` is cancelled.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_with_progress")]
+    fn prove_with_progress(
+        program_io: JoltDevice,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        previous: Option<MemoryState>,
+        progress: &ProverProgress,
+    ) -> Result<
+        (
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+                Self::Memory,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+            Option<ProverDebugInfo<F, ProofTranscript>>,
+            MemoryState,
+        ),
+        ProvingCancelled,
+    > {
+        let (
+            mut jolt_proof,
+            jolt_commitments,
+            mut opening_accumulator,
+            mut transcript,
+            final_state,
+        ) = Self::prove_claims(program_io, trace, &mut preprocessing, previous, progress)?;
+
+        // Batch-prove all openings
+        progress.enter(ProverStage::Openings)?;
+        let stage = tracing::info_span!(
+            STAGE_SPAN,
+            stage = %ProverStage::Openings,
+            openings = opening_accumulator.len()
+        )
+        .entered();
+        jolt_proof.opening_proof = Some(
+            opening_accumulator.reduce_and_prove::<PCS>(&preprocessing.generators, &mut transcript),
+        );
+        drop(stage);
+        // Release the scratch vectors of this proof's MSMs and sumchecks
+        ScratchPool::global().clear();
+
+        #[cfg(test)]
+        let debug_info = Some(ProverDebugInfo {
+            transcript,
+            opening_accumulator,
+        });
+        #[cfg(not(test))]
+        let debug_info = None;
+        Ok((jolt_proof, jolt_commitments, debug_info, final_state))
+    }
+
+    /// Preprocessing for `prove_dev`: like `preprocess_program`, but with the
+    /// smallest PCS setup, so it is cheap to compute. It cannot be used with `prove`.
+    fn preprocess_dev(
+        program: ProgramPreprocessing,
+    ) -> JoltPreprocessing<C, F, PCS, ProofTranscript> {
+        Self::preprocess_program(program, 1, 1, 1)
+    }
+
+    /// Dev mode: generates the witness for `trace` and checks it against the
+    /// constraints, but skips committing and proving, so it takes a fraction of
+    /// the time of `prove`. Returns the first violated constraint, if any, and
+    /// otherwise a `DevProof`, which cannot be verified. `preprocessing` may be
+    /// from `preprocess_dev`, since the PCS setup is not used.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_dev")]
+    fn prove_dev(
+        program_io: JoltDevice,
+        mut trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<DevProof, ConstraintViolation> {
+        let mut preprocessing = preprocessing.clone();
+        let trace_length = trace.len();
+        F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
+        JoltTraceStep::pad(&mut trace);
+        preprocessing.read_write_memory.pages = preprocessing
+            .read_write_memory
+            .touched_pages(&program_io, &trace);
+
+        let (jolt_polynomials, r1cs_builder) =
+            Self::generate_witness(&program_io, &mut trace, &preprocessing);
+        r1cs_builder.check_constraints(&jolt_polynomials)?;
+        drop_in_background_thread(jolt_polynomials);
+
+        Ok(DevProof {
+            trace_length,
+            program_io,
+            program_hash: preprocessing.program_hash,
+        })
+    }
+
+    /// Runs `prove_continuation` up to the batched opening proof: returns the
+    /// proof without it, along with the openings it would prove and the
+    /// transcript to prove them with (see `prove_aggregated`). Updates
+    /// `preprocessing` with this proof's memory pages and initial state.
+    fn prove_claims(
+        program_io: JoltDevice,
+        mut trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        previous: Option<MemoryState>,
+        progress: &ProverProgress,
+    ) -> Result<
+        (
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+                Self::Memory,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+            ProverOpeningAccumulator<F, ProofTranscript>,
+            ProofTranscript,
+            MemoryState,
+        ),
+        ProvingCancelled,
+    > {
         icicle::icicle_init();
+        preprocessing.read_write_memory.continuation = previous;
         let trace_length = trace.len();
         let padded_trace_length = trace_length.next_power_of_two();
         println!("Trace length: {}", trace_length);
@@ -387,74 +1437,83 @@ where
         // TODO(JP): Drop padding on number of steps
         JoltTraceStep::pad(&mut trace);
 
+        let memory_pages = preprocessing
+            .read_write_memory
+            .touched_pages(&program_io, &trace);
+        preprocessing.read_write_memory.pages = memory_pages.clone();
+
+        // Committed inputs are left out of the proof, which carries their
+        // commitment instead
+        let mut public_io = program_io.clone();
+        if preprocessing.read_write_memory.commit_inputs {
+            public_io.inputs.clear();
+        }
+
+        let metadata = Self::proof_metadata(preprocessing);
         let mut transcript = ProofTranscript::new(b"Jolt transcript");
         Self::fiat_shamir_preamble(
             &mut transcript,
-            &program_io,
+            &metadata,
+            &public_io,
             &program_io.memory_layout,
+            &memory_pages,
+            preprocessing.read_write_memory.continuation.as_ref(),
             trace_length,
         );
 
-        let instruction_polynomials =
-            InstructionLookupsProof::<
-                C,
-                M,
-                F,
-                PCS,
-                Self::InstructionSet,
-                Self::Subtables,
-                ProofTranscript,
-            >::generate_witness(&preprocessing.instruction_lookups, &trace);
-
-        let memory_polynomials = ReadWriteMemoryPolynomials::generate_witness(
+        progress.enter(ProverStage::WitnessGeneration)?;
+        let stage = tracing::info_span!(
+            STAGE_SPAN,
+            stage = %ProverStage::WitnessGeneration,
+            trace_length,
+            padded_trace_length
+        )
+        .entered();
+        // Most commitments are computed alongside the witness, see
+        // `generate_witness_pipelined`
+        let mut jolt_commitments =
+            JoltCommitments::<PCS, ProofTranscript>::initialize(&*preprocessing);
+        let (mut jolt_polynomials, r1cs_builder) = Self::generate_witness_pipelined(
             &program_io,
-            &preprocessing.read_write_memory,
-            &trace,
+            &mut trace,
+            preprocessing,
+            Some(&mut jolt_commitments),
         );
+        #[cfg(debug_assertions)]
+        if let Err(violation) = r1cs_builder.check_constraints(&jolt_polynomials) {
+            panic!("{violation}");
+        }
+        // Committed to already, and not needed again until the memory sumchecks
+        jolt_polynomials.drop_timestamp_range_check();
+        drop(stage);
 
-        let (bytecode_polynomials, range_check_polys) = rayon::join(
-            || {
-                BytecodeProof::<F, PCS, ProofTranscript>::generate_witness(
-                    &preprocessing.bytecode,
-                    &mut trace,
-                )
-            },
+        let spartan_key = preprocessing.spartan_keys.get_or_insert(
+            padded_trace_length,
+            program_io.memory_layout.input_start,
             || {
-                TimestampValidityProof::<F, PCS, ProofTranscript>::generate_witness(
-                    &memory_polynomials,
-                )
+                spartan::UniformSpartanProof::<
+                    C,
+                    <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                    F,
+                    ProofTranscript,
+                >::setup(&r1cs_builder, padded_trace_length)
             },
         );
 
-        let r1cs_builder = Self::Constraints::construct_constraints(
+        progress.enter(ProverStage::Commitments)?;
+        let stage = tracing::info_span!(
+            STAGE_SPAN,
+            stage = %ProverStage::Commitments,
             padded_trace_length,
-            program_io.memory_layout.input_start,
+            commitments = tracing::field::Empty
+        )
+        .entered();
+        jolt_polynomials.commit_remaining(&mut jolt_commitments, preprocessing);
+        stage.record(
+            "commitments",
+            jolt_commitments.read_write_values().len() + jolt_commitments.init_final_values().len(),
         );
-        let spartan_key = spartan::UniformSpartanProof::<
-            C,
-            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
-            F,
-            ProofTranscript,
-        >::setup(&r1cs_builder, padded_trace_length);
-
-        let r1cs_polynomials = R1CSPolynomials::new::<
-            C,
-            M,
-            Self::InstructionSet,
-            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
-        >(&trace);
-
-        let mut jolt_polynomials = JoltPolynomials {
-            bytecode: bytecode_polynomials,
-            read_write_memory: memory_polynomials,
-            timestamp_range_check: range_check_polys,
-            instruction_lookups: instruction_polynomials,
-            r1cs: r1cs_polynomials,
-        };
-
-        r1cs_builder.compute_aux(&mut jolt_polynomials);
-
-        let jolt_commitments = jolt_polynomials.commit::<C, PCS, ProofTranscript>(&preprocessing);
+        drop(stage);
 
         transcript.append_scalar(&spartan_key.vk_digest);
 
@@ -470,6 +1529,11 @@ where
         let mut opening_accumulator: ProverOpeningAccumulator<F, ProofTranscript> =
             ProverOpeningAccumulator::new();
 
+        progress.enter(ProverStage::Sumchecks)?;
+        let stage =
+            tracing::info_span!(STAGE_SPAN, stage = %ProverStage::Sumchecks, padded_trace_length)
+                .entered();
+        let sumcheck = tracing::info_span!(SUMCHECK_SPAN, sumcheck = "bytecode").entered();
         let bytecode_proof = BytecodeProof::prove_memory_checking(
             &preprocessing.generators,
             &preprocessing.bytecode,
@@ -478,7 +1542,11 @@ where
             &mut opening_accumulator,
             &mut transcript,
         );
+        drop(sumcheck);
 
+        progress.check()?;
+        let sumcheck =
+            tracing::info_span!(SUMCHECK_SPAN, sumcheck = "instruction_lookups").entered();
         let instruction_proof = InstructionLookupsProof::prove(
             &preprocessing.generators,
             &mut jolt_polynomials,
@@ -486,8 +1554,12 @@ where
             &mut opening_accumulator,
             &mut transcript,
         );
+        drop(sumcheck);
 
-        let memory_proof = ReadWriteMemoryProof::prove(
+        progress.check()?;
+        let sumcheck = tracing::info_span!(SUMCHECK_SPAN, sumcheck = "read_write_memory").entered();
+        jolt_polynomials.materialize_timestamp_range_check::<PCS, ProofTranscript>();
+        let memory_proof = Self::Memory::prove(
             &preprocessing.generators,
             &preprocessing.read_write_memory,
             &jolt_polynomials,
@@ -495,7 +1567,11 @@ where
             &mut opening_accumulator,
             &mut transcript,
         );
+        jolt_polynomials.drop_timestamp_range_check();
+        drop(sumcheck);
 
+        progress.check()?;
+        let sumcheck = tracing::info_span!(SUMCHECK_SPAN, sumcheck = "r1cs").entered();
         let spartan_proof = UniformSpartanProof::<
             C,
             <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
@@ -509,36 +1585,389 @@ where
             &mut transcript,
         )
         .expect("r1cs proof failed");
+        drop(sumcheck);
+        drop(stage);
 
-        // Batch-prove all openings
-        let opening_proof =
-            opening_accumulator.reduce_and_prove::<PCS>(&preprocessing.generators, &mut transcript);
+        let segment_boundary = SegmentBoundary::prove::<
+            C,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            ProofTranscript,
+        >(
+            preprocessing.read_write_memory.segment,
+            &jolt_polynomials,
+            &mut opening_accumulator,
+            &mut transcript,
+        );
+
+        let v_final: &CompactPolynomial<u32, F> = (&jolt_polynomials.read_write_memory.v_final)
+            .try_into()
+            .unwrap();
+        let final_state = MemoryState {
+            words: v_final.coeffs.clone(),
+            pages: memory_pages.clone(),
+            program_io: program_io.clone(),
+        };
 
         drop_in_background_thread(jolt_polynomials);
 
         let jolt_proof = JoltProof {
+            metadata,
             trace_length,
-            program_io,
+            program_io: public_io,
+            memory_pages,
             bytecode: bytecode_proof,
             read_write_memory: memory_proof,
             instruction_lookups: instruction_proof,
             r1cs: spartan_proof,
-            opening_proof,
+            segment_boundary,
+            opening_proof: None,
         };
 
-        #[cfg(test)]
-        let debug_info = Some(ProverDebugInfo {
-            transcript,
+        Ok((
+            jolt_proof,
+            jolt_commitments,
             opening_accumulator,
-        });
-        #[cfg(not(test))]
-        let debug_info = None;
-        (jolt_proof, jolt_commitments, debug_info)
+            transcript,
+            final_state,
+        ))
+    }
+
+    /// Generates the witness polynomials for the (padded) `trace`, along with the
+    /// constraint system they satisfy.
+    #[tracing::instrument(skip_all, name = "Jolt::generate_witness")]
+    fn generate_witness(
+        program_io: &JoltDevice,
+        trace: &mut Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> (
+        JoltPolynomials<F>,
+        CombinedUniformBuilder<C, F, <Self::Constraints as R1CSConstraints<C, F>>::Inputs>,
+    ) {
+        Self::generate_witness_pipelined(program_io, trace, preprocessing, None)
+    }
+
+    /// `generate_witness`, committing to each part of the witness into
+    /// `commitments` (if given) while the next part is generated, so that the
+    /// commitment MSMs run on the cores left idle by the serial parts of witness
+    /// generation. Commits to the read/write values of all parts but the R1CS
+    /// inputs, whose auxiliary variables are computed last; the rest is left to
+    /// `JoltPolynomials::commit_remaining`.
+    fn generate_witness_pipelined(
+        program_io: &JoltDevice,
+        trace: &mut Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        mut commitments: Option<&mut JoltCommitments<PCS, ProofTranscript>>,
+    ) -> (
+        JoltPolynomials<F>,
+        CombinedUniformBuilder<C, F, <Self::Constraints as R1CSConstraints<C, F>>::Inputs>,
+    ) {
+        let padded_trace_length = trace.len().next_power_of_two();
+        let generators = &preprocessing.generators;
+
+        let instruction_polynomials =
+            InstructionLookupsProof::<
+                C,
+                M,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+            >::generate_witness(&preprocessing.instruction_lookups, trace);
+
+        let instruction_commitments = commitments
+            .as_deref_mut()
+            .map(|commitments| &mut commitments.instruction_lookups);
+        let (memory_polynomials, ()) = parallel::join(
+            || {
+                ReadWriteMemoryPolynomials::generate_witness(
+                    program_io,
+                    &preprocessing.read_write_memory,
+                    trace,
+                )
+            },
+            || {
+                commit_read_write_values::<F, PCS, ProofTranscript, _, _>(
+                    &instruction_polynomials,
+                    instruction_commitments,
+                    generators,
+                )
+            },
+        );
+
+        let memory_commitments = commitments
+            .as_deref_mut()
+            .map(|commitments| &mut commitments.read_write_memory);
+        let ((bytecode_polynomials, range_check_polys), ()) = parallel::join(
+            || {
+                parallel::join(
+                    || {
+                        BytecodeProof::<F, PCS, ProofTranscript>::generate_witness(
+                            &preprocessing.bytecode,
+                            trace,
+                        )
+                    },
+                    || {
+                        TimestampValidityProof::<F, PCS, ProofTranscript>::generate_witness(
+                            &memory_polynomials,
+                        )
+                    },
+                )
+            },
+            || {
+                commit_read_write_values::<F, PCS, ProofTranscript, _, _>(
+                    &memory_polynomials,
+                    memory_commitments,
+                    generators,
+                )
+            },
+        );
+
+        let r1cs_builder = Self::Constraints::construct_constraints(
+            padded_trace_length,
+            program_io.memory_layout.input_start,
+        );
+
+        let (bytecode_commitments, range_check_commitments) = match commitments {
+            Some(commitments) => (
+                Some(&mut commitments.bytecode),
+                Some(&mut commitments.timestamp_range_check),
+            ),
+            None => (None, None),
+        };
+        let (r1cs_polynomials, ()) = parallel::join(
+            || {
+                R1CSPolynomials::new::<
+                    C,
+                    M,
+                    Self::InstructionSet,
+                    <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                >(trace)
+            },
+            || {
+                commit_read_write_values::<F, PCS, ProofTranscript, _, _>(
+                    &bytecode_polynomials,
+                    bytecode_commitments,
+                    generators,
+                );
+                commit_read_write_values::<F, PCS, ProofTranscript, _, _>(
+                    &range_check_polys,
+                    range_check_commitments,
+                    generators,
+                );
+            },
+        );
+
+        let mut jolt_polynomials = JoltPolynomials {
+            bytecode: bytecode_polynomials,
+            read_write_memory: memory_polynomials,
+            timestamp_range_check: range_check_polys,
+            instruction_lookups: instruction_polynomials,
+            r1cs: r1cs_polynomials,
+        };
+
+        r1cs_builder.compute_aux(&mut jolt_polynomials);
+
+        (jolt_polynomials, r1cs_builder)
+    }
+
+    /// Writes the constraint system to `dir` in circom's formats (`jolt.r1cs` and
+    /// `jolt.sym`), together with the witness for the window of `trace` starting
+    /// at `step` (`jolt.wtns`), for external tooling. See `r1cs::export`.
+    fn export_constraint_system(
+        program_io: JoltDevice,
+        mut trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        step: usize,
+        dir: &Path,
+    ) -> std::io::Result<()> {
+        F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
+        JoltTraceStep::pad(&mut trace);
+        preprocessing.read_write_memory.pages = preprocessing
+            .read_write_memory
+            .touched_pages(&program_io, &trace);
+
+        let (jolt_polynomials, r1cs_builder) =
+            Self::generate_witness(&program_io, &mut trace, &preprocessing);
+
+        std::fs::create_dir_all(dir)?;
+        let mut r1cs = std::io::BufWriter::new(std::fs::File::create(dir.join("jolt.r1cs"))?);
+        export::write_r1cs(&r1cs_builder, &mut r1cs)?;
+        let mut symbols = std::io::BufWriter::new(std::fs::File::create(dir.join("jolt.sym"))?);
+        export::write_symbols::<C, <Self::Constraints as R1CSConstraints<C, F>>::Inputs>(
+            &mut symbols,
+        )?;
+        let mut witness = std::io::BufWriter::new(std::fs::File::create(dir.join("jolt.wtns"))?);
+        export::write_witness::<C, F, <Self::Constraints as R1CSConstraints<C, F>>::Inputs>(
+            &jolt_polynomials,
+            step,
+            &mut witness,
+        )?;
+        r1cs.flush()?;
+        symbols.flush()?;
+        witness.flush()
+    }
+
+    /// Reports the number of committed columns and constraints, and the (estimated)
+    /// size of the commitments and proof, for proving `trace_length` steps with
+    /// `preprocessing`, without running the prover. `memory_size` is the number of
+    /// words of RAM covered by the memory-checking argument (see `MemoryPages`).
+    /// Columns committed only by continuation proofs or with a program commitment
+    /// are not included.
+    fn cost_report(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        trace_length: usize,
+        memory_size: usize,
+    ) -> CostReport {
+        let commitments = JoltCommitments::<PCS, ProofTranscript>::initialize(preprocessing);
+        let (report, _, _) = estimate_costs::<Self, F, PCS, C, M, ProofTranscript>(
+            &commitments,
+            preprocessing.memory_layout.input_start,
+            preprocessing.bytecode.code_size(),
+            trace_length,
+            memory_size,
+        );
+        report
+    }
+
+    /// The largest power-of-two chunk size, at most `max_chunk_size`, for which
+    /// proving a chunk is estimated to take at most `max_memory` bytes (see
+    /// `CostReport::prover_memory`), or `None` if no chunk size fits. Chunks of
+    /// this size can then be proven with `prove_chunks`. `memory_size` is as for
+    /// `cost_report`; since the init/final polynomials do not shrink with the
+    /// chunk size, it bounds the budget from below.
+    fn chunk_size_for_memory(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        memory_size: usize,
+        max_chunk_size: usize,
+        max_memory: usize,
+    ) -> Option<usize> {
+        assert!(max_chunk_size.is_power_of_two());
+        (0..=max_chunk_size.log_2())
+            .rev()
+            .map(|num_vars| 1 << num_vars)
+            .find(|chunk_size| {
+                Self::cost_report(preprocessing, *chunk_size, memory_size).prover_memory
+                    <= max_memory
+            })
+    }
+
+    /// Estimates the cost of verifying a proof of `trace_length` steps for the
+    /// program of `key`, see `VerificationCost`. `memory_size` is as for
+    /// `cost_report`.
+    fn verification_cost(
+        key: &VerifierKey<PCS, ProofTranscript>,
+        trace_length: usize,
+        memory_size: usize,
+    ) -> VerificationCost {
+        // The columns committed for the program, as in `preprocess_light`
+        let program_commitment = &key.program_commitment;
+        let commitments = JoltCommitments::<PCS, ProofTranscript> {
+            bytecode: BytecodeStuff::initialize(&BytecodePreprocessing::<F>::committed(
+                program_commitment.code_size,
+            )),
+            read_write_memory: ReadWriteMemoryStuff::initialize(
+                &ReadWriteMemoryPreprocessing::committed(
+                    program_commitment.memory_image_pages.clone(),
+                ),
+            ),
+            instruction_lookups: InstructionLookupStuff::initialize(
+                &InstructionLookupsPreprocessing::<C, F>::preprocess::<
+                    M,
+                    Self::InstructionSet,
+                    Self::Subtables,
+                >(),
+            ),
+            timestamp_range_check: TimestampRangeCheckStuff::initialize(
+                &crate::lasso::memory_checking::NoPreprocessing,
+            ),
+            r1cs: R1CSStuff::initialize(&C),
+        };
+        let (_, cost, _) = estimate_costs::<Self, F, PCS, C, M, ProofTranscript>(
+            &commitments,
+            program_commitment.memory_layout.input_start,
+            program_commitment.code_size,
+            trace_length,
+            memory_size,
+        );
+        cost
+    }
+
+    /// Checks that proving `trace_length` steps with `preprocessing` meets the
+    /// soundness required by `config`, returning the soundness of each subprotocol.
+    /// `memory_size` is as for `cost_report`. Challenges are drawn from `F` itself
+    /// and each subprotocol runs once, so configs asking for an extension field or
+    /// repetitions are rejected.
+    fn check_security(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        trace_length: usize,
+        memory_size: usize,
+        config: SecurityConfig,
+    ) -> Result<SoundnessReport, SecurityError> {
+        if config.extension_degree != 1 {
+            return Err(SecurityError::UnsupportedExtensionDegree(
+                config.extension_degree,
+            ));
+        }
+        if config.repetitions != 1 {
+            return Err(SecurityError::UnsupportedRepetitions(config.repetitions));
+        }
+
+        let commitments = JoltCommitments::<PCS, ProofTranscript>::initialize(preprocessing);
+        let (_, _, error_degrees) = estimate_costs::<Self, F, PCS, C, M, ProofTranscript>(
+            &commitments,
+            preprocessing.memory_layout.input_start,
+            preprocessing.bytecode.code_size(),
+            trace_length,
+            memory_size,
+        );
+        let report = SoundnessReport::new::<F>(config, error_degrees);
+        let bits = report.bits().floor() as u32;
+        if bits < report.config.target_bits {
+            return Err(SecurityError::InsufficientSecurity(
+                bits,
+                report.config.target_bits,
+            ));
+        }
+        Ok(report)
     }
 
     #[tracing::instrument(skip_all)]
     fn verify(
         mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        mut proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        debug_info: Option<ProverDebugInfo<F, ProofTranscript>>,
+    ) -> Result<(), ProofVerifyError> {
+        let Some(opening_proof) = proof.opening_proof.take() else {
+            return Err(ProofVerifyError::InternalError);
+        };
+        let (opening_accumulator, mut transcript) =
+            Self::verify_claims(&mut preprocessing, proof, commitments, debug_info)?;
+
+        // Batch-verify all openings
+        opening_accumulator.reduce_and_verify(
+            &preprocessing.generators,
+            &opening_proof,
+            &mut transcript,
+        )
+    }
+
+    /// Verifies everything in `proof` but the batched opening proof, returning the
+    /// openings it reduces to along with the transcript to verify them with (see
+    /// `verify_aggregated`).
+    fn verify_claims(
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
         proof: JoltProof<
             C,
             M,
@@ -548,57 +1977,210 @@ where
             Self::InstructionSet,
             Self::Subtables,
             ProofTranscript,
+            Self::Memory,
         >,
         commitments: JoltCommitments<PCS, ProofTranscript>,
         _debug_info: Option<ProverDebugInfo<F, ProofTranscript>>,
-    ) -> Result<(), ProofVerifyError> {
+    ) -> Result<
+        (
+            VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+            ProofTranscript,
+        ),
+        ProofVerifyError,
+    > {
         let mut transcript = ProofTranscript::new(b"Jolt transcript");
         let mut opening_accumulator: VerifierOpeningAccumulator<F, PCS, ProofTranscript> =
             VerifierOpeningAccumulator::new();
 
-        #[cfg(test)]
-        if let Some(debug_info) = _debug_info {
-            transcript.compare_to(debug_info.transcript);
-            opening_accumulator
-                .compare_to(debug_info.opening_accumulator, &preprocessing.generators);
-        }
+        #[cfg(test)]
+        if let Some(debug_info) = _debug_info {
+            transcript.compare_to(debug_info.transcript);
+            opening_accumulator
+                .compare_to(debug_info.opening_accumulator, &preprocessing.generators);
+        }
+        let spartan_key = Self::verify_preamble(
+            preprocessing,
+            &proof.metadata,
+            proof.trace_length,
+            &proof.program_io,
+            proof.memory_pages,
+            &commitments,
+            &mut transcript,
+        )?;
+        let padded_trace_length = proof.trace_length.next_power_of_two();
+        let r1cs_proof = R1CSProof {
+            key: spartan_key,
+            proof: proof.r1cs,
+            _marker: PhantomData,
+        };
+
+        Self::verify_bytecode(
+            &preprocessing.bytecode,
+            &preprocessing.generators,
+            proof.bytecode,
+            &commitments,
+            &mut opening_accumulator,
+            &mut transcript,
+        )?;
+        Self::verify_instruction_lookups(
+            &preprocessing.instruction_lookups,
+            &preprocessing.generators,
+            proof.instruction_lookups,
+            &commitments,
+            &mut opening_accumulator,
+            &mut transcript,
+        )?;
+        Self::verify_memory(
+            &mut preprocessing.read_write_memory,
+            &preprocessing.generators,
+            &preprocessing.memory_layout,
+            proof.read_write_memory,
+            &commitments,
+            proof.program_io,
+            &mut opening_accumulator,
+            &mut transcript,
+        )?;
+        Self::verify_r1cs(
+            r1cs_proof,
+            &commitments,
+            &mut opening_accumulator,
+            &mut transcript,
+        )?;
+        proof
+            .segment_boundary
+            .verify::<C, <Self::Constraints as R1CSConstraints<C, F>>::Inputs, _, _>(
+                preprocessing.read_write_memory.segment,
+                padded_trace_length,
+                &commitments,
+                &mut opening_accumulator,
+                &mut transcript,
+            )?;
+
+        Ok((opening_accumulator, transcript))
+    }
+
+    /// Checks the public parts of a proof (its metadata, trace length, I/O and
+    /// memory pages) and its commitments, and absorbs them into `transcript`.
+    /// Returns the uniform Spartan key for the proof's trace length.
+    fn verify_preamble(
+        preprocessing: &mut JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        metadata: &ProofMetadata,
+        trace_length: usize,
+        program_io: &JoltDevice,
+        memory_pages: MemoryPages,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<
+        UniformSpartanKey<C, <Self::Constraints as R1CSConstraints<C, F>>::Inputs, F>,
+        ProofVerifyError,
+    > {
+        metadata.check(&Self::proof_metadata(preprocessing))?;
+        memory_pages.check(
+            &preprocessing.memory_layout,
+            &preprocessing
+                .read_write_memory
+                .image(&preprocessing.memory_layout),
+            preprocessing
+                .read_write_memory
+                .continuation
+                .as_ref()
+                .map(|state| &state.pages),
+        )?;
         Self::fiat_shamir_preamble(
-            &mut transcript,
-            &proof.program_io,
+            transcript,
+            metadata,
+            program_io,
             &preprocessing.memory_layout,
-            proof.trace_length,
+            &memory_pages,
+            preprocessing.read_write_memory.continuation.as_ref(),
+            trace_length,
         );
+        // A committed input polynomial could hold any values, e.g. in the output
+        // region, where they would cancel out of the output check, so it must be
+        // the one with the digest the verifier expects
+        let read_write_memory = &preprocessing.read_write_memory;
+        let inputs_match = match &commitments.read_write_memory.v_inputs {
+            Some(commitment) => {
+                let commitment = InputCommitment::<PCS, ProofTranscript> {
+                    commitment: commitment.clone(),
+                };
+                read_write_memory.commit_inputs
+                    && read_write_memory.input_digest == Some(commitment.digest())
+            }
+            None => !read_write_memory.commit_inputs,
+        };
+        if !inputs_match {
+            return Err(ProofVerifyError::InternalError);
+        }
+        if let Some(program_commitment) = &preprocessing.program_commitment {
+            Self::check_program_commitment(
+                program_commitment,
+                &memory_pages,
+                commitments,
+                preprocessing.read_write_memory.continuation.is_some(),
+            )?;
+        }
+        preprocessing.read_write_memory.pages = memory_pages;
 
-        // Regenerate the uniform Spartan key
-        let padded_trace_length = proof.trace_length.next_power_of_two();
+        // Regenerate the uniform Spartan key, unless it is cached
+        let padded_trace_length = trace_length.next_power_of_two();
         let memory_start = preprocessing.memory_layout.input_start;
-        let r1cs_builder =
-            Self::Constraints::construct_constraints(padded_trace_length, memory_start);
-        let spartan_key = spartan::UniformSpartanProof::<C, _, F, ProofTranscript>::setup(
-            &r1cs_builder,
-            padded_trace_length,
-        );
+        let spartan_key =
+            preprocessing
+                .spartan_keys
+                .get_or_insert(padded_trace_length, memory_start, || {
+                    let r1cs_builder =
+                        Self::Constraints::construct_constraints(padded_trace_length, memory_start);
+                    spartan::UniformSpartanProof::<C, _, F, ProofTranscript>::setup(
+                        &r1cs_builder,
+                        padded_trace_length,
+                    )
+                });
         transcript.append_scalar(&spartan_key.vk_digest);
 
-        let r1cs_proof = R1CSProof {
-            key: spartan_key,
-            proof: proof.r1cs,
-            _marker: PhantomData,
-        };
-
         commitments
             .read_write_values()
             .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
+            .for_each(|value| value.append_to_transcript(transcript));
         commitments
             .init_final_values()
             .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
+            .for_each(|value| value.append_to_transcript(transcript));
+        Ok(UniformSpartanKey::clone(&spartan_key))
+    }
+
+    /// Verifies a proof written by `JoltProof::serialize_streaming`, reading each
+    /// component only when it is verified. Only one component is held in memory at
+    /// a time, rather than the whole proof.
+    #[tracing::instrument(skip_all)]
+    fn verify_from_reader(
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        mut reader: impl Read,
+    ) -> Result<(), ProofVerifyError> {
+        let metadata = ProofMetadata::deserialize_compressed(&mut reader)?;
+        let trace_length = usize::deserialize_compressed(&mut reader)?;
+        let program_io = JoltDevice::deserialize_compressed(&mut reader)?;
+        let memory_pages = MemoryPages::deserialize_compressed(&mut reader)?;
+        let commitments =
+            JoltCommitments::<PCS, ProofTranscript>::deserialize_compressed(&mut reader)?;
+
+        let mut transcript = ProofTranscript::new(b"Jolt transcript");
+        let mut opening_accumulator: VerifierOpeningAccumulator<F, PCS, ProofTranscript> =
+            VerifierOpeningAccumulator::new();
+        let spartan_key = Self::verify_preamble(
+            &mut preprocessing,
+            &metadata,
+            trace_length,
+            &program_io,
+            memory_pages,
+            &commitments,
+            &mut transcript,
+        )?;
 
         Self::verify_bytecode(
             &preprocessing.bytecode,
             &preprocessing.generators,
-            proof.bytecode,
+            BytecodeProof::deserialize_compressed(&mut reader)?,
             &commitments,
             &mut opening_accumulator,
             &mut transcript,
@@ -606,7 +2188,7 @@ where
         Self::verify_instruction_lookups(
             &preprocessing.instruction_lookups,
             &preprocessing.generators,
-            proof.instruction_lookups,
+            InstructionLookupsProof::deserialize_compressed(&mut reader)?,
             &commitments,
             &mut opening_accumulator,
             &mut transcript,
@@ -615,29 +2197,546 @@ where
             &mut preprocessing.read_write_memory,
             &preprocessing.generators,
             &preprocessing.memory_layout,
-            proof.read_write_memory,
+            Self::Memory::deserialize_compressed(&mut reader)?,
             &commitments,
-            proof.program_io,
+            program_io,
             &mut opening_accumulator,
             &mut transcript,
         )?;
+        let r1cs_proof = R1CSProof {
+            key: spartan_key,
+            proof: UniformSpartanProof::deserialize_compressed(&mut reader)?,
+            _marker: PhantomData,
+        };
         Self::verify_r1cs(
             r1cs_proof,
             &commitments,
             &mut opening_accumulator,
             &mut transcript,
         )?;
+        SegmentBoundary::<F>::deserialize_compressed(&mut reader)?
+            .verify::<C, <Self::Constraints as R1CSConstraints<C, F>>::Inputs, _, _>(
+            preprocessing.read_write_memory.segment,
+            trace_length.next_power_of_two(),
+            &commitments,
+            &mut opening_accumulator,
+            &mut transcript,
+        )?;
 
-        // Batch-verify all openings
+        let opening_proof = ReducedOpeningProof::deserialize_compressed(&mut reader)?;
         opening_accumulator.reduce_and_verify(
             &preprocessing.generators,
-            &proof.opening_proof,
+            &opening_proof,
             &mut transcript,
-        )?;
+        )
+    }
+
+    /// Verifies `proof` against public inputs and outputs supplied by the verifier,
+    /// rather than the ones carried in the proof. The final contents of the I/O
+    /// region (inputs, outputs, panic bit) are checked against `program_io` by the
+    /// output sumcheck, so verification fails unless the guest actually wrote the
+    /// claimed outputs.
+    #[tracing::instrument(skip_all)]
+    fn verify_with_io(
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        mut proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        program_io: &JoltDevice,
+    ) -> Result<(), ProofVerifyError> {
+        if program_io.inputs.len() > preprocessing.memory_layout.max_input_size as usize
+            || program_io.outputs.len() > preprocessing.memory_layout.max_output_size as usize
+        {
+            return Err(ProofVerifyError::InputTooLarge);
+        }
+        proof.program_io = JoltDevice {
+            inputs: program_io.inputs.clone(),
+            outputs: program_io.outputs.clone(),
+            panic: program_io.panic,
+            memory_layout: preprocessing.memory_layout.clone(),
+        };
+        Self::verify(preprocessing, proof, commitments, None)
+    }
+
+    /// Verifies a proof made after `commit_inputs`, whose inputs are those with
+    /// the `InputCommitment` digest `input_digest`. The inputs are checked
+    /// through openings of the commitment rather than by processing them, so
+    /// verification costs the same whatever their size.
+    #[tracing::instrument(skip_all)]
+    fn verify_with_input_digest(
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        input_digest: &[u8; 32],
+    ) -> Result<(), ProofVerifyError> {
+        if !proof.program_io.inputs.is_empty() {
+            return Err(ProofVerifyError::InternalError);
+        }
+        Self::commit_inputs(&mut preprocessing);
+        preprocessing.read_write_memory.input_digest = Some(*input_digest);
+        Self::verify(preprocessing, proof, commitments, None)
+    }
+
+    /// Verifies a proof produced by `prove_continuation`, whose execution started
+    /// from the final memory state of the proof with public I/O `previous_io`,
+    /// memory pages `previous_pages` and commitments `previous_commitments`. The link between the two is checked
+    /// succinctly, by requiring both proofs to use the same commitment to that state.
+    #[tracing::instrument(skip_all)]
+    fn verify_continuation(
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        previous_io: &JoltDevice,
+        previous_pages: &MemoryPages,
+        previous_commitments: &JoltCommitments<PCS, ProofTranscript>,
+    ) -> Result<(), ProofVerifyError> {
+        if previous_io.memory_layout != preprocessing.memory_layout
+            || commitments.read_write_memory.v_final_previous.as_ref()
+                != Some(&previous_commitments.read_write_memory.v_final)
+        {
+            return Err(ProofVerifyError::InternalError);
+        }
+        preprocessing.read_write_memory.continuation = Some(MemoryState {
+            words: vec![],
+            pages: previous_pages.clone(),
+            program_io: previous_io.clone(),
+        });
+        Self::verify(preprocessing, proof, commitments, None)
+    }
+
+    /// Proves `trace` as consecutive segments of the given lengths (see
+    /// `segment::segment_lengths`), chained like continuations. Each segment is
+    /// padded separately, so a trace just above a power of two costs little more
+    /// than one just below it. All segments but the last must have power-of-two
+    /// lengths.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_segmented")]
+    fn prove_segmented(
+        program_io: JoltDevice,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        segment_lengths: &[usize],
+    ) -> ChainedProof<
+        C,
+        M,
+        <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        PCS,
+        Self::InstructionSet,
+        Self::Subtables,
+        ProofTranscript,
+        Self::Memory,
+    > {
+        assert_eq!(segment_lengths.iter().sum::<usize>(), trace.len());
+        let mut steps = trace.into_iter();
+        let segments = segment_lengths
+            .iter()
+            .map(|length| steps.by_ref().take(*length).collect::<Vec<_>>());
+        Self::prove_chunks(program_io, segments, preprocessing)
+    }
+
+    /// Like `prove_segmented`, but takes the segments one at a time, e.g. as
+    /// they are traced (see `Program::prove_continuations`), so that only one of
+    /// them is in memory at once. `program_io` is the I/O of the whole execution.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_chunks")]
+    fn prove_chunks(
+        program_io: JoltDevice,
+        chunks: impl IntoIterator<Item = Vec<JoltTraceStep<Self::InstructionSet>>>,
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> ChainedProof<
+        C,
+        M,
+        <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        PCS,
+        Self::InstructionSet,
+        Self::Subtables,
+        ProofTranscript,
+        Self::Memory,
+    > {
+        assert!(
+            !preprocessing.read_write_memory.commit_inputs,
+            "Segmented proofs cannot commit to their inputs"
+        );
+        let mut chunks = chunks.into_iter().peekable();
+        let mut chained_proof = ChainedProof {
+            proofs: vec![],
+            commitments: vec![],
+        };
+        let mut previous = None;
+        while let Some(chunk) = chunks.next() {
+            let segment = Segment {
+                first: previous.is_none(),
+                last: chunks.peek().is_none(),
+            };
+            assert!(
+                segment.last || chunk.len().is_power_of_two(),
+                "Only the last segment may be padded"
+            );
+            let mut preprocessing = preprocessing.clone();
+            preprocessing.read_write_memory.segment = Some(segment);
+            let (proof, commitments, _, final_state) =
+                Self::prove_continuation(program_io.clone(), chunk, preprocessing, previous.take());
+            previous = Some(final_state);
+            chained_proof.proofs.push(proof);
+            chained_proof.commitments.push(commitments);
+        }
+        assert!(previous.is_some(), "Nothing to prove");
+        chained_proof
+    }
+
+    /// Verifies a proof produced by `prove_segmented` or `prove_chunks`.
+    /// Consecutive segments must agree on the memory state between them, which
+    /// is checked through their commitments as in `verify_continuation`, and on
+    /// the cross-step constraints between the last step of one and the first
+    /// step of the next.
+    #[tracing::instrument(skip_all)]
+    fn verify_segmented(
+        preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        chained_proof: ChainedProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+    ) -> Result<(), ProofVerifyError> {
+        let ChainedProof {
+            proofs,
+            commitments,
+        } = chained_proof;
+        if proofs.is_empty() {
+            return Err(ProofVerifyError::InternalError);
+        }
+        if commitments.len() != proofs.len() {
+            return Err(ProofVerifyError::InvalidInputLength(
+                proofs.len(),
+                commitments.len(),
+            ));
+        }
+        for index in 1..proofs.len() {
+            let (previous, proof) = (&proofs[index - 1], &proofs[index]);
+            // Only the last segment is padded: the boundary is checked against
+            // the last step of the previous segment, which must be a real one
+            if !previous.trace_length.is_power_of_two() {
+                return Err(ProofVerifyError::InternalError);
+            }
+            if proof.program_io != previous.program_io
+                || commitments[index]
+                    .read_write_memory
+                    .v_final_previous
+                    .as_ref()
+                    != Some(&commitments[index - 1].read_write_memory.v_final)
+            {
+                return Err(ProofVerifyError::InternalError);
+            }
+            let (Some(last_step), Some(first_step)) = (
+                &previous.segment_boundary.last_step,
+                &proof.segment_boundary.first_step,
+            ) else {
+                return Err(ProofVerifyError::InternalError);
+            };
+            let r1cs_builder = Self::Constraints::construct_constraints(
+                previous.trace_length.next_power_of_two(),
+                preprocessing.memory_layout.input_start,
+            );
+            segment::check_boundary::<C, <Self::Constraints as R1CSConstraints<C, F>>::Inputs, F>(
+                &r1cs_builder.materialize_offset_eq(),
+                last_step,
+                first_step,
+            )?;
+        }
 
+        let num_segments = proofs.len();
+        let mut previous: Option<MemoryState> = None;
+        for (index, (proof, commitments)) in proofs.into_iter().zip(commitments).enumerate() {
+            let mut preprocessing = preprocessing.clone();
+            preprocessing.read_write_memory.segment = Some(Segment::new(index, num_segments));
+            preprocessing.read_write_memory.continuation = previous.take();
+            previous = Some(MemoryState {
+                words: vec![],
+                pages: proof.memory_pages.clone(),
+                program_io: proof.program_io.clone(),
+            });
+            Self::verify(preprocessing, proof, commitments, None)?;
+        }
         Ok(())
     }
 
+    /// Proves several executions, possibly of different programs, with a single
+    /// batched opening proof for all of them. Verifying the result takes one PCS
+    /// opening check (e.g. HyperKZG's pairing check) rather than one per proof;
+    /// the sumchecks are still verified for each proof. All preprocessings must
+    /// share the PCS setup, i.e. be preprocessed with the same maximum sizes.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_aggregated")]
+    fn prove_aggregated(
+        executions: Vec<(
+            JoltDevice,
+            Vec<JoltTraceStep<Self::InstructionSet>>,
+            JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        )>,
+    ) -> (
+        AggregatedProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        Vec<JoltCommitments<PCS, ProofTranscript>>,
+    ) {
+        assert!(!executions.is_empty(), "Nothing to aggregate");
+        let mut transcript = ProofTranscript::new(b"Jolt aggregation");
+        let mut opening_accumulator = ProverOpeningAccumulator::new();
+        let mut generators = None;
+        let mut proofs = Vec::with_capacity(executions.len());
+        let mut commitments = Vec::with_capacity(executions.len());
+        for (program_io, trace, mut preprocessing) in executions {
+            let (proof, proof_commitments, proof_openings, mut proof_transcript, _) =
+                Self::prove_claims(
+                    program_io,
+                    trace,
+                    &mut preprocessing,
+                    None,
+                    &ProverProgress::default(),
+                )
+                .expect("proving without a cancellation token cannot be cancelled");
+            // Bind the batched opening proof to every proof's transcript
+            let binding: F = proof_transcript.challenge_scalar();
+            transcript.append_scalar(&binding);
+            opening_accumulator.extend(proof_openings);
+            generators.get_or_insert(preprocessing.generators);
+            proofs.push(proof);
+            commitments.push(proof_commitments);
+        }
+
+        let opening_proof =
+            opening_accumulator.reduce_and_prove::<PCS>(&generators.unwrap(), &mut transcript);
+        (
+            AggregatedProof {
+                proofs,
+                opening_proof,
+            },
+            commitments,
+        )
+    }
+
+    /// Verifies a proof produced by `prove_aggregated`, given the preprocessing
+    /// and commitments for each of the aggregated proofs, in order.
+    #[tracing::instrument(skip_all)]
+    fn verify_aggregated(
+        preprocessing: Vec<JoltPreprocessing<C, F, PCS, ProofTranscript>>,
+        proof: AggregatedProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: Vec<JoltCommitments<PCS, ProofTranscript>>,
+    ) -> Result<(), ProofVerifyError> {
+        let num_proofs = proof.proofs.len();
+        if num_proofs == 0 || preprocessing.len() != num_proofs {
+            return Err(ProofVerifyError::InternalError);
+        }
+        if commitments.len() != num_proofs {
+            return Err(ProofVerifyError::InvalidInputLength(
+                num_proofs,
+                commitments.len(),
+            ));
+        }
+
+        let mut transcript = ProofTranscript::new(b"Jolt aggregation");
+        let mut opening_accumulator = VerifierOpeningAccumulator::new();
+        let mut generators = None;
+        for ((mut preprocessing, jolt_proof), jolt_commitments) in
+            preprocessing.into_iter().zip(proof.proofs).zip(commitments)
+        {
+            if jolt_proof.opening_proof.is_some() {
+                return Err(ProofVerifyError::InternalError);
+            }
+            let (proof_openings, mut proof_transcript) =
+                Self::verify_claims(&mut preprocessing, jolt_proof, jolt_commitments, None)?;
+            let binding: F = proof_transcript.challenge_scalar();
+            transcript.append_scalar(&binding);
+            opening_accumulator.extend(proof_openings);
+            generators.get_or_insert(preprocessing.generators);
+        }
+
+        opening_accumulator.reduce_and_verify(
+            &generators.unwrap(),
+            &proof.opening_proof,
+            &mut transcript,
+        )
+    }
+
+    /// Verifies independent proofs of the program `preprocessing` was derived from,
+    /// in parallel, returning the result for each proof in order. The final PCS
+    /// opening checks are deferred and verified together by
+    /// `CommitmentScheme::batch_verify` (for HyperKZG, a single multi-pairing for the
+    /// whole batch); only if that fails is each opening checked on its own, to find
+    /// the invalid proofs.
+    #[tracing::instrument(skip_all)]
+    fn verify_batch(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        proofs: Vec<(
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+                Self::Memory,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+        )>,
+    ) -> Vec<Result<(), ProofVerifyError>> {
+        // `verify_claims` overwrites the proof-specific parts of the preprocessing,
+        // so each thread can reuse a single copy
+        let deferred: Vec<Result<OpeningInstance<PCS, ProofTranscript>, ProofVerifyError>> = proofs
+            .into_par_iter()
+            .map_init(
+                || preprocessing.clone(),
+                |preprocessing, (mut proof, commitments)| {
+                    let Some(opening_proof) = proof.opening_proof.take() else {
+                        return Err(ProofVerifyError::InternalError);
+                    };
+                    let (opening_accumulator, transcript) =
+                        Self::verify_claims(preprocessing, proof, commitments, None)?;
+                    opening_accumulator.reduce_and_defer(opening_proof, transcript)
+                },
+            )
+            .collect();
+
+        let mut results = Vec::with_capacity(deferred.len());
+        let mut instances = vec![];
+        let mut instance_indices = vec![];
+        for result in deferred {
+            match result {
+                Ok(instance) => {
+                    instance_indices.push(results.len());
+                    instances.push(instance);
+                    results.push(Ok(()));
+                }
+                Err(e) => results.push(Err(e)),
+            }
+        }
+        if PCS::batch_verify(&preprocessing.generators, &instances).is_err() {
+            for (instance, index) in instances.iter().zip(instance_indices) {
+                results[index] =
+                    PCS::batch_verify(&preprocessing.generators, std::slice::from_ref(instance));
+            }
+        }
+        results
+    }
+
+    /// Verifies `proof` except for its final PCS opening check, which is folded
+    /// into `accumulator` instead (see `AccumulationScheme`). The proof is only
+    /// verified once `PCS::decide` accepts the accumulator, which can be done once
+    /// for many proofs, e.g. all segments of a long execution or every step of a
+    /// pipeline, rather than paying for an opening check per proof.
+    #[tracing::instrument(skip_all)]
+    fn verify_accumulate(
+        mut preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        mut proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+            Self::Memory,
+        >,
+        commitments: JoltCommitments<PCS, ProofTranscript>,
+        accumulator: &mut PCS::Accumulator,
+    ) -> Result<(), ProofVerifyError>
+    where
+        PCS: AccumulationScheme<ProofTranscript>,
+    {
+        let Some(opening_proof) = proof.opening_proof.take() else {
+            return Err(ProofVerifyError::InternalError);
+        };
+        let (opening_accumulator, transcript) =
+            Self::verify_claims(&mut preprocessing, proof, commitments, None)?;
+        let instance = opening_accumulator.reduce_and_defer(opening_proof, transcript)?;
+        PCS::accumulate(&preprocessing.generators, accumulator, instance)
+    }
+
+    /// Checks that a proof's commitments to the program are the ones in
+    /// `program_commitment`.
+    fn check_program_commitment(
+        program_commitment: &ProgramCommitment<PCS, ProofTranscript>,
+        memory_pages: &MemoryPages,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        continuation: bool,
+    ) -> Result<(), ProofVerifyError> {
+        let bytecode_matches = commitments
+            .bytecode
+            .v_program
+            .as_ref()
+            .is_some_and(|v_program| v_program[..] == program_commitment.bytecode[..]);
+        // Continuations start from the previous memory state, not the memory image
+        let memory_image_matches = continuation
+            || (commitments.read_write_memory.v_image.as_ref()
+                == Some(&program_commitment.memory_image)
+                && memory_pages
+                    .check_image(
+                        &program_commitment.memory_image_pages,
+                        &program_commitment.memory_layout,
+                    )
+                    .is_ok());
+        if bytecode_matches && memory_image_matches {
+            Ok(())
+        } else {
+            Err(ProofVerifyError::InternalError)
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     fn verify_instruction_lookups<'a>(
         preprocessing: &InstructionLookupsPreprocessing<C, F>,
@@ -650,6 +2749,7 @@ where
             Self::InstructionSet,
             Self::Subtables,
             ProofTranscript,
+            Self::Memory,
         >,
         commitments: &'a JoltCommitments<PCS, ProofTranscript>,
         opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
@@ -691,7 +2791,7 @@ where
         preprocessing: &mut ReadWriteMemoryPreprocessing,
         generators: &PCS::Setup,
         memory_layout: &MemoryLayout,
-        proof: ReadWriteMemoryProof<F, PCS, ProofTranscript>,
+        proof: Self::Memory,
         commitment: &'a JoltCommitments<PCS, ProofTranscript>,
         program_io: JoltDevice,
         opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
@@ -707,8 +2807,7 @@ where
             memory_layout: memory_layout.clone(),
         });
 
-        ReadWriteMemoryProof::verify(
-            proof,
+        proof.verify(
             generators,
             preprocessing,
             commitment,
@@ -729,17 +2828,58 @@ where
         opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
+        let num_custom_aux = <Self::Constraints as R1CSConstraints<C, F>>::Inputs::num_custom_aux();
+        if commitments.r1cs.aux.custom.len() != num_custom_aux {
+            return Err(ProofVerifyError::InvalidInputLength(
+                num_custom_aux,
+                commitments.r1cs.aux.custom.len(),
+            ));
+        }
         proof
             .verify(commitments, opening_accumulator, transcript)
             .map_err(|e| ProofVerifyError::SpartanError(e.to_string()))
     }
 
+    /// The metadata of proofs made with `preprocessing`, see `ProofMetadata`
+    fn proof_metadata(
+        preprocessing: &JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> ProofMetadata {
+        let memory_layout = &preprocessing.memory_layout;
+        let mut hasher = Sha3_256::new();
+        hasher.update(PCS::protocol_name());
+        for parameter in [
+            C as u64,
+            M as u64,
+            Self::InstructionSet::COUNT as u64,
+            Self::Subtables::COUNT as u64,
+            memory_layout.max_input_size,
+            memory_layout.max_output_size,
+            preprocessing.program_commitment.is_some() as u64,
+            preprocessing.read_write_memory.commit_inputs as u64,
+        ] {
+            hasher.update(parameter.to_le_bytes());
+        }
+        if let Some(program_commitment) = &preprocessing.program_commitment {
+            hasher.update(program_commitment.digest());
+        }
+        ProofMetadata {
+            program_hash: preprocessing.program_hash,
+            verifier_key_digest: preprocessing.verifier_key_digest,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_digest: hasher.finalize().into(),
+        }
+    }
+
     fn fiat_shamir_preamble(
         transcript: &mut ProofTranscript,
+        metadata: &ProofMetadata,
         program_io: &JoltDevice,
         memory_layout: &MemoryLayout,
+        memory_pages: &MemoryPages,
+        previous: Option<&MemoryState>,
         trace_length: usize,
     ) {
+        metadata.append_to_transcript(transcript);
         transcript.append_u64(trace_length as u64);
         transcript.append_u64(C as u64);
         transcript.append_u64(M as u64);
@@ -750,11 +2890,229 @@ where
         transcript.append_bytes(&program_io.inputs);
         transcript.append_bytes(&program_io.outputs);
         transcript.append_u64(program_io.panic as u64);
+        transcript.append_u64(memory_pages.page_size as u64);
+        transcript.append_u64(memory_pages.pages.len() as u64);
+        for page in memory_pages.pages.iter() {
+            transcript.append_u64(*page);
+        }
+        if let Some(previous) = previous {
+            transcript.append_bytes(&previous.program_io.inputs);
+            transcript.append_bytes(&previous.program_io.outputs);
+            transcript.append_u64(previous.program_io.panic as u64);
+        }
+    }
+}
+
+/// Sets up the PCS for polynomials of up to the given sizes.
+/// `Jolt::cost_report` and `Jolt::verification_cost` for a program with `code_size`
+/// instructions whose proofs commit to the columns of `commitments`
+fn estimate_costs<J, F, PCS, const C: usize, const M: usize, ProofTranscript>(
+    commitments: &JoltCommitments<PCS, ProofTranscript>,
+    input_start: u64,
+    code_size: usize,
+    trace_length: usize,
+    memory_size: usize,
+) -> (CostReport, VerificationCost, Vec<(&'static str, usize)>)
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript> + ?Sized,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let padded_trace_length = trace_length.next_power_of_two();
+    let r1cs_builder = J::Constraints::construct_constraints(padded_trace_length, input_start);
+    let spartan_key = spartan::UniformSpartanProof::<
+        C,
+        <J::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        ProofTranscript,
+    >::setup(&r1cs_builder, padded_trace_length);
+
+    let read_write_columns = commitments.read_write_values().len()
+        + <J::Constraints as R1CSConstraints<C, F>>::Inputs::num_custom_aux();
+    let init_final_columns = commitments.init_final_values().len();
+    let commitment_size = PCS::Commitment::default().compressed_size();
+
+    // Each memory-checking instance has a read/write and an init/final grand product,
+    // and the timestamp range check one more
+    let grand_products = [
+        padded_trace_length,
+        code_size,
+        padded_trace_length,
+        memory_size,
+        padded_trace_length,
+        M,
+        padded_trace_length,
+    ];
+    let memory_checking: usize = grand_products
+        .iter()
+        .map(|num_leaves| cost::grand_product_size(*num_leaves))
+        .sum();
+    let primary_sumcheck_degree = InstructionLookupsProof::<
+        C,
+        M,
+        F,
+        PCS,
+        J::InstructionSet,
+        J::Subtables,
+        ProofTranscript,
+    >::sumcheck_poly_degree();
+    let lookups = cost::sumcheck_size(padded_trace_length.log_2(), primary_sumcheck_degree)
+        + cost::sumcheck_size(memory_size.next_power_of_two().log_2(), 3);
+    // Roughly two evaluation claims per committed polynomial
+    let openings = 2 * (read_write_columns + init_final_columns);
+    // Approximates the batched opening proof as one group element per variable,
+    // as in KZG-based schemes
+    let max_poly_len = padded_trace_length.max(memory_size).max(code_size).max(M);
+    let opening_num_vars = max_poly_len.next_power_of_two().log_2();
+    let opening_proof_size = opening_num_vars * commitment_size;
+
+    // Counts every witness polynomial as dense field elements, and each grand
+    // product tree as twice its leaves for both multisets
+    let witness_elements = read_write_columns * padded_trace_length
+        + init_final_columns * memory_size.max(code_size).max(M).next_power_of_two();
+    let spartan_elements = 3 * spartan_key.num_rows_total();
+    let grand_product_elements: usize = grand_products
+        .iter()
+        .map(|num_leaves| 4 * num_leaves.next_power_of_two())
+        .sum();
+    let prover_memory = padded_trace_length
+        * std::mem::size_of::<JoltTraceStep<J::InstructionSet>>()
+        + (witness_elements + spartan_elements + grand_product_elements) * F::NUM_BYTES;
+
+    let report = CostReport {
+        padded_trace_length,
+        read_write_columns,
+        init_final_columns,
+        r1cs_inputs: spartan_key.uniform_r1cs.num_vars,
+        uniform_constraints: spartan_key.uniform_r1cs.num_rows,
+        cross_step_constraints: spartan_key.offset_eq_r1cs.constraints.len(),
+        product_constraints: spartan_key.product_constraints.len(),
+        constraint_rows: spartan_key.num_rows_total(),
+        commitments_size: (read_write_columns + init_final_columns) * commitment_size,
+        r1cs_proof_size: cost::spartan_proof_size(&spartan_key) * F::NUM_BYTES,
+        other_proof_size: (memory_checking + lookups + openings) * F::NUM_BYTES
+            + opening_proof_size,
+        prover_memory,
+    };
+
+    let num_commitments = report.num_commitments();
+    let sumcheck_rounds = grand_products
+        .iter()
+        .map(|num_leaves| cost::grand_product_rounds(*num_leaves))
+        .sum::<usize>()
+        + padded_trace_length.log_2()
+        + memory_size.next_power_of_two().log_2()
+        + cost::spartan_rounds(&spartan_key)
+        // The sumcheck reducing the evaluation claims to a single opening
+        + opening_num_vars;
+    let proof_field_elements =
+        memory_checking + lookups + openings + cost::spartan_proof_size(&spartan_key);
+    let (pairings, opening_scalar_muls) = PCS::verifier_group_ops(opening_num_vars);
+    let cost = VerificationCost {
+        pairings,
+        scalar_muls: num_commitments + opening_scalar_muls,
+        field_muls: proof_field_elements
+            // Updating the eq polynomial with each round's challenge
+            + 2 * sumcheck_rounds
+            + num_commitments
+            + cost::matrix_evaluation_muls(&spartan_key),
+        hashes: proof_field_elements + opening_num_vars + num_commitments + sumcheck_rounds,
+        proof_size: report.total_size(),
+    };
+
+    let grand_product = security::grand_product_error_degree;
+    let spartan_degree = 3.max(spartan_key.product_constraint_degree() + 1);
+    let error_degrees = vec![
+        (
+            "bytecode",
+            grand_product(padded_trace_length) + grand_product(code_size),
+        ),
+        (
+            "instruction lookups",
+            grand_product(padded_trace_length)
+                + grand_product(M)
+                + cost::sumcheck_size(padded_trace_length.log_2(), primary_sumcheck_degree),
+        ),
+        (
+            "read/write memory",
+            grand_product(padded_trace_length)
+                + grand_product(memory_size)
+                + cost::sumcheck_size(memory_size.next_power_of_two().log_2(), 3),
+        ),
+        ("timestamp range check", grand_product(padded_trace_length)),
+        (
+            "r1cs",
+            cost::sumcheck_size(cost::spartan_rounds(&spartan_key), spartan_degree),
+        ),
+        // A random linear combination of the claims, then a degree-2 sumcheck
+        (
+            "opening reduction",
+            openings + cost::sumcheck_size(opening_num_vars, 2),
+        ),
+    ];
+    (report, cost, error_degrees)
+}
+
+/// The `JoltPreprocessing::verifier_key_digest` of preprocessing that does not
+/// commit to its program: Sha3-256 of the program hash and the maximum sizes, so
+/// that its proofs only verify with preprocessing of the same program and sizes.
+fn unbound_verifier_key_digest(
+    program_hash: &[u8; 32],
+    max_bytecode_size: usize,
+    max_memory_address: usize,
+    max_trace_length: usize,
+) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(program_hash);
+    for size in [max_bytecode_size, max_memory_address, max_trace_length] {
+        hasher.update((size as u64).to_le_bytes());
+    }
+    hasher.finalize().into()
+}
+
+fn pcs_setup<PCS, ProofTranscript>(
+    max_bytecode_size: usize,
+    max_memory_address: usize,
+    max_trace_length: usize,
+    lookup_table_size: usize,
+    cache: Option<&SetupCache>,
+) -> PCS::Setup
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    let max_poly_len: usize = [
+        (max_bytecode_size + 1).next_power_of_two(), // Account for no-op prepended to bytecode
+        max_trace_length.next_power_of_two(),
+        max_memory_address.next_power_of_two(),
+        lookup_table_size,
+    ]
+    .into_iter()
+    .max()
+    .unwrap();
+    match cache {
+        Some(cache) => cache.setup::<PCS, ProofTranscript>(max_poly_len),
+        None => PCS::setup(max_poly_len),
     }
 }
 
 pub mod bytecode;
+pub mod calibration;
+pub mod config;
+pub mod cost;
+pub mod distributed;
+pub mod envelope;
 pub mod instruction_lookups;
+pub mod json;
+pub mod kat;
+pub mod memory_tracker;
+pub mod progress;
 pub mod read_write_memory;
+pub mod report;
 pub mod rv32i_vm;
+pub mod security;
+pub mod segment;
+pub mod stage_summary;
+pub mod stream;
 pub mod timestamp_range_check;
@@ -0,0 +1,221 @@
+use std::fmt;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::utils::errors::ProvingCancelled;
+
+/// The stages of proving, in order, as reported to `ProverProgress`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverStage {
+    /// Tracing the guest. Reported by callers that trace before proving, e.g. the
+    /// `prove_*_async` functions generated by `jolt::provable`.
+    Tracing,
+    /// Generating the witness polynomials from the trace, and committing to most
+    /// of them as they are generated
+    WitnessGeneration,
+    /// Committing to the remaining witness polynomials: the R1CS inputs and the
+    /// memory-checking init/final values
+    Commitments,
+    /// Proving bytecode, instruction lookups, memory and R1CS
+    Sumchecks,
+    /// Proving the batched opening of all committed polynomials
+    Openings,
+}
+
+impl fmt::Display for ProverStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Tracing => "tracing",
+            Self::WitnessGeneration => "witness generation",
+            Self::Commitments => "commitments",
+            Self::Sumchecks => "sumchecks",
+            Self::Openings => "openings",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Cancels a proof in progress, see `ProverProgress::with_cancellation`.
+/// Clones cancel the same proof.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Reports the progress of a proof to a callback, and lets the caller cancel it
+/// (see `Jolt::prove_with_progress`). Cancellation is cooperative: the prover
+/// checks the token between stages and between the subprotocols of a stage.
+#[derive(Clone, Default)]
+pub struct ProverProgress {
+    on_stage: Option<Arc<dyn Fn(ProverStage) + Send + Sync>>,
+    cancellation: CancellationToken,
+}
+
+impl ProverProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `on_stage` as the prover enters each stage
+    pub fn on_stage(mut self, on_stage: impl Fn(ProverStage) + Send + Sync + 'static) -> Self {
+        self.on_stage = Some(Arc::new(on_stage));
+        self
+    }
+
+    /// Stops proving once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Reports that proving enters `stage`, unless it has been cancelled
+    pub fn enter(&self, stage: ProverStage) -> Result<(), ProvingCancelled> {
+        self.check()?;
+        if let Some(on_stage) = &self.on_stage {
+            on_stage(stage);
+        }
+        Ok(())
+    }
+
+    /// Fails if proving has been cancelled
+    pub fn check(&self) -> Result<(), ProvingCancelled> {
+        if self.cancellation.is_cancelled() {
+            return Err(ProvingCancelled);
+        }
+        Ok(())
+    }
+}
+
+struct TaskState<T> {
+    output: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// A future resolving to the result of a computation run on its own thread,
+/// e.g. a proof. Works with any executor, since it only needs the task to be
+/// woken once the thread is done. A panic of the computation is resumed when
+/// the future is polled. Without the `parallel` feature, the computation runs
+/// in `spawn` instead, and the future is ready right away.
+pub struct ProvingTask<T> {
+    state: Arc<Mutex<TaskState<T>>>,
+}
+
+impl<T: Send + 'static> ProvingTask<T> {
+    #[cfg(not(feature = "parallel"))]
+    pub fn spawn(f: impl FnOnce() -> T + Send + 'static) -> Self {
+        let state = TaskState {
+            output: Some(panic::catch_unwind(AssertUnwindSafe(f))),
+            waker: None,
+        };
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn spawn(f: impl FnOnce() -> T + Send + 'static) -> Self {
+        let state = Arc::new(Mutex::new(TaskState {
+            output: None,
+            waker: None,
+        }));
+        let thread_state = state.clone();
+        thread::spawn(move || {
+            let output = panic::catch_unwind(AssertUnwindSafe(f));
+            let waker = {
+                let mut state = thread_state.lock().unwrap();
+                state.output = Some(output);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+        Self { state }
+    }
+}
+
+impl<T> Future for ProvingTask<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.output.take() {
+            Some(Ok(output)) => Poll::Ready(output),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Wake;
+    use std::thread::Thread;
+
+    use super::*;
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<T>(mut future: impl Future<Output = T> + Unpin) -> T {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match Pin::new(&mut future).poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn proving_task() {
+        let progress = ProverProgress::new();
+        let token = progress.cancellation_token();
+        let task = ProvingTask::spawn(move || {
+            progress.enter(ProverStage::Tracing)?;
+            Ok::<_, ProvingCancelled>(42)
+        });
+        assert_eq!(block_on(task).unwrap(), 42);
+
+        token.cancel();
+        let progress = ProverProgress::new().with_cancellation(token);
+        let task = ProvingTask::spawn(move || progress.enter(ProverStage::Tracing));
+        assert!(block_on(task).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "guest panicked")]
+    fn proving_task_panics() {
+        block_on(ProvingTask::spawn(|| panic!("guest panicked")));
+    }
+}
@@ -6,10 +6,9 @@ use crate::lasso::memory_checking::{
 use crate::poly::compact_polynomial::{CompactPolynomial, SmallScalar};
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
+use crate::utils::parallel::prelude::*;
 use crate::utils::thread::unsafe_allocate_zero_vec;
-use rayon::prelude::*;
-#[cfg(test)]
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::marker::PhantomData;
 
 use crate::poly::commitment::commitment_scheme::CommitmentScheme;
@@ -30,6 +29,7 @@ use common::constants::{
 };
 use common::rv_trace::{JoltDevice, MemoryLayout, MemoryOp};
 
+use super::segment::Segment;
 use super::{timestamp_range_check::TimestampValidityProof, JoltCommitments};
 use super::{JoltPolynomials, JoltStuff, JoltTraceStep};
 
@@ -43,6 +43,203 @@ pub struct ReadWriteMemoryPreprocessing {
     // to compute the v_init and v_final openings, with no impact
     // on existing function signatures.
     pub program_io: Option<JoltDevice>,
+    /// If set, this is a continuation proof: memory starts out in the given state
+    /// rather than containing just the bytecode and inputs. See `MemoryState`.
+    pub continuation: Option<MemoryState>,
+    /// If set, this proof is one segment of a longer execution (see
+    /// `Jolt::prove_segmented`).
+    pub segment: Option<Segment>,
+    /// The pages of memory covered by the init/final polynomials. Set by the
+    /// prover (see `ReadWriteMemoryPreprocessing::touched_pages`) and, after
+    /// checking them, by the verifier from the proof.
+    pub pages: MemoryPages,
+    /// If set, proofs also commit to the program's initial memory image (see
+    /// `ProgramCommitment`), and verifiers open it instead of computing it from
+    /// `bytecode_words`.
+    pub memory_image: Option<MemoryImage>,
+    /// If set, proofs commit to the program's inputs (see `InputCommitment`)
+    /// rather than carrying them, and verifiers open the commitment instead of
+    /// computing the inputs' contribution to `v_init` and the output check.
+    /// Not supported for segmented proofs.
+    pub(crate) commit_inputs: bool,
+    /// Digest of the `InputCommitment` a verifier accepts, set by
+    /// `Jolt::verify_with_input_digest`. Only the digest ties a proof's input
+    /// commitment to the inputs (and to zero outside of them), so proofs with
+    /// committed inputs are rejected without it.
+    pub(crate) input_digest: Option<[u8; 32]>,
+}
+
+/// The pages holding a program's initial memory image (its code and data).
+///
+/// These always directly follow the register and I/O pages in `MemoryPages`, so
+/// the image occupies the same positions of `v_init` in every proof of the
+/// program, and can be committed to ahead of time.
+#[derive(Clone, Copy, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MemoryImage {
+    pub first_page: u64,
+    pub num_pages: usize,
+}
+
+/// Words per page of the RAM witness (i.e. 4 KiB pages), unless the I/O region is
+/// smaller than that (see `MemoryPages::page_size`).
+const PAGE_SIZE: usize = 1 << 10;
+
+/// The pages of the RAM witness that the init/final polynomials cover.
+///
+/// Most guests only touch a small fraction of their address space, so rather than
+/// committing to the initial and final value of every word up to the highest
+/// address accessed, memory checking only covers the pages listed here: position
+/// `i` of `v_init`, `v_final` and `t_final` holds the word at witness index
+/// `pages[i / page_size] * page_size + i % page_size`. The page list is public
+/// and the verifier derives the corresponding addresses (`a_init_final`) from
+/// it, so a read of any word outside these pages has no matching init/final
+/// tuple and fails memory checking.
+///
+/// The first pages are always the ones holding the registers and I/O region, in
+/// order, so those words sit at the same positions as their witness indices.
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MemoryPages {
+    pub page_size: usize,
+    pub pages: Vec<u64>,
+}
+
+impl MemoryPages {
+    /// Page size (in words) used for the given memory layout.
+    pub fn page_size(memory_layout: &MemoryLayout) -> usize {
+        // `ram_start_index` is a power of two, so the I/O region spans whole pages
+        PAGE_SIZE.min(memory_address_to_witness_index(
+            RAM_START_ADDRESS,
+            memory_layout,
+        ))
+    }
+
+    /// Number of words covered, i.e. the size of the init/final polynomials.
+    pub fn memory_size(&self) -> usize {
+        self.pages.len() * self.page_size
+    }
+
+    /// Witness index of the word at `position` in the init/final polynomials.
+    fn index(&self, position: usize) -> u64 {
+        self.pages[position / self.page_size] * self.page_size as u64
+            + (position % self.page_size) as u64
+    }
+
+    /// Checks that the page list is well-formed for `memory_layout`: pages are
+    /// distinct (otherwise a word could have several init/final tuples), start
+    /// with the registers and I/O region followed by the program's memory
+    /// `image`, number a power of two and, for a continuation, extend the pages
+    /// of the previous proof.
+    pub fn check(
+        &self,
+        memory_layout: &MemoryLayout,
+        image: &MemoryImage,
+        previous: Option<&MemoryPages>,
+    ) -> Result<(), ProofVerifyError> {
+        let page_size = Self::page_size(memory_layout);
+        let io_pages =
+            memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout) / page_size;
+        let max_page = u64::MAX / page_size as u64;
+
+        let mut seen = HashSet::with_capacity(self.pages.len());
+        let well_formed = self.page_size == page_size
+            && self.pages.len().is_power_of_two()
+            && self.pages.len() >= io_pages
+            && self.pages[..io_pages]
+                .iter()
+                .enumerate()
+                .all(|(i, page)| *page == i as u64)
+            && self
+                .pages
+                .iter()
+                .all(|page| *page < max_page && seen.insert(*page))
+            && previous.map_or(true, |previous| {
+                self.page_size == previous.page_size && self.pages.starts_with(&previous.pages)
+            });
+        if !well_formed {
+            return Err(ProofVerifyError::InternalError);
+        }
+        self.check_image(image, memory_layout)
+    }
+
+    /// Checks that the memory image pages directly follow the register and I/O
+    /// pages, as assumed by a commitment to the image (see `MemoryImage`).
+    pub fn check_image(
+        &self,
+        image: &MemoryImage,
+        memory_layout: &MemoryLayout,
+    ) -> Result<(), ProofVerifyError> {
+        let io_pages = memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout)
+            / Self::page_size(memory_layout);
+        let image_pages = image.first_page..image.first_page + image.num_pages as u64;
+        if self.pages.len() >= io_pages + image.num_pages
+            && self.pages[io_pages..io_pages + image.num_pages]
+                .iter()
+                .copied()
+                .eq(image_pages)
+        {
+            Ok(())
+        } else {
+            Err(ProofVerifyError::InternalError)
+        }
+    }
+}
+
+/// Maps witness indices to their positions in the init/final polynomials.
+struct PageTable<'a> {
+    pages: &'a MemoryPages,
+    slots: HashMap<u64, usize>,
+}
+
+impl<'a> PageTable<'a> {
+    fn new(pages: &'a MemoryPages) -> Self {
+        let slots = pages
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(slot, page)| (*page, slot))
+            .collect();
+        Self { pages, slots }
+    }
+
+    /// The position of witness index `index`, if its page is covered.
+    fn position(&self, index: u64) -> Option<usize> {
+        let page_size = self.pages.page_size;
+        let slot = self.slots.get(&(index / page_size as u64))?;
+        Some(slot * page_size + (index % page_size as u64) as usize)
+    }
+}
+
+/// The final memory state of a proven execution, which can be carried over as
+/// the initial state of the next proof in a chain (see `Jolt::prove_continuation`).
+///
+/// A continuation's initial memory is the previous execution's final memory, with
+/// the I/O region replaced by the new inputs. It is linked to the previous proof
+/// succinctly: the continuation commits to the previous `v_final` (which must yield
+/// the same commitment as in the previous proof), opens it as part of memory
+/// checking, and the verifier derives the `v_init` opening from it and the public
+/// I/O of both executions.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct MemoryState {
+    /// Final values of registers, the I/O region and RAM, laid out like `v_final`
+    /// (see `MemoryPages`). Only needed by the prover; verifiers can leave this empty.
+    pub words: Vec<u32>,
+    /// Pages of memory covered by `words`.
+    pub pages: MemoryPages,
+    /// Public I/O of the execution that produced this state.
+    pub program_io: JoltDevice,
+}
+
+impl MemoryState {
+    /// The nonzero words of the state as `(witness index, word)` pairs, e.g. for
+    /// `tracer::trace_from_state`.
+    pub fn nonzero_words(&self) -> Vec<(usize, u32)> {
+        self.words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| **word != 0)
+            .map(|(position, word)| (self.pages.index(position) as usize, *word))
+            .collect()
+    }
 }
 
 impl ReadWriteMemoryPreprocessing {
@@ -80,7 +277,131 @@ impl ReadWriteMemoryPreprocessing {
             min_bytecode_address,
             bytecode_words,
             program_io: None,
+            continuation: None,
+            segment: None,
+            pages: MemoryPages::default(),
+            memory_image: None,
+            commit_inputs: false,
+            input_digest: None,
+        }
+    }
+
+    /// Preprocessing for a verifier that only knows the program by its
+    /// `ProgramCommitment`: it holds no memory image, which is opened from its
+    /// commitment instead.
+    pub fn committed(memory_image: MemoryImage) -> Self {
+        Self {
+            min_bytecode_address: 0,
+            bytecode_words: vec![],
+            program_io: None,
+            continuation: None,
+            segment: None,
+            pages: MemoryPages::default(),
+            memory_image: Some(memory_image),
+            commit_inputs: false,
+            input_digest: None,
+        }
+    }
+
+    /// Whether the memory image is part of this proof's init/final polynomials.
+    /// Continuations start from the previous memory state instead.
+    fn commits_memory_image(&self) -> bool {
+        self.memory_image.is_some() && self.continuation.is_none()
+    }
+
+    /// Whether memory starts out exactly in the continuation state, rather than
+    /// with its I/O region replaced by this execution's inputs.
+    fn resumes(&self) -> bool {
+        self.segment.is_some_and(|segment| !segment.first)
+    }
+
+    /// Whether the execution ends in this proof, so that its outputs are checked.
+    fn checks_outputs(&self) -> bool {
+        self.segment.map_or(true, |segment| segment.last)
+    }
+
+    /// The pages holding the program's initial memory image, as committed to if
+    /// the program is only known by its commitment.
+    pub fn image(&self, memory_layout: &MemoryLayout) -> MemoryImage {
+        self.memory_image
+            .unwrap_or_else(|| self.memory_image_pages(memory_layout))
+    }
+
+    /// The pages holding the program's initial memory image.
+    pub fn memory_image_pages(&self, memory_layout: &MemoryLayout) -> MemoryImage {
+        let page_size = MemoryPages::page_size(memory_layout);
+        let start = memory_address_to_witness_index(self.min_bytecode_address, memory_layout);
+        let end = start + self.bytecode_words.len();
+        let first_page = start / page_size;
+        MemoryImage {
+            first_page: first_page as u64,
+            num_pages: end.div_ceil(page_size) - first_page,
+        }
+    }
+
+    /// The initial memory image laid out as in `v_init` (see `MemoryImage`),
+    /// without trailing zeros beyond the next power of two. Its commitment is
+    /// the one proofs make when the image is committed to.
+    pub fn memory_image_polynomial(&self, memory_layout: &MemoryLayout) -> Vec<u32> {
+        let page_size = MemoryPages::page_size(memory_layout);
+        let image = self.memory_image_pages(memory_layout);
+        let io_size = memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout);
+        // Offset of the image within its first page
+        let start = memory_address_to_witness_index(self.min_bytecode_address, memory_layout)
+            - image.first_page as usize * page_size;
+
+        let len = io_size + image.num_pages * page_size;
+        let mut words = vec![0; len.next_power_of_two()];
+        words[io_size + start..io_size + start + self.bytecode_words.len()]
+            .copy_from_slice(&self.bytecode_words);
+        words
+    }
+
+    /// The pages of memory to cover in the init/final polynomials when proving
+    /// `trace`: the registers and I/O region, the pages holding the program's
+    /// initial memory image, and every page accessed by the trace, padded with
+    /// unused pages to a power of two. For a continuation, these extend the pages
+    /// of the previous state.
+    #[tracing::instrument(skip_all, name = "ReadWriteMemoryPreprocessing::touched_pages")]
+    pub fn touched_pages<InstructionSet: JoltInstructionSet>(
+        &self,
+        program_io: &JoltDevice,
+        trace: &[JoltTraceStep<InstructionSet>],
+    ) -> MemoryPages {
+        let memory_layout = &program_io.memory_layout;
+        let page_size = MemoryPages::page_size(memory_layout);
+        let io_pages =
+            memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout) / page_size;
+
+        let mut pages: Vec<u64> = match &self.continuation {
+            Some(state) => state.pages.pages.clone(),
+            None => (0..io_pages as u64).collect(),
+        };
+
+        if self.continuation.is_none() {
+            // The memory image goes first, in order (see `MemoryImage`)
+            let image = self.memory_image_pages(memory_layout);
+            pages.extend(image.first_page..image.first_page + image.num_pages as u64);
+        }
+
+        let mut touched: BTreeSet<u64> = BTreeSet::new();
+        touched.extend(trace.iter().map(|step| match step.memory_ops[RAM] {
+            MemoryOp::Read(a) | MemoryOp::Write(a, _) => {
+                remap_address(a, memory_layout) / page_size as u64
+            }
+        }));
+
+        let present: HashSet<u64> = pages.iter().copied().collect();
+        pages.extend(touched.into_iter().filter(|page| !present.contains(page)));
+
+        // Pad with pages beyond the highest one in use
+        let mut next_page = pages.iter().max().unwrap() + 1;
+        while !pages.len().is_power_of_two() {
+            pages.push(next_page);
+            next_page += 1;
         }
+
+        MemoryPages { page_size, pages }
     }
 }
 
@@ -100,6 +421,88 @@ fn remap_address(a: u64, memory_layout: &MemoryLayout) -> u64 {
     }
 }
 
+/// Number of words of the registers and I/O region, which come first in the
+/// init/final polynomials. A power of two.
+pub(crate) fn io_region_size(memory_layout: &MemoryLayout) -> usize {
+    memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout)
+}
+
+/// The words of `inputs` laid out as in `v_init`, without trailing zeros beyond
+/// the I/O region. Its commitment is the `InputCommitment` to `inputs`.
+pub fn input_polynomial(inputs: &[u8], memory_layout: &MemoryLayout) -> Vec<u32> {
+    let mut words = vec![0; io_region_size(memory_layout)];
+    let input_start = memory_address_to_witness_index(memory_layout.input_start, memory_layout);
+    for (i, chunk) in inputs.chunks(4).enumerate() {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        words[input_start + i] = u32::from_le_bytes(word);
+    }
+    words
+}
+
+/// eq(r, index), with the most significant bit of `index` first
+fn eq_at_index<F: JoltField>(r: &[F], index: usize) -> F {
+    r.iter()
+        .rev()
+        .enumerate()
+        .map(|(bit, r_i)| {
+            if (index >> bit) & 1 == 1 {
+                *r_i
+            } else {
+                F::one() - r_i
+            }
+        })
+        .product()
+}
+
+/// The multilinear extension of `i >= start` (over `r.len()` variables) at `r`,
+/// in time linear in `r.len()` rather than in the size of the hypercube
+fn range_start_eval<F: JoltField>(start: usize, r: &[F]) -> F {
+    // Sums over the most significant bit at which `i` exceeds `start`, tracking
+    // eq(r, start) over the bits above it
+    let mut eval = F::zero();
+    let mut eq_prefix = F::one();
+    for (i, r_i) in r.iter().enumerate() {
+        if (start >> (r.len() - 1 - i)) & 1 == 1 {
+            eq_prefix *= *r_i;
+        } else {
+            eval += eq_prefix * r_i;
+            eq_prefix *= F::one() - r_i;
+        }
+    }
+    // i == start
+    eval + eq_prefix
+}
+
+/// The nonzero words of the I/O region implied by `program_io` (inputs, outputs,
+/// panic and termination bits) at the end of execution, as enforced by the output
+/// sumcheck, as `(witness index, word)` pairs.
+fn io_witness(program_io: &JoltDevice) -> Vec<(usize, u64)> {
+    let memory_layout = &program_io.memory_layout;
+    let mut v_io: Vec<(usize, u64)> = vec![];
+    let regions = [
+        (memory_layout.input_start, &program_io.inputs),
+        (memory_layout.output_start, &program_io.outputs),
+    ];
+    for (start, bytes) in regions {
+        let start = memory_address_to_witness_index(start, memory_layout);
+        for (i, chunk) in bytes.chunks(4).enumerate() {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            v_io.push((start + i, u32::from_le_bytes(word) as u64));
+        }
+    }
+    let panic_index = memory_address_to_witness_index(memory_layout.panic, memory_layout);
+    let termination_index =
+        memory_address_to_witness_index(memory_layout.termination, memory_layout);
+    if program_io.panic {
+        v_io.push((panic_index, 1));
+    } else {
+        v_io.push((termination_index, 1));
+    }
+    v_io
+}
+
 const RS1: usize = 0;
 const RS2: usize = 1;
 const RD: usize = 2;
@@ -134,6 +537,12 @@ pub struct ReadWriteMemoryStuff<T: CanonicalSerialize + CanonicalDeserialize> {
     pub t_read_ram: T,
     /// Final timestamps.
     pub t_final: T,
+    /// Final memory state of the previous proof, for continuations.
+    pub v_final_previous: Option<T>,
+    /// The program's initial memory image, if it is committed to (see `MemoryImage`).
+    pub v_image: Option<T>,
+    /// The program's inputs, if they are committed to (see `input_polynomial`).
+    pub v_inputs: Option<T>,
 
     a_init_final: VerifierComputedOpening<T>,
     /// Initial memory values. RAM is initialized to contain the program bytecode and inputs.
@@ -177,11 +586,19 @@ impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
     }
 
     fn init_final_values(&self) -> Vec<&T> {
-        vec![&self.v_final, &self.t_final]
+        let mut values = vec![&self.v_final, &self.t_final];
+        values.extend(self.v_final_previous.as_ref());
+        values.extend(self.v_image.as_ref());
+        values.extend(self.v_inputs.as_ref());
+        values
     }
 
     fn init_final_values_mut(&mut self) -> Vec<&mut T> {
-        vec![&mut self.v_final, &mut self.t_final]
+        let mut values = vec![&mut self.v_final, &mut self.t_final];
+        values.extend(self.v_final_previous.as_mut());
+        values.extend(self.v_image.as_mut());
+        values.extend(self.v_inputs.as_mut());
+        values
     }
 }
 
@@ -210,6 +627,14 @@ pub type ReadWriteMemoryCommitments<
 impl<T: CanonicalSerialize + CanonicalDeserialize + Default>
     Initializable<T, ReadWriteMemoryPreprocessing> for ReadWriteMemoryStuff<T>
 {
+    fn initialize(preprocessing: &ReadWriteMemoryPreprocessing) -> Self {
+        Self {
+            v_final_previous: preprocessing.continuation.as_ref().map(|_| T::default()),
+            v_image: preprocessing.commits_memory_image().then(T::default),
+            v_inputs: preprocessing.commit_inputs.then(T::default),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Default, CanonicalSerialize, CanonicalDeserialize)]
@@ -262,33 +687,55 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
         let m = trace.len();
         assert!(m.is_power_of_two());
 
-        let max_trace_address = trace
-            .iter()
-            .map(|step| match step.memory_ops[RAM] {
-                MemoryOp::Read(a) => remap_address(a, &program_io.memory_layout),
-                MemoryOp::Write(a, _) => remap_address(a, &program_io.memory_layout),
-            })
-            .max()
-            .unwrap();
-
-        let memory_size = max_trace_address.next_power_of_two() as usize;
+        let pages = &preprocessing.pages;
+        let page_table = PageTable::new(pages);
+        let memory_size = pages.memory_size();
         let mut v_init: Vec<u32> = vec![0; memory_size];
-        // Copy bytecode
-        let mut v_init_index = memory_address_to_witness_index(
-            preprocessing.min_bytecode_address,
-            &program_io.memory_layout,
-        );
-        for word in preprocessing.bytecode_words.iter() {
-            v_init[v_init_index] = *word;
-            v_init_index += 1;
+        if let Some(state) = &preprocessing.continuation {
+            // Start from the previous final state, minus its I/O region (unless
+            // resuming a segment, which keeps it as is)
+            v_init[..state.words.len()].copy_from_slice(&state.words);
+            if !preprocessing.resumes() {
+                let io_start = memory_address_to_witness_index(
+                    program_io.memory_layout.input_start,
+                    &program_io.memory_layout,
+                );
+                let io_end =
+                    memory_address_to_witness_index(RAM_START_ADDRESS, &program_io.memory_layout);
+                v_init[io_start..io_end].fill(0);
+            }
+        } else {
+            // Copy bytecode
+            let bytecode_start = memory_address_to_witness_index(
+                preprocessing.min_bytecode_address,
+                &program_io.memory_layout,
+            );
+            for (i, word) in preprocessing.bytecode_words.iter().enumerate() {
+                let position = page_table.position((bytecode_start + i) as u64).unwrap();
+                v_init[position] = *word;
+            }
         }
+        let v_image = preprocessing
+            .commits_memory_image()
+            .then(|| MultilinearPolynomial::from(v_init.clone()));
+        let v_inputs = preprocessing.commit_inputs.then(|| {
+            let mut words = input_polynomial(&program_io.inputs, &program_io.memory_layout);
+            words.resize(memory_size, 0);
+            MultilinearPolynomial::from(words)
+        });
         // Copy input bytes
-        v_init_index = memory_address_to_witness_index(
+        let mut v_init_index = memory_address_to_witness_index(
             program_io.memory_layout.input_start,
             &program_io.memory_layout,
         );
-        // Convert input bytes into words and populate `v_init`
-        for chunk in program_io.inputs.chunks(4) {
+        // Convert input bytes into words and populate `v_init`. A resumed
+        // segment already has them from the previous segment.
+        let inputs: &[u8] = if preprocessing.resumes() {
+            &[]
+        } else {
+            &program_io.inputs
+        };
+        for chunk in inputs.chunks(4) {
             let mut word = [0u8; 4];
             for (i, byte) in chunk.iter().enumerate() {
                 word[i] = *byte;
@@ -302,8 +749,8 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
         let mut init_tuples: HashSet<(usize, u32, u32)> = HashSet::new();
         #[cfg(test)]
         {
-            for (a, v) in v_init.iter().enumerate() {
-                init_tuples.insert((a, *v, 0));
+            for (position, v) in v_init.iter().enumerate() {
+                init_tuples.insert((pages.index(position) as usize, *v, 0));
             }
         }
         #[cfg(test)]
@@ -403,38 +850,44 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             match step.memory_ops[RAM] {
                 MemoryOp::Read(a) => {
                     debug_assert!(a % 4 == 0);
-                    let remapped_a = remap_address(a, &program_io.memory_layout) as usize;
-                    let v = v_final[remapped_a];
+                    let remapped_a = remap_address(a, &program_io.memory_layout);
+                    let position = page_table
+                        .position(remapped_a)
+                        .expect("accessed page should be covered");
+                    let v = v_final[position];
 
                     #[cfg(test)]
                     {
-                        read_tuples.insert((remapped_a, v, t_final[remapped_a]));
-                        write_tuples.insert((remapped_a, v, timestamp));
+                        read_tuples.insert((remapped_a as usize, v, t_final[position]));
+                        write_tuples.insert((remapped_a as usize, v, timestamp));
                     }
 
                     a_ram.push(remapped_a as u32);
                     v_read_ram.push(v);
-                    t_read_ram.push(t_final[remapped_a]);
+                    t_read_ram.push(t_final[position]);
                     v_write_ram.push(v);
-                    t_final[remapped_a] = timestamp;
+                    t_final[position] = timestamp;
                 }
                 MemoryOp::Write(a, v_new) => {
                     debug_assert!(a % 4 == 0);
-                    let remapped_a = remap_address(a, &program_io.memory_layout) as usize;
-                    let v_old = v_final[remapped_a];
+                    let remapped_a = remap_address(a, &program_io.memory_layout);
+                    let position = page_table
+                        .position(remapped_a)
+                        .expect("accessed page should be covered");
+                    let v_old = v_final[position];
 
                     #[cfg(test)]
                     {
-                        read_tuples.insert((remapped_a, v_old, t_final[remapped_a]));
-                        write_tuples.insert((remapped_a, v_new as u32, timestamp));
+                        read_tuples.insert((remapped_a as usize, v_old, t_final[position]));
+                        write_tuples.insert((remapped_a as usize, v_new as u32, timestamp));
                     }
 
                     a_ram.push(remapped_a as u32);
                     v_read_ram.push(v_old);
-                    t_read_ram.push(t_final[remapped_a]);
+                    t_read_ram.push(t_final[position]);
                     v_write_ram.push(v_new as u32);
-                    v_final[remapped_a] = v_new as u32;
-                    t_final[remapped_a] = timestamp;
+                    v_final[position] = v_new as u32;
+                    t_final[position] = timestamp;
                 }
             }
         }
@@ -445,8 +898,8 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
         #[cfg(test)]
         {
             let mut final_tuples: HashSet<(usize, u32, u32)> = HashSet::new();
-            for (a, (v, t)) in v_final.iter().zip(t_final.iter()).enumerate() {
-                final_tuples.insert((a, *v, *t));
+            for (position, (v, t)) in v_final.iter().zip(t_final.iter()).enumerate() {
+                final_tuples.insert((pages.index(position) as usize, *v, *t));
             }
 
             let init_write: HashSet<_> = init_tuples.union(&write_tuples).collect();
@@ -455,6 +908,11 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             assert_eq!(set_difference.len(), 0);
         }
 
+        let v_final_previous = preprocessing.continuation.as_ref().map(|state| {
+            let mut words = state.words.clone();
+            words.resize(memory_size, 0);
+            MultilinearPolynomial::from(words)
+        });
         let [a_ram, v_read_rd, v_read_rs1, v_read_rs2, v_read_ram, v_write_rd, v_write_ram, v_final, t_read_rd_poly, t_read_rs1_poly, t_read_rs2_poly, t_read_ram_poly, t_final, v_init] =
             map_to_polys([
                 a_ram,
@@ -487,6 +945,9 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             t_read_rs2: t_read_rs2_poly,
             t_read_ram: t_read_ram_poly,
             t_final,
+            v_final_previous,
+            v_image,
+            v_inputs,
             v_init: Some(v_init),
             a_init_final: None,
             identity: None,
@@ -518,7 +979,7 @@ where
 
     #[tracing::instrument(skip_all, name = "ReadWriteMemory::compute_leaves")]
     fn compute_leaves<'a>(
-        _: &Self::Preprocessing,
+        preprocessing: &Self::Preprocessing,
         polynomials: &Self::Polynomials,
         jolt_polynomials: &'a JoltPolynomials<F>,
         gamma: &F,
@@ -628,9 +1089,13 @@ where
 
         let v_init: &CompactPolynomial<u32, F> =
             polynomials.v_init.as_ref().unwrap().try_into().unwrap();
+        let pages = &preprocessing.pages;
         let init_fingerprints: Vec<F> = (0..memory_size)
             .into_par_iter()
-            .map(|i| /* 0 * gamma^2 + */ v_init[i].field_mul(gamma) + F::from_u32(i as u32) - *tau)
+            .map(|i| {
+                /* 0 * gamma^2 + */
+                v_init[i].field_mul(gamma) + F::from_u64(pages.index(i)) - *tau
+            })
             .collect();
 
         let v_final: &CompactPolynomial<u32, F> = (&polynomials.v_final).try_into().unwrap();
@@ -640,7 +1105,7 @@ where
             .map(|i| {
                 t_final[i].field_mul(gamma_squared)
                     + v_final[i].field_mul(gamma)
-                    + F::from_u32(i as u32)
+                    + F::from_u64(pages.index(i))
                     - *tau
             })
             .collect();
@@ -720,38 +1185,86 @@ where
         preprocessing: &Self::Preprocessing,
         r_read_write: &[F],
         r_init_final: &[F],
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         openings.identity =
             Some(IdentityPolynomial::new(r_read_write.len()).evaluate(r_read_write));
 
-        openings.a_init_final =
-            Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
+        // The init/final polynomials must cover exactly the memory pages
+        let pages = &preprocessing.pages;
+        if r_init_final.len() != pages.memory_size().log_2() {
+            return Err(ProofVerifyError::InvalidInputLength(
+                pages.memory_size().log_2(),
+                r_init_final.len(),
+            ));
+        }
+        let log_page_size = pages.page_size.log_2();
+        let (r_page, r_offset) = r_init_final.split_at(r_init_final.len() - log_page_size);
+        let eq_page = EqPolynomial::evals(r_page);
+        let eq_offset = EqPolynomial::evals(r_offset);
+        // eq(r_init_final, position), computed from the page and offset halves
+        let eq = |position: usize| {
+            eq_page[position / pages.page_size] * eq_offset[position % pages.page_size]
+        };
 
-        let memory_layout = &preprocessing.program_io.as_ref().unwrap().memory_layout;
+        // a_init_final(position) = page * page_size + offset
+        let page_eval: F = eq_page
+            .iter()
+            .zip(pages.pages.iter())
+            .map(|(eq, page)| *eq * F::from_u64(*page))
+            .sum();
+        openings.a_init_final = Some(
+            page_eval * F::from_u64(pages.page_size as u64)
+                + IdentityPolynomial::new(log_page_size).evaluate(r_offset),
+        );
 
-        // TODO(moodlezoup): Compute opening without instantiating v_init polynomial itself
-        let memory_size = r_init_final.len().pow2();
-        let mut v_init: Vec<u64> = vec![0; memory_size];
-        // Copy bytecode
-        let mut v_init_index =
-            memory_address_to_witness_index(preprocessing.min_bytecode_address, memory_layout);
-        for word in preprocessing.bytecode_words.iter() {
-            v_init[v_init_index] = *word as u64;
-            v_init_index += 1;
+        let program_io = preprocessing.program_io.as_ref().unwrap();
+        let memory_layout = &program_io.memory_layout;
+        let page_table = PageTable::new(pages);
+
+        // v_init is sparse (bytecode and inputs), so its opening is computed
+        // directly rather than by evaluating the full polynomial
+        let mut v_init_eval = F::zero();
+        // For continuations, v_init = v_final_previous - (previous I/O) + (inputs),
+        // and for resumed segments just v_final_previous
+        if preprocessing.resumes() {
+            openings.v_init = openings.v_final_previous;
+            return Ok(());
         }
-        v_init_index = memory_address_to_witness_index(memory_layout.input_start, memory_layout);
-        // Convert input bytes into words and populate `v_init`
-        for chunk in preprocessing.program_io.as_ref().unwrap().inputs.chunks(4) {
+        if let Some(state) = &preprocessing.continuation {
+            v_init_eval = openings.v_final_previous.unwrap_or_default();
+            for (position, word) in io_witness(&state.program_io) {
+                v_init_eval -= eq(position) * F::from_u64(word);
+            }
+        } else if let Some(v_image) = openings.v_image {
+            // Already checked against the program commitment by the opening proof
+            v_init_eval = v_image;
+        } else {
+            let bytecode_start =
+                memory_address_to_witness_index(preprocessing.min_bytecode_address, memory_layout);
+            for (i, word) in preprocessing.bytecode_words.iter().enumerate() {
+                let position = page_table
+                    .position((bytecode_start + i) as u64)
+                    .ok_or(ProofVerifyError::InternalError)?;
+                v_init_eval += eq(position) * F::from_u32(*word);
+            }
+        }
+        let input_start = memory_address_to_witness_index(memory_layout.input_start, memory_layout);
+        // Convert input bytes into words and add them to the `v_init` opening
+        for (i, chunk) in program_io.inputs.chunks(4).enumerate() {
             let mut word = [0u8; 4];
             for (i, byte) in chunk.iter().enumerate() {
                 word[i] = *byte;
             }
             let word = u32::from_le_bytes(word);
-            v_init[v_init_index] = word as u64;
-            v_init_index += 1;
+            v_init_eval += eq(input_start + i) * F::from_u32(word);
+        }
+        // Committed inputs are opened instead, and absent from `program_io`
+        if let Some(v_inputs) = openings.v_inputs {
+            v_init_eval += v_inputs;
         }
 
-        openings.v_init = Some(DensePolynomial::from_u64(&v_init).evaluate(r_init_final));
+        openings.v_init = Some(v_init_eval);
+        Ok(())
     }
 
     fn read_tuples(
@@ -844,6 +1357,8 @@ where
     sumcheck_proof: SumcheckInstanceProof<F, ProofTranscript>,
     /// Opening of v_final at the random point chosen over the course of sumcheck
     opening: F,
+    /// Opening of v_inputs at the same point, if the inputs are committed to
+    inputs_opening: Option<F>,
 }
 
 impl<F, PCS, ProofTranscript> OutputSumcheckProof<F, PCS, ProofTranscript>
@@ -943,11 +1458,19 @@ where
                 transcript,
             );
 
+        let mut opened = vec![&polynomials.v_final];
+        let mut openings = vec![sumcheck_openings[2]];
+        let mut inputs_opening = None;
+        if let Some(v_inputs) = &polynomials.v_inputs {
+            inputs_opening = Some(v_inputs.evaluate(&r_sumcheck));
+            opened.push(v_inputs);
+            openings.extend(inputs_opening);
+        }
         opening_accumulator.append(
-            &[&polynomials.v_final],
+            &opened,
             DensePolynomial::new(EqPolynomial::evals(&r_sumcheck)),
             r_sumcheck.to_vec(),
-            &[sumcheck_openings[2]],
+            &openings,
             transcript,
         );
 
@@ -955,6 +1478,7 @@ where
             num_rounds,
             sumcheck_proof,
             opening: sumcheck_openings[2], // only need v_final; verifier computes the rest on its own
+            inputs_opening,
             _pcs: PhantomData,
         }
     }
@@ -990,60 +1514,81 @@ where
         let io_memory_size = ram_start_index as usize;
         let log_io_memory_size = io_memory_size.log_2();
 
-        let io_witness_range: Vec<_> = (0..io_memory_size)
-            .map(|i| {
-                if i >= input_start_index {
-                    F::one()
-                } else {
-                    F::zero()
-                }
-            })
-            .collect();
-        let mut io_witness_range_eval = DensePolynomial::new(io_witness_range)
-            .evaluate(&r_sumcheck[(proof.num_rounds - log_io_memory_size)..]);
-
         let r_prod: F = r_sumcheck[..(proof.num_rounds - log_io_memory_size)]
             .iter()
             .map(|r| F::one() - r)
             .product();
-        io_witness_range_eval *= r_prod;
-
-        let mut v_io: Vec<u64> = vec![0; io_memory_size];
-        let mut input_index =
-            memory_address_to_witness_index(memory_layout.input_start, memory_layout);
-        // Convert input bytes into words and populate `v_io`
-        for chunk in program_io.inputs.chunks(4) {
-            let mut word = [0u8; 4];
-            for (i, byte) in chunk.iter().enumerate() {
-                word[i] = *byte;
+        let r_io = &r_sumcheck[(proof.num_rounds - log_io_memory_size)..];
+
+        let (io_witness_range_eval, v_io_eval) = match proof.inputs_opening {
+            // With committed inputs, the I/O region is evaluated sparsely, at a
+            // cost independent of its size
+            Some(inputs_opening) if preprocessing.commit_inputs => {
+                let v_io_eval: F = io_witness(program_io)
+                    .into_iter()
+                    .map(|(index, word)| eq_at_index(r_io, index) * F::from_u64(word))
+                    .sum();
+                (
+                    range_start_eval(input_start_index, r_io) * r_prod,
+                    v_io_eval * r_prod + inputs_opening,
+                )
             }
-            let word = u32::from_le_bytes(word);
-            v_io[input_index] = word as u64;
-            input_index += 1;
-        }
-        let mut output_index =
-            memory_address_to_witness_index(memory_layout.output_start, memory_layout);
-        // Convert output bytes into words and populate `v_io`
-        for chunk in program_io.outputs.chunks(4) {
-            let mut word = [0u8; 4];
-            for (i, byte) in chunk.iter().enumerate() {
-                word[i] = *byte;
-            }
-            let word = u32::from_le_bytes(word);
-            v_io[output_index] = word as u64;
-            output_index += 1;
-        }
-        // Copy panic bit
-        v_io[memory_address_to_witness_index(memory_layout.panic, memory_layout)] =
-            program_io.panic as u64;
-        if !program_io.panic {
-            // Set termination bit
-            v_io[memory_address_to_witness_index(memory_layout.termination, memory_layout)] = 1;
-        }
+            None if !preprocessing.commit_inputs => {
+                let io_witness_range: Vec<_> = (0..io_memory_size)
+                    .map(|i| {
+                        if i >= input_start_index {
+                            F::one()
+                        } else {
+                            F::zero()
+                        }
+                    })
+                    .collect();
+                let mut io_witness_range_eval =
+                    DensePolynomial::new(io_witness_range).evaluate(r_io);
+                io_witness_range_eval *= r_prod;
+
+                let mut v_io: Vec<u64> = vec![0; io_memory_size];
+                let mut input_index =
+                    memory_address_to_witness_index(memory_layout.input_start, memory_layout);
+                // Convert input bytes into words and populate `v_io`
+                for chunk in program_io.inputs.chunks(4) {
+                    let mut word = [0u8; 4];
+                    for (i, byte) in chunk.iter().enumerate() {
+                        word[i] = *byte;
+                    }
+                    let word = u32::from_le_bytes(word);
+                    v_io[input_index] = word as u64;
+                    input_index += 1;
+                }
+                let mut output_index =
+                    memory_address_to_witness_index(memory_layout.output_start, memory_layout);
+                // Convert output bytes into words and populate `v_io`
+                for chunk in program_io.outputs.chunks(4) {
+                    let mut word = [0u8; 4];
+                    for (i, byte) in chunk.iter().enumerate() {
+                        word[i] = *byte;
+                    }
+                    let word = u32::from_le_bytes(word);
+                    v_io[output_index] = word as u64;
+                    output_index += 1;
+                }
+                // Copy panic bit
+                v_io[memory_address_to_witness_index(memory_layout.panic, memory_layout)] =
+                    program_io.panic as u64;
+                if !program_io.panic {
+                    // Set termination bit
+                    v_io[memory_address_to_witness_index(
+                        memory_layout.termination,
+                        memory_layout,
+                    )] = 1;
+                }
 
-        let mut v_io_eval = DensePolynomial::from_u64(&v_io)
-            .evaluate(&r_sumcheck[(proof.num_rounds - log_io_memory_size)..]);
-        v_io_eval *= r_prod;
+                let mut v_io_eval = DensePolynomial::from_u64(&v_io).evaluate(r_io);
+                v_io_eval *= r_prod;
+                (io_witness_range_eval, v_io_eval)
+            }
+            _ => return Err(ProofVerifyError::InternalError),
+        };
 
         assert_eq!(
             eq_eval * io_witness_range_eval * (proof.opening - v_io_eval),
@@ -1051,17 +1596,52 @@ where
             "Output sumcheck check failed."
         );
 
-        opening_accumulator.append(
-            &[&commitment.v_final],
-            r_sumcheck,
-            &[&proof.opening],
-            transcript,
-        );
+        let mut commitments = vec![&commitment.v_final];
+        let mut openings = vec![&proof.opening];
+        if let Some(inputs_opening) = &proof.inputs_opening {
+            let v_inputs = commitment.v_inputs.as_ref();
+            commitments.push(v_inputs.ok_or(ProofVerifyError::InternalError)?);
+            openings.push(inputs_opening);
+        }
+        opening_accumulator.append(&commitments, r_sumcheck, &openings, transcript);
 
         Ok(())
     }
 }
 
+/// A memory consistency argument for registers and RAM. Jolt is generic over
+/// this argument (see `Jolt::Memory`), so alternative constructions can be
+/// swapped in without modifying the rest of the prover and verifier.
+/// `ReadWriteMemoryProof` is the default, based on offline memory checking.
+pub trait MemoryArgument<F, PCS, ProofTranscript>:
+    CanonicalSerialize + CanonicalDeserialize + Sized + Send + Sync
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    fn prove<'a>(
+        generators: &PCS::Setup,
+        preprocessing: &ReadWriteMemoryPreprocessing,
+        polynomials: &'a JoltPolynomials<F>,
+        program_io: &JoltDevice,
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Self;
+
+    fn verify(
+        self,
+        generators: &PCS::Setup,
+        preprocessing: &ReadWriteMemoryPreprocessing,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError>;
+
+    /// The sumchecks of the proof, see `JoltProof::report`
+    fn sumcheck_proofs(&self) -> Vec<&SumcheckInstanceProof<F, ProofTranscript>>;
+}
+
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct ReadWriteMemoryProof<F, PCS, ProofTranscript>
 where
@@ -1077,17 +1657,19 @@ where
         ProofTranscript,
     >,
     pub timestamp_validity_proof: TimestampValidityProof<F, PCS, ProofTranscript>,
-    pub output_proof: OutputSumcheckProof<F, PCS, ProofTranscript>,
+    /// `None` for all but the last segment of a segmented proof
+    pub output_proof: Option<OutputSumcheckProof<F, PCS, ProofTranscript>>,
 }
 
-impl<F, PCS, ProofTranscript> ReadWriteMemoryProof<F, PCS, ProofTranscript>
+impl<F, PCS, ProofTranscript> MemoryArgument<F, PCS, ProofTranscript>
+    for ReadWriteMemoryProof<F, PCS, ProofTranscript>
 where
     F: JoltField,
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
     #[tracing::instrument(skip_all, name = "ReadWriteMemoryProof::prove")]
-    pub fn prove<'a>(
+    fn prove<'a>(
         generators: &PCS::Setup,
         preprocessing: &ReadWriteMemoryPreprocessing,
         polynomials: &'a JoltPolynomials<F>,
@@ -1104,12 +1686,14 @@ where
             transcript,
         );
 
-        let output_proof = OutputSumcheckProof::prove_outputs(
-            &polynomials.read_write_memory,
-            program_io,
-            opening_accumulator,
-            transcript,
-        );
+        let output_proof = preprocessing.checks_outputs().then(|| {
+            OutputSumcheckProof::prove_outputs(
+                &polynomials.read_write_memory,
+                program_io,
+                opening_accumulator,
+                transcript,
+            )
+        });
 
         let timestamp_validity_proof = TimestampValidityProof::prove(
             generators,
@@ -1126,7 +1710,7 @@ where
         }
     }
 
-    pub fn verify(
+    fn verify(
         mut self,
         generators: &PCS::Setup,
         preprocessing: &ReadWriteMemoryPreprocessing,
@@ -1143,13 +1727,17 @@ where
             opening_accumulator,
             transcript,
         )?;
-        OutputSumcheckProof::verify(
-            &self.output_proof,
-            preprocessing,
-            &commitments.read_write_memory,
-            opening_accumulator,
-            transcript,
-        )?;
+        match (&self.output_proof, preprocessing.checks_outputs()) {
+            (Some(output_proof), true) => OutputSumcheckProof::verify(
+                output_proof,
+                preprocessing,
+                &commitments.read_write_memory,
+                opening_accumulator,
+                transcript,
+            )?,
+            (None, false) => {}
+            _ => return Err(ProofVerifyError::InternalError),
+        }
         TimestampValidityProof::verify(
             &mut self.timestamp_validity_proof,
             generators,
@@ -1158,6 +1746,17 @@ where
             transcript,
         )
     }
+
+    fn sumcheck_proofs(&self) -> Vec<&SumcheckInstanceProof<F, ProofTranscript>> {
+        let mut proofs = self.memory_checking_proof.sumcheck_proofs();
+        proofs.extend(self.timestamp_validity_proof.sumcheck_proofs());
+        proofs.extend(
+            self.output_proof
+                .iter()
+                .map(|output_proof| &output_proof.sumcheck_proof),
+        );
+        proofs
+    }
 }
 
 #[cfg(test)]
@@ -1165,10 +1764,136 @@ mod tests {
     use ark_bn254::Fr;
 
     use super::*;
+    use crate::poly::commitment::mock::MockCommitScheme;
+    use crate::utils::transcript::KeccakTranscript;
 
     #[test]
     fn read_write_memory_stuff_ordering() {
         let preprocessing = ReadWriteMemoryPreprocessing::preprocess(vec![]);
         ReadWriteMemoryOpenings::<Fr>::test_ordering_consistency(&preprocessing);
     }
+
+    #[test]
+    fn sparse_io_evaluations() {
+        let mut rng = ark_std::test_rng();
+        let r: Vec<Fr> = (0..5).map(|_| Fr::random(&mut rng)).collect();
+        for start in [0, 1, 13, 31] {
+            let indicator: Vec<Fr> = (0..32).map(|i| Fr::from((i >= start) as u64)).collect();
+            assert_eq!(
+                range_start_eval(start, &r),
+                DensePolynomial::new(indicator).evaluate(&r)
+            );
+        }
+        assert_eq!(eq_at_index(&r, 13), EqPolynomial::evals(&r)[13]);
+    }
+
+    #[test]
+    fn memory_pages_check() {
+        let memory_layout = MemoryLayout::new(4096, 4096);
+        let page_size = MemoryPages::page_size(&memory_layout);
+        assert_eq!(page_size, PAGE_SIZE);
+        let pages = |pages: Vec<u64>| MemoryPages { page_size, pages };
+        let image = MemoryImage {
+            first_page: 1 << 20,
+            num_pages: 2,
+        };
+        let no_image = MemoryImage {
+            first_page: 4,
+            num_pages: 0,
+        };
+
+        let valid = pages(vec![0, 1, 2, 3, 1 << 20, (1 << 20) + 1, 5, 6]);
+        assert!(valid.check(&memory_layout, &image, None).is_ok());
+        assert!(pages(vec![0, 1, 2, 3])
+            .check(&memory_layout, &no_image, Some(&pages(vec![0, 1, 2, 3])))
+            .is_ok());
+        assert!(valid
+            .check(&memory_layout, &image, Some(&pages(vec![0, 1, 2, 3])))
+            .is_ok());
+
+        // Duplicate page
+        assert!(pages(vec![0, 1, 2, 3, 5, 5, 6, 7])
+            .check(&memory_layout, &no_image, None)
+            .is_err());
+        // I/O region out of order
+        assert!(pages(vec![1, 0, 2, 3])
+            .check(&memory_layout, &no_image, None)
+            .is_err());
+        // Not a power of two
+        assert!(pages(vec![0, 1, 2, 3, 4])
+            .check(&memory_layout, &no_image, None)
+            .is_err());
+        // Doesn't extend the previous pages
+        assert!(valid
+            .check(
+                &memory_layout,
+                &image,
+                Some(&pages(vec![0, 1, 2, 3, 5, 6, 7, 8]))
+            )
+            .is_err());
+        // Missing or moved memory image pages
+        assert!(pages(vec![0, 1, 2, 3, 1 << 20, 5, 6, 7])
+            .check(&memory_layout, &image, None)
+            .is_err());
+        assert!(pages(vec![0, 1, 2, 3, 5, 1 << 20, (1 << 20) + 1, 6])
+            .check(&memory_layout, &image, None)
+            .is_err());
+        // Wrong page size
+        let wrong_page_size = MemoryPages {
+            page_size: page_size / 2,
+            pages: vec![0, 1, 2, 3, 4, 5, 6, 7],
+        };
+        assert!(wrong_page_size
+            .check(&memory_layout, &no_image, None)
+            .is_err());
+    }
+
+    #[test]
+    fn page_table_position() {
+        let pages = MemoryPages {
+            page_size: 4,
+            pages: vec![0, 1, 7, 3],
+        };
+        let page_table = PageTable::new(&pages);
+        assert_eq!(page_table.position(5), Some(5));
+        assert_eq!(page_table.position(29), Some(9));
+        assert_eq!(page_table.position(8), None);
+    }
+
+    #[test]
+    fn verifier_openings_reject_malformed_pages() {
+        type Proof =
+            ReadWriteMemoryProof<Fr, MockCommitScheme<Fr, KeccakTranscript>, KeccakTranscript>;
+        let compute_verifier_openings = |preprocessing: &ReadWriteMemoryPreprocessing, r: &[Fr]| {
+            Proof::compute_verifier_openings(
+                &mut ReadWriteMemoryOpenings::default(),
+                preprocessing,
+                r,
+                r,
+            )
+        };
+
+        let program_io = JoltDevice::new(4096, 4096);
+        let page_size = MemoryPages::page_size(&program_io.memory_layout);
+        let mut preprocessing =
+            ReadWriteMemoryPreprocessing::preprocess(vec![(RAM_START_ADDRESS, 1)]);
+        preprocessing.program_io = Some(program_io);
+
+        // The page holding the bytecode is missing
+        preprocessing.pages = MemoryPages {
+            page_size,
+            pages: vec![0, 1, 2, 3],
+        };
+        let r = vec![Fr::from(5u64); 12];
+        assert!(compute_verifier_openings(&preprocessing, &r).is_err());
+
+        // The init/final polynomials don't match the pages
+        preprocessing.pages.pages.extend([4, 5, 6, 7]);
+        assert!(matches!(
+            compute_verifier_openings(&preprocessing, &r),
+            Err(ProofVerifyError::InvalidInputLength(13, 12))
+        ));
+        let r = vec![Fr::from(5u64); 13];
+        assert!(compute_verifier_openings(&preprocessing, &r).is_ok());
+    }
 }
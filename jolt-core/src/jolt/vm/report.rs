@@ -0,0 +1,196 @@
+use ark_serialize::CanonicalSerialize;
+use std::fmt;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::jolt::subtable::JoltSubtableSet;
+use crate::lasso::memory_checking::StructuredPolynomialData;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::subprotocols::sumcheck::SumcheckInstanceProof;
+use crate::utils::transcript::Transcript;
+
+use super::read_write_memory::MemoryArgument;
+use super::{JoltCommitments, JoltProof};
+
+/// Size breakdown of one component of a `JoltProof`, see `JoltProof::report`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentReport {
+    pub name: &'static str,
+    /// Compressed size in bytes
+    pub size: usize,
+    /// Number of sumcheck instances, counting each GKR layer of a grand product
+    pub sumchecks: usize,
+    pub sumcheck_rounds: usize,
+    /// Compressed size in bytes of the sumcheck round polynomials
+    pub sumcheck_size: usize,
+}
+
+impl ComponentReport {
+    fn new<F: JoltField, ProofTranscript: Transcript>(
+        name: &'static str,
+        component: &impl CanonicalSerialize,
+        sumcheck_proofs: Vec<&SumcheckInstanceProof<F, ProofTranscript>>,
+    ) -> Self {
+        Self {
+            name,
+            size: component.compressed_size(),
+            sumchecks: sumcheck_proofs.len(),
+            sumcheck_rounds: sumcheck_proofs
+                .iter()
+                .map(|proof| proof.compressed_polys.len())
+                .sum(),
+            sumcheck_size: sumcheck_proofs
+                .iter()
+                .map(|proof| proof.compressed_size())
+                .sum(),
+        }
+    }
+
+    /// Size in bytes of everything but the sumchecks: evaluation claims, multiset
+    /// hashes and, for the opening proof, the PCS proof
+    pub fn openings_size(&self) -> usize {
+        self.size - self.sumcheck_size
+    }
+}
+
+/// Size breakdown of a proof and its commitments, see `JoltProof::report`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofReport {
+    pub trace_length: usize,
+    pub num_commitments: usize,
+    /// Compressed size in bytes of the commitments
+    pub commitments_size: usize,
+    /// Compressed size in bytes of the metadata, trace length, public I/O and memory pages
+    pub public_size: usize,
+    pub components: Vec<ComponentReport>,
+}
+
+impl ProofReport {
+    /// Compressed size in bytes of all sumcheck round polynomials
+    pub fn sumcheck_size(&self) -> usize {
+        self.components
+            .iter()
+            .map(|component| component.sumcheck_size)
+            .sum()
+    }
+
+    /// See `ComponentReport::openings_size`
+    pub fn openings_size(&self) -> usize {
+        self.components
+            .iter()
+            .map(|component| component.openings_size())
+            .sum()
+    }
+
+    /// Compressed size in bytes of the proof and its commitments
+    pub fn total_size(&self) -> usize {
+        self.commitments_size
+            + self.public_size
+            + self
+                .components
+                .iter()
+                .map(|component| component.size)
+                .sum::<usize>()
+    }
+}
+
+impl fmt::Display for ProofReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "steps:                  {}", self.trace_length)?;
+        writeln!(
+            f,
+            "commitments:            {} bytes ({} commitments)",
+            self.commitments_size, self.num_commitments
+        )?;
+        writeln!(f, "public I/O:             {} bytes", self.public_size)?;
+        for component in self.components.iter() {
+            writeln!(
+                f,
+                "{:<24}{} bytes ({} bytes in {} sumchecks of {} rounds, {} bytes of openings)",
+                format!("{}:", component.name),
+                component.size,
+                component.sumcheck_size,
+                component.sumchecks,
+                component.sumcheck_rounds,
+                component.openings_size()
+            )?;
+        }
+        writeln!(f, "sumchecks:              {} bytes", self.sumcheck_size())?;
+        writeln!(f, "openings:               {} bytes", self.openings_size())?;
+        write!(f, "total:                  {} bytes", self.total_size())
+    }
+}
+
+impl<
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    > JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    /// Breaks down the size of the proof and its commitments by component, and
+    /// within each component into sumcheck messages and openings.
+    pub fn report(&self, commitments: &JoltCommitments<PCS, ProofTranscript>) -> ProofReport {
+        let r1cs = &self.r1cs;
+        let mut r1cs_sumchecks = vec![
+            &r1cs.outer_sumcheck_proof,
+            &r1cs.inner_sumcheck_proof,
+            &r1cs.shift_sumcheck_proof,
+        ];
+        r1cs_sumchecks.extend(r1cs.product_proof.iter().map(|proof| &proof.sumcheck_proof));
+
+        let components = vec![
+            ComponentReport::new("bytecode", &self.bytecode, self.bytecode.sumcheck_proofs()),
+            ComponentReport::new(
+                "read/write memory",
+                &self.read_write_memory,
+                self.read_write_memory.sumcheck_proofs(),
+            ),
+            ComponentReport::new(
+                "instruction lookups",
+                &self.instruction_lookups,
+                self.instruction_lookups.sumcheck_proofs(),
+            ),
+            ComponentReport::new("r1cs", r1cs, r1cs_sumchecks),
+            ComponentReport::new::<F, ProofTranscript>(
+                "segment boundary",
+                &self.segment_boundary,
+                vec![],
+            ),
+            ComponentReport::new(
+                "opening proof",
+                &self.opening_proof,
+                self.opening_proof
+                    .iter()
+                    .map(|proof| proof.sumcheck_proof())
+                    .collect(),
+            ),
+        ];
+
+        ProofReport {
+            trace_length: self.trace_length,
+            num_commitments: commitments.read_write_values().len()
+                + commitments.init_final_values().len(),
+            commitments_size: commitments.compressed_size(),
+            public_size: self.metadata.compressed_size()
+                + self.trace_length.compressed_size()
+                + self.program_io.compressed_size()
+                + self.memory_pages.compressed_size(),
+            components,
+        }
+    }
+}
@@ -7,18 +7,23 @@ use crate::jolt::subtable::div_by_zero::DivByZeroSubtable;
 use crate::jolt::subtable::low_bit::LowBitSubtable;
 use crate::jolt::subtable::right_is_zero::RightIsZeroSubtable;
 use crate::poly::commitment::hyperkzg::HyperKZG;
-use crate::r1cs::constraints::JoltRV32IMConstraints;
-use crate::r1cs::inputs::JoltR1CSInputs;
+use crate::r1cs::constraints::{
+    CustomConstraints, JoltRV32IMConstraints, JoltRV32IMWithConstraints,
+};
+use crate::r1cs::inputs::{CustomR1CSInputs, JoltR1CSInputs};
 use ark_bn254::{Bn254, Fr};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use enum_dispatch::enum_dispatch;
 use rand::{prelude::StdRng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::any::TypeId;
+use std::marker::PhantomData;
 use strum::{EnumCount, IntoEnumIterator};
 use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
 
-use super::{Jolt, JoltCommitments, JoltProof};
+use super::envelope::ProofEnvelope;
+use super::read_write_memory::ReadWriteMemoryProof;
+use super::{ChainedProof, Jolt, JoltCommitments, JoltProof, ProgramPreprocessing, VerifierKey};
 use crate::jolt::instruction::{
     add::ADDInstruction, and::ANDInstruction, beq::BEQInstruction, bge::BGEInstruction,
     bgeu::BGEUInstruction, bne::BNEInstruction, mul::MULInstruction, mulhu::MULHUInstruction,
@@ -178,12 +183,40 @@ where
     type InstructionSet = RV32I;
     type Subtables = RV32ISubtables<F>;
     type Constraints = JoltRV32IMConstraints;
+    type Memory = ReadWriteMemoryProof<F, PCS, ProofTranscript>;
 }
 
 pub type RV32IJoltProof<F, PCS, ProofTranscript> =
     JoltProof<C, M, JoltR1CSInputs, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
+pub type RV32IChainedProof<F, PCS, ProofTranscript> =
+    ChainedProof<C, M, JoltR1CSInputs, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
+pub type RV32IProofEnvelope<F, PCS, ProofTranscript> =
+    ProofEnvelope<C, M, JoltR1CSInputs, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
+
+/// `RV32IJoltVM` with the additional user-defined R1CS constraints `E`.
+pub struct RV32IJoltVMWithConstraints<E>(PhantomData<E>);
 
+impl<F, PCS, ProofTranscript, E> Jolt<F, PCS, C, M, ProofTranscript>
+    for RV32IJoltVMWithConstraints<E>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+    E: CustomConstraints,
+{
+    type InstructionSet = RV32I;
+    type Subtables = RV32ISubtables<F>;
+    type Constraints = JoltRV32IMWithConstraints<E>;
+    type Memory = ReadWriteMemoryProof<F, PCS, ProofTranscript>;
+}
+
+pub type RV32IJoltProofWithConstraints<E, F, PCS, ProofTranscript> =
+    JoltProof<C, M, CustomR1CSInputs<E>, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
+
+use crate::utils::canonical_serde::impl_canonical_serde;
+use crate::utils::mmap::deserialize_file;
 use crate::utils::transcript::{KeccakTranscript, Transcript};
+use ark_serialize::{Compress, Validate};
 use eyre::Result;
 use std::fs::File;
 use std::io::Cursor;
@@ -204,10 +237,21 @@ pub trait Serializable: CanonicalSerialize + CanonicalDeserialize + Sized {
         Ok(())
     }
 
-    /// Reads data from a file
+    /// Reads data from a file, through a memory map (see `utils::mmap`)
     fn from_file<P: Into<PathBuf>>(path: P) -> Result<Self> {
-        let file = File::open(path.into())?;
-        Ok(Self::deserialize_compressed(file)?)
+        Ok(deserialize_file(
+            &path.into(),
+            Compress::Yes,
+            Validate::Yes,
+        )?)
+    }
+
+    /// Like `from_file`, without validating the points and field elements read,
+    /// which is most of the time spent loading large keys. This skips the curve
+    /// and subgroup checks of the points, so it is only for trusted files, e.g.
+    /// ones written by `save_to_file` on the same machine.
+    fn from_trusted_file<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        Ok(deserialize_file(&path.into(), Compress::Yes, Validate::No)?)
     }
 
     /// Serializes the data to a byte vector
@@ -232,26 +276,77 @@ pub struct JoltHyperKZGProof {
     pub commitments: JoltCommitments<PCS, ProofTranscript>,
 }
 
+impl_canonical_serde!([] JoltHyperKZGProof);
+
+impl JoltHyperKZGProof {
+    /// If the proven execution panicked, the guest's panic message, if it wrote
+    /// one (see `JoltDevice::panic_message`)
+    pub fn panic_message(&self) -> Option<String> {
+        self.proof.program_io.panic_message()
+    }
+}
+
 impl Serializable for JoltHyperKZGProof {}
 
+impl Serializable for ProgramPreprocessing {}
+
+impl<PCS, ProofTranscript> Serializable for VerifierKey<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+}
+
 // ==================== TEST ====================
 
 #[cfg(test)]
 mod tests {
     use ark_bn254::{Bn254, Fr};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
 
     use std::collections::HashSet;
 
     use crate::field::JoltField;
     use crate::host;
-    use crate::jolt::instruction::JoltInstruction;
-    use crate::jolt::vm::rv32i_vm::{Jolt, RV32IJoltVM, C, M};
-    use crate::poly::commitment::commitment_scheme::CommitmentScheme;
-    use crate::poly::commitment::hyperkzg::HyperKZG;
+    use crate::jolt::instruction::{JoltInstruction, JoltInstructionSet};
+    use crate::jolt::vm::config::{PcsBackend, ProverConfig};
+    #[cfg(feature = "parallel")]
+    use crate::jolt::vm::distributed::{prove_distributed, serve};
+    use crate::jolt::vm::envelope::{ProofHeader, PROOF_FORMAT_VERSION, PROOF_MAGIC};
+    use crate::jolt::vm::kat::RecordingPCS;
+    use crate::jolt::vm::progress::{CancellationToken, ProverProgress, ProverStage};
+    use crate::jolt::vm::read_write_memory::io_region_size;
+    use crate::jolt::vm::rv32i_vm::{
+        Jolt, RV32IChainedProof, RV32IJoltProof, RV32IJoltVM, RV32IJoltVMWithConstraints,
+        RV32IProofEnvelope, Serializable, C, M, RV32I,
+    };
+    use crate::jolt::vm::security::SecurityConfig;
+    use crate::jolt::vm::segment::Segment;
+    use crate::jolt::vm::{
+        JoltCommitments, JoltPreprocessing, JoltTraceStep, ProgramPreprocessing, VerifierKey,
+    };
+    use crate::lasso::memory_checking::{Initializable, StructuredPolynomialData};
+    use crate::poly::commitment::commitment_scheme::{AccumulationScheme, CommitmentScheme};
+    use crate::poly::commitment::hyperkzg::{HyperKZG, HyperKZGAccumulator};
     use crate::poly::commitment::mock::MockCommitScheme;
     use crate::poly::commitment::zeromorph::Zeromorph;
+    use crate::r1cs::builder::R1CSBuilder;
+    use crate::r1cs::constraints::CustomConstraints;
+    use crate::r1cs::export::tests::{parse_r1cs, parse_wtns};
+    use crate::r1cs::inputs::{CustomR1CSInputs, JoltR1CSInputs};
+    use crate::r1cs::ops::LC;
+    use crate::test_utils::Tamper;
+    use crate::utils::errors::{
+        ConfigError, ProofFormatError, ProofVerifyError, ProvingCancelled, SecurityError,
+    };
     use crate::utils::transcript::{KeccakTranscript, Transcript};
-    use std::sync::{LazyLock, Mutex};
+    use common::constants::DEFAULT_MEMORY_SIZE;
+    use common::rv_trace::{CircuitFlags, JoltDevice};
+    #[cfg(feature = "parallel")]
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Arc, LazyLock, Mutex};
+    #[cfg(feature = "parallel")]
+    use std::thread;
     use strum::{EnumCount, IntoEnumIterator};
 
     // If multiple tests try to read the same trace artifacts simultaneously, they will fail
@@ -288,35 +383,1451 @@ mod tests {
         test_instruction_set_subtables::<HyperKZG<Bn254, KeccakTranscript>, KeccakTranscript>();
     }
 
+    /// Traces the fibonacci guest on input 9, returning its I/O and trace.
+    fn fib_trace() -> (JoltDevice, Vec<JoltTraceStep<RV32I>>) {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let execution = program.trace();
+        drop(artifact_guard);
+        execution
+    }
+
+    /// Traces the fibonacci guest on input 9, returning its preprocessing (for up
+    /// to 2^20 instructions, memory words and steps), I/O and trace.
+    fn fib_fixture<F, PCS, ProofTranscript>() -> (
+        JoltPreprocessing<C, F, PCS, ProofTranscript>,
+        JoltDevice,
+        Vec<JoltTraceStep<RV32I>>,
+    )
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let (bytecode, memory_init) = host::Program::new("fibonacci-guest").decode();
+        drop(artifact_guard);
+        let (io_device, trace) = fib_trace();
+
+        let preprocessing = <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::preprocess(
+            bytecode,
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        (preprocessing, io_device, trace)
+    }
+
+    /// The preprocessing of `fib_fixture`, and the serialized proof and
+    /// commitments of its execution
+    type FibProof<PCS> = (JoltPreprocessing<C, Fr, PCS, KeccakTranscript>, Vec<u8>);
+
+    /// Proves the execution of `fib_fixture`.
+    fn prove_fib<PCS>() -> FibProof<PCS>
+    where
+        PCS: CommitmentScheme<KeccakTranscript, Field = Fr>,
+    {
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+            io_device,
+            trace,
+            preprocessing.clone(),
+        );
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        commitments.serialize_compressed(&mut bytes).unwrap();
+        (preprocessing, bytes)
+    }
+
+    // Proofs of `fib_fixture` for the tests that only inspect, verify or tamper
+    // with one, made once rather than by each test
+    static MOCK_FIB_PROOF: LazyLock<FibProof<MockCommitScheme<Fr, KeccakTranscript>>> =
+        LazyLock::new(prove_fib);
+    static HYPERKZG_FIB_PROOF: LazyLock<FibProof<HyperKZG<Bn254, KeccakTranscript>>> =
+        LazyLock::new(prove_fib);
+
+    /// A copy of a shared proof of `fib_fixture`, with its preprocessing
+    fn fib_proof<PCS>(
+        (preprocessing, bytes): &FibProof<PCS>,
+    ) -> (
+        JoltPreprocessing<C, Fr, PCS, KeccakTranscript>,
+        RV32IJoltProof<Fr, PCS, KeccakTranscript>,
+        JoltCommitments<PCS, KeccakTranscript>,
+    )
+    where
+        PCS: CommitmentScheme<KeccakTranscript, Field = Fr>,
+    {
+        let mut reader = &bytes[..];
+        let proof = CanonicalDeserialize::deserialize_compressed(&mut reader).unwrap();
+        let commitments = CanonicalDeserialize::deserialize_compressed(&mut reader).unwrap();
+        (preprocessing.clone(), proof, commitments)
+    }
+
     fn fib_e2e<F, PCS, ProofTranscript>()
     where
         F: JoltField,
         PCS: CommitmentScheme<ProofTranscript, Field = F>,
         ProofTranscript: Transcript,
     {
+        let (preprocessing, io_device, trace) = fib_fixture::<F, PCS, ProofTranscript>();
+        let (proof, commitments, debug_info) =
+            <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::prove(
+                io_device,
+                trace,
+                preprocessing.clone(),
+            );
+        let verification_result =
+            RV32IJoltVM::verify(preprocessing, proof, commitments, debug_info);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    /// Proves the execution of the fibonacci guest on input 9 with
+    /// `Program::prove_with_config`, and verifies the result. Returns the
+    /// number of chunks it was proven in.
+    fn fib_e2e_with_config(config: &ProverConfig) -> usize {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let (io_device, _) = program.execute();
+        let preprocessing =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess_with_config(
+                program.program_preprocessing(),
+                config,
+            )
+            .unwrap();
+        let chained_proof = program
+            .prove_with_config::<Fr, PCS, KeccakTranscript>(&preprocessing, config)
+            .unwrap();
+        drop(artifact_guard);
+
+        let num_chunks = chained_proof.proofs.len();
+        assert_eq!(chained_proof.proofs.last().unwrap().program_io, io_device);
+        let verification_result = RV32IJoltVM::verify_segmented(preprocessing, chained_proof);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+        num_chunks
+    }
+
+    #[test]
+    fn tampered_proofs_rejected() {
+        for tamper in Tamper::iter() {
+            let (preprocessing, mut proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+            tamper.apply(&mut proof);
+            assert!(
+                RV32IJoltVM::verify(preprocessing, proof, commitments, None).is_err(),
+                "{tamper:?}"
+            );
+        }
+
+        let (preprocessing, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+        let mut claimed_io = proof.program_io.clone();
+        claimed_io.outputs[0] ^= 1;
+        assert!(
+            RV32IJoltVM::verify_with_io(preprocessing, proof, commitments, &claimed_io).is_err()
+        );
+
+        // Replace the first page of the memory image, which follows the
+        // registers and I/O region, with an unused one
+        let (preprocessing, mut proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+        let pages = &mut proof.memory_pages;
+        let image_page = io_region_size(&preprocessing.memory_layout) / pages.page_size;
+        pages.pages[image_page] = pages.pages.iter().max().unwrap() + 1;
+        assert!(RV32IJoltVM::verify(preprocessing, proof, commitments, None).is_err());
+    }
+
+    #[test]
+    fn fib_matches_reference() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let result = program.diff_against_reference();
+        drop(artifact_guard);
+        if let Err(divergence) = result {
+            panic!("{divergence}");
+        }
+    }
+
+    #[test]
+    fn fib_known_answer_vectors() {
         let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
         let mut program = host::Program::new("fibonacci-guest");
         program.set_input(&9u32);
+        let vectors = program.known_answer_vectors::<Fr, RecordingPCS>().unwrap();
         let (bytecode, memory_init) = program.decode();
         let (io_device, trace) = program.trace();
         drop(artifact_guard);
 
+        let first = &vectors.transcript[0];
+        assert_eq!(first.operation, "new");
+        assert_eq!(first.value, b"Jolt transcript");
+        assert!(vectors
+            .transcript
+            .iter()
+            .any(|event| event.operation == "append_point"));
+
+        // The recorded proof is the one `KeccakTranscript` gives
+        let trace_length = trace.len().next_power_of_two();
         let preprocessing = RV32IJoltVM::preprocess(
             bytecode.clone(),
             io_device.memory_layout.clone(),
             memory_init,
+            bytecode.len().next_power_of_two(),
+            DEFAULT_MEMORY_SIZE as usize,
+            trace_length,
+        );
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(io_device, trace, preprocessing);
+        let json = vectors.to_json();
+        assert_eq!(json["proof"], proof.to_json(&commitments));
+        assert_eq!(
+            json["transcript"].as_array().unwrap().len(),
+            vectors.transcript.len()
+        );
+    }
+
+    #[test]
+    fn proof_envelope() {
+        type MockPCS = MockCommitScheme<Fr, KeccakTranscript>;
+        type HyperKZGEnvelope =
+            RV32IProofEnvelope<Fr, HyperKZG<Bn254, KeccakTranscript>, KeccakTranscript>;
+        let (preprocessing, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+        let io_device = proof.program_io.clone();
+        let bytes = RV32IProofEnvelope::new(proof, commitments, &preprocessing)
+            .serialize_to_bytes()
+            .unwrap();
+
+        let header = ProofHeader::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(header.program_io, io_device);
+        assert_eq!(header.program_hash, preprocessing.program_hash);
+
+        let envelope =
+            RV32IProofEnvelope::<Fr, MockPCS, KeccakTranscript>::deserialize_from_bytes(&bytes)
+                .unwrap();
+        envelope.check_program(&preprocessing).unwrap();
+        assert!(
+            RV32IJoltVM::verify(preprocessing, envelope.proof, envelope.commitments, None).is_ok()
+        );
+
+        let mut future_version = bytes.clone();
+        future_version[PROOF_MAGIC.len()] += 1;
+        assert!(matches!(
+            RV32IProofEnvelope::<Fr, MockPCS, KeccakTranscript>::deserialize_from_bytes(
+                &future_version
+            ),
+            Err(ProofFormatError::UnsupportedVersion(found, PROOF_FORMAT_VERSION))
+                if found == PROOF_FORMAT_VERSION + 1
+        ));
+        assert!(matches!(
+            HyperKZGEnvelope::deserialize_from_bytes(&bytes),
+            Err(ProofFormatError::CommitmentSchemeMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn proof_json() {
+        let (preprocessing, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+
+        let json = proof.to_json(&commitments);
+        let num_rounds = proof.trace_length.next_power_of_two().trailing_zeros();
+        assert_eq!(
+            json["components"]["instruction_lookups"]["primary_sumcheck_rounds"],
+            num_rounds
+        );
+        let total_size = proof.compressed_size() + commitments.compressed_size();
+        assert_eq!(json["size"], total_size);
+
+        let json = serde_json::from_str(&json.to_string()).unwrap();
+        let (proof, commitments) = RV32IJoltProof::from_json(&json).unwrap();
+        assert!(RV32IJoltVM::verify(preprocessing, proof, commitments, None).is_ok());
+    }
+
+    #[test]
+    fn proof_report() {
+        let (_, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+
+        let report = proof.report(&commitments);
+        let total_size = proof.compressed_size() + commitments.compressed_size();
+        assert_eq!(report.total_size(), total_size);
+        assert_eq!(
+            report.commitments_size
+                + report.public_size
+                + report.sumcheck_size()
+                + report.openings_size(),
+            total_size
+        );
+        assert_eq!(
+            report.num_commitments,
+            commitments.read_write_values().len() + commitments.init_final_values().len()
+        );
+
+        let lookups = &report.components[2];
+        assert_eq!(lookups.name, "instruction lookups");
+        assert!(lookups.sumcheck_rounds > proof.instruction_lookups.primary_sumcheck_rounds());
+        for component in report.components.iter() {
+            assert_eq!(
+                component.sumchecks == 0,
+                component.name == "segment boundary"
+            );
+            assert!(component.sumcheck_size <= component.size);
+        }
+    }
+
+    #[test]
+    fn prover_progress() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+        let (preprocessing, _) = &*MOCK_FIB_PROOF;
+        let (io_device, trace) = fib_trace();
+
+        let stages = Arc::new(Mutex::new(vec![]));
+        let reported = stages.clone();
+        let progress =
+            ProverProgress::new().on_stage(move |stage| reported.lock().unwrap().push(stage));
+        let (proof, commitments, _, _) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_with_progress(
+                io_device.clone(),
+                trace.clone(),
+                preprocessing.clone(),
+                None,
+                &progress,
+            )
+            .unwrap();
+        assert_eq!(
+            *stages.lock().unwrap(),
+            vec![
+                ProverStage::WitnessGeneration,
+                ProverStage::Commitments,
+                ProverStage::Sumchecks,
+                ProverStage::Openings,
+            ]
+        );
+        assert!(RV32IJoltVM::verify(preprocessing.clone(), proof, commitments, None).is_ok());
+
+        // Cancel as soon as the witness is generated
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let progress = ProverProgress::new()
+            .with_cancellation(token)
+            .on_stage(move |stage| {
+                if stage == ProverStage::WitnessGeneration {
+                    cancel.cancel();
+                }
+            });
+        let result = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_with_progress(
+            io_device,
+            trace,
+            preprocessing.clone(),
+            None,
+            &progress,
+        );
+        assert!(matches!(result, Err(ProvingCancelled)));
+    }
+
+    #[test]
+    fn deterministic_proofs() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        // Proving the same trace again gives the shared proof, byte for byte
+        let (preprocessing, bytes) = &*HYPERKZG_FIB_PROOF;
+        let (io_device, trace) = fib_trace();
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+            io_device,
+            trace,
+            preprocessing.clone(),
+        );
+        let mut reproven = vec![];
+        proof.serialize_compressed(&mut reproven).unwrap();
+        commitments.serialize_compressed(&mut reproven).unwrap();
+        assert_eq!(&reproven, bytes);
+    }
+
+    #[test]
+    fn fib_e2e_verify_batch() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, proof, commitments) = fib_proof(&HYPERKZG_FIB_PROOF);
+        let mut proofs = vec![(proof, commitments)];
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        for input in [10u32, 11] {
+            let mut program = host::Program::new("fibonacci-guest");
+            program.set_input(&input);
+            let (io_device, trace) = program.trace();
+            let (proof, commitments, _) =
+                <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+                    io_device,
+                    trace,
+                    preprocessing.clone(),
+                );
+            proofs.push((proof, commitments));
+        }
+        drop(artifact_guard);
+        let mut bytes = vec![];
+        proofs.serialize_compressed(&mut bytes).unwrap();
+        let batch = || -> Vec<(RV32IJoltProof<Fr, PCS, KeccakTranscript>, _)> {
+            CanonicalDeserialize::deserialize_compressed(&bytes[..]).unwrap()
+        };
+
+        let results = RV32IJoltVM::verify_batch(&preprocessing, batch());
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.is_ok()));
+
+        let mut swapped = batch();
+        let (first, rest) = swapped.split_at_mut(1);
+        std::mem::swap(&mut first[0].1, &mut rest[0].1);
+        let results = RV32IJoltVM::verify_batch(&preprocessing, swapped);
+        assert!(results[0].is_err());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+
+        // The opening checks of the same proofs can also be accumulated and
+        // decided at once
+        let mut accumulator = HyperKZGAccumulator::default();
+        for (proof, commitments) in batch().into_iter().take(2) {
+            RV32IJoltVM::verify_accumulate(
+                preprocessing.clone(),
+                proof,
+                commitments,
+                &mut accumulator,
+            )
+            .unwrap();
+        }
+        assert_ne!(accumulator, HyperKZGAccumulator::default());
+        assert!(PCS::decide(&preprocessing.generators, &accumulator).is_ok());
+    }
+
+    #[test]
+    fn verify_from_reader() {
+        let (preprocessing, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+
+        let mut bytes = vec![];
+        proof.serialize_streaming(&commitments, &mut bytes).unwrap();
+        assert_eq!(
+            bytes.len(),
+            proof.compressed_size() + commitments.compressed_size() - 1
+        );
+        let verification_result =
+            RV32IJoltVM::verify_from_reader(preprocessing.clone(), bytes.as_slice());
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+
+        let truncated = &bytes[..bytes.len() - 1];
+        assert!(matches!(
+            RV32IJoltVM::verify_from_reader(preprocessing, truncated),
+            Err(ProofVerifyError::Serialization(_))
+        ));
+    }
+
+    #[test]
+    fn fib_e2e_continuation() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let (first_proof, first_commitments, _, state) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_continuation(
+                io_device,
+                trace,
+                preprocessing.clone(),
+                None,
+            );
+
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let (io_device, trace) = program.trace_from_state(&state);
+        drop(artifact_guard);
+        let (proof, commitments, _, _) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_continuation(
+                io_device,
+                trace,
+                preprocessing.clone(),
+                Some(state),
+            );
+
+        let verification_result = RV32IJoltVM::verify_continuation(
+            preprocessing.clone(),
+            proof,
+            commitments,
+            &first_proof.program_io,
+            &first_proof.memory_pages,
+            &first_commitments,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+        assert!(RV32IJoltVM::verify(preprocessing, first_proof, first_commitments, None).is_ok());
+    }
+
+    #[test]
+    fn fib_e2e_segmented() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let head = trace.len().next_power_of_two() / 4;
+        let segment_lengths = [head, trace.len() - head];
+        let segments = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_segmented(
+            io_device,
+            trace,
+            preprocessing.clone(),
+            &segment_lengths,
+        );
+        assert_eq!(segments.proofs.len(), 2);
+        let mut bytes = vec![];
+        segments.serialize_compressed(&mut bytes).unwrap();
+        let verification_result = RV32IJoltVM::verify_segmented(preprocessing.clone(), segments);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+
+        // The second segment alone doesn't prove the execution
+        let mut segments =
+            RV32IChainedProof::<Fr, PCS, KeccakTranscript>::deserialize_compressed(&bytes[..])
+                .unwrap();
+        segments.proofs.remove(0);
+        segments.commitments.remove(0);
+        assert!(RV32IJoltVM::verify_segmented(preprocessing, segments).is_err());
+    }
+
+    #[test]
+    fn fib_e2e_padded_segment() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        // Prove a middle segment that is not a power of two long, which
+        // `prove_chunks` refuses to do
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let head = trace.len().next_power_of_two() / 4;
+        let segment_lengths = [head, head - 1, trace.len() - 2 * head + 1];
+        let mut steps = trace.into_iter();
+        let mut chained_proof = RV32IChainedProof {
+            proofs: vec![],
+            commitments: vec![],
+        };
+        let mut previous = None;
+        for (index, length) in segment_lengths.iter().enumerate() {
+            let mut preprocessing = preprocessing.clone();
+            preprocessing.read_write_memory.segment = Some(Segment::new(index, 3));
+            let (proof, commitments, _, state) =
+                <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_continuation(
+                    io_device.clone(),
+                    steps.by_ref().take(*length).collect(),
+                    preprocessing,
+                    previous.take(),
+                );
+            previous = Some(state);
+            chained_proof.proofs.push(proof);
+            chained_proof.commitments.push(commitments);
+        }
+        assert!(matches!(
+            RV32IJoltVM::verify_segmented(preprocessing, chained_proof),
+            Err(ProofVerifyError::InternalError)
+        ));
+    }
+
+    #[test]
+    fn fib_e2e_configs() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let config = || {
+            ProverConfig::builder()
+                .with_max_trace_length(1 << 16)
+                .with_pcs(PcsBackend::Mock)
+        };
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let preprocessing =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess_with_config(
+                program.program_preprocessing(),
+                &config().build().unwrap(),
+            )
+            .unwrap();
+        drop(artifact_guard);
+        let (_, trace) = fib_trace();
+        let chunk_size = trace.len().next_power_of_two() / 4;
+        let num_chunks = trace.len().div_ceil(chunk_size);
+        let prover_memory = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::cost_report(
+            &preprocessing,
+            chunk_size,
+            program.max_memory_words(),
+        )
+        .prover_memory;
+
+        // The whole trace at once, on the config's threads
+        assert_eq!(
+            fib_e2e_with_config(&config().with_threads(2).build().unwrap()),
+            1
+        );
+        assert_eq!(
+            fib_e2e_with_config(&config().with_chunk_size(chunk_size).build().unwrap()),
+            num_chunks
+        );
+        // The largest chunk size within the memory budget
+        assert_eq!(
+            fib_e2e_with_config(&config().with_memory_budget(prover_memory).build().unwrap()),
+            num_chunks
+        );
+    }
+
+    #[test]
+    fn prove_with_config_checks_config() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let (preprocessing, _) = &*MOCK_FIB_PROOF;
+        let (io_device, trace) = fib_trace();
+        // The config is checked before proving
+        let too_short = ProverConfig::builder()
+            .with_max_trace_length(trace.len() - 1)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            RV32IJoltVM::prove_with_config(
+                io_device.clone(),
+                trace.clone(),
+                preprocessing.clone(),
+                &too_short
+            ),
+            Err(ConfigError::TraceTooLong(..))
+        ));
+        let no_memory = ProverConfig::builder()
+            .with_memory_budget(1)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            RV32IJoltVM::prove_with_config(io_device, trace, preprocessing.clone(), &no_memory),
+            Err(ConfigError::OverMemoryBudget(..))
+        ));
+
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let program_preprocessing = host::Program::new("fibonacci-guest").program_preprocessing();
+        drop(artifact_guard);
+        let zeromorph = ProverConfig::builder()
+            .with_pcs(PcsBackend::Zeromorph)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess_with_config(
+                program_preprocessing,
+                &zeromorph
+            ),
+            Err(ConfigError::PcsMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn chunk_size_for_memory() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let (preprocessing, _) = &*MOCK_FIB_PROOF;
+        let (_, trace) = fib_trace();
+        let mut program = host::Program::new("fibonacci-guest");
+        let memory_size = program.max_memory_words();
+        let chunk_size = trace.len().next_power_of_two() / 4;
+        let max_memory = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::cost_report(
+            preprocessing,
+            chunk_size,
+            memory_size,
+        )
+        .prover_memory;
+        assert_eq!(
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::chunk_size_for_memory(
+                preprocessing,
+                memory_size,
+                1 << 20,
+                max_memory,
+            ),
+            Some(chunk_size)
+        );
+        assert_eq!(
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::chunk_size_for_memory(
+                preprocessing,
+                memory_size,
+                1 << 20,
+                0,
+            ),
+            None
+        );
+        // No chunk size fits, so nothing is proven
+        assert_eq!(
+            program
+                .prove_within_memory::<Fr, PCS, KeccakTranscript>(preprocessing, 1 << 20, 0)
+                .err(),
+            Some(ConfigError::NoChunkFits(0))
+        );
+    }
+
+    #[test]
+    fn fib_guest_tests() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_tests(&["fib_small"]);
+        let outcome = program.run_test("fib_small");
+        drop(artifact_guard);
+
+        assert!(outcome.passed(), "{:?}", outcome.panic);
+        assert!(outcome.cycles > 0);
+    }
+
+    #[test]
+    fn fib_profile() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let report = program.profile::<Fr, PCS, KeccakTranscript>();
+        let (_, trace) = program.trace();
+        drop(artifact_guard);
+
+        assert_eq!(report.trace_length, trace.len());
+        assert_eq!(
+            report.cost.padded_trace_length,
+            trace.len().next_power_of_two()
+        );
+        assert_eq!(
+            report.padding(),
+            report.cost.padded_trace_length - trace.len()
+        );
+        assert_eq!(
+            report
+                .functions
+                .iter()
+                .map(|(_, cycles)| cycles)
+                .sum::<usize>(),
+            report.cycles
+        );
+        assert!(report.cycles <= report.trace_length);
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn fib_e2e_distributed() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let chunk_size = trace.len().next_power_of_two() / 4;
+        let chunks: Vec<_> = trace.chunks(chunk_size).map(<[_]>::to_vec).collect();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let chained_proof = thread::scope(|scope| {
+            let workers: Vec<_> = (0..2)
+                .map(|_| TcpStream::connect(address).unwrap())
+                .collect();
+            for _ in 0..workers.len() {
+                let (stream, _) = listener.accept().unwrap();
+                let preprocessing = &preprocessing;
+                scope.spawn(move || {
+                    serve::<RV32IJoltVM, Fr, PCS, C, M, KeccakTranscript>(preprocessing, stream)
+                        .unwrap()
+                });
+            }
+            prove_distributed::<RV32IJoltVM, Fr, PCS, C, M, KeccakTranscript, _>(
+                io_device.clone(),
+                chunks.clone(),
+                &preprocessing,
+                workers,
+            )
+            .unwrap()
+        });
+        assert_eq!(chained_proof.proofs.len(), chunks.len());
+
+        // Workers produce the same proof as a single machine
+        let expected = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_chunks(
+            io_device,
+            chunks,
+            preprocessing.clone(),
+        );
+        let mut bytes = vec![];
+        chained_proof.serialize_compressed(&mut bytes).unwrap();
+        let mut expected_bytes = vec![];
+        expected.serialize_compressed(&mut expected_bytes).unwrap();
+        assert_eq!(bytes, expected_bytes);
+
+        let verification_result = RV32IJoltVM::verify_segmented(preprocessing, chained_proof);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn e2e_aggregated() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let mut executions = vec![];
+        let mut verifier_preprocessing = vec![];
+        for (lock, guest) in [
+            (&FIB_FILE_LOCK, "fibonacci-guest"),
+            (&SHA3_FILE_LOCK, "sha3-guest"),
+        ] {
+            let artifact_guard = lock.lock().unwrap();
+            let mut program = host::Program::new(guest);
+            if guest == "sha3-guest" {
+                program.set_input(&[5u8; 32]);
+            } else {
+                program.set_input(&9u32);
+            }
+            let (bytecode, memory_init) = program.decode();
+            let (io_device, trace) = program.trace();
+            drop(artifact_guard);
+
+            let preprocessing = RV32IJoltVM::preprocess(
+                bytecode.clone(),
+                io_device.memory_layout.clone(),
+                memory_init,
+                1 << 20,
+                1 << 20,
+                1 << 20,
+            );
+            verifier_preprocessing.push(preprocessing.clone());
+            executions.push((io_device, trace, preprocessing));
+        }
+
+        let (proof, commitments) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove_aggregated(executions);
+        assert_eq!(proof.proofs.len(), 2);
+        let verification_result =
+            RV32IJoltVM::verify_aggregated(verifier_preprocessing, proof, commitments);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn fib_e2e_program_commitment() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (mut preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let program_commitment =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::commit_program(
+                &mut preprocessing,
+            );
+        let key = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::bind_verifier_key(
+            &mut preprocessing,
+        );
+        assert_eq!(key.program_commitment, program_commitment);
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+            io_device,
+            trace,
+            preprocessing.clone(),
+        );
+        assert_eq!(proof.metadata.verifier_key_digest, key.digest());
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        commitments.serialize_compressed(&mut bytes).unwrap();
+        let reload = || {
+            let mut reader = &bytes[..];
+            let proof =
+                RV32IJoltProof::<Fr, PCS, KeccakTranscript>::deserialize_compressed(&mut reader)
+                    .unwrap();
+            let commitments = CanonicalDeserialize::deserialize_compressed(&mut reader).unwrap();
+            (proof, commitments)
+        };
+
+        // Light verifiers know the program only by its commitment...
+        let light_preprocessing =
+            RV32IJoltVM::preprocess_light(program_commitment.clone(), 1 << 20, 1 << 20, 1 << 20);
+        assert_eq!(light_preprocessing.program_hash, preprocessing.program_hash);
+        let (proof, commitments) = reload();
+        let verification_result =
+            RV32IJoltVM::verify(light_preprocessing.clone(), proof, commitments, None);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+        // ...or by a verifier key
+        let key_bytes = key.serialize_to_bytes().unwrap();
+        let key = VerifierKey::<PCS, KeccakTranscript>::deserialize_from_bytes(&key_bytes).unwrap();
+        assert_eq!(key.program_hash(), preprocessing.program_hash);
+        let (proof, commitments) = reload();
+        let verification_result = RV32IJoltVM::verify(
+            RV32IJoltVM::preprocess_verifier(key.clone()),
+            proof,
+            commitments,
+            None,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+
+        // Proofs of other inputs reuse the program's commitments and keys
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&10u32);
+        let (io_device, trace) = program.trace();
+        drop(artifact_guard);
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+            io_device,
+            trace,
+            preprocessing.clone(),
+        );
+        assert!(RV32IJoltVM::verify(light_preprocessing, proof, commitments, None).is_ok());
+        assert!(!preprocessing.spartan_keys.is_empty());
+
+        // A key with other maximum sizes rejects the proof, even with the same PCS
+        // setup, and the key rejects proofs made before the program was committed to
+        let other_key = VerifierKey {
+            max_bytecode_size: 1 << 19,
+            ..key.clone()
+        };
+        let (proof, commitments) = reload();
+        assert!(matches!(
+            RV32IJoltVM::verify(
+                RV32IJoltVM::preprocess_verifier(other_key),
+                proof,
+                commitments,
+                None
+            ),
+            Err(ProofVerifyError::VerifierKeyMismatch)
+        ));
+        let (_, unbound_proof, unbound_commitments) = fib_proof(&HYPERKZG_FIB_PROOF);
+        assert_ne!(unbound_proof.metadata.verifier_key_digest, key.digest());
+        assert!(matches!(
+            RV32IJoltVM::verify(
+                RV32IJoltVM::preprocess_verifier(key),
+                unbound_proof,
+                unbound_commitments,
+                None
+            ),
+            Err(ProofVerifyError::VerifierKeyMismatch)
+        ));
+
+        // Commitments to other programs, or tampered ones, reject the proof
+        let artifact_guard = SHA3_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("sha3-guest");
+        program.set_input(&[5u8; 32]);
+        let (bytecode, memory_init) = program.decode();
+        drop(artifact_guard);
+        let mut other_preprocessing = RV32IJoltVM::preprocess(
+            bytecode,
+            program.memory_layout(),
+            memory_init,
             1 << 20,
             1 << 20,
             1 << 20,
         );
+        let other_commitment =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::commit_program(
+                &mut other_preprocessing,
+            );
+        let mut tampered_bytecode = program_commitment.clone();
+        tampered_bytecode.bytecode.swap(0, 1);
+        let mut tampered_image = program_commitment;
+        tampered_image.memory_image = other_commitment.memory_image.clone();
+        for program_commitment in [other_commitment, tampered_bytecode, tampered_image] {
+            let light_preprocessing =
+                RV32IJoltVM::preprocess_light(program_commitment, 1 << 20, 1 << 20, 1 << 20);
+            let (proof, commitments) = reload();
+            assert!(RV32IJoltVM::verify(light_preprocessing, proof, commitments, None).is_err());
+        }
+    }
+
+    #[test]
+    fn fib_e2e_committed_inputs() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (mut preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::commit_inputs(&mut preprocessing);
+        let input_digest =
+            RV32IJoltVM::input_commitment(&preprocessing, &io_device.inputs).digest();
+        let mut other_inputs = io_device.inputs.clone();
+        other_inputs[0] ^= 1;
+        let other_digest = RV32IJoltVM::input_commitment(&preprocessing, &other_inputs).digest();
+
+        let (proof, commitments, _) = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+            io_device.clone(),
+            trace,
+            preprocessing.clone(),
+        );
+        assert!(proof.program_io.inputs.is_empty());
+
+        let bytes = proof.serialize_to_bytes().unwrap();
+        let copy = || RV32IJoltProof::<Fr, PCS, KeccakTranscript>::deserialize_from_bytes(&bytes);
+        assert!(RV32IJoltVM::verify_with_input_digest(
+            preprocessing.clone(),
+            copy().unwrap(),
+            commitments.clone(),
+            &other_digest
+        )
+        .is_err());
+        // The input commitment is only checked against a digest, so other
+        // verifiers reject the proof
+        assert!(RV32IJoltVM::verify(
+            preprocessing.clone(),
+            copy().unwrap(),
+            commitments.clone(),
+            None
+        )
+        .is_err());
+        assert!(RV32IJoltVM::verify_with_io(
+            preprocessing.clone(),
+            copy().unwrap(),
+            commitments.clone(),
+            &io_device
+        )
+        .is_err());
+        let verification_result =
+            RV32IJoltVM::verify_with_input_digest(preprocessing, proof, commitments, &input_digest);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn fib_pipelined_commitments() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (mut preprocessing, io_device, mut trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        JoltTraceStep::pad(&mut trace);
+        preprocessing.read_write_memory.pages = preprocessing
+            .read_write_memory
+            .touched_pages(&io_device, &trace);
+
+        let (polynomials, _) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::generate_witness(
+                &io_device,
+                &mut trace.clone(),
+                &preprocessing,
+            );
+        let commitments = polynomials.commit::<C, PCS, KeccakTranscript>(&preprocessing);
+
+        let mut pipelined = JoltCommitments::<PCS, KeccakTranscript>::initialize(&preprocessing);
+        let (polynomials, _) =
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::generate_witness_pipelined(
+                &io_device,
+                &mut trace,
+                &preprocessing,
+                Some(&mut pipelined),
+            );
+        polynomials.commit_remaining(&mut pipelined, &preprocessing);
+
+        let mut expected = vec![];
+        commitments.serialize_compressed(&mut expected).unwrap();
+        let mut actual = vec![];
+        pipelined.serialize_compressed(&mut actual).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    /// A load can't also be a store, expressed through a custom aux variable
+    struct LoadXorStore;
+    impl CustomConstraints for LoadXorStore {
+        const NUM_AUX: usize = 1;
+
+        fn uniform_constraints<const N: usize, F: JoltField>(
+            cs: &mut R1CSBuilder<N, F, CustomR1CSInputs<Self>>,
+            _memory_start: u64,
+        ) {
+            let load_and_store = cs.allocate_prod(
+                CustomR1CSInputs::aux(0),
+                JoltR1CSInputs::OpFlags(CircuitFlags::Load),
+                JoltR1CSInputs::OpFlags(CircuitFlags::Store),
+            );
+            cs.constrain_eq(load_and_store, 0);
+        }
+    }
+
+    /// Same check as `LoadXorStore` but as a single degree-3 gate, without an
+    /// aux column, plus a degree-4 gate
+    struct HighDegreeGates;
+    impl CustomConstraints for HighDegreeGates {
+        fn uniform_constraints<const N: usize, F: JoltField>(
+            cs: &mut R1CSBuilder<N, F, CustomR1CSInputs<Self>>,
+            _memory_start: u64,
+        ) {
+            let load: LC = JoltR1CSInputs::OpFlags(CircuitFlags::Load).into();
+            let store: LC = JoltR1CSInputs::OpFlags(CircuitFlags::Store).into();
+            let jump: LC = JoltR1CSInputs::OpFlags(CircuitFlags::Jump).into();
+            cs.constrain_product(
+                vec![load, store, JoltR1CSInputs::RAM_Read.into()],
+                LC::zero(),
+            );
+            cs.constrain_product(vec![jump.clone(); 4], jump);
+        }
+    }
+
+    /// Stands in for a precompile: a committed column computed from the trace
+    /// by the witness hook, and constrained against Jolt's own witness
+    struct CopyBitflags;
+    impl CustomConstraints for CopyBitflags {
+        const NUM_AUX: usize = 1;
+
+        fn witness<InstructionSet: JoltInstructionSet>(
+            step: &JoltTraceStep<InstructionSet>,
+            columns: &mut [u64],
+        ) {
+            columns[0] = step.bytecode_row.bitflags;
+        }
+
+        fn uniform_constraints<const N: usize, F: JoltField>(
+            cs: &mut R1CSBuilder<N, F, CustomR1CSInputs<Self>>,
+            _memory_start: u64,
+        ) {
+            cs.constrain_eq(
+                CustomR1CSInputs::<Self>::aux(0),
+                JoltR1CSInputs::Bytecode_Bitflags,
+            );
+        }
+    }
+
+    /// Proves and verifies the execution of `fib_fixture` with the custom
+    /// constraints `E`, returning whether the proof has a product constraint
+    /// sumcheck.
+    fn fib_e2e_with_constraints<E: CustomConstraints>() -> bool {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
         let (proof, commitments, debug_info) =
-            <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::prove(
+            <RV32IJoltVMWithConstraints<E> as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
                 io_device,
                 trace,
                 preprocessing.clone(),
             );
-        let verification_result =
-            RV32IJoltVM::verify(preprocessing, proof, commitments, debug_info);
+        assert_eq!(commitments.r1cs.aux.custom.len(), E::NUM_AUX);
+        let has_product_proof = proof.r1cs.product_proof.is_some();
+        let verification_result = <RV32IJoltVMWithConstraints<E> as Jolt<
+            Fr,
+            PCS,
+            C,
+            M,
+            KeccakTranscript,
+        >>::verify(preprocessing, proof, commitments, debug_info);
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+        has_product_proof
+    }
+
+    #[test]
+    fn fib_e2e_custom_constraints() {
+        assert!(!fib_e2e_with_constraints::<LoadXorStore>());
+        assert!(fib_e2e_with_constraints::<HighDegreeGates>());
+        assert!(!fib_e2e_with_constraints::<CopyBitflags>());
+    }
+
+    #[test]
+    fn fib_cost_report() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, proof, commitments) = fib_proof(&HYPERKZG_FIB_PROOF);
+        let trace_length = proof.trace_length;
+        let report = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::cost_report(
+            &preprocessing,
+            trace_length,
+            proof.memory_pages.memory_size(),
+        );
+
+        assert_eq!(report.padded_trace_length, trace_length.next_power_of_two());
+        assert_eq!(
+            report.num_commitments(),
+            commitments.read_write_values().len() + commitments.init_final_values().len()
+        );
+        assert_eq!(report.r1cs_inputs, JoltR1CSInputs::num_inputs::<C>());
+
+        let r1cs_proof_size = proof.r1cs.compressed_size();
+        assert!(report.r1cs_proof_size <= r1cs_proof_size);
+        assert!(report.r1cs_proof_size * 2 > r1cs_proof_size);
+        let total_size = proof.compressed_size() + commitments.compressed_size();
+        assert!(report.total_size() * 4 > total_size && report.total_size() < total_size * 4);
+    }
+
+    #[test]
+    fn fib_verification_cost() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (mut preprocessing, proof, _) = fib_proof(&HYPERKZG_FIB_PROOF);
+        let key = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::bind_verifier_key(
+            &mut preprocessing,
+        );
+        let trace_length = proof.trace_length;
+        let memory_size = proof.memory_pages.memory_size();
+        let cost = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::verification_cost(
+            &key,
+            trace_length,
+            memory_size,
+        );
+        let report = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::cost_report(
+            &preprocessing,
+            trace_length,
+            memory_size,
+        );
+
+        assert_eq!(cost.pairings, 2);
+        assert!(cost.scalar_muls > report.num_commitments());
+        assert!(cost.hashes > report.num_commitments());
+        assert_eq!(cost.proof_size, report.total_size());
+        assert!(cost.evm_gas() > 21_000 + 45_000 + 2 * 34_000);
+
+        // More steps means more sumcheck rounds
+        let longer = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::verification_cost(
+            &key,
+            trace_length * 4,
+            memory_size,
+        );
+        assert!(longer.field_muls > cost.field_muls);
+        assert!(longer.hashes > cost.hashes);
+        assert_eq!(longer.pairings, cost.pairings);
+    }
+
+    #[test]
+    fn fib_check_security() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let (preprocessing, proof, _) = fib_proof(&HYPERKZG_FIB_PROOF);
+        let check_security = |config| {
+            <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::check_security(
+                &preprocessing,
+                proof.trace_length,
+                1 << 16,
+                config,
+            )
+        };
+
+        let report = check_security(SecurityConfig::default()).unwrap();
+        assert_eq!(report.challenge_bits, 253);
+        let bits = report.bits();
+        assert!(bits > 200.0);
+        assert!(report
+            .subprotocols
+            .iter()
+            .all(|subprotocol| subprotocol.bits > bits));
+
+        let config = SecurityConfig {
+            target_bits: 253,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_security(config),
+            Err(SecurityError::InsufficientSecurity(
+                bits.floor() as u32,
+                253
+            ))
+        );
+        let config = SecurityConfig {
+            extension_degree: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_security(config),
+            Err(SecurityError::UnsupportedExtensionDegree(2))
+        );
+    }
+
+    #[test]
+    fn fib_export_constraint_system() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let dir = std::env::temp_dir().join(format!("jolt-export-{}", std::process::id()));
+        let step = trace.len() / 2;
+        <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::export_constraint_system(
+            io_device,
+            trace,
+            preprocessing,
+            step,
+            &dir,
+        )
+        .unwrap();
+
+        let (num_wires, constraints) = parse_r1cs(&std::fs::read(dir.join("jolt.r1cs")).unwrap());
+        let witness = parse_wtns(&std::fs::read(dir.join("jolt.wtns")).unwrap());
+        let symbols = std::fs::read_to_string(dir.join("jolt.sym")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(witness.len(), num_wires);
+        assert_eq!(symbols.lines().count(), num_wires - 1);
+        let eval = |row: &Vec<(usize, Fr)>| -> Fr {
+            row.iter()
+                .map(|(wire, coeff)| *coeff * witness[*wire])
+                .sum()
+        };
+        for [a, b, c] in constraints.iter() {
+            assert_eq!(eval(a) * eval(b), eval(c));
+        }
+    }
+
+    struct NoLoads;
+    impl CustomConstraints for NoLoads {
+        fn uniform_constraints<const N: usize, F: JoltField>(
+            cs: &mut R1CSBuilder<N, F, CustomR1CSInputs<Self>>,
+            _memory_start: u64,
+        ) {
+            cs.labeled("no loads", |cs| {
+                cs.constrain_eq(JoltR1CSInputs::OpFlags(CircuitFlags::Load), 0)
+            });
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "uniform constraint `no loads")]
+    fn fib_e2e_violated_constraint() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+        type VM = RV32IJoltVMWithConstraints<NoLoads>;
+
+        let (preprocessing, io_device, trace) = fib_fixture::<Fr, PCS, KeccakTranscript>();
+        let _ =
+            <VM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(io_device, trace, preprocessing);
+    }
+
+    #[test]
+    fn program_preprocessing_round_trip() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let program_preprocessing = program.program_preprocessing();
+        drop(artifact_guard);
+
+        let bytes = program_preprocessing.serialize_to_bytes().unwrap();
+        assert_eq!(
+            ProgramPreprocessing::deserialize_from_bytes(&bytes).unwrap(),
+            program_preprocessing
+        );
+
+        // A file whose contents don't match its program hash is rejected, unless
+        // it is trusted
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(ProgramPreprocessing::deserialize_from_bytes(&tampered).is_err());
+        let trusted =
+            ProgramPreprocessing::deserialize_with_mode(&tampered[..], Compress::Yes, Validate::No)
+                .unwrap();
+        assert_eq!(trusted.program_hash, program_preprocessing.program_hash);
+    }
+
+    #[test]
+    fn fib_e2e_dev() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.set_input(&9u32);
+        let program_preprocessing = program.program_preprocessing();
+        let (io_device, trace) = program.trace();
+        drop(artifact_guard);
+        let trace_length = trace.len();
+
+        let preprocessing = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess_dev(
+            program_preprocessing.clone(),
+        );
+        let proof = RV32IJoltVM::prove_dev(io_device.clone(), trace.clone(), &preprocessing)
+            .expect("the witness should satisfy the constraints");
+        assert_eq!(proof.trace_length, trace_length);
+        assert_eq!(proof.program_io.outputs, io_device.outputs);
+        assert_eq!(proof.program_hash, program_preprocessing.program_hash);
+        assert!(matches!(proof.verify(), Err(ProofVerifyError::DevProof)));
+
+        // Violations are returned rather than panicking
+        type VM = RV32IJoltVMWithConstraints<NoLoads>;
+        let preprocessing =
+            <VM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess_dev(program_preprocessing);
+        let violation = VM::prove_dev(io_device, trace, &preprocessing).unwrap_err();
+        assert!(violation.label.starts_with("no loads"));
+    }
+
+    #[test]
+    fn fib_e2e_proof_metadata() {
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+        let (preprocessing, proof, commitments) = fib_proof(&MOCK_FIB_PROOF);
+        assert_eq!(proof.metadata.program_hash, preprocessing.program_hash);
+        assert_eq!(proof.metadata.version, env!("CARGO_PKG_VERSION"));
+
+        let mut bytes = vec![];
+        proof.serialize_compressed(&mut bytes).unwrap();
+        commitments.serialize_compressed(&mut bytes).unwrap();
+        let reload = || {
+            let mut reader = &bytes[..];
+            let proof =
+                RV32IJoltProof::<Fr, PCS, KeccakTranscript>::deserialize_compressed(&mut reader)
+                    .unwrap();
+            let commitments = CanonicalDeserialize::deserialize_compressed(&mut reader).unwrap();
+            (proof, commitments)
+        };
+
+        // Mismatches are reported precisely
+        let mut other_program = preprocessing.clone();
+        other_program.program_hash = [0; 32];
+        let (proof, commitments) = reload();
+        assert!(matches!(
+            RV32IJoltVM::verify(other_program, proof, commitments, None),
+            Err(ProofVerifyError::ProgramMismatch)
+        ));
+        let (mut proof, commitments) = reload();
+        proof.metadata.version = "0.0.0".to_string();
+        assert!(matches!(
+            RV32IJoltVM::verify(preprocessing.clone(), proof, commitments, None),
+            Err(ProofVerifyError::VersionMismatch(..))
+        ));
+        let (mut proof, commitments) = reload();
+        proof.metadata.config_digest = [0; 32];
+        assert!(matches!(
+            RV32IJoltVM::verify(preprocessing.clone(), proof, commitments, None),
+            Err(ProofVerifyError::ConfigMismatch)
+        ));
+        let mut other_key = preprocessing.clone();
+        other_key.verifier_key_digest = [0; 32];
+        let (proof, commitments) = reload();
+        assert!(matches!(
+            RV32IJoltVM::verify(other_key, proof, commitments, None),
+            Err(ProofVerifyError::VerifierKeyMismatch)
+        ));
+        // Proofs are bound to the maximum sizes even without a verifier key, so
+        // preprocessing of the same program with other sizes rejects them too
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let (bytecode, memory_init) = host::Program::new("fibonacci-guest").decode();
+        drop(artifact_guard);
+        let other_sizes = <RV32IJoltVM as Jolt<Fr, PCS, C, M, KeccakTranscript>>::preprocess(
+            bytecode,
+            preprocessing.memory_layout.clone(),
+            memory_init,
+            1 << 19,
+            1 << 20,
+            1 << 20,
+        );
+        assert_eq!(other_sizes.program_hash, preprocessing.program_hash);
+        let (proof, commitments) = reload();
+        assert!(matches!(
+            RV32IJoltVM::verify(other_sizes, proof, commitments, None),
+            Err(ProofVerifyError::VerifierKeyMismatch)
+        ));
+        let (mut proof, commitments) = reload();
+        proof.metadata.verifier_key_digest[0] ^= 1;
+        assert!(matches!(
+            RV32IJoltVM::verify(preprocessing.clone(), proof, commitments, None),
+            Err(ProofVerifyError::VerifierKeyMismatch)
+        ));
+
+        let (proof, commitments) = reload();
+        let verification_result = RV32IJoltVM::verify(preprocessing, proof, commitments, None);
         assert!(
             verification_result.is_ok(),
             "Verification failed with error: {:?}",
@@ -462,7 +1973,7 @@ mod tests {
         let (bytecode, memory_init) = program.decode();
         let (mut io_device, mut trace) = program.trace();
         trace.truncate(100);
-        io_device.outputs[0] = 0; // change the output to 0
+        io_device.outputs[4] = 0; // change the output, after the journal's length, to 0
         drop(artifact_guard);
 
         let preprocessing = RV32IJoltVM::preprocess(
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use crate::field::JoltField;
+use crate::r1cs::export::modulus_bytes;
+use crate::utils::math::Math;
+
+use super::cost;
+
+/// The soundness a deployment requires of its proofs, see `Jolt::check_security`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SecurityConfig {
+    /// Required bits of statistical soundness of the proof system, i.e. the
+    /// negated log2 of the probability that a false statement is accepted
+    pub target_bits: u32,
+    /// Degree of the extension of the proof's field that challenges are drawn from
+    pub extension_degree: u32,
+    /// Number of independent repetitions of each subprotocol
+    pub repetitions: u32,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            target_bits: 100,
+            extension_degree: 1,
+            repetitions: 1,
+        }
+    }
+}
+
+/// The soundness error of one subprotocol, accounted for by the Schwartz-Zippel
+/// lemma: a false claim survives a random challenge with probability at most
+/// `d / |K|` for a polynomial of degree `d` over the challenge field `K`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubprotocolSoundness {
+    pub name: &'static str,
+    /// Sum of the degrees of the polynomials checked at random challenges,
+    /// over all rounds, fingerprints and random linear combinations
+    pub error_degree: usize,
+    /// Bits of soundness of the subprotocol under the config of the report
+    pub bits: f64,
+}
+
+/// Per-subprotocol soundness of proving a given configuration, computed
+/// without running the prover (see `Jolt::check_security`). The errors are
+/// those of the interactive protocol. Made non-interactive with Fiat-Shamir,
+/// an attacker making `2^q` transcript hash queries gains at most `q` bits.
+/// The computational security of the commitment scheme is not included.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoundnessReport {
+    pub config: SecurityConfig,
+    /// Bits of the challenge field: a random challenge hits any given value
+    /// with probability at most `2^-challenge_bits`
+    pub challenge_bits: usize,
+    pub subprotocols: Vec<SubprotocolSoundness>,
+}
+
+impl SoundnessReport {
+    /// Accounts for the subprotocols given as `(name, error_degree)` under `config`,
+    /// with challenges drawn from (an extension of) `F`.
+    pub(crate) fn new<F: JoltField>(
+        config: SecurityConfig,
+        error_degrees: Vec<(&'static str, usize)>,
+    ) -> Self {
+        let challenge_bits = config.extension_degree as usize * (field_bits::<F>() - 1);
+        let subprotocols = error_degrees
+            .into_iter()
+            .map(|(name, error_degree)| SubprotocolSoundness {
+                name,
+                error_degree,
+                bits: config.repetitions as f64
+                    * (challenge_bits as f64 - (error_degree.max(1) as f64).log2()),
+            })
+            .collect();
+        Self {
+            config,
+            challenge_bits,
+            subprotocols,
+        }
+    }
+
+    /// Bits of soundness of the whole proof: the negated log2 of the sum of the
+    /// errors of its subprotocols
+    pub fn bits(&self) -> f64 {
+        let min_bits = self
+            .subprotocols
+            .iter()
+            .map(|subprotocol| subprotocol.bits)
+            .fold(f64::INFINITY, f64::min);
+        // Factor out the largest error, so that the sum does not underflow
+        let relative_error: f64 = self
+            .subprotocols
+            .iter()
+            .map(|subprotocol| (min_bits - subprotocol.bits).exp2())
+            .sum();
+        min_bits - relative_error.log2()
+    }
+}
+
+impl fmt::Display for SoundnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "challenge field:        {} bits (extension degree {}, {} repetition(s))",
+            self.challenge_bits, self.config.extension_degree, self.config.repetitions
+        )?;
+        for subprotocol in self.subprotocols.iter() {
+            writeln!(
+                f,
+                "{:<24}{:.1} bits",
+                format!("{}:", subprotocol.name),
+                subprotocol.bits
+            )?;
+        }
+        write!(
+            f,
+            "total:                  {:.1} bits (target {})",
+            self.bits(),
+            self.config.target_bits
+        )
+    }
+}
+
+/// Number of bits of the modulus of `F`
+fn field_bits<F: JoltField>() -> usize {
+    let modulus = modulus_bytes::<F>();
+    let (index, top_byte) = modulus
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, byte)| **byte != 0)
+        .unwrap();
+    8 * index + (8 - top_byte.leading_zeros() as usize)
+}
+
+/// Error degree of a grand product over `num_leaves` fingerprints: the product
+/// of the fingerprints has degree `num_leaves` in the fingerprint challenge, and
+/// each GKR layer runs a degree-3 sumcheck and then combines two claims.
+pub(crate) fn grand_product_error_degree(num_leaves: usize) -> usize {
+    let num_leaves = num_leaves.next_power_of_two();
+    let num_layers = num_leaves.log_2();
+    num_leaves + 3 * cost::grand_product_rounds(num_leaves) + 2 * num_layers
+}
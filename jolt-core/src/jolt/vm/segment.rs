@@ -0,0 +1,227 @@
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+use crate::field::JoltField;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::dense_mlpoly::DensePolynomial;
+use crate::poly::multilinear_polynomial::MultilinearPolynomial;
+use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
+use crate::r1cs::inputs::ConstraintInput;
+use crate::r1cs::key::{CrossStepR1CS, SparseEqualityItem};
+use crate::utils::errors::ProofVerifyError;
+use crate::utils::index_to_field_bitvector;
+use crate::utils::math::Math;
+use crate::utils::transcript::Transcript;
+
+use super::{JoltCommitments, JoltPolynomials};
+
+/// `segment_lengths` only splits traces longer than this; for shorter ones the
+/// fixed cost of an extra proof outweighs the padding saved.
+pub const MIN_SEGMENT_LENGTH: usize = 1 << 10;
+
+/// Position of a proof within an execution split into segments, see
+/// `Jolt::prove_segmented`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// Whether the segment starts the execution. Later segments resume from the
+    /// previous segment's final memory state exactly, including its I/O region.
+    pub first: bool,
+    /// Whether the segment ends the execution. Outputs are only checked for the
+    /// last segment.
+    pub last: bool,
+}
+
+impl Segment {
+    /// The position of segment `index` out of `count`.
+    pub fn new(index: usize, count: usize) -> Self {
+        Self {
+            first: index == 0,
+            last: index + 1 == count,
+        }
+    }
+}
+
+/// Splits a trace of `trace_length` steps into segments which, padded
+/// individually, cover fewer steps than `trace_length.next_power_of_two()`.
+/// All segments but the last have power-of-two lengths, so only the last one
+/// is padded.
+pub fn segment_lengths(trace_length: usize) -> Vec<usize> {
+    let mut lengths = vec![];
+    let mut remaining = trace_length;
+    while remaining > MIN_SEGMENT_LENGTH {
+        let padded_length = remaining.next_power_of_two();
+        let head = padded_length / 2;
+        if head + (remaining - head).next_power_of_two() >= padded_length {
+            break;
+        }
+        lengths.push(head);
+        remaining -= head;
+    }
+    lengths.push(remaining);
+    lengths
+}
+
+/// Openings of the R1CS inputs at the first and last step of a segment, from
+/// which the verifier checks the cross-step constraints between consecutive
+/// segments (see `check_boundary`).
+#[derive(Clone, Debug, Default, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SegmentBoundary<F: JoltField> {
+    /// Inputs at the first step, for all but the first segment
+    pub first_step: Option<Vec<F>>,
+    /// Inputs at the last step, for all but the last segment
+    pub last_step: Option<Vec<F>>,
+}
+
+impl<F: JoltField> SegmentBoundary<F> {
+    #[tracing::instrument(skip_all, name = "SegmentBoundary::prove")]
+    pub fn prove<const C: usize, I: ConstraintInput, ProofTranscript: Transcript>(
+        segment: Option<Segment>,
+        polynomials: &JoltPolynomials<F>,
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Self {
+        let Some(segment) = segment else {
+            return Self::default();
+        };
+        let flattened_polys: Vec<&MultilinearPolynomial<F>> = I::flatten::<C>()
+            .iter()
+            .map(|var| var.get_ref(polynomials))
+            .collect();
+        let num_steps = flattened_polys[0].len();
+
+        let mut open_step = |step: usize| {
+            let point = index_to_field_bitvector(step, num_steps.log_2());
+            let (claims, chis) = MultilinearPolynomial::batch_evaluate(&flattened_polys, &point);
+            opening_accumulator.append(
+                &flattened_polys,
+                DensePolynomial::new(chis),
+                point,
+                &claims,
+                transcript,
+            );
+            claims
+        };
+        let first_step = (!segment.first).then(|| open_step(0));
+        let last_step = (!segment.last).then(|| open_step(num_steps - 1));
+
+        Self {
+            first_step,
+            last_step,
+        }
+    }
+
+    pub fn verify<const C: usize, I, PCS, ProofTranscript>(
+        &self,
+        segment: Option<Segment>,
+        num_steps: usize,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError>
+    where
+        I: ConstraintInput,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let segment = segment.unwrap_or(Segment {
+            first: true,
+            last: true,
+        });
+        if self.first_step.is_some() == segment.first || self.last_step.is_some() == segment.last {
+            return Err(ProofVerifyError::InternalError);
+        }
+        let num_inputs = I::num_inputs::<C>();
+        let flattened_commitments: Vec<_> = I::flatten::<C>()
+            .iter()
+            .map(|var| var.get_ref(commitments))
+            .collect();
+
+        for (step, claims) in [(0, &self.first_step), (num_steps - 1, &self.last_step)] {
+            let Some(claims) = claims else {
+                continue;
+            };
+            if claims.len() != num_inputs {
+                return Err(ProofVerifyError::InvalidInputLength(
+                    num_inputs,
+                    claims.len(),
+                ));
+            }
+            opening_accumulator.append(
+                &flattened_commitments,
+                index_to_field_bitvector(step, num_steps.log_2()),
+                &claims.iter().collect::<Vec<_>>(),
+                transcript,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Checks the cross-step constraints between the last step of a segment and
+/// the first step of the next one, given the openings of their inputs.
+pub fn check_boundary<const C: usize, I: ConstraintInput, F: JoltField>(
+    constraints: &CrossStepR1CS<F>,
+    last_step: &[F],
+    first_step: &[F],
+) -> Result<(), ProofVerifyError> {
+    let num_inputs = I::num_inputs::<C>();
+    for claims in [last_step, first_step] {
+        if claims.len() != num_inputs {
+            return Err(ProofVerifyError::InvalidInputLength(
+                num_inputs,
+                claims.len(),
+            ));
+        }
+    }
+    let evaluate = |item: &SparseEqualityItem<F>| {
+        item.offset_vars
+            .iter()
+            .map(|(col, offset, coeff)| {
+                let value = if *offset {
+                    first_step[*col]
+                } else {
+                    last_step[*col]
+                };
+                *coeff * value
+            })
+            .sum::<F>()
+            + item.constant
+    };
+    for constraint in constraints.constraints.iter() {
+        if evaluate(&constraint.eq) * evaluate(&constraint.condition) != F::zero() {
+            return Err(ProofVerifyError::InternalError);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segment_lengths_avoid_padding_cliff() {
+        assert_eq!(
+            segment_lengths(MIN_SEGMENT_LENGTH),
+            vec![MIN_SEGMENT_LENGTH]
+        );
+        assert_eq!(segment_lengths(1 << 16), vec![1 << 16]);
+        assert_eq!(segment_lengths((1 << 16) + 5), vec![1 << 16, 5]);
+        assert_eq!(
+            segment_lengths((1 << 16) + (1 << 14) + 500),
+            vec![1 << 16, 1 << 14, 500]
+        );
+        assert_eq!(segment_lengths(3 << 14), vec![1 << 15, 1 << 14]);
+        // Splitting would not save anything
+        assert_eq!(segment_lengths((3 << 14) + 1), vec![(3 << 14) + 1]);
+
+        for trace_length in [5000, 70_000, 100_000, 130_000] {
+            let lengths = segment_lengths(trace_length);
+            assert_eq!(lengths.iter().sum::<usize>(), trace_length);
+            assert!(lengths[..lengths.len() - 1]
+                .iter()
+                .all(|length| length.is_power_of_two()));
+            let padded: usize = lengths.iter().map(|l| l.next_power_of_two()).sum();
+            assert!(padded <= trace_length.next_power_of_two());
+        }
+    }
+}
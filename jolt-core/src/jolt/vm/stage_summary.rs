@@ -0,0 +1,229 @@
+use std::fmt::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
+
+/// Name of the spans the prover opens for each `ProverStage`. Their `stage`
+/// field is the stage's name, and their other fields the sizes it works on,
+/// e.g. `padded_trace_length`.
+pub const STAGE_SPAN: &str = "prover_stage";
+
+/// Name of the spans the prover opens within the sumchecks stage for each part
+/// of the proof. Their `sumcheck` field is the part's name, e.g. `bytecode`.
+pub const SUMCHECK_SPAN: &str = "prover_sumcheck";
+
+/// The time spent in one `ProverStage` of a proof
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageTiming {
+    pub stage: String,
+    /// Time the stage's span was entered for, i.e. its wall-clock time
+    pub duration: Duration,
+    /// The sizes recorded on the stage's span, formatted
+    pub fields: Vec<(String, String)>,
+}
+
+/// Collects the `prover_stage` spans of proofs into a stage-level breakdown of
+/// proving time. Opt in with `install`, or by adding its `layer` to your own
+/// subscriber:
+///
+/// ```ignore
+/// let summary = StageSummary::install()?;
+/// let (proof, commitments, _) = RV32IJoltVM::prove(io_device, trace, preprocessing);
+/// println!("{summary}");
+/// ```
+#[derive(Clone, Default)]
+pub struct StageSummary {
+    stages: Arc<Mutex<Vec<StageTiming>>>,
+}
+
+impl StageSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Installs a new summary as the global `tracing` subscriber, for
+    /// applications that do not set up their own. Fails if one is already set.
+    pub fn install() -> Result<Self, TryInitError> {
+        let summary = Self::new();
+        tracing_subscriber::registry()
+            .with(summary.layer())
+            .try_init()?;
+        Ok(summary)
+    }
+
+    /// A `tracing` layer recording into this summary
+    pub fn layer(&self) -> StageSummaryLayer {
+        StageSummaryLayer {
+            stages: self.stages.clone(),
+        }
+    }
+
+    /// The stages completed so far, in the order they finished
+    pub fn stages(&self) -> Vec<StageTiming> {
+        self.stages.lock().unwrap().clone()
+    }
+
+    /// Forgets the stages recorded so far, e.g. between proofs
+    pub fn clear(&self) {
+        self.stages.lock().unwrap().clear();
+    }
+}
+
+impl fmt::Display for StageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages = self.stages();
+        let total: Duration = stages.iter().map(|stage| stage.duration).sum();
+        writeln!(f, "{:<20} {:>12} {:>7}", "stage", "time", "share")?;
+        for stage in &stages {
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                100.0 * stage.duration.as_secs_f64() / total.as_secs_f64()
+            };
+            let mut fields = String::new();
+            for (name, value) in &stage.fields {
+                let _ = write!(fields, " {}={}", name, value);
+            }
+            writeln!(
+                f,
+                "{:<20} {:>12.3?} {:>6.1}%{}",
+                stage.stage, stage.duration, share, fields
+            )?;
+        }
+        write!(f, "{:<20} {:>12.3?}", "total", total)
+    }
+}
+
+/// See `StageSummary::layer`
+pub struct StageSummaryLayer {
+    stages: Arc<Mutex<Vec<StageTiming>>>,
+}
+
+/// A stage span in progress, stored in the span's extensions
+struct StageState {
+    timing: StageTiming,
+    entered: Option<Instant>,
+}
+
+impl Visit for StageTiming {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+impl StageTiming {
+    fn record(&mut self, field: &Field, value: String) {
+        if field.name() == "stage" {
+            self.stage = value;
+        } else {
+            self.fields.push((field.name().to_string(), value));
+        }
+    }
+}
+
+impl<S> Layer<S> for StageSummaryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != STAGE_SPAN {
+            return;
+        }
+        let mut timing = StageTiming {
+            stage: String::new(),
+            duration: Duration::ZERO,
+            fields: vec![],
+        };
+        attrs.record(&mut timing);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(StageState {
+                timing,
+                entered: None,
+            });
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(state) = span.extensions_mut().get_mut::<StageState>() {
+                values.record(&mut state.timing);
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(state) = span.extensions_mut().get_mut::<StageState>() {
+                state.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(state) = span.extensions_mut().get_mut::<StageState>() {
+                if let Some(entered) = state.entered.take() {
+                    state.timing.duration += entered.elapsed();
+                }
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(state) = span.extensions_mut().remove::<StageState>() {
+                self.stages.lock().unwrap().push(state.timing);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_summary() {
+        let summary = StageSummary::new();
+        let subscriber = tracing_subscriber::registry().with(summary.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                STAGE_SPAN,
+                stage = "commitments",
+                padded_trace_length = 1024,
+                commitments = tracing::field::Empty
+            );
+            let entered = span.enter();
+            span.record("commitments", 7);
+            drop(entered);
+            drop(span);
+            // Other spans are ignored
+            tracing::info_span!("Jolt::prove").in_scope(|| {});
+        });
+
+        let stages = summary.stages();
+        assert_eq!(stages.len(), 1);
+        assert_eq!(stages[0].stage, "commitments");
+        assert_eq!(
+            stages[0].fields,
+            vec![
+                ("padded_trace_length".to_string(), "1024".to_string()),
+                ("commitments".to_string(), "7".to_string()),
+            ]
+        );
+        assert!(summary.to_string().contains("commitments"));
+
+        summary.clear();
+        assert!(summary.stages().is_empty());
+    }
+}
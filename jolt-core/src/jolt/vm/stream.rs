@@ -0,0 +1,60 @@
+use ark_serialize::{CanonicalSerialize, SerializationError};
+use std::io::Write;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::jolt::subtable::JoltSubtableSet;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::utils::transcript::Transcript;
+
+use super::read_write_memory::MemoryArgument;
+use super::{JoltCommitments, JoltProof};
+
+impl<
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    > JoltProof<C, M, I, F, PCS, InstructionSet, Subtables, ProofTranscript, MemoryProof>
+where
+    I: ConstraintInput,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    InstructionSet: JoltInstructionSet,
+    Subtables: JoltSubtableSet<F>,
+    ProofTranscript: Transcript,
+    MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+{
+    /// Writes the proof and its commitments for `Jolt::verify_from_reader`: the
+    /// public parts of the proof and the commitments first, then each component in
+    /// the order the verifier checks it, all compressed. Unlike the layout of
+    /// `serialize_compressed`, this lets the verifier read a component only when
+    /// it needs it. Proofs in an `AggregatedProof` have no opening proof of their
+    /// own and cannot be streamed.
+    pub fn serialize_streaming(
+        &self,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        mut writer: impl Write,
+    ) -> Result<(), SerializationError> {
+        let Some(opening_proof) = &self.opening_proof else {
+            return Err(SerializationError::InvalidData);
+        };
+        self.metadata.serialize_compressed(&mut writer)?;
+        self.trace_length.serialize_compressed(&mut writer)?;
+        self.program_io.serialize_compressed(&mut writer)?;
+        self.memory_pages.serialize_compressed(&mut writer)?;
+        commitments.serialize_compressed(&mut writer)?;
+        self.bytecode.serialize_compressed(&mut writer)?;
+        self.instruction_lookups.serialize_compressed(&mut writer)?;
+        self.read_write_memory.serialize_compressed(&mut writer)?;
+        self.r1cs.serialize_compressed(&mut writer)?;
+        self.segment_boundary.serialize_compressed(&mut writer)?;
+        opening_proof.serialize_compressed(&mut writer)
+    }
+}
@@ -11,6 +11,7 @@ use crate::subprotocols::grand_product::{
     BatchedDenseGrandProduct, BatchedGrandProduct, BatchedGrandProductLayer,
     BatchedGrandProductProof,
 };
+use crate::subprotocols::sumcheck::SumcheckInstanceProof;
 use crate::utils::math::Math;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::Transcript;
@@ -26,10 +27,10 @@ use crate::{
 };
 
 use super::read_write_memory::ReadWriteMemoryPolynomials;
+use crate::utils::parallel::prelude::*;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use common::constants::MEMORY_OPS_PER_INSTRUCTION;
 use itertools::interleave;
-use rayon::prelude::*;
 #[cfg(test)]
 use std::collections::HashSet;
 
@@ -513,7 +514,12 @@ where
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
-    fn compute_verifier_openings(_: &mut Self::Openings, _: &NoPreprocessing, _: &[F], _: &[F]) {
+    fn compute_verifier_openings(
+        _: &mut Self::Openings,
+        _: &NoPreprocessing,
+        _: &[F],
+        _: &[F],
+    ) -> Result<(), ProofVerifyError> {
         unimplemented!("")
     }
 
@@ -674,6 +680,11 @@ where
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
+    /// The sumchecks of the grand product
+    pub(crate) fn sumcheck_proofs(&self) -> Vec<&SumcheckInstanceProof<F, ProofTranscript>> {
+        self.batched_grand_product.sumcheck_proofs()
+    }
+
     #[tracing::instrument(skip_all, name = "TimestampValidityProof::prove")]
     pub fn prove<'a>(
         generators: &PCS::Setup,
@@ -6,6 +6,7 @@ use crate::poly::dense_mlpoly::DensePolynomial;
 use crate::poly::eq_poly::EqPolynomial;
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
+use crate::subprotocols::sumcheck::SumcheckInstanceProof;
 use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
 use crate::utils::thread::drop_in_background_thread;
@@ -18,9 +19,9 @@ use crate::{
 };
 
 use crate::field::JoltField;
+use crate::utils::parallel::prelude::*;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use itertools::interleave;
-use rayon::prelude::*;
 
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct MultisetHashes<F: JoltField> {
@@ -68,6 +69,23 @@ where
     pub exogenous_openings: OtherOpenings,
 }
 
+impl<F, PCS, Openings, OtherOpenings, ProofTranscript>
+    MemoryCheckingProof<F, PCS, Openings, OtherOpenings, ProofTranscript>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    Openings: StructuredPolynomialData<F> + Sync + CanonicalSerialize + CanonicalDeserialize,
+    OtherOpenings: ExogenousOpenings<F> + Sync,
+    ProofTranscript: Transcript,
+{
+    /// The sumchecks of the read/write and init/final grand products
+    pub(crate) fn sumcheck_proofs(&self) -> Vec<&SumcheckInstanceProof<F, ProofTranscript>> {
+        let mut proofs = self.read_write_grand_product.sumcheck_proofs();
+        proofs.extend(self.init_final_grand_product.sumcheck_proofs());
+        proofs
+    }
+}
+
 /// This type, used within a `StructuredPolynomialData` struct, indicates that the
 /// field has a corresponding opening but no corresponding polynomial or commitment ––
 /// the prover doesn't need to compute a witness polynomial or commitment because
@@ -617,7 +635,7 @@ where
             preprocessing,
             r_read_write_opening,
             r_init_final_opening,
-        );
+        )?;
 
         Self::check_fingerprints(
             preprocessing,
@@ -642,7 +660,8 @@ where
         _preprocessing: &Self::Preprocessing,
         _r_read_write: &[F],
         _r_init_final: &[F],
-    ) {
+    ) -> Result<(), ProofVerifyError> {
+        Ok(())
     }
 
     /// Computes "read" memory tuples (one per memory) from the given `openings`.
@@ -1,3 +1,4 @@
+use crate::utils::parallel::prelude::*;
 use crate::{
     field::JoltField,
     jolt::vm::{JoltCommitments, JoltPolynomials, ProverDebugInfo},
@@ -8,7 +9,6 @@ use crate::{
     },
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::marker::{PhantomData, Sync};
 
 use super::memory_checking::{
@@ -213,7 +213,7 @@ where
         _preprocessing: &Self::Preprocessing,
         _r_read_write: &[F],
         r_init_final: &[F],
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         openings.a_init_final =
             Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
         openings.v_init_final = Some(
@@ -223,6 +223,7 @@ where
                 .map(|(subtable, _)| subtable.evaluate_mle(r_init_final))
                 .collect(),
         );
+        Ok(())
     }
 
     fn read_tuples(
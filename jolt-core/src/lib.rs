@@ -22,7 +22,10 @@ pub mod field;
 pub mod jolt;
 pub mod lasso;
 pub mod msm;
+pub mod pairing;
 pub mod poly;
 pub mod r1cs;
 pub mod subprotocols;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod utils;
@@ -1,9 +1,13 @@
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
+#[cfg(feature = "bench")]
+use jolt_core::benches::runner;
 use jolt_core::benches::{
     bench::{benchmarks, BenchType, PCSType},
     sum_timer::CumulativeTimingLayer,
 };
+use jolt_core::jolt::vm::memory_tracker::{MemoryTracker, TrackingAllocator};
+use jolt_core::jolt::vm::stage_summary::StageSummary;
 
 use std::any::Any;
 
@@ -12,6 +16,11 @@ use tracing_flame::FlameLayer;
 use tracing_subscriber::{self, fmt::format::FmtSpan, prelude::*};
 use tracing_texray::TeXRayLayer;
 
+/// Counts allocations for `--format memory`. The counting is a couple of
+/// atomic operations per allocation, so it is left on for the other formats.
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
 /// Search for a pattern in a file and display the lines that contain it.
 #[derive(Parser, Debug)]
 struct Cli {
@@ -22,6 +31,9 @@ struct Cli {
 #[derive(Subcommand, Debug)]
 enum Commands {
     Trace(TraceArgs),
+    /// Proves the reference guests and writes the results as JSON
+    #[cfg(feature = "bench")]
+    Bench(BenchArgs),
 }
 
 #[derive(Args, Debug)]
@@ -42,6 +54,29 @@ struct TraceArgs {
     num_cycles: Option<usize>,
 }
 
+#[cfg(feature = "bench")]
+#[derive(Args, Debug)]
+struct BenchArgs {
+    /// Guests to prove, all of them by default
+    #[clap(long, value_enum, num_args = 1..)]
+    guest: Vec<runner::ReferenceGuest>,
+
+    /// Approximate trace lengths to prove each guest at
+    #[clap(long, num_args = 1.., default_value = "65536")]
+    trace_length: Vec<usize>,
+
+    #[clap(long, value_enum, num_args = 1.., default_value = "hyper-kzg")]
+    pcs: Vec<PCSType>,
+
+    /// Number of proofs of each configuration, of which the fastest is reported
+    #[clap(long, default_value_t = 1)]
+    repetitions: usize,
+
+    /// File to write the results to, instead of stdout
+    #[clap(short, long)]
+    out: Option<String>,
+}
+
 #[derive(Args, Debug)]
 struct PlotArgs {
     /// Type of benchmark to run
@@ -72,12 +107,36 @@ enum Format {
     Flamegraph,
     Chrome,
     Sum,
+    /// Time spent in each stage of proving
+    Stages,
+    /// Peak heap memory of each stage of proving, and of each sumcheck
+    Memory,
 }
 
 fn main() {
     let cli = Cli::parse();
     match cli.command {
         Commands::Trace(args) => trace(args),
+        #[cfg(feature = "bench")]
+        Commands::Bench(args) => bench(args),
+    }
+}
+
+#[cfg(feature = "bench")]
+fn bench(args: BenchArgs) {
+    let mut config = runner::BenchmarkConfig {
+        trace_lengths: args.trace_length,
+        schemes: args.pcs,
+        repetitions: args.repetitions,
+        ..Default::default()
+    };
+    if !args.guest.is_empty() {
+        config.guests = args.guest;
+    }
+    let json = runner::run(&config).to_json();
+    match args.out {
+        Some(out) => std::fs::write(out, json).unwrap(),
+        None => println!("{}", json),
     }
 }
 
@@ -85,6 +144,8 @@ fn trace(args: TraceArgs) {
     let mut layers = Vec::new();
 
     let mut guards: Vec<Box<dyn Any>> = vec![];
+    let mut stage_summary = None;
+    let mut memory_tracker = None;
 
     if let Some(format) = &args.format {
         if format.contains(&Format::Default) {
@@ -113,6 +174,16 @@ fn trace(args: TraceArgs) {
             layers.push(sum_timing_layer.boxed());
             guards.push(Box::new(guard));
         }
+        if format.contains(&Format::Stages) {
+            let summary = StageSummary::new();
+            layers.push(summary.layer().boxed());
+            stage_summary = Some(summary);
+        }
+        if format.contains(&Format::Memory) {
+            let tracker = MemoryTracker::new();
+            layers.push(tracker.layer().boxed());
+            memory_tracker = Some(tracker);
+        }
     }
 
     tracing_subscriber::registry().with(layers).init();
@@ -122,4 +193,10 @@ fn trace(args: TraceArgs) {
             tracing::info!("Bench Complete");
         });
     }
+    if let Some(summary) = stage_summary {
+        println!("{}", summary);
+    }
+    if let Some(tracker) = memory_tracker {
+        println!("{}", tracker);
+    }
 }
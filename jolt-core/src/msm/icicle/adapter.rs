@@ -1,5 +1,6 @@
 use crate::field::JoltField;
 use crate::msm::{GpuBaseType, VariableBaseMSM};
+use crate::utils::parallel::prelude::*;
 use ark_bn254::G1Projective;
 use ark_ec::{CurveGroup, ScalarMul};
 use ark_ff::{BigInteger, Field, PrimeField};
@@ -15,7 +16,6 @@ use icicle_runtime::{
     memory::{DeviceVec, HostSlice},
     stream::IcicleStream,
 };
-use rayon::prelude::*;
 use std::os::raw::c_void;
 
 impl Icicle for G1Projective {
@@ -1,21 +1,23 @@
-use ark_ec::{CurveGroup, ScalarMul};
+use crate::utils::parallel::prelude::*;
+use ark_ec::ScalarMul;
 use ark_ff::{prelude::*, PrimeField};
 use ark_std::cmp::Ordering;
 use ark_std::vec::Vec;
 #[cfg(feature = "icicle")]
 use icicle_core::curve::Affine;
 use num_integer::Integer;
-use rayon::prelude::*;
 use std::borrow::Borrow;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 
 pub(crate) mod icicle;
 use crate::field::JoltField;
 use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
+use crate::utils::pool::ScratchPool;
 pub use icicle::*;
 
-impl<F: JoltField, G: CurveGroup<ScalarField = F> + Icicle> VariableBaseMSM for G {}
+impl VariableBaseMSM for ark_bn254::G1Projective {}
 
 #[cfg(feature = "icicle")]
 pub type GpuBaseType<G: Icicle> = Affine<G::C>;
@@ -27,6 +29,10 @@ use itertools::Either;
 
 /// Copy of ark_ec::VariableBaseMSM with minor modifications to speed up
 /// known small element sized MSMs.
+///
+/// Implemented for BN254's G1. Another implementation of the group, e.g. of a
+/// `JoltPairing` backend, implements it itself, overriding the methods it
+/// computes faster.
 pub trait VariableBaseMSM: ScalarMul + Icicle
 where
     Self::ScalarField: JoltField,
@@ -540,11 +546,7 @@ fn msm_bigint_wnaf<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F
     scalars: &[<F as PrimeField>::BigInt],
     max_num_bits: usize,
 ) -> V {
-    let c = if bases.len() < 32 {
-        3
-    } else {
-        ln_without_floats(bases.len()) + 2
-    };
+    let c = window_size(bases.len());
 
     let num_bits = max_num_bits;
     let digits_count = num_bits.div_ceil(c);
@@ -556,7 +558,7 @@ fn msm_bigint_wnaf<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F
     let window_sums: Vec<_> = (0..digits_count)
         .into_par_iter()
         .map(|i| {
-            let mut buckets = vec![zero; 1 << c];
+            let mut buckets = ScratchPool::global().take(1 << c, zero);
             for (digits, base) in scalar_digits.chunks(digits_count).zip(bases) {
                 // digits is the digits thing of the first scalar?
                 let scalar = digits[i];
@@ -569,8 +571,8 @@ fn msm_bigint_wnaf<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F
 
             let mut running_sum = V::zero();
             let mut res = V::zero();
-            buckets.into_iter().rev().for_each(|b| {
-                running_sum += &b;
+            buckets.iter().rev().for_each(|b| {
+                running_sum += b;
                 res += &running_sum;
             });
             res
@@ -602,11 +604,7 @@ fn msm_bigint<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F>>(
 ) -> V {
     let scalars_and_bases_iter = scalars.iter().zip(bases).filter(|(s, _)| !s.is_zero());
 
-    let c = if bases.len() < 32 {
-        3
-    } else {
-        ln_without_floats(bases.len()) + 2
-    };
+    let c = window_size(bases.len());
 
     let one = V::ScalarField::one().into_bigint();
 
@@ -620,7 +618,7 @@ fn msm_bigint<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F>>(
         .map(|w_start| {
             let mut res = zero;
             // We don't need the "zero" bucket, so we only have 2^c - 1 buckets.
-            let mut buckets = vec![zero; (1 << c) - 1];
+            let mut buckets = ScratchPool::global().take((1 << c) - 1, zero);
             // This clone is cheap, because the iterator contains just a
             // pointer and an index into the original vectors.
             scalars_and_bases_iter.clone().for_each(|(&scalar, base)| {
@@ -663,8 +661,8 @@ fn msm_bigint<F: JoltField + PrimeField, V: VariableBaseMSM<ScalarField = F>>(
             // `running_sum` = sum_{j in i..num_buckets} bucket[j],
             // where we iterate backward from i = num_buckets to 0.
             let mut running_sum = V::zero();
-            buckets.into_iter().rev().for_each(|b| {
-                running_sum += &b;
+            buckets.iter().rev().for_each(|b| {
+                running_sum += b;
                 res += &running_sum;
             });
             res
@@ -747,11 +745,7 @@ where
     V: VariableBaseMSM<ScalarField = F>,
     T: Into<u64> + Zero + Copy + Sync,
 {
-    let c = if bases.len() < 32 {
-        3
-    } else {
-        ln_without_floats(bases.len()) + 2
-    };
+    let c = window_size(bases.len());
 
     let zero = V::zero();
 
@@ -765,7 +759,7 @@ where
         .map(|w_start| {
             let mut res = zero;
             // We don't need the "zero" bucket, so we only have 2^c - 1 buckets.
-            let mut buckets = vec![zero; (1 << c) - 1];
+            let mut buckets = ScratchPool::global().take((1 << c) - 1, zero);
             // This clone is cheap, because the iterator contains just a
             // pointer and an index into the original vectors.
             scalars_and_bases_iter.clone().for_each(|(&scalar, base)| {
@@ -809,8 +803,8 @@ where
             // `running_sum` = sum_{j in i..num_buckets} bucket[j],
             // where we iterate backward from i = num_buckets to 0.
             let mut running_sum = V::zero();
-            buckets.into_iter().rev().for_each(|b| {
-                running_sum += &b;
+            buckets.iter().rev().for_each(|b| {
+                running_sum += b;
                 res += &running_sum;
             });
             res
@@ -856,7 +850,7 @@ where
 {
     let num_buckets: usize = 1 << max_num_bits;
     // Assign things to buckets based on the scalar
-    let mut buckets: Vec<V> = vec![V::zero(); num_buckets];
+    let mut buckets = ScratchPool::global().take(num_buckets, V::zero());
     scalars
         .iter()
         .zip(bases)
@@ -875,6 +869,31 @@ where
     result
 }
 
+/// Added to about `ln` of the number of bases to get the window size of an MSM
+static WINDOW_OFFSET: AtomicUsize = AtomicUsize::new(DEFAULT_WINDOW_OFFSET);
+
+/// The window offset tuned for the reference machine
+pub const DEFAULT_WINDOW_OFFSET: usize = 2;
+
+/// Sets the offset added to about `ln` of the number of bases to get the window
+/// size of MSMs in this process, e.g. to the fastest one on this machine (see
+/// `CalibrationProfile`). Only affects performance, never the results.
+pub fn set_window_offset(offset: usize) {
+    WINDOW_OFFSET.store(offset, AtomicOrdering::Relaxed);
+}
+
+pub fn window_offset() -> usize {
+    WINDOW_OFFSET.load(AtomicOrdering::Relaxed)
+}
+
+fn window_size(num_bases: usize) -> usize {
+    if num_bases < 32 {
+        3
+    } else {
+        ln_without_floats(num_bases) + window_offset()
+    }
+}
+
 /// The result of this function is only approximately `ln(a)`
 /// [`Explanation of usage`]
 ///
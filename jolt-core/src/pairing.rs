@@ -0,0 +1,45 @@
+//! The pairing-friendly curve the KZG-based commitment schemes (KZG, HyperKZG
+//! and Zeromorph) are built on. They are generic over a `JoltPairing` rather
+//! than over arkworks' `Pairing`, and compute with it only through this trait
+//! and the `VariableBaseMSM` implementation of its G1 group. A faster BN254,
+//! e.g. hand-optimized or behind an FFI, is plugged in by implementing the two
+//! for an engine type of its own, without changing the commitment code.
+//!
+//! Group elements remain arkworks' types, so commitments, keys and proofs
+//! serialize the same whichever implementation computed them.
+
+use ark_ec::pairing::Pairing;
+use ark_std::Zero;
+
+use crate::field::JoltField;
+use crate::msm::VariableBaseMSM;
+
+pub trait JoltPairing: Pairing<ScalarField: JoltField, G1: VariableBaseMSM> {
+    /// Whether `prod_i e(g1[i], g2[i])` is the identity of the target group,
+    /// the check the schemes' verifiers end with. Defaults to arkworks'
+    /// multi-pairing; backends typically fuse the Miller loops and the final
+    /// exponentiation of the check.
+    fn pairing_check(g1: &[Self::G1Affine], g2: &[Self::G2Affine]) -> bool {
+        Self::multi_pairing(g1.iter().copied(), g2.iter().copied()).is_zero()
+    }
+}
+
+impl JoltPairing for ark_bn254::Bn254 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn pairing_check() {
+        let (g1, g2) = (G1Affine::generator(), G2Affine::generator());
+        let x = Fr::from(5u64);
+        // e(x * g1, g2) * e(-g1, x * g2) = 1
+        let lhs = [(g1 * x).into_affine(), -g1];
+        let rhs = [g2, (g2 * x).into_affine()];
+        assert!(Bn254::pairing_check(&lhs, &rhs));
+        assert!(!Bn254::pairing_check(&lhs, &[g2, g2]));
+    }
+}
@@ -13,7 +13,7 @@ pub struct Binius128Scheme<ProofTranscript: Transcript> {
     _phantom: PhantomData<ProofTranscript>,
 }
 
-#[derive(Default, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Default, Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct BiniusCommitment {}
 
 impl AppendToTranscript for BiniusCommitment {
@@ -28,7 +28,7 @@ pub struct BiniusProof {}
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct BiniusBatchedProof {}
 
-#[derive(Clone)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct None {}
 
 impl<ProofTranscript: Transcript> CommitmentScheme<ProofTranscript>
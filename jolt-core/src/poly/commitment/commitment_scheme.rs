@@ -9,10 +9,27 @@ use crate::{
     utils::{errors::ProofVerifyError, transcript::AppendToTranscript},
 };
 
+/// An opening proof with everything `CommitmentScheme::verify` checks it against,
+/// for verifying many independent openings at once (see `batch_verify`).
+pub struct OpeningInstance<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    pub proof: PCS::Proof,
+    /// The transcript to verify the proof with
+    pub transcript: ProofTranscript,
+    pub opening_point: Vec<PCS::Field>,
+    pub opening: PCS::Field,
+    pub commitment: PCS::Commitment,
+}
+
 pub trait CommitmentScheme<ProofTranscript: Transcript>: Clone + Sync + Send + 'static {
     type Field: JoltField + Sized;
-    type Setup: Clone + Sync + Send;
+    /// Serializable so that it can be cached on disk, see `SetupCache`
+    type Setup: Clone + Sync + Send + CanonicalSerialize + CanonicalDeserialize;
     type Commitment: Default
+        + Clone
         + Debug
         + Sync
         + Send
@@ -54,5 +71,61 @@ pub trait CommitmentScheme<ProofTranscript: Transcript>: Clone + Sync + Send + '
         commitment: &Self::Commitment,
     ) -> Result<(), ProofVerifyError>;
 
+    /// Verifies independent opening proofs, failing if any of them is invalid.
+    /// Schemes ending in a pairing check can defer it and check all proofs with a
+    /// single multi-pairing; by default each proof is verified on its own.
+    fn batch_verify(
+        setup: &Self::Setup,
+        instances: &[OpeningInstance<Self, ProofTranscript>],
+    ) -> Result<(), ProofVerifyError> {
+        instances.iter().try_for_each(|instance| {
+            Self::verify(
+                &instance.proof,
+                setup,
+                &mut instance.transcript.clone(),
+                &instance.opening_point,
+                &instance.opening,
+                &instance.commitment,
+            )
+        })
+    }
+
+    /// Number of pairings and G1 scalar multiplications performed by `verify` for a
+    /// polynomial in `num_vars` variables. Only used to estimate verification costs,
+    /// see `Jolt::verification_cost`.
+    fn verifier_group_ops(_num_vars: usize) -> (usize, usize) {
+        (0, 0)
+    }
+
     fn protocol_name() -> &'static [u8];
 }
+
+/// A commitment scheme whose opening checks can be folded into a running
+/// accumulator and decided once at the end, rather than verified one by one. Each
+/// accumulated step costs a fraction of `verify`; e.g. for HyperKZG it skips the
+/// pairings. An accumulator is only as sound as its final `decide`.
+pub trait AccumulationScheme<ProofTranscript: Transcript>:
+    CommitmentScheme<ProofTranscript>
+{
+    type Accumulator: Default
+        + Clone
+        + Debug
+        + PartialEq
+        + Sync
+        + Send
+        + CanonicalSerialize
+        + CanonicalDeserialize;
+
+    /// Checks `instance` up to its final step, and folds that step into
+    /// `accumulator`.
+    fn accumulate(
+        setup: &Self::Setup,
+        accumulator: &mut Self::Accumulator,
+        instance: OpeningInstance<Self, ProofTranscript>,
+    ) -> Result<(), ProofVerifyError>;
+
+    /// Succeeds iff every opening folded into `accumulator` is valid, except
+    /// with negligible probability.
+    fn decide(setup: &Self::Setup, accumulator: &Self::Accumulator)
+        -> Result<(), ProofVerifyError>;
+}
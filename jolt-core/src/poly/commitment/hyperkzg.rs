@@ -8,11 +8,14 @@
 //! (2) HyperKZG is specialized to use KZG as the univariate commitment scheme, so it includes several optimizations (both during the transformation of multilinear-to-univariate claims
 //! and within the KZG commitment scheme implementation itself).
 use super::{
-    commitment_scheme::CommitmentScheme,
+    commitment_scheme::{AccumulationScheme, CommitmentScheme, OpeningInstance},
     kzg::{KZGProverKey, KZGVerifierKey, UnivariateKZG},
 };
 use crate::field::JoltField;
+use crate::pairing::JoltPairing;
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
+use crate::utils::canonical_serde::impl_canonical_serde;
+use crate::utils::parallel::prelude::*;
 use crate::utils::transcript::Transcript;
 use crate::{
     msm::{Icicle, VariableBaseMSM},
@@ -24,18 +27,14 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{One, Zero};
 use rand_chacha::ChaCha20Rng;
 use rand_core::{CryptoRng, RngCore, SeedableRng};
-use rayon::iter::{
-    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
-    IntoParallelRefMutIterator, ParallelIterator,
-};
 use std::borrow::Borrow;
 use std::{marker::PhantomData, sync::Arc};
 
-pub struct HyperKZGSRS<P: Pairing>(Arc<SRS<P>>)
+pub struct HyperKZGSRS<P: JoltPairing>(Arc<SRS<P>>)
 where
     P::G1: Icicle;
 
-impl<P: Pairing> HyperKZGSRS<P>
+impl<P: JoltPairing> HyperKZGSRS<P>
 where
     P::G1: Icicle,
 {
@@ -52,41 +51,66 @@ where
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct HyperKZGProverKey<P: Pairing>
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyperKZGProverKey<P: JoltPairing>
 where
     P::G1: Icicle,
 {
     pub kzg_pk: KZGProverKey<P>,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct HyperKZGVerifierKey<P: Pairing> {
+#[derive(Copy, Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyperKZGVerifierKey<P: JoltPairing> {
     pub kzg_vk: KZGVerifierKey<P>,
 }
 
+impl_canonical_serde!([P: JoltPairing] HyperKZGVerifierKey<P>);
+
 #[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct HyperKZGCommitment<P: Pairing>(pub P::G1Affine);
+pub struct HyperKZGCommitment<P: JoltPairing>(pub P::G1Affine);
+
+impl_canonical_serde!([P: JoltPairing] HyperKZGCommitment<P>);
 
-impl<P: Pairing> Default for HyperKZGCommitment<P> {
+impl<P: JoltPairing> Default for HyperKZGCommitment<P> {
     fn default() -> Self {
         Self(P::G1Affine::zero())
     }
 }
 
-impl<P: Pairing> AppendToTranscript for HyperKZGCommitment<P> {
+/// Accumulator of HyperKZG opening checks (see `AccumulationScheme`). Every proof
+/// reduces to a pairing equation e(L_i, H) == e(R_i, tau_H), see
+/// `HyperKZG::verify_deferred`; the accumulator holds a random linear combination
+/// (L, R) of those points, which satisfies the same equation iff all of them do.
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct HyperKZGAccumulator<P: JoltPairing> {
+    pub L: P::G1Affine,
+    pub R: P::G1Affine,
+}
+
+impl<P: JoltPairing> Default for HyperKZGAccumulator<P> {
+    fn default() -> Self {
+        Self {
+            L: P::G1Affine::zero(),
+            R: P::G1Affine::zero(),
+        }
+    }
+}
+
+impl<P: JoltPairing> AppendToTranscript for HyperKZGCommitment<P> {
     fn append_to_transcript<ProofTranscript: Transcript>(&self, transcript: &mut ProofTranscript) {
         transcript.append_point(&self.0.into_group());
     }
 }
 
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug)]
-pub struct HyperKZGProof<P: Pairing> {
+pub struct HyperKZGProof<P: JoltPairing> {
     pub com: Vec<P::G1Affine>,
     pub w: Vec<P::G1Affine>,
     pub v: Vec<Vec<P::ScalarField>>,
 }
 
+impl_canonical_serde!([P: JoltPairing] HyperKZGProof<P>);
+
 // On input f(x) and u compute the witness polynomial used to prove
 // that f(u) = v. The main part of this is to compute the
 // division (f(x) - f(u)) / (x - u), but we don't use a general
@@ -100,7 +124,7 @@ pub struct HyperKZGProof<P: Pairing> {
 // the quotient of f(x)/(x-u) and (f(x) - f(v))/(x-u) is the
 // same.  One advantage is that computing f(u) could be decoupled
 // from kzg_open, it could be done later or separate from computing W.
-fn kzg_batch_open_no_rem<P: Pairing>(
+fn kzg_batch_open_no_rem<P: JoltPairing>(
     f: &MultilinearPolynomial<P::ScalarField>,
     u: &[P::ScalarField],
     pk: &HyperKZGProverKey<P>,
@@ -121,7 +145,7 @@ where
     UnivariateKZG::commit_batch(&pk.kzg_pk, &h).unwrap()
 }
 
-fn compute_witness_polynomial<P: Pairing>(
+fn compute_witness_polynomial<P: JoltPairing>(
     f: &[P::ScalarField],
     u: P::ScalarField,
 ) -> Vec<P::ScalarField>
@@ -139,7 +163,7 @@ where
     h
 }
 
-fn kzg_open_batch<P: Pairing, ProofTranscript: Transcript>(
+fn kzg_open_batch<P: JoltPairing, ProofTranscript: Transcript>(
     f: &[MultilinearPolynomial<P::ScalarField>],
     u: &[P::ScalarField],
     pk: &HyperKZGProverKey<P>,
@@ -180,15 +204,17 @@ where
     (w, v)
 }
 
+/// Batched check of the KZG openings `v` of `C` at the points `u`: returns the
+/// points (L, R) such that the openings are valid iff e(L, H) == e(R, tau_H)
 // vk is hashed in transcript already, so we do not add it here
-fn kzg_verify_batch<P: Pairing, ProofTranscript: Transcript>(
+fn kzg_pairing_inputs<P: JoltPairing, ProofTranscript: Transcript>(
     vk: &HyperKZGVerifierKey<P>,
     C: &[P::G1Affine],
     W: &[P::G1Affine],
     u: &[P::ScalarField],
     v: &[Vec<P::ScalarField>],
     transcript: &mut ProofTranscript,
-) -> bool
+) -> (P::G1, P::G1)
 where
     <P as Pairing>::ScalarField: JoltField,
     <P as Pairing>::G1: Icicle,
@@ -263,16 +289,15 @@ where
 
     let R = W[0] + W[1] * d_0 + W[2] * d_1;
 
-    // Check that e(L, vk.H) == e(R, vk.tau_H)
-    P::multi_pairing([L, -R], [vk.kzg_vk.g2, vk.kzg_vk.beta_g2]).is_zero()
+    (L, R)
 }
 
 #[derive(Clone)]
-pub struct HyperKZG<P: Pairing, ProofTranscript: Transcript> {
+pub struct HyperKZG<P: JoltPairing, ProofTranscript: Transcript> {
     _phantom: PhantomData<(P, ProofTranscript)>,
 }
 
-impl<P: Pairing, ProofTranscript: Transcript> HyperKZG<P, ProofTranscript>
+impl<P: JoltPairing, ProofTranscript: Transcript> HyperKZG<P, ProofTranscript>
 where
     <P as Pairing>::ScalarField: JoltField,
     <P as Pairing>::G1: Icicle,
@@ -353,6 +378,28 @@ where
         pi: &HyperKZGProof<P>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
+        let (L, R) = Self::verify_deferred(vk, C, point, P_of_x, pi, transcript)?;
+        // Check that e(L, vk.H) == e(R, vk.tau_H)
+        if !P::pairing_check(
+            &[L.into_affine(), (-R).into_affine()],
+            &[vk.kzg_vk.g2, vk.kzg_vk.beta_g2],
+        ) {
+            return Err(ProofVerifyError::InternalError);
+        }
+        Ok(())
+    }
+
+    /// `verify` without the final pairing check: returns the points (L, R) for
+    /// which the proof is valid iff e(L, H) == e(R, tau_H), so that the checks of
+    /// several proofs can be combined (see `CommitmentScheme::batch_verify`).
+    pub fn verify_deferred(
+        vk: &HyperKZGVerifierKey<P>,
+        C: &HyperKZGCommitment<P>,
+        point: &[P::ScalarField],
+        P_of_x: &P::ScalarField,
+        pi: &HyperKZGProof<P>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(P::G1, P::G1), ProofVerifyError> {
         let y = P_of_x;
 
         let ell = point.len();
@@ -397,16 +444,12 @@ where
             // check below requires it
         }
 
-        // Check commitments to (Y, ypos, yneg) are valid
-        if !kzg_verify_batch(vk, &com, &pi.w, &u, &pi.v, transcript) {
-            return Err(ProofVerifyError::InternalError);
-        }
-
-        Ok(())
+        // The pairing check that commitments to (Y, ypos, yneg) are valid
+        Ok(kzg_pairing_inputs(vk, &com, &pi.w, &u, &pi.v, transcript))
     }
 }
 
-impl<P: Pairing, ProofTranscript: Transcript> CommitmentScheme<ProofTranscript>
+impl<P: JoltPairing, ProofTranscript: Transcript> CommitmentScheme<ProofTranscript>
     for HyperKZG<P, ProofTranscript>
 where
     <P as Pairing>::ScalarField: JoltField,
@@ -491,11 +534,109 @@ where
         )
     }
 
+    fn batch_verify(
+        setup: &Self::Setup,
+        instances: &[OpeningInstance<Self, ProofTranscript>],
+    ) -> Result<(), ProofVerifyError> {
+        let vk = &setup.1;
+        let pairing_inputs = instances
+            .par_iter()
+            .map(|instance| {
+                HyperKZG::<P, ProofTranscript>::verify_deferred(
+                    vk,
+                    &instance.commitment,
+                    &instance.opening_point,
+                    &instance.opening,
+                    &instance.proof,
+                    &mut instance.transcript.clone(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Each proof is valid iff e(L_i, H) == e(R_i, tau_H). For a random linear
+        // combination, all of them hold (except with negligible probability) iff
+        // e(sum_i c_i L_i, H) == e(sum_i c_i R_i, tau_H).
+        let mut transcript = ProofTranscript::new(b"HyperKZG batch verification");
+        for (L, R) in pairing_inputs.iter() {
+            transcript.append_point(L);
+            transcript.append_point(R);
+        }
+        let coeffs: Vec<P::ScalarField> = transcript.challenge_scalar_powers(instances.len());
+        let (L, R) = pairing_inputs
+            .into_par_iter()
+            .zip(coeffs.into_par_iter())
+            .map(|((L, R), coeff)| (L * coeff, R * coeff))
+            .reduce(
+                || (P::G1::zero(), P::G1::zero()),
+                |(L_0, R_0), (L_1, R_1)| (L_0 + L_1, R_0 + R_1),
+            );
+        if !P::pairing_check(
+            &[L.into_affine(), (-R).into_affine()],
+            &[vk.kzg_vk.g2, vk.kzg_vk.beta_g2],
+        ) {
+            return Err(ProofVerifyError::InternalError);
+        }
+        Ok(())
+    }
+
+    fn verifier_group_ops(num_vars: usize) -> (usize, usize) {
+        // One multi-pairing of two pairs, and an MSM over the commitments to the
+        // folded polynomials alongside the seven scalar multiplications of
+        // `kzg_pairing_inputs`
+        (2, num_vars + 7)
+    }
+
     fn protocol_name() -> &'static [u8] {
         b"hyperkzg"
     }
 }
 
+impl<P: JoltPairing, ProofTranscript: Transcript> AccumulationScheme<ProofTranscript>
+    for HyperKZG<P, ProofTranscript>
+where
+    <P as Pairing>::ScalarField: JoltField,
+    <P as Pairing>::G1: Icicle,
+{
+    type Accumulator = HyperKZGAccumulator<P>;
+
+    fn accumulate(
+        setup: &Self::Setup,
+        accumulator: &mut Self::Accumulator,
+        mut instance: OpeningInstance<Self, ProofTranscript>,
+    ) -> Result<(), ProofVerifyError> {
+        let (L, R) = HyperKZG::<P, ProofTranscript>::verify_deferred(
+            &setup.1,
+            &instance.commitment,
+            &instance.opening_point,
+            &instance.opening,
+            &instance.proof,
+            &mut instance.transcript,
+        )?;
+
+        // The folding coefficient depends on both the accumulator and the new check
+        let mut transcript = ProofTranscript::new(b"HyperKZG accumulation");
+        transcript.append_point(&accumulator.L.into_group());
+        transcript.append_point(&accumulator.R.into_group());
+        transcript.append_point(&L);
+        transcript.append_point(&R);
+        let coeff: P::ScalarField = transcript.challenge_scalar();
+        accumulator.L = (L * coeff + accumulator.L).into_affine();
+        accumulator.R = (R * coeff + accumulator.R).into_affine();
+        Ok(())
+    }
+
+    fn decide(
+        setup: &Self::Setup,
+        accumulator: &Self::Accumulator,
+    ) -> Result<(), ProofVerifyError> {
+        let vk = &setup.1.kzg_vk;
+        if !P::pairing_check(&[accumulator.L, -accumulator.R], &[vk.g2, vk.beta_g2]) {
+            return Err(ProofVerifyError::InternalError);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -611,6 +752,45 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_hyperkzg_accumulation() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+        let srs = HyperKZGSRS::setup(&mut rng, 16);
+        let setup: (HyperKZGProverKey<Bn254>, HyperKZGVerifierKey<Bn254>) = srs.trim(16);
+
+        let mut accumulator = HyperKZGAccumulator::default();
+        for _ in 0..3 {
+            let poly = MultilinearPolynomial::from(
+                (0..16).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>(),
+            );
+            let point: Vec<Fr> = (0..4).map(|_| Fr::rand(&mut rng)).collect();
+            let opening = poly.evaluate(&point);
+            let commitment = <PCS as CommitmentScheme<KeccakTranscript>>::commit(&poly, &setup);
+            let mut transcript = KeccakTranscript::new(b"TestEval");
+            let proof = <PCS as CommitmentScheme<KeccakTranscript>>::prove(
+                &setup,
+                &poly,
+                &point,
+                &mut transcript,
+            );
+            let instance = OpeningInstance {
+                proof,
+                transcript: KeccakTranscript::new(b"TestEval"),
+                opening_point: point,
+                opening,
+                commitment,
+            };
+            PCS::accumulate(&setup, &mut accumulator, instance).unwrap();
+        }
+        assert!(PCS::decide(&setup, &accumulator).is_ok());
+
+        let mut bad_accumulator = accumulator.clone();
+        bad_accumulator.R = accumulator.L;
+        assert!(PCS::decide(&setup, &bad_accumulator).is_err());
+    }
+
     #[test]
     fn test_hyperkzg_large() {
         // test the hyperkzg prover and verifier with random instances (derived from a seed)
@@ -13,15 +13,15 @@ use crate::poly::eq_poly::EqPolynomial;
 use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
+use crate::utils::parallel::{self, prelude::*};
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use crate::utils::{compute_dotproduct, mul_0_1_optimized};
 use ark_ec::CurveGroup;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use num_integer::Roots;
-use rayon::prelude::*;
 use tracing::trace_span;
 
-use crate::msm::{Icicle, VariableBaseMSM};
+use crate::msm::VariableBaseMSM;
 
 /// Hyrax commits to a multilinear polynomial by interpreting its coefficients as a
 /// matrix. Given the number of variables in the polynomial, and the desired "aspect
@@ -56,7 +56,7 @@ pub struct HyraxCommitment<const RATIO: usize, G: CurveGroup> {
     pub row_commitments: Vec<G>,
 }
 
-impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + Icicle>
+impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + VariableBaseMSM>
     HyraxCommitment<RATIO, G>
 {
     #[tracing::instrument(skip_all, name = "HyraxCommitment::commit")]
@@ -124,7 +124,7 @@ pub struct HyraxOpeningProof<const RATIO: usize, G: CurveGroup> {
 }
 
 /// See Section 14.3 of Thaler's Proofs, Arguments, and Zero-Knowledge
-impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + Icicle>
+impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + VariableBaseMSM>
     HyraxOpeningProof<RATIO, G>
 {
     #[tracing::instrument(skip_all, name = "HyraxOpeningProof::prove")]
@@ -220,7 +220,7 @@ pub struct BatchedHyraxOpeningProof<const RATIO: usize, G: CurveGroup> {
 }
 
 /// See Section 16.1 of Thaler's Proofs, Arguments, and Zero-Knowledge
-impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + Icicle>
+impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + VariableBaseMSM>
     BatchedHyraxOpeningProof<RATIO, G>
 {
     #[tracing::instrument(skip_all, name = "BatchedHyraxOpeningProof::prove")]
@@ -240,7 +240,7 @@ impl<const RATIO: usize, F: JoltField, G: CurveGroup<ScalarField = F> + Icicle>
 
         let poly_len = polynomials[0].len();
 
-        let num_chunks = rayon::current_num_threads().next_power_of_two();
+        let num_chunks = parallel::current_num_threads().next_power_of_two();
         let chunk_size = poly_len / num_chunks;
 
         let rlc_poly = if chunk_size > 0 {
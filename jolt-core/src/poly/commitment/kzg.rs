@@ -1,20 +1,25 @@
 use crate::field::JoltField;
 use crate::msm::{use_icicle, GpuBaseType, Icicle, VariableBaseMSM};
+use crate::pairing::JoltPairing;
 use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::poly::unipoly::UniPoly;
+use crate::utils::canonical_serde::impl_canonical_serde;
 use crate::utils::errors::ProofVerifyError;
+use crate::utils::parallel::{self, prelude::*};
 use ark_ec::scalar_mul::fixed_base::FixedBase;
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_ff::PrimeField;
-use ark_std::{One, UniformRand, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
+use ark_std::{One, UniformRand};
 use rand_core::{CryptoRng, RngCore};
-use rayon::prelude::*;
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
 #[derive(Clone, Debug)]
-pub struct SRS<P: Pairing>
+pub struct SRS<P: JoltPairing>
 where
     P::G1: Icicle,
 {
@@ -25,7 +30,7 @@ where
     pub gpu_g1: Option<Vec<GpuBaseType<P::G1>>>,
 }
 
-impl<P: Pairing> SRS<P>
+impl<P: JoltPairing> SRS<P>
 where
     P::G1: Icicle,
 {
@@ -48,7 +53,7 @@ where
         let g1_table = FixedBase::get_window_table(scalar_bits, g1_window_size, g1);
         let g2_table = FixedBase::get_window_table(scalar_bits, g2_window_size, g2);
 
-        let (g1_powers_projective, g2_powers_projective) = rayon::join(
+        let (g1_powers_projective, g2_powers_projective) = parallel::join(
             || {
                 let beta_powers: Vec<P::ScalarField> = (0..=num_g1_powers)
                     .scan(beta, |acc, _| {
@@ -71,7 +76,7 @@ where
             },
         );
 
-        let (g1_powers, g2_powers) = rayon::join(
+        let (g1_powers, g2_powers) = parallel::join(
             || P::G1::normalize_batch(&g1_powers_projective),
             || P::G2::normalize_batch(&g2_powers_projective),
         );
@@ -125,8 +130,74 @@ where
     }
 }
 
+// The GPU copy of the powers is not serialized, but recomputed on deserialization
+impl<P: JoltPairing> CanonicalSerialize for SRS<P>
+where
+    P::G1: Icicle,
+{
+    fn serialize_with_mode<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.g1_powers.serialize_with_mode(&mut writer, compress)?;
+        self.g2_powers.serialize_with_mode(&mut writer, compress)?;
+        self.g_products.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.g1_powers.serialized_size(compress)
+            + self.g2_powers.serialized_size(compress)
+            + self.g_products.serialized_size(compress)
+    }
+}
+
+impl<P: JoltPairing> Valid for SRS<P>
+where
+    P::G1: Icicle,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g1_powers.check()?;
+        self.g2_powers.check()?;
+        self.g_products.check()
+    }
+}
+
+impl<P: JoltPairing> CanonicalDeserialize for SRS<P>
+where
+    P::G1: Icicle,
+{
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let g1_powers: Vec<P::G1Affine> =
+            Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g2_powers = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g_products = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        #[cfg(feature = "icicle")]
+        let gpu_g1 = Some(
+            g1_powers
+                .par_iter()
+                .map(<P::G1 as Icicle>::from_ark_affine)
+                .collect::<Vec<_>>(),
+        );
+        #[cfg(not(feature = "icicle"))]
+        let gpu_g1 = None;
+
+        Ok(Self {
+            g1_powers,
+            g2_powers,
+            g_products,
+            gpu_g1,
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
-pub struct KZGProverKey<P: Pairing>
+pub struct KZGProverKey<P: JoltPairing>
 where
     P::G1: Icicle,
 {
@@ -137,7 +208,7 @@ where
     supported_size: usize,
 }
 
-impl<P: Pairing> KZGProverKey<P>
+impl<P: JoltPairing> KZGProverKey<P>
 where
     P::G1: Icicle,
 {
@@ -156,6 +227,18 @@ where
         }
     }
 
+    pub fn srs(&self) -> &Arc<SRS<P>> {
+        &self.srs
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn supported_size(&self) -> usize {
+        self.supported_size
+    }
+
     pub fn g1_powers(&self) -> &[P::G1Affine] {
         &self.srs.g1_powers[self.offset..self.offset + self.supported_size]
     }
@@ -168,19 +251,74 @@ where
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub struct KZGVerifierKey<P: Pairing> {
+impl<P: JoltPairing> CanonicalSerialize for KZGProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn serialize_with_mode<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.srs.serialize_with_mode(&mut writer, compress)?;
+        self.offset.serialize_with_mode(&mut writer, compress)?;
+        self.supported_size
+            .serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.srs.serialized_size(compress)
+            + self.offset.serialized_size(compress)
+            + self.supported_size.serialized_size(compress)
+    }
+}
+
+impl<P: JoltPairing> Valid for KZGProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl<P: JoltPairing> CanonicalDeserialize for KZGProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let srs = SRS::deserialize_with_mode(&mut reader, compress, validate)?;
+        let offset = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let supported_size = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        if offset
+            .checked_add(supported_size)
+            .map_or(true, |end| end > srs.g1_powers.len())
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(Self::new(Arc::new(srs), offset, supported_size))
+    }
+}
+
+#[derive(Clone, Copy, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct KZGVerifierKey<P: JoltPairing> {
     pub g1: P::G1Affine,
     pub g2: P::G2Affine,
     pub beta_g2: P::G2Affine,
 }
 
+impl_canonical_serde!([P: JoltPairing] KZGVerifierKey<P>);
+
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
-pub struct UnivariateKZG<P: Pairing> {
+pub struct UnivariateKZG<P: JoltPairing> {
     _phantom: PhantomData<P>,
 }
 
-impl<P: Pairing> UnivariateKZG<P>
+impl<P: JoltPairing> UnivariateKZG<P>
 where
     P::ScalarField: JoltField,
     P::G1: Icicle,
@@ -358,14 +496,16 @@ where
         proof: &P::G1Affine,
         evaluation: &P::ScalarField,
     ) -> Result<bool, ProofVerifyError> {
-        Ok(P::multi_pairing(
-            [
-                commitment.into_group() - vk.g1.into_group() * evaluation,
-                -proof.into_group(),
+        Ok(P::pairing_check(
+            &[
+                (commitment.into_group() - vk.g1.into_group() * evaluation).into_affine(),
+                -*proof,
+            ],
+            &[
+                vk.g2,
+                (vk.beta_g2.into_group() - (vk.g2 * point)).into_affine(),
             ],
-            [vk.g2, (vk.beta_g2.into_group() - (vk.g2 * point)).into()],
-        )
-        .is_zero())
+        ))
     }
 }
 
@@ -19,7 +19,7 @@ pub struct MockCommitScheme<F: JoltField, ProofTranscript: Transcript> {
     _marker: PhantomData<(F, ProofTranscript)>,
 }
 
-#[derive(CanonicalSerialize, CanonicalDeserialize, Default, Debug, PartialEq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Default, Debug, PartialEq)]
 pub struct MockCommitment<F: JoltField> {
     poly: MultilinearPolynomial<F>,
 }
@@ -4,7 +4,8 @@ pub mod hyperkzg;
 pub mod hyrax;
 pub mod kzg;
 pub mod pedersen;
+pub mod setup_cache;
 pub mod zeromorph;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "test-utils"))]
 pub mod mock;
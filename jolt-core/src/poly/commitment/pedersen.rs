@@ -7,7 +7,7 @@ use sha3::Shake256;
 use std::io::Read;
 
 use crate::field::JoltField;
-use crate::msm::{Icicle, VariableBaseMSM};
+use crate::msm::VariableBaseMSM;
 
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct PedersenGenerators<G: CurveGroup> {
@@ -55,7 +55,7 @@ pub trait PedersenCommitment<G: CurveGroup>: Sized {
     fn commit_vector(inputs: &[Self], bases: &[G::Affine]) -> G;
 }
 
-impl<G: CurveGroup + Icicle> PedersenCommitment<G> for G::ScalarField
+impl<G: CurveGroup + VariableBaseMSM> PedersenCommitment<G> for G::ScalarField
 where
     G::ScalarField: JoltField,
 {
@@ -0,0 +1,199 @@
+//! Cache of PCS setups on disk, so that processes needing the same setup, e.g.
+//! a prover run again the next day, load it rather than recompute it, which
+//! takes minutes for large traces.
+//!
+//! Each setup is a file named after its scheme and size, e.g.
+//! `hyperkzg-1a2b3c4d-2^24.setup`, where the hex digits identify the setup's
+//! type (and so its curve). A file holds a SHA3-256 hash of its contents
+//! followed by the uncompressed setup; files failing the hash check are
+//! regenerated. Files are loaded through memory maps (see `utils::mmap`). Since
+//! the points are not validated on load (`Validate::No`, which skips their curve
+//! and subgroup checks), the cache must be trusted like the binary itself: a
+//! verifier loading a tampered setup may accept invalid proofs.
+//! Caches shared with others should be opened with `SetupCache::validated`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::hex;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use sha3::{Digest, Sha3_256};
+
+use super::commitment_scheme::CommitmentScheme;
+use crate::utils::math::Math;
+use crate::utils::mmap::map_file;
+use crate::utils::transcript::Transcript;
+
+/// Environment variable setting the directory setups are cached in by default,
+/// see `SetupCache::from_env`
+pub const SETUP_CACHE_ENV: &str = "JOLT_SETUP_CACHE";
+
+/// A directory of cached PCS setups, see the module documentation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetupCache {
+    dir: PathBuf,
+    validate: bool,
+}
+
+impl SetupCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            validate: false,
+        }
+    }
+
+    /// This cache, validating the points of the setups it loads. Loading takes
+    /// several times longer, but a tampered setup is then no worse than a
+    /// missing one.
+    pub fn validated(self) -> Self {
+        Self {
+            validate: true,
+            ..self
+        }
+    }
+
+    /// The cache in the directory set by `JOLT_SETUP_CACHE`, if it is set. Used
+    /// by `Jolt::preprocess` and the default `ProverConfig`.
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(SETUP_CACHE_ENV).map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file the setup of `PCS` for polynomials of up to `max_poly_len`
+    /// coefficients is cached in
+    pub fn path<PCS, ProofTranscript>(&self, max_poly_len: usize) -> PathBuf
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        let setup_type = Sha3_256::digest(std::any::type_name::<PCS::Setup>().as_bytes());
+        self.dir.join(format!(
+            "{}-{}-2^{}.setup",
+            String::from_utf8_lossy(PCS::protocol_name()),
+            hex::encode(&setup_type[..4]),
+            max_poly_len.next_power_of_two().log_2()
+        ))
+    }
+
+    /// `PCS::setup(max_poly_len)`, loaded from the cache if it is there, and
+    /// otherwise computed and cached. Failing to write the cache only loses the
+    /// time saved next time, so it is logged rather than returned.
+    #[tracing::instrument(skip_all, name = "SetupCache::setup")]
+    pub fn setup<PCS, ProofTranscript>(&self, max_poly_len: usize) -> PCS::Setup
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        if let Some(setup) = self.load::<PCS, ProofTranscript>(max_poly_len) {
+            return setup;
+        }
+        let setup = PCS::setup(max_poly_len);
+        if let Err(err) = self.store::<PCS, ProofTranscript>(max_poly_len, &setup) {
+            tracing::warn!("failed to cache the PCS setup in {:?}: {}", self.dir, err);
+        }
+        setup
+    }
+
+    /// The cached setup, or `None` if it is missing or fails its integrity check
+    pub fn load<PCS, ProofTranscript>(&self, max_poly_len: usize) -> Option<PCS::Setup>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        let path = self.path::<PCS, ProofTranscript>(max_poly_len);
+        let contents = map_file(&path).ok()?;
+        if contents.len() < 32 || Sha3_256::digest(&contents[32..])[..] != contents[..32] {
+            tracing::warn!("ignoring corrupted PCS setup {:?}", path);
+            return None;
+        }
+        let validate = if self.validate {
+            Validate::Yes
+        } else {
+            Validate::No
+        };
+        let setup = PCS::Setup::deserialize_with_mode(&contents[32..], Compress::No, validate).ok();
+        if setup.is_none() {
+            tracing::warn!("ignoring invalid PCS setup {:?}", path);
+        }
+        setup
+    }
+
+    /// Caches `setup` as the setup for `max_poly_len`. Writes to a temporary
+    /// file first, so that concurrent processes never load a partial setup.
+    pub fn store<PCS, ProofTranscript>(
+        &self,
+        max_poly_len: usize,
+        setup: &PCS::Setup,
+    ) -> io::Result<()>
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        let mut contents = vec![0; 32];
+        setup
+            .serialize_uncompressed(&mut contents)
+            .map_err(io::Error::other)?;
+        let hash = Sha3_256::digest(&contents[32..]);
+        contents[..32].copy_from_slice(&hash);
+
+        let path = self.path::<PCS, ProofTranscript>(max_poly_len);
+        fs::create_dir_all(&self.dir)?;
+        let temp = path.with_extension(format!("tmp-{}", std::process::id()));
+        fs::write(&temp, contents)?;
+        fs::rename(temp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::commitment::hyperkzg::HyperKZG;
+    use crate::utils::transcript::KeccakTranscript;
+    use ark_bn254::Bn254;
+
+    type PCS = HyperKZG<Bn254, KeccakTranscript>;
+
+    fn serialized(setup: &<PCS as CommitmentScheme<KeccakTranscript>>::Setup) -> Vec<u8> {
+        let mut bytes = vec![];
+        setup.serialize_uncompressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn setup_cache() {
+        let dir = std::env::temp_dir().join(format!("jolt-setup-cache-{}", std::process::id()));
+        let cache = SetupCache::new(&dir);
+        assert!(cache.load::<PCS, KeccakTranscript>(1 << 4).is_none());
+
+        let setup = cache.setup::<PCS, KeccakTranscript>(1 << 4);
+        let path = cache.path::<PCS, KeccakTranscript>(1 << 4);
+        assert!(path.exists());
+        let loaded = cache.load::<PCS, KeccakTranscript>(1 << 4).unwrap();
+        assert_eq!(serialized(&loaded), serialized(&setup));
+        // Setups of other sizes are cached separately
+        assert!(cache.load::<PCS, KeccakTranscript>(1 << 5).is_none());
+
+        // Corrupted setups are ignored, and regenerated
+        let mut contents = fs::read(&path).unwrap();
+        *contents.last_mut().unwrap() ^= 1;
+        fs::write(&path, contents).unwrap();
+        assert!(cache.load::<PCS, KeccakTranscript>(1 << 4).is_none());
+        let regenerated = cache.setup::<PCS, KeccakTranscript>(1 << 4);
+        assert_eq!(serialized(&regenerated), serialized(&setup));
+        assert!(cache.load::<PCS, KeccakTranscript>(1 << 4).is_some());
+
+        // Valid setups pass validation
+        let validated = cache
+            .clone()
+            .validated()
+            .load::<PCS, KeccakTranscript>(1 << 4);
+        assert_eq!(serialized(&validated.unwrap()), serialized(&setup));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
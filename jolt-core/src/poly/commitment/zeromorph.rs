@@ -2,6 +2,7 @@
 #![allow(clippy::type_complexity)]
 
 use crate::msm::{use_icicle, Icicle, VariableBaseMSM};
+use crate::pairing::JoltPairing;
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
 use crate::poly::{dense_mlpoly::DensePolynomial, unipoly::UniPoly};
 use crate::utils::{
@@ -10,7 +11,9 @@ use crate::utils::{
 };
 use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
 use ark_ff::batch_inversion;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
+};
 use ark_std::{One, Zero};
 use itertools::izip;
 use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
@@ -24,13 +27,14 @@ use super::{
     kzg::{KZGProverKey, KZGVerifierKey, UnivariateKZG, SRS},
 };
 use crate::field::JoltField;
-use rayon::prelude::*;
+use crate::utils::canonical_serde::impl_canonical_serde;
+use crate::utils::parallel::prelude::*;
 
-pub struct ZeromorphSRS<P: Pairing>(Arc<SRS<P>>)
+pub struct ZeromorphSRS<P: JoltPairing>(Arc<SRS<P>>)
 where
     P::G1: Icicle;
 
-impl<P: Pairing> ZeromorphSRS<P>
+impl<P: JoltPairing> ZeromorphSRS<P>
 where
     P::G1: Icicle,
 {
@@ -58,7 +62,7 @@ where
 
 //TODO: adapt interface to have prover and verifier key
 #[derive(Clone, Debug)]
-pub struct ZeromorphProverKey<P: Pairing>
+pub struct ZeromorphProverKey<P: JoltPairing>
 where
     P::G1: Icicle,
 {
@@ -66,35 +70,103 @@ where
     pub open_pp: KZGProverKey<P>,
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct ZeromorphVerifierKey<P: Pairing> {
+// Serialized by hand so that the SRS shared by both keys is only written once
+impl<P: JoltPairing> CanonicalSerialize for ZeromorphProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn serialize_with_mode<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.commit_pp
+            .srs()
+            .serialize_with_mode(&mut writer, compress)?;
+        for pp in [&self.commit_pp, &self.open_pp] {
+            pp.offset().serialize_with_mode(&mut writer, compress)?;
+            pp.supported_size()
+                .serialize_with_mode(&mut writer, compress)?;
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.commit_pp.srs().serialized_size(compress) + 4 * 0usize.serialized_size(compress)
+    }
+}
+
+impl<P: JoltPairing> Valid for ZeromorphProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl<P: JoltPairing> CanonicalDeserialize for ZeromorphProverKey<P>
+where
+    P::G1: Icicle,
+{
+    fn deserialize_with_mode<R: std::io::Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let srs = Arc::new(SRS::deserialize_with_mode(&mut reader, compress, validate)?);
+        let mut pp = || -> Result<KZGProverKey<P>, SerializationError> {
+            let offset = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+            let supported_size = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+            if offset
+                .checked_add(supported_size)
+                .map_or(true, |end| end > srs.g1_powers.len())
+            {
+                return Err(SerializationError::InvalidData);
+            }
+            Ok(KZGProverKey::new(srs.clone(), offset, supported_size))
+        };
+        let commit_pp = pp()?;
+        let open_pp = pp()?;
+        Ok(Self { commit_pp, open_pp })
+    }
+}
+
+#[derive(Copy, Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZeromorphVerifierKey<P: JoltPairing> {
     pub kzg_vk: KZGVerifierKey<P>,
     pub tau_N_max_sub_2_N: P::G2Affine,
 }
 
-#[derive(Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct ZeromorphCommitment<P: Pairing>(P::G1Affine);
+impl_canonical_serde!([P: JoltPairing] ZeromorphVerifierKey<P>);
+
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ZeromorphCommitment<P: JoltPairing>(P::G1Affine);
 
-impl<P: Pairing> Default for ZeromorphCommitment<P> {
+impl_canonical_serde!([P: JoltPairing] ZeromorphCommitment<P>);
+
+impl<P: JoltPairing> Default for ZeromorphCommitment<P> {
     fn default() -> Self {
         Self(P::G1Affine::zero())
     }
 }
 
-impl<P: Pairing> AppendToTranscript for ZeromorphCommitment<P> {
+impl<P: JoltPairing> AppendToTranscript for ZeromorphCommitment<P> {
     fn append_to_transcript<ProofTranscript: Transcript>(&self, transcript: &mut ProofTranscript) {
         transcript.append_point(&self.0.into_group());
     }
 }
 
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug)]
-pub struct ZeromorphProof<P: Pairing> {
+pub struct ZeromorphProof<P: JoltPairing> {
     pub pi: P::G1Affine,
     pub q_hat_com: P::G1Affine,
     pub q_k_com: Vec<P::G1Affine>,
 }
 
-fn compute_multilinear_quotients<P: Pairing>(
+impl_canonical_serde!([P: JoltPairing] ZeromorphProof<P>);
+
+fn compute_multilinear_quotients<P: JoltPairing>(
     poly: &DensePolynomial<P::ScalarField>,
     point: &[P::ScalarField],
 ) -> (Vec<UniPoly<P::ScalarField>>, P::ScalarField)
@@ -137,7 +209,7 @@ where
 }
 
 // Compute the batched, lifted-degree quotient `\hat{q}`
-fn compute_batched_lifted_degree_quotient<P: Pairing>(
+fn compute_batched_lifted_degree_quotient<P: JoltPairing>(
     quotients: &[UniPoly<P::ScalarField>],
     y_challenge: &P::ScalarField,
 ) -> (UniPoly<P::ScalarField>, usize)
@@ -166,7 +238,7 @@ where
     (UniPoly::from_coeff(q_hat), 1 << (num_vars - 1))
 }
 
-fn eval_and_quotient_scalars<P: Pairing>(
+fn eval_and_quotient_scalars<P: JoltPairing>(
     y_challenge: P::ScalarField,
     x_challenge: P::ScalarField,
     z_challenge: P::ScalarField,
@@ -231,7 +303,7 @@ where
 }
 
 #[derive(Clone)]
-pub struct Zeromorph<P: Pairing, ProofTranscript: Transcript> {
+pub struct Zeromorph<P: JoltPairing, ProofTranscript: Transcript> {
     _phantom: PhantomData<(P, ProofTranscript)>,
 }
 
@@ -239,7 +311,7 @@ impl<P, ProofTranscript> Zeromorph<P, ProofTranscript>
 where
     <P as Pairing>::ScalarField: JoltField,
     <P as Pairing>::G1: Icicle,
-    P: Pairing,
+    P: JoltPairing,
     ProofTranscript: Transcript,
 {
     pub fn protocol_name() -> &'static [u8] {
@@ -400,14 +472,13 @@ where
         .into_affine();
 
         // e(pi, [tau]_2 - x * [1]_2) == e(C_{\zeta,Z}, -[X^(N_max - 2^n - 1)]_2) <==> e(C_{\zeta,Z} - x * pi, [X^{N_max - 2^n - 1}]_2) * e(-pi, [tau_2]) == 1
-        let pairing = P::multi_pairing(
-            [zeta_z_com, proof.pi],
-            [
-                (-vk.tau_N_max_sub_2_N.into_group()).into_affine(),
-                (vk.kzg_vk.beta_g2.into_group() - (vk.kzg_vk.g2 * x_challenge)).into(),
+        if P::pairing_check(
+            &[zeta_z_com, proof.pi],
+            &[
+                -vk.tau_N_max_sub_2_N,
+                (vk.kzg_vk.beta_g2.into_group() - (vk.kzg_vk.g2 * x_challenge)).into_affine(),
             ],
-        );
-        if pairing.is_zero() {
+        ) {
             Ok(())
         } else {
             Err(ProofVerifyError::InternalError)
@@ -415,7 +486,7 @@ where
     }
 }
 
-impl<P: Pairing, ProofTranscript: Transcript> CommitmentScheme<ProofTranscript>
+impl<P: JoltPairing, ProofTranscript: Transcript> CommitmentScheme<ProofTranscript>
     for Zeromorph<P, ProofTranscript>
 where
     <P as Pairing>::ScalarField: JoltField,
@@ -502,6 +573,11 @@ where
         )
     }
 
+    fn verifier_group_ops(num_vars: usize) -> (usize, usize) {
+        // One multi-pairing of two pairs, and an MSM over the quotient commitments
+        (2, num_vars + 3)
+    }
+
     fn protocol_name() -> &'static [u8] {
         b"zeromorph"
     }
@@ -518,7 +594,7 @@ mod test {
     use rand_core::SeedableRng;
 
     // Evaluate Phi_k(x) = \sum_{i=0}^k x^i using the direct inefficient formula
-    fn phi<P: Pairing>(challenge: &P::ScalarField, subscript: usize) -> P::ScalarField {
+    fn phi<P: JoltPairing>(challenge: &P::ScalarField, subscript: usize) -> P::ScalarField {
         let len = (1 << subscript) as u64;
         (0..len).fold(P::ScalarField::zero(), |mut acc, i| {
             //Note this is ridiculous DevX
@@ -2,13 +2,13 @@ use std::ops::Index;
 
 use super::multilinear_polynomial::{BindingOrder, PolynomialBinding};
 use crate::utils::math::Math;
+use crate::utils::parallel::prelude::*;
 use crate::utils::thread::unsafe_allocate_zero_vec;
 use crate::{field::JoltField, utils};
 use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
 };
 use num_integer::Integer;
-use rayon::prelude::*;
 
 pub trait SmallScalar: Copy + Integer + Sync {
     /// Performs a field multiplication. Uses `JoltField::mul_u64_unchecked` under the hood.
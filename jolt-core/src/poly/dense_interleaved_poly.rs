@@ -1,3 +1,4 @@
+use crate::utils::parallel::{prelude::*, Chunks};
 use crate::{
     field::JoltField,
     subprotocols::{
@@ -6,7 +7,6 @@ use crate::{
     },
     utils::{thread::unsafe_allocate_zero_vec, transcript::Transcript},
 };
-use rayon::{prelude::*, slice::Chunks};
 
 #[cfg(test)]
 use super::dense_mlpoly::DensePolynomial;
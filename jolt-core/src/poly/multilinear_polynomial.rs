@@ -1,8 +1,8 @@
+use crate::utils::parallel::{self, prelude::*};
 use crate::utils::{compute_dotproduct, math::Math};
 use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError, Valid, Validate,
 };
-use rayon::prelude::*;
 use strum_macros::EnumIter;
 
 use super::{
@@ -115,7 +115,7 @@ impl<F: JoltField> MultilinearPolynomial<F> {
             .map(|poly| poly.original_len())
             .max()
             .unwrap();
-        let num_chunks = rayon::current_num_threads()
+        let num_chunks = parallel::current_num_threads()
             .next_power_of_two()
             .min(max_length);
         let chunk_size = (max_length / num_chunks).max(1);
@@ -4,12 +4,12 @@
 //! can use a sumcheck to reduce multiple opening proofs (multiple polynomials, not
 //! necessarily of the same size, each opened at a different point) into a single opening.
 
+use crate::utils::parallel::{self, prelude::*};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use rayon::prelude::*;
 use std::marker::PhantomData;
 
 use super::{
-    commitment::commitment_scheme::CommitmentScheme,
+    commitment::commitment_scheme::{CommitmentScheme, OpeningInstance},
     dense_mlpoly::DensePolynomial,
     eq_poly::EqPolynomial,
     multilinear_polynomial::{BindingOrder, MultilinearPolynomial, PolynomialBinding},
@@ -142,6 +142,18 @@ pub struct ReducedOpeningProof<
     joint_opening_proof: PCS::Proof,
 }
 
+impl<F, PCS, ProofTranscript> ReducedOpeningProof<F, PCS, ProofTranscript>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    /// The sumcheck reducing the evaluation claims to a single opening
+    pub(crate) fn sumcheck_proof(&self) -> &SumcheckInstanceProof<F, ProofTranscript> {
+        &self.sumcheck_proof
+    }
+}
+
 impl<F: JoltField, ProofTranscript: Transcript> Default
     for ProverOpeningAccumulator<F, ProofTranscript>
 {
@@ -162,6 +174,12 @@ impl<F: JoltField, ProofTranscript: Transcript> ProverOpeningAccumulator<F, Proo
         self.openings.len()
     }
 
+    /// Takes over the openings accumulated in `other`, e.g. for another proof,
+    /// so that they are reduced to the same opening proof as these.
+    pub fn extend(&mut self, other: Self) {
+        self.openings.extend(other.openings);
+    }
+
     /// Adds openings to the accumulator. The given `polynomials` are opened at
     /// `opening_point`, yielding the claimed evaluations `claims`. `eq_poly` is
     /// the multilinear extension EQ(x, opening_point), which is typically an
@@ -333,7 +351,7 @@ impl<F: JoltField, ProofTranscript: Transcript> ProverOpeningAccumulator<F, Proo
 
             self.openings.par_iter_mut().for_each(|opening| {
                 if remaining_rounds <= opening.opening_point.len() {
-                    rayon::join(
+                    parallel::join(
                         || opening.eq_poly.bind(r_j, BindingOrder::HighToLow),
                         || opening.polynomial.bind(r_j, BindingOrder::HighToLow),
                     );
@@ -451,6 +469,12 @@ where
         self.openings.len()
     }
 
+    /// Takes over the openings accumulated in `other`, as verified for another
+    /// proof; see `ProverOpeningAccumulator::extend`.
+    pub fn extend(&mut self, other: Self) {
+        self.openings.extend(other.openings);
+    }
+
     /// Adds openings to the accumulator. The polynomials underlying the given
     /// `commitments` are opened at `opening_point`, yielding the claimed evaluations
     /// `claims`.
@@ -543,6 +567,45 @@ where
         reduced_opening_proof: &ReducedOpeningProof<F, PCS, ProofTranscript>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
+        let (opening_point, joint_claim, joint_commitment) =
+            self.reduce(reduced_opening_proof, transcript)?;
+
+        // Verify the reduced opening proof
+        PCS::verify(
+            &reduced_opening_proof.joint_opening_proof,
+            pcs_setup,
+            transcript,
+            &opening_point,
+            &joint_claim,
+            &joint_commitment,
+        )
+    }
+
+    /// `reduce_and_verify` without the final opening proof verification, which is
+    /// returned to be checked along with others by `CommitmentScheme::batch_verify`.
+    pub fn reduce_and_defer(
+        &self,
+        reduced_opening_proof: ReducedOpeningProof<F, PCS, ProofTranscript>,
+        mut transcript: ProofTranscript,
+    ) -> Result<OpeningInstance<PCS, ProofTranscript>, ProofVerifyError> {
+        let (opening_point, opening, commitment) =
+            self.reduce(&reduced_opening_proof, &mut transcript)?;
+        Ok(OpeningInstance {
+            proof: reduced_opening_proof.joint_opening_proof,
+            transcript,
+            opening_point,
+            opening,
+            commitment,
+        })
+    }
+
+    /// Verifies the batch opening reduction sumcheck, returning the opening point,
+    /// claim and commitment of the single opening the accumulated openings reduce to.
+    fn reduce(
+        &self,
+        reduced_opening_proof: &ReducedOpeningProof<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(Vec<F>, F, PCS::Commitment), ProofVerifyError> {
         let num_sumcheck_rounds = self
             .openings
             .iter()
@@ -615,15 +678,7 @@ where
             })
             .sum();
 
-        // Verify the reduced opening proof
-        PCS::verify(
-            &reduced_opening_proof.joint_opening_proof,
-            pcs_setup,
-            transcript,
-            &r_sumcheck,
-            &joint_claim,
-            &joint_commitment,
-        )
+        Ok((r_sumcheck, joint_claim, joint_commitment))
     }
 
     /// Verifies the sumcheck proven in `ProverOpeningAccumulator::prove_batch_opening_reduction`.
@@ -2,6 +2,7 @@ use super::{
     dense_interleaved_poly::DenseInterleavedPolynomial, dense_mlpoly::DensePolynomial,
     split_eq_poly::SplitEqPolynomial, unipoly::UniPoly,
 };
+use crate::utils::parallel::prelude::*;
 use crate::{
     field::{JoltField, OptimizedMul},
     subprotocols::{
@@ -10,7 +11,6 @@ use crate::{
     },
     utils::{math::Math, transcript::Transcript},
 };
-use rayon::prelude::*;
 
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct SparseCoefficient<T> {
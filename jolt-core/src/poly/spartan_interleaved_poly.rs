@@ -8,16 +8,17 @@ use super::{
 use crate::poly::dense_mlpoly::DensePolynomial;
 #[cfg(test)]
 use crate::r1cs::inputs::JoltR1CSInputs;
+use crate::utils::parallel::{self, prelude::*};
 use crate::{
     field::{JoltField, OptimizedMul},
     r1cs::builder::{eval_offset_lc, Constraint, OffsetEqConstraint},
     utils::{
         math::Math,
+        thread::num_parallel_chunks,
         transcript::{AppendToTranscript, Transcript},
     },
 };
 use ark_ff::Zero;
-use rayon::prelude::*;
 
 #[derive(Default, Debug, Clone)]
 pub struct SpartanInterleavedPolynomial<F: JoltField> {
@@ -49,7 +50,7 @@ impl<F: JoltField> SpartanInterleavedPolynomial<F> {
     ) -> Self {
         let num_steps = flattened_polynomials[0].len();
 
-        let num_chunks = rayon::current_num_threads().next_power_of_two() * 4;
+        let num_chunks = num_parallel_chunks();
         let chunk_size = num_steps.div_ceil(num_chunks);
 
         let unbound_coeffs: Vec<SparseCoefficient<i128>> = (0..num_chunks)
@@ -99,7 +100,8 @@ impl<F: JoltField> SpartanInterleavedPolynomial<F> {
                                             );
                                         println!("{constraint_string}");
                                         panic!(
-                                            "Uniform constraint {constraint_index} violated at step {step_index}",
+                                            "Uniform constraint {constraint_index} ({}) violated at step {step_index}",
+                                            constraint.label,
                                         );
                                     }
                             }
@@ -147,7 +149,7 @@ impl<F: JoltField> SpartanInterleavedPolynomial<F> {
                                     step_index,
                                     next_step_index,
                                 );
-                                assert_eq!(bz_coeff, 0, "Cross-step constraint {constraint_index} violated at step {step_index}");
+                                assert_eq!(bz_coeff, 0, "Cross-step constraint {constraint_index} ({}) violated at step {step_index}", constraint.label);
                             }
                         } else {
                             // Bz
@@ -243,7 +245,7 @@ impl<F: JoltField> SpartanInterleavedPolynomial<F> {
         let block_size = self
             .unbound_coeffs
             .len()
-            .div_ceil(rayon::current_num_threads())
+            .div_ceil(parallel::current_num_threads())
             .next_multiple_of(6);
         let chunks: Vec<_> = self
             .unbound_coeffs
@@ -466,7 +468,7 @@ impl<F: JoltField> SpartanInterleavedPolynomial<F> {
         let block_size = self
             .bound_coeffs
             .len()
-            .div_ceil(rayon::current_num_threads())
+            .div_ceil(parallel::current_num_threads())
             .next_multiple_of(6);
         let chunks: Vec<_> = self
             .bound_coeffs
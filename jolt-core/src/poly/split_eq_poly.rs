@@ -2,6 +2,7 @@
 //! https://eprint.iacr.org/2024/1210.pdf
 #[cfg(test)]
 use super::dense_mlpoly::DensePolynomial;
+use crate::utils::parallel;
 use crate::{field::JoltField, poly::eq_poly::EqPolynomial};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,7 +19,7 @@ impl<F: JoltField> SplitEqPolynomial<F> {
     pub fn new(w: &[F]) -> Self {
         let m = w.len() / 2;
         let (w2, w1) = w.split_at(m);
-        let (E2, E1) = rayon::join(|| EqPolynomial::evals(w2), || EqPolynomial::evals(w1));
+        let (E2, E1) = parallel::join(|| EqPolynomial::evals(w2), || EqPolynomial::evals(w1));
         let E1_len = E1.len();
         let E2_len = E2.len();
         Self {
@@ -4,10 +4,10 @@ use std::cmp::Ordering;
 use std::ops::{AddAssign, Index, IndexMut, Mul, MulAssign};
 
 use crate::utils::gaussian_elimination::gaussian_elimination;
+use crate::utils::parallel::prelude::*;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use ark_serialize::*;
 use rand_core::{CryptoRng, RngCore};
-use rayon::prelude::*;
 
 use super::compact_polynomial::SmallScalar;
 use super::multilinear_polynomial::MultilinearPolynomial;
@@ -1,22 +1,22 @@
 use super::{
     inputs::ConstraintInput,
-    key::{CrossStepR1CS, CrossStepR1CSConstraint, SparseEqualityItem},
+    key::{
+        CrossStepR1CS, CrossStepR1CSConstraint, SparseEqualityItem, SparseLinearCombination,
+        SparseProductConstraint,
+    },
     ops::{Term, Variable, LC},
 };
 use crate::poly::multilinear_polynomial::MultilinearPolynomial;
+use crate::utils::parallel::prelude::*;
 use crate::{
     field::JoltField,
     jolt::vm::JoltPolynomials,
     poly::spartan_interleaved_poly::SpartanInterleavedPolynomial,
     r1cs::key::{SparseConstraints, UniformR1CS},
+    utils::thread::num_parallel_chunks,
 };
 use ark_ff::One;
-use rayon::prelude::*;
-use std::{
-    collections::BTreeMap,
-    marker::PhantomData,
-    sync::atomic::{AtomicBool, Ordering},
-};
+use std::{collections::BTreeMap, fmt, marker::PhantomData};
 
 /// Constraints over a single row. Each variable points to a single item in Z and the corresponding coefficient.
 #[derive(Clone)]
@@ -24,10 +24,21 @@ pub struct Constraint {
     pub(crate) a: LC,
     pub(crate) b: LC,
     pub(crate) c: LC,
+    /// Identifies the constraint in diagnostics: the builder's current label (if any)
+    /// and the source location where the constraint was added.
+    pub(crate) label: String,
+}
+
+#[track_caller]
+fn constraint_label(label: Option<&str>) -> String {
+    let location = std::panic::Location::caller();
+    match label {
+        Some(label) => format!("{label} ({}:{})", location.file(), location.line()),
+        None => format!("{}:{}", location.file(), location.line()),
+    }
 }
 
 impl Constraint {
-    #[cfg(test)]
     pub(crate) fn pretty_fmt<const C: usize, I: ConstraintInput, F: JoltField>(
         &self,
         f: &mut String,
@@ -41,40 +52,136 @@ impl Constraint {
         self.b.pretty_fmt::<C, I>(f)?;
         write!(f, " == ")?;
         self.c.pretty_fmt::<C, I>(f)?;
-        writeln!(f, "")?;
+        writeln!(f)?;
 
-        let mut terms = Vec::new();
-        for term in self
-            .a
-            .terms()
-            .iter()
-            .chain(self.b.terms().iter())
-            .chain(self.c.terms().iter())
-        {
-            if !terms.contains(term) {
-                terms.push(*term);
+        fmt_input_values::<C, I, F>(
+            f,
+            [&self.a, &self.b, &self.c],
+            flattened_polynomials,
+            step_index,
+        )
+    }
+}
+
+/// Writes the value at `step_index` of each distinct variable appearing in `lcs`.
+fn fmt_input_values<'a, const C: usize, I: ConstraintInput, F: JoltField>(
+    f: &mut String,
+    lcs: impl IntoIterator<Item = &'a LC>,
+    flattened_polynomials: &[&MultilinearPolynomial<F>],
+    step_index: usize,
+) -> std::fmt::Result {
+    use std::fmt::Write as _;
+
+    let mut terms = Vec::new();
+    for term in lcs.into_iter().flat_map(|lc| lc.terms().iter()) {
+        if !terms.contains(term) {
+            terms.push(*term);
+        }
+    }
+
+    for term in terms {
+        match term.0 {
+            Variable::Input(var_index) | Variable::Auxiliary(var_index) => {
+                writeln!(
+                    f,
+                    "    {:?} = {}",
+                    I::from_index::<C>(var_index),
+                    flattened_polynomials[var_index].get_coeff(step_index)
+                )?;
             }
+            Variable::Constant => {}
         }
+    }
 
-        for term in terms {
-            match term.0 {
-                Variable::Input(var_index) | Variable::Auxiliary(var_index) => {
-                    writeln!(
-                        f,
-                        "    {:?} = {}",
-                        I::from_index::<C>(var_index),
-                        flattened_polynomials[var_index].get_coeff(step_index)
-                    )?;
-                }
-                Variable::Constant => {}
+    Ok(())
+}
+
+/// Highest degree supported for product constraints. Spartan's product sumcheck has
+/// round polynomials of degree one more than this.
+pub const MAX_CONSTRAINT_DEGREE: usize = 4;
+
+/// A constraint `factors[0] ⋅ factors[1] ⋅ ... == result` over a single row, whose degree
+/// is the number of factors. Expressing a high-degree gate this way avoids allocating
+/// aux variables for its intermediate products. Unlike `Constraint`s, product constraints
+/// are not part of the R1CS matrices; Spartan proves them with a separate sumcheck.
+#[derive(Clone)]
+pub struct ProductConstraint {
+    pub(crate) factors: Vec<LC>,
+    pub(crate) result: LC,
+    /// See `Constraint::label`
+    pub(crate) label: String,
+}
+
+impl ProductConstraint {
+    pub fn degree(&self) -> usize {
+        self.factors.len()
+    }
+
+    pub(crate) fn pretty_fmt<const C: usize, I: ConstraintInput, F: JoltField>(
+        &self,
+        f: &mut String,
+        flattened_polynomials: &[&MultilinearPolynomial<F>],
+        step_index: usize,
+    ) -> std::fmt::Result {
+        use std::fmt::Write as _;
+
+        for (i, factor) in self.factors.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ⋅ ")?;
             }
+            factor.pretty_fmt::<C, I>(f)?;
         }
+        write!(f, " == ")?;
+        self.result.pretty_fmt::<C, I>(f)?;
+        writeln!(f)?;
 
-        Ok(())
+        fmt_input_values::<C, I, F>(
+            f,
+            self.factors.iter().chain([&self.result]),
+            flattened_polynomials,
+            step_index,
+        )
     }
 }
 
-type AuxComputationFunction = dyn Fn(&[i128]) -> i128 + Send + Sync;
+/// A constraint that does not hold at some step of the execution trace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintViolation {
+    /// See `Constraint::label`
+    pub label: String,
+    pub cross_step: bool,
+    pub step: usize,
+    /// For uniform constraints, the evaluations of A and B (or of each factor of a
+    /// product constraint), whose product should equal `right` (the evaluation of C).
+    /// For cross-step constraints, the condition and the difference between the two
+    /// sides, whose product should be zero.
+    pub left: Vec<i128>,
+    pub right: i128,
+    /// The constraint and the values of its inputs at the offending step
+    pub description: String,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.cross_step {
+            "cross-step"
+        } else {
+            "uniform"
+        };
+        let left: Vec<String> = self.left.iter().map(|value| value.to_string()).collect();
+        writeln!(
+            f,
+            "{kind} constraint `{}` violated at step {}: {} != {}",
+            self.label,
+            self.step,
+            left.join(" ⋅ "),
+            self.right
+        )?;
+        write!(f, "{}", self.description)
+    }
+}
+
+pub type AuxComputationFunction = dyn Fn(&[i128]) -> i128 + Send + Sync;
 
 struct AuxComputation<F: JoltField> {
     symbolic_inputs: Vec<LC>,
@@ -124,67 +231,14 @@ impl<F: JoltField> AuxComputation<F> {
             _field: PhantomData,
         }
     }
-
-    fn compute_aux_poly<const C: usize, I: ConstraintInput>(
-        &self,
-        jolt_polynomials: &JoltPolynomials<F>,
-        poly_len: usize,
-    ) -> MultilinearPolynomial<F> {
-        let flattened_polys: Vec<&MultilinearPolynomial<F>> = I::flatten::<C>()
-            .iter()
-            .map(|var| var.get_ref(jolt_polynomials))
-            .collect();
-
-        let mut aux_poly: Vec<i64> = vec![0; poly_len];
-        let num_threads = rayon::current_num_threads();
-        let chunk_size = poly_len.div_ceil(num_threads);
-        let contains_negative_values = AtomicBool::new(false);
-
-        aux_poly
-            .par_chunks_mut(chunk_size)
-            .enumerate()
-            .for_each(|(chunk_index, chunk)| {
-                chunk.iter_mut().enumerate().for_each(|(offset, result)| {
-                    let global_index = chunk_index * chunk_size + offset;
-                    let compute_inputs: Vec<_> = self
-                        .symbolic_inputs
-                        .iter()
-                        .map(|lc| {
-                            let mut input = 0;
-                            for term in lc.terms().iter() {
-                                match term.0 {
-                                    Variable::Input(index) | Variable::Auxiliary(index) => {
-                                        input += flattened_polys[index]
-                                            .get_coeff_i128(global_index)
-                                            * term.1 as i128;
-                                    }
-                                    Variable::Constant => input += term.1 as i128,
-                                }
-                            }
-                            input
-                        })
-                        .collect();
-                    let aux_value = (self.compute)(&compute_inputs);
-                    if aux_value.is_negative() {
-                        contains_negative_values.store(true, Ordering::Relaxed);
-                    }
-                    *result = aux_value as i64;
-                });
-            });
-
-        if contains_negative_values.into_inner() {
-            MultilinearPolynomial::from(aux_poly)
-        } else {
-            let aux_poly: Vec<_> = aux_poly.into_iter().map(|x| x as u64).collect();
-            MultilinearPolynomial::from(aux_poly)
-        }
-    }
 }
 
 pub struct R1CSBuilder<const C: usize, F: JoltField, I: ConstraintInput> {
     _inputs: PhantomData<I>,
     constraints: Vec<Constraint>,
+    product_constraints: Vec<ProductConstraint>,
     aux_computations: BTreeMap<usize, AuxComputation<F>>,
+    label: Option<String>,
 }
 
 impl<const C: usize, F: JoltField, I: ConstraintInput> Default for R1CSBuilder<C, F, I> {
@@ -198,11 +252,32 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         Self {
             _inputs: PhantomData,
             constraints: vec![],
+            product_constraints: vec![],
             aux_computations: BTreeMap::new(),
+            label: None,
         }
     }
 
-    fn allocate_aux(
+    /// Runs `f`, labelling all constraints it adds with `label` so that they can
+    /// be identified if violated.
+    pub fn labeled<T>(&mut self, label: impl Into<String>, f: impl FnOnce(&mut Self) -> T) -> T {
+        let previous = self.label.replace(label.into());
+        let result = f(self);
+        self.label = previous;
+        result
+    }
+
+    #[track_caller]
+    fn push_constraint(&mut self, a: LC, b: LC, c: LC) {
+        let label = constraint_label(self.label.as_deref());
+        self.constraints.push(Constraint { a, b, c, label });
+    }
+
+    /// Allocates the auxiliary variable `aux_symbol`, whose value at each step is
+    /// `compute` applied to the values of `symbolic_inputs`. The variable is
+    /// unconstrained until used in a constraint. Aux computations may only depend
+    /// on inputs and aux variables with a lower index.
+    pub fn allocate_aux(
         &mut self,
         aux_symbol: I,
         symbolic_inputs: Vec<LC>,
@@ -216,6 +291,7 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         new_aux
     }
 
+    #[track_caller]
     pub fn constrain_eq(&mut self, left: impl Into<LC>, right: impl Into<LC>) {
         // left - right == 0
         let left: LC = left.into();
@@ -223,14 +299,36 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
 
         let a = left - right.clone();
         let b = Variable::Constant.into();
-        let constraint = Constraint {
-            a,
-            b,
-            c: LC::zero(),
-        };
-        self.constraints.push(constraint);
+        self.push_constraint(a, b, LC::zero());
+    }
+
+    /// Constrains the product of `factors` to equal `result`. Constraints of degree at
+    /// most 2 are added as regular R1CS constraints; higher-degree ones (up to
+    /// `MAX_CONSTRAINT_DEGREE`) become `ProductConstraint`s.
+    #[track_caller]
+    pub fn constrain_product(&mut self, factors: Vec<LC>, result: impl Into<LC>) {
+        let result: LC = result.into();
+        assert!(!factors.is_empty(), "product constraint without factors");
+        assert!(
+            factors.len() <= MAX_CONSTRAINT_DEGREE,
+            "product constraints are limited to degree {MAX_CONSTRAINT_DEGREE}"
+        );
+
+        match factors.len() {
+            1 => self.push_constraint(factors[0].clone(), Variable::Constant.into(), result),
+            2 => self.push_constraint(factors[0].clone(), factors[1].clone(), result),
+            _ => {
+                let label = constraint_label(self.label.as_deref());
+                self.product_constraints.push(ProductConstraint {
+                    factors,
+                    result,
+                    label,
+                });
+            }
+        }
     }
 
+    #[track_caller]
     pub fn constrain_eq_conditional(
         &mut self,
         condition: impl Into<LC>,
@@ -245,23 +343,19 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         let a = condition;
         let b = left - right;
         let c = LC::zero();
-        let constraint = Constraint { a, b, c }; // TODO(sragss): Can do better on middle term.
-        self.constraints.push(constraint);
+        self.push_constraint(a, b, c); // TODO(sragss): Can do better on middle term.
     }
 
+    #[track_caller]
     pub fn constrain_binary(&mut self, value: impl Into<LC>) {
         let one: LC = Variable::Constant.into();
         let a: LC = value.into();
         let b = one - a.clone();
         // value * (1 - value) == 0
-        let constraint = Constraint {
-            a,
-            b,
-            c: LC::zero(),
-        };
-        self.constraints.push(constraint);
+        self.push_constraint(a, b, LC::zero());
     }
 
+    #[track_caller]
     pub fn constrain_if_else(
         &mut self,
         condition: impl Into<LC>,
@@ -277,15 +371,15 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         // result == condition * true_coutcome + (1 - condition) * false_outcome
         // simplify to single mul, single constraint => condition * (true_outcome - false_outcome) == (result - false_outcome)
 
-        let constraint = Constraint {
-            a: condition.clone(),
-            b: (result_true - result_false.clone()),
-            c: (alleged_result - result_false),
-        };
-        self.constraints.push(constraint);
+        self.push_constraint(
+            condition.clone(),
+            result_true - result_false.clone(),
+            alleged_result - result_false,
+        );
     }
 
     #[must_use]
+    #[track_caller]
     pub fn allocate_if_else(
         &mut self,
         aux_symbol: I,
@@ -347,6 +441,7 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         packed.into()
     }
 
+    #[track_caller]
     pub fn constrain_pack_le(
         &mut self,
         unpacked: Vec<Variable>,
@@ -363,6 +458,7 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
         self.constrain_eq(packed, result);
     }
 
+    #[track_caller]
     pub fn constrain_pack_be(
         &mut self,
         unpacked: Vec<Variable>,
@@ -382,16 +478,13 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> R1CSBuilder<C, F, I> {
     }
 
     /// Constrain x * y == z
+    #[track_caller]
     pub fn constrain_prod(&mut self, x: impl Into<LC>, y: impl Into<LC>, z: impl Into<LC>) {
-        let constraint = Constraint {
-            a: x.into(),
-            b: y.into(),
-            c: z.into(),
-        };
-        self.constraints.push(constraint);
+        self.push_constraint(x.into(), y.into(), z.into());
     }
 
     #[must_use]
+    #[track_caller]
     pub fn allocate_prod(&mut self, aux_symbol: I, x: impl Into<LC>, y: impl Into<LC>) -> Variable {
         let (x, y) = (x.into(), y.into());
         let z = self.aux_prod(aux_symbol, &x, &y);
@@ -464,9 +557,12 @@ pub struct OffsetEqConstraint {
     pub(crate) cond: OffsetLC,
     pub(crate) a: OffsetLC,
     pub(crate) b: OffsetLC,
+    /// Source location where the constraint was created, for diagnostics
+    pub(crate) label: String,
 }
 
 impl OffsetEqConstraint {
+    #[track_caller]
     pub fn new(
         condition: (impl Into<LC>, bool),
         a: (impl Into<LC>, bool),
@@ -476,9 +572,53 @@ impl OffsetEqConstraint {
             cond: (condition.1, condition.0.into()),
             a: (a.1, a.0.into()),
             b: (b.1, b.0.into()),
+            label: constraint_label(None),
         }
     }
 
+    fn pretty_fmt<const C: usize, I: ConstraintInput, F: JoltField>(
+        &self,
+        f: &mut String,
+        flattened_polynomials: &[&MultilinearPolynomial<F>],
+        step: usize,
+        next_step: Option<usize>,
+    ) -> fmt::Result {
+        use std::fmt::Write as _;
+
+        let offsets = [&self.cond, &self.a, &self.b];
+        let [cond, a, b] = offsets.map(|(offset, lc)| {
+            let mut lc_string = String::new();
+            let _ = lc.pretty_fmt::<C, I>(&mut lc_string);
+            format!("{lc_string}[{}]", if *offset { "i+1" } else { "i" })
+        });
+        writeln!(f, "{cond} ⋅ ({a} - {b}) == 0")?;
+
+        for (offset, lc) in offsets {
+            let row = if *offset { next_step } else { Some(step) };
+            for term in lc.terms() {
+                if let (Variable::Input(var_index) | Variable::Auxiliary(var_index), Some(row)) =
+                    (term.0, row)
+                {
+                    writeln!(
+                        f,
+                        "    {:?}[{}] = {}",
+                        I::from_index::<C>(var_index),
+                        row,
+                        flattened_polynomials[var_index].get_coeff(row)
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Labels the constraint for diagnostics.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = format!("{label} ({})", self.label);
+        self
+    }
+
     #[cfg(test)]
     pub fn empty() -> Self {
         Self::new(
@@ -530,13 +670,195 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> CombinedUniformBuilder<C,
 
     #[tracing::instrument(skip_all)]
     pub fn compute_aux(&self, jolt_polynomials: &mut JoltPolynomials<F>) {
+        let aux_computations: Vec<_> = self.uniform_builder.aux_computations.iter().collect();
+        if aux_computations.is_empty() {
+            return;
+        }
+
         let flattened_vars = I::flatten::<C>();
-        for (aux_index, aux_compute) in self.uniform_builder.aux_computations.iter() {
-            *flattened_vars[*aux_index].get_ref_mut(jolt_polynomials) =
-                aux_compute.compute_aux_poly::<C, I>(jolt_polynomials, self.uniform_repeat);
+        // Maps each variable index to its position in `aux_computations`, if any
+        let mut aux_slots = vec![None; flattened_vars.len()];
+        for (slot, (aux_index, _)) in aux_computations.iter().enumerate() {
+            aux_slots[**aux_index] = Some(slot);
+        }
+
+        let poly_len = self.uniform_repeat;
+        let num_chunks = num_parallel_chunks();
+        let chunk_size = poly_len.div_ceil(num_chunks);
+
+        // Each chunk of rows computes all of the aux variables, in allocation order
+        // since aux computations may depend on previously allocated aux variables.
+        let chunks: Vec<(Vec<Vec<i64>>, Vec<bool>)> = {
+            let flattened_polys: Vec<&MultilinearPolynomial<F>> = flattened_vars
+                .iter()
+                .map(|var| var.get_ref(jolt_polynomials))
+                .collect();
+            // Aux variables already computed for the current row are read from `row_values`
+            let evaluate = |lc: &LC, row: usize, row_values: &[i128]| -> i128 {
+                lc.terms()
+                    .iter()
+                    .map(|term| match term.0 {
+                        Variable::Input(index) | Variable::Auxiliary(index) => {
+                            let value = match aux_slots[index] {
+                                Some(slot) => row_values[slot],
+                                None => flattened_polys[index].get_coeff_i128(row),
+                            };
+                            value * term.1 as i128
+                        }
+                        Variable::Constant => term.1 as i128,
+                    })
+                    .sum()
+            };
+
+            (0..poly_len.div_ceil(chunk_size))
+                .into_par_iter()
+                .map(|chunk_index| {
+                    let rows =
+                        chunk_index * chunk_size..poly_len.min((chunk_index + 1) * chunk_size);
+                    let mut columns: Vec<Vec<i64>> = (0..aux_computations.len())
+                        .map(|_| Vec::with_capacity(rows.len()))
+                        .collect();
+                    let mut contains_negative_values = vec![false; aux_computations.len()];
+                    let mut row_values = vec![0i128; aux_computations.len()];
+
+                    for row in rows {
+                        for (slot, (_, aux_compute)) in aux_computations.iter().enumerate() {
+                            let compute_inputs: Vec<i128> = aux_compute
+                                .symbolic_inputs
+                                .iter()
+                                .map(|lc| evaluate(lc, row, &row_values))
+                                .collect();
+                            let aux_value = (aux_compute.compute)(&compute_inputs);
+                            contains_negative_values[slot] |= aux_value.is_negative();
+                            row_values[slot] = aux_value;
+                            columns[slot].push(aux_value as i64);
+                        }
+                    }
+
+                    (columns, contains_negative_values)
+                })
+                .collect()
+        };
+
+        // Stitch the chunks together into one polynomial per aux variable
+        let aux_polys: Vec<MultilinearPolynomial<F>> = (0..aux_computations.len())
+            .into_par_iter()
+            .map(|slot| {
+                let aux_poly: Vec<i64> = chunks
+                    .iter()
+                    .flat_map(|(columns, _)| columns[slot].iter().copied())
+                    .collect();
+                if chunks.iter().any(|(_, negative)| negative[slot]) {
+                    MultilinearPolynomial::from(aux_poly)
+                } else {
+                    let aux_poly: Vec<_> = aux_poly.into_iter().map(|x| x as u64).collect();
+                    MultilinearPolynomial::from(aux_poly)
+                }
+            })
+            .collect();
+
+        for ((aux_index, _), aux_poly) in aux_computations.iter().zip(aux_polys) {
+            *flattened_vars[**aux_index].get_ref_mut(jolt_polynomials) = aux_poly;
         }
     }
 
+    /// Checks that every constraint holds at every step of the trace, returning
+    /// the violation at the earliest offending step otherwise.
+    #[tracing::instrument(skip_all)]
+    pub fn check_constraints(
+        &self,
+        jolt_polynomials: &JoltPolynomials<F>,
+    ) -> Result<(), ConstraintViolation> {
+        let flattened_polys: Vec<&MultilinearPolynomial<F>> = I::flatten::<C>()
+            .iter()
+            .map(|var| var.get_ref(jolt_polynomials))
+            .collect();
+        let num_steps = flattened_polys[0].len();
+
+        match (0..num_steps)
+            .into_par_iter()
+            .find_map_first(|step| self.check_step(&flattened_polys, step))
+        {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
+    }
+
+    fn check_step(
+        &self,
+        flattened_polys: &[&MultilinearPolynomial<F>],
+        step: usize,
+    ) -> Option<ConstraintViolation> {
+        for constraint in self.uniform_builder.constraints.iter() {
+            let a = constraint.a.evaluate_row(flattened_polys, step);
+            let b = constraint.b.evaluate_row(flattened_polys, step);
+            let c = constraint.c.evaluate_row(flattened_polys, step);
+            if a * b != c {
+                let mut description = String::new();
+                let _ = constraint.pretty_fmt::<C, I, F>(&mut description, flattened_polys, step);
+                return Some(ConstraintViolation {
+                    label: constraint.label.clone(),
+                    cross_step: false,
+                    step,
+                    left: vec![a, b],
+                    right: c,
+                    description,
+                });
+            }
+        }
+
+        for constraint in self.uniform_builder.product_constraints.iter() {
+            let factors: Vec<i128> = constraint
+                .factors
+                .iter()
+                .map(|factor| factor.evaluate_row(flattened_polys, step))
+                .collect();
+            let result = constraint.result.evaluate_row(flattened_polys, step);
+            // The product may overflow an i128, so compare in the field
+            let product: F = factors.iter().map(|factor| F::from_i128(*factor)).product();
+            if product != F::from_i128(result) {
+                let mut description = String::new();
+                let _ = constraint.pretty_fmt::<C, I, F>(&mut description, flattened_polys, step);
+                return Some(ConstraintViolation {
+                    label: constraint.label.clone(),
+                    cross_step: false,
+                    step,
+                    left: factors,
+                    right: result,
+                    description,
+                });
+            }
+        }
+
+        // As in the prover, cross-step constraints only see the constant terms of
+        // next-step linear combinations at the final step.
+        let next_step = (step + 1 < flattened_polys[0].len()).then_some(step + 1);
+        for constraint in self.offset_equality_constraints.iter() {
+            let condition = eval_offset_lc(&constraint.cond, flattened_polys, step, next_step);
+            let difference = eval_offset_lc(&constraint.a, flattened_polys, step, next_step)
+                - eval_offset_lc(&constraint.b, flattened_polys, step, next_step);
+            if condition * difference != 0 {
+                let mut description = String::new();
+                let _ = constraint.pretty_fmt::<C, I, F>(
+                    &mut description,
+                    flattened_polys,
+                    step,
+                    next_step,
+                );
+                return Some(ConstraintViolation {
+                    label: constraint.label.clone(),
+                    cross_step: true,
+                    step,
+                    left: vec![condition, difference],
+                    right: 0,
+                    description,
+                });
+            }
+        }
+
+        None
+    }
+
     /// Number of constraint rows per step, padded to the next power of two.
     pub(super) fn padded_rows_per_step(&self) -> usize {
         let num_constraints =
@@ -558,6 +880,42 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> CombinedUniformBuilder<C,
         self.uniform_builder.materialize()
     }
 
+    pub(super) fn uniform_constraints(&self) -> &[Constraint] {
+        &self.uniform_builder.constraints
+    }
+
+    pub(super) fn offset_eq_constraints(&self) -> &[OffsetEqConstraint] {
+        &self.offset_equality_constraints
+    }
+
+    /// Constraints of degree greater than 2, proven outside of the R1CS matrices.
+    pub(super) fn product_constraints(&self) -> &[ProductConstraint] {
+        &self.uniform_builder.product_constraints
+    }
+
+    /// Converts builder::ProductConstraints into key::SparseProductConstraints
+    pub fn materialize_product_constraints(&self) -> Vec<SparseProductConstraint<F>> {
+        let materialize_lc = |lc: &LC| {
+            let mut sparse = SparseLinearCombination::<F>::empty();
+            lc.terms().iter().for_each(|term| match term.0 {
+                Variable::Input(inner) | Variable::Auxiliary(inner) => {
+                    sparse.vars.push((inner, F::from_i64(term.1)))
+                }
+                Variable::Constant => sparse.constant = F::from_i64(term.1),
+            });
+            sparse
+        };
+
+        self.uniform_builder
+            .product_constraints
+            .iter()
+            .map(|constraint| SparseProductConstraint {
+                factors: constraint.factors.iter().map(materialize_lc).collect(),
+                result: materialize_lc(&constraint.result),
+            })
+            .collect()
+    }
+
     /// Converts builder::OffsetEqConstraints into key::CrossStepR1CSConstraint
     pub fn materialize_offset_eq(&self) -> CrossStepR1CS<F> {
         // (a - b) * condition == 0
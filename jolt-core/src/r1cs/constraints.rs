@@ -1,4 +1,5 @@
 use common::{constants::REGISTER_COUNT, rv_trace::CircuitFlags};
+use std::marker::PhantomData;
 use strum::IntoEnumIterator;
 
 use crate::{
@@ -9,15 +10,15 @@ use crate::{
             mulu::MULUInstruction, sll::SLLInstruction, sra::SRAInstruction, srl::SRLInstruction,
             sub::SUBInstruction,
             virtual_assert_aligned_memory_access::AssertAlignedMemoryAccessInstruction,
-            virtual_move::MOVEInstruction, virtual_movsign::MOVSIGNInstruction,
+            virtual_move::MOVEInstruction, virtual_movsign::MOVSIGNInstruction, JoltInstructionSet,
         },
-        vm::rv32i_vm::RV32I,
+        vm::{rv32i_vm::RV32I, JoltTraceStep},
     },
 };
 
 use super::{
     builder::{CombinedUniformBuilder, OffsetEqConstraint, R1CSBuilder},
-    inputs::{AuxVariable, ConstraintInput, JoltR1CSInputs},
+    inputs::{AuxVariable, ConstraintInput, CustomR1CSInputs, JoltR1CSInputs},
     ops::Variable,
 };
 
@@ -60,211 +61,278 @@ impl<const C: usize, F: JoltField> R1CSConstraints<C, F> for JoltRV32IMConstrain
     type Inputs = JoltR1CSInputs;
 
     fn uniform_constraints(cs: &mut R1CSBuilder<C, F, Self::Inputs>, memory_start: u64) {
-        for flag in RV32I::iter() {
-            cs.constrain_binary(JoltR1CSInputs::InstructionFlags(flag));
-        }
-        for flag in CircuitFlags::iter() {
-            cs.constrain_binary(JoltR1CSInputs::OpFlags(flag));
-        }
+        rv32im_uniform_constraints(cs, memory_start);
+    }
 
-        let flags = CircuitFlags::iter()
-            .map(|flag| JoltR1CSInputs::OpFlags(flag).into())
-            .chain(RV32I::iter().map(|flag| JoltR1CSInputs::InstructionFlags(flag).into()))
-            .collect();
-        cs.constrain_pack_be(flags, JoltR1CSInputs::Bytecode_Bitflags, 1);
+    fn cross_step_constraints() -> Vec<OffsetEqConstraint> {
+        rv32im_cross_step_constraints()
+    }
+}
 
-        let real_pc =
-            4i64 * JoltR1CSInputs::Bytecode_ELFAddress + (PC_START_ADDRESS - PC_NOOP_SHIFT);
-        let x = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::LeftLookupOperand),
-            JoltR1CSInputs::OpFlags(CircuitFlags::LeftOperandIsPC),
-            real_pc,
-            JoltR1CSInputs::RS1_Read,
-        );
-        let y = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::RightLookupOperand),
-            JoltR1CSInputs::OpFlags(CircuitFlags::RightOperandIsImm),
-            JoltR1CSInputs::Bytecode_Imm,
-            JoltR1CSInputs::RS2_Read,
-        );
+/// Application-specific constraints added on top of Jolt's RV32IM constraints,
+/// e.g. range facts about a program's outputs (see `JoltRV32IMWithConstraints`).
+///
+/// Constraints may reference any of Jolt's witness columns (`JoltR1CSInputs`)
+/// and up to `NUM_AUX` new, committed auxiliary columns, `CustomR1CSInputs::aux(i)`.
+/// Each aux column is either allocated via the builder's `allocate_*` methods,
+/// or computed from the trace by `witness`.
+///
+/// This is also the extension point for precompiles: a single implementation
+/// declares the precompile's columns, computes them from the trace steps that
+/// invoke it (`JoltTraceStep::precompile_input`), and constrains them.
+pub trait CustomConstraints: Sized + 'static {
+    /// The number of new auxiliary variables.
+    const NUM_AUX: usize = 0;
 
-        let is_load_or_store = JoltR1CSInputs::OpFlags(CircuitFlags::Load)
-            + JoltR1CSInputs::OpFlags(CircuitFlags::Store);
-        let memory_start: i64 = memory_start.try_into().unwrap();
-        cs.constrain_eq_conditional(
-            is_load_or_store,
-            JoltR1CSInputs::RS1_Read + JoltR1CSInputs::Bytecode_Imm,
-            4 * JoltR1CSInputs::RAM_Address + memory_start - 4 * REGISTER_COUNT as i64,
-        );
+    /// Writes the values of the aux columns that aren't allocated via the
+    /// builder for one step of the trace, e.g. a precompile's inputs and
+    /// outputs. `columns` has length `NUM_AUX` and is zeroed beforehand; entries
+    /// for columns allocated via the builder are overwritten.
+    fn witness<InstructionSet: JoltInstructionSet>(
+        _step: &JoltTraceStep<InstructionSet>,
+        _columns: &mut [u64],
+    ) {
+    }
 
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Load),
-            JoltR1CSInputs::RAM_Read,
-            JoltR1CSInputs::RAM_Write,
-        );
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Load),
-            JoltR1CSInputs::RAM_Read,
-            JoltR1CSInputs::RD_Write,
-        );
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Store),
-            JoltR1CSInputs::RS2_Read,
-            JoltR1CSInputs::RAM_Write,
-        );
+    /// Adds constraints that must hold for each step of the execution trace.
+    fn uniform_constraints<const C: usize, F: JoltField>(
+        cs: &mut R1CSBuilder<C, F, CustomR1CSInputs<Self>>,
+        memory_start: u64,
+    );
 
-        let query_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksQuery(i).to_index::<C>()))
-            .collect();
-        let packed_query =
-            R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(query_chunks.clone(), LOG_M);
+    /// Adds constraints between consecutive steps of the execution trace.
+    fn cross_step_constraints() -> Vec<OffsetEqConstraint> {
+        vec![]
+    }
+}
 
-        // For the `AssertAlignedMemoryAccessInstruction` lookups, we add the `rs1` and `imm` values
-        // to obtain the memory address being accessed.
-        let add_operands = JoltR1CSInputs::InstructionFlags(ADDInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(
-                AssertAlignedMemoryAccessInstruction::<32, 2>::default().into(),
-            )
-            + JoltR1CSInputs::InstructionFlags(
-                AssertAlignedMemoryAccessInstruction::<32, 4>::default().into(),
-            );
-        cs.constrain_eq_conditional(add_operands, packed_query.clone(), x + y);
-        // Converts from unsigned to twos-complement representation
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::InstructionFlags(SUBInstruction::default().into()),
-            packed_query.clone(),
-            x - y + (0xffffffffi64 + 1),
-        );
-        let is_mul = JoltR1CSInputs::InstructionFlags(MULInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(MULUInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(MULHUInstruction::default().into());
-        let product = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::Product),
-            JoltR1CSInputs::RS1_Read,
-            JoltR1CSInputs::RS2_Read,
-        );
-        cs.constrain_eq_conditional(is_mul, packed_query.clone(), product);
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::InstructionFlags(MOVSIGNInstruction::default().into())
-                + JoltR1CSInputs::InstructionFlags(MOVEInstruction::default().into()),
-            packed_query.clone(),
-            x,
-        );
+/// Jolt's RV32IM constraints together with the user-defined constraints `E`.
+pub struct JoltRV32IMWithConstraints<E>(PhantomData<E>);
+impl<const C: usize, F: JoltField, E: CustomConstraints> R1CSConstraints<C, F>
+    for JoltRV32IMWithConstraints<E>
+{
+    type Inputs = CustomR1CSInputs<E>;
 
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Assert),
-            JoltR1CSInputs::LookupOutput,
-            1,
-        );
+    fn uniform_constraints(cs: &mut R1CSBuilder<C, F, Self::Inputs>, memory_start: u64) {
+        rv32im_uniform_constraints(cs, memory_start);
+        E::uniform_constraints(cs, memory_start);
+    }
 
-        let x_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksX(i).to_index::<C>()))
-            .collect();
-        let y_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksY(i).to_index::<C>()))
-            .collect();
-        let x_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(x_chunks.clone(), OPERAND_SIZE);
-        let y_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(y_chunks.clone(), OPERAND_SIZE);
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
-            x_concat,
-            x,
-        );
-        cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
-            y_concat,
-            y,
-        );
+    fn cross_step_constraints() -> Vec<OffsetEqConstraint> {
+        let mut constraints = rv32im_cross_step_constraints();
+        constraints.extend(E::cross_step_constraints());
+        constraints
+    }
+}
 
-        // if is_shift ? chunks_query[i] == zip(chunks_x[i], chunks_y[C-1]) : chunks_query[i] == zip(chunks_x[i], chunks_y[i])
-        let is_shift = JoltR1CSInputs::InstructionFlags(SLLInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(SRLInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(SRAInstruction::default().into());
-        for i in 0..C {
-            let relevant_chunk_y = cs.allocate_if_else(
-                JoltR1CSInputs::Aux(AuxVariable::RelevantYChunk(i)),
-                is_shift.clone(),
-                y_chunks[C - 1],
-                y_chunks[i],
-            );
-            cs.constrain_eq_conditional(
-                JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
-                query_chunks[i],
-                x_chunks[i] * (1i64 << 8) + relevant_chunk_y,
-            );
-        }
+fn rv32im_uniform_constraints<const C: usize, F: JoltField, I>(
+    cs: &mut R1CSBuilder<C, F, I>,
+    memory_start: u64,
+) where
+    I: ConstraintInput + From<JoltR1CSInputs>,
+{
+    for flag in RV32I::iter() {
+        cs.constrain_binary(JoltR1CSInputs::InstructionFlags(flag));
+    }
+    for flag in CircuitFlags::iter() {
+        cs.constrain_binary(JoltR1CSInputs::OpFlags(flag));
+    }
 
-        // if (rd != 0 && update_rd_with_lookup_output == 1) constrain(rd_val == LookupOutput)
-        let rd_nonzero_and_lookup_to_rd = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::WriteLookupOutputToRD),
-            JoltR1CSInputs::Bytecode_RD,
-            JoltR1CSInputs::OpFlags(CircuitFlags::WriteLookupOutputToRD),
-        );
-        cs.constrain_eq_conditional(
-            rd_nonzero_and_lookup_to_rd,
-            JoltR1CSInputs::RD_Write,
-            JoltR1CSInputs::LookupOutput,
-        );
-        // if (rd != 0 && is_jump_instr == 1) constrain(rd_val == 4 * PC)
-        let rd_nonzero_and_jmp = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::WritePCtoRD),
-            JoltR1CSInputs::Bytecode_RD,
-            JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
-        );
-        cs.constrain_eq_conditional(
-            rd_nonzero_and_jmp,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
-            JoltR1CSInputs::RD_Write,
-        );
+    let flags = CircuitFlags::iter()
+        .map(|flag| JoltR1CSInputs::OpFlags(flag).into())
+        .chain(RV32I::iter().map(|flag| JoltR1CSInputs::InstructionFlags(flag).into()))
+        .collect();
+    cs.constrain_pack_be(flags, JoltR1CSInputs::Bytecode_Bitflags, 1);
+
+    let real_pc = 4i64 * JoltR1CSInputs::Bytecode_ELFAddress + (PC_START_ADDRESS - PC_NOOP_SHIFT);
+    let x = cs.allocate_if_else(
+        JoltR1CSInputs::Aux(AuxVariable::LeftLookupOperand).into(),
+        JoltR1CSInputs::OpFlags(CircuitFlags::LeftOperandIsPC),
+        real_pc,
+        JoltR1CSInputs::RS1_Read,
+    );
+    let y = cs.allocate_if_else(
+        JoltR1CSInputs::Aux(AuxVariable::RightLookupOperand).into(),
+        JoltR1CSInputs::OpFlags(CircuitFlags::RightOperandIsImm),
+        JoltR1CSInputs::Bytecode_Imm,
+        JoltR1CSInputs::RS2_Read,
+    );
+
+    let is_load_or_store =
+        JoltR1CSInputs::OpFlags(CircuitFlags::Load) + JoltR1CSInputs::OpFlags(CircuitFlags::Store);
+    let memory_start: i64 = memory_start.try_into().unwrap();
+    cs.constrain_eq_conditional(
+        is_load_or_store,
+        JoltR1CSInputs::RS1_Read + JoltR1CSInputs::Bytecode_Imm,
+        4 * JoltR1CSInputs::RAM_Address + memory_start - 4 * REGISTER_COUNT as i64,
+    );
 
-        let next_pc_jump = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::NextPCJump),
-            JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
-            JoltR1CSInputs::LookupOutput + 4,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS + 4
-                - 4 * JoltR1CSInputs::OpFlags(CircuitFlags::DoNotUpdatePC),
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::Load),
+        JoltR1CSInputs::RAM_Read,
+        JoltR1CSInputs::RAM_Write,
+    );
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::Load),
+        JoltR1CSInputs::RAM_Read,
+        JoltR1CSInputs::RD_Write,
+    );
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::Store),
+        JoltR1CSInputs::RS2_Read,
+        JoltR1CSInputs::RAM_Write,
+    );
+
+    let query_chunks: Vec<Variable> = (0..C)
+        .map(|i| Variable::Input(JoltR1CSInputs::ChunksQuery(i).to_index::<C>()))
+        .collect();
+    let packed_query = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(query_chunks.clone(), LOG_M);
+
+    // For the `AssertAlignedMemoryAccessInstruction` lookups, we add the `rs1` and `imm` values
+    // to obtain the memory address being accessed.
+    let add_operands = JoltR1CSInputs::InstructionFlags(ADDInstruction::default().into())
+        + JoltR1CSInputs::InstructionFlags(
+            AssertAlignedMemoryAccessInstruction::<32, 2>::default().into(),
+        )
+        + JoltR1CSInputs::InstructionFlags(
+            AssertAlignedMemoryAccessInstruction::<32, 4>::default().into(),
         );
+    cs.constrain_eq_conditional(add_operands, packed_query.clone(), x + y);
+    // Converts from unsigned to twos-complement representation
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::InstructionFlags(SUBInstruction::default().into()),
+        packed_query.clone(),
+        x - y + (0xffffffffi64 + 1),
+    );
+    let is_mul = JoltR1CSInputs::InstructionFlags(MULInstruction::default().into())
+        + JoltR1CSInputs::InstructionFlags(MULUInstruction::default().into())
+        + JoltR1CSInputs::InstructionFlags(MULHUInstruction::default().into());
+    let product = cs.allocate_prod(
+        JoltR1CSInputs::Aux(AuxVariable::Product).into(),
+        JoltR1CSInputs::RS1_Read,
+        JoltR1CSInputs::RS2_Read,
+    );
+    cs.constrain_eq_conditional(is_mul, packed_query.clone(), product);
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::InstructionFlags(MOVSIGNInstruction::default().into())
+            + JoltR1CSInputs::InstructionFlags(MOVEInstruction::default().into()),
+        packed_query.clone(),
+        x,
+    );
+
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::Assert),
+        JoltR1CSInputs::LookupOutput,
+        1,
+    );
 
-        let should_branch = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::ShouldBranch),
-            JoltR1CSInputs::OpFlags(CircuitFlags::Branch),
-            JoltR1CSInputs::LookupOutput,
+    let x_chunks: Vec<Variable> = (0..C)
+        .map(|i| Variable::Input(JoltR1CSInputs::ChunksX(i).to_index::<C>()))
+        .collect();
+    let y_chunks: Vec<Variable> = (0..C)
+        .map(|i| Variable::Input(JoltR1CSInputs::ChunksY(i).to_index::<C>()))
+        .collect();
+    let x_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(x_chunks.clone(), OPERAND_SIZE);
+    let y_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(y_chunks.clone(), OPERAND_SIZE);
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+        x_concat,
+        x,
+    );
+    cs.constrain_eq_conditional(
+        JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+        y_concat,
+        y,
+    );
+
+    // if is_shift ? chunks_query[i] == zip(chunks_x[i], chunks_y[C-1]) : chunks_query[i] == zip(chunks_x[i], chunks_y[i])
+    let is_shift = JoltR1CSInputs::InstructionFlags(SLLInstruction::default().into())
+        + JoltR1CSInputs::InstructionFlags(SRLInstruction::default().into())
+        + JoltR1CSInputs::InstructionFlags(SRAInstruction::default().into());
+    for i in 0..C {
+        let relevant_chunk_y = cs.allocate_if_else(
+            JoltR1CSInputs::Aux(AuxVariable::RelevantYChunk(i)).into(),
+            is_shift.clone(),
+            y_chunks[C - 1],
+            y_chunks[i],
         );
-        let _next_pc = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::NextPC),
-            should_branch,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress
-                + PC_START_ADDRESS
-                + JoltR1CSInputs::Bytecode_Imm,
-            next_pc_jump,
+        cs.constrain_eq_conditional(
+            JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+            query_chunks[i],
+            x_chunks[i] * (1i64 << 8) + relevant_chunk_y,
         );
     }
 
-    fn cross_step_constraints() -> Vec<OffsetEqConstraint> {
-        // If the next instruction's ELF address is not zero (i.e. it's
-        // not padding), then check the PC update.
-        let pc_constraint = OffsetEqConstraint::new(
-            (JoltR1CSInputs::Bytecode_ELFAddress, true),
-            (JoltR1CSInputs::Aux(AuxVariable::NextPC), false),
-            (
-                4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
-                true,
-            ),
-        );
+    // if (rd != 0 && update_rd_with_lookup_output == 1) constrain(rd_val == LookupOutput)
+    let rd_nonzero_and_lookup_to_rd = cs.allocate_prod(
+        JoltR1CSInputs::Aux(AuxVariable::WriteLookupOutputToRD).into(),
+        JoltR1CSInputs::Bytecode_RD,
+        JoltR1CSInputs::OpFlags(CircuitFlags::WriteLookupOutputToRD),
+    );
+    cs.constrain_eq_conditional(
+        rd_nonzero_and_lookup_to_rd,
+        JoltR1CSInputs::RD_Write,
+        JoltR1CSInputs::LookupOutput,
+    );
+    // if (rd != 0 && is_jump_instr == 1) constrain(rd_val == 4 * PC)
+    let rd_nonzero_and_jmp = cs.allocate_prod(
+        JoltR1CSInputs::Aux(AuxVariable::WritePCtoRD).into(),
+        JoltR1CSInputs::Bytecode_RD,
+        JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
+    );
+    cs.constrain_eq_conditional(
+        rd_nonzero_and_jmp,
+        4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
+        JoltR1CSInputs::RD_Write,
+    );
 
-        // If the current instruction is virtual, check that the next instruction
-        // in the trace is the next instruction in bytecode. Virtual sequences
-        // do not involve jumps or branches, so this should always hold,
-        // EXCEPT if we encounter a virtual instruction followed by a padding
-        // instruction. But that should never happen because the execution
-        // trace should always end with some return handling, which shouldn't involve
-        // any virtual sequences.
-        let virtual_sequence_constraint = OffsetEqConstraint::new(
-            (JoltR1CSInputs::OpFlags(CircuitFlags::Virtual), false),
-            (JoltR1CSInputs::Bytecode_A, true),
-            (JoltR1CSInputs::Bytecode_A + 1, false),
-        );
+    let next_pc_jump = cs.allocate_if_else(
+        JoltR1CSInputs::Aux(AuxVariable::NextPCJump).into(),
+        JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
+        JoltR1CSInputs::LookupOutput + 4,
+        4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS + 4
+            - 4 * JoltR1CSInputs::OpFlags(CircuitFlags::DoNotUpdatePC),
+    );
 
-        vec![pc_constraint, virtual_sequence_constraint]
-    }
+    let should_branch = cs.allocate_prod(
+        JoltR1CSInputs::Aux(AuxVariable::ShouldBranch).into(),
+        JoltR1CSInputs::OpFlags(CircuitFlags::Branch),
+        JoltR1CSInputs::LookupOutput,
+    );
+    let _next_pc = cs.allocate_if_else(
+        JoltR1CSInputs::Aux(AuxVariable::NextPC).into(),
+        should_branch,
+        4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS + JoltR1CSInputs::Bytecode_Imm,
+        next_pc_jump,
+    );
+}
+
+fn rv32im_cross_step_constraints() -> Vec<OffsetEqConstraint> {
+    // If the next instruction's ELF address is not zero (i.e. it's
+    // not padding), then check the PC update.
+    let pc_constraint = OffsetEqConstraint::new(
+        (JoltR1CSInputs::Bytecode_ELFAddress, true),
+        (JoltR1CSInputs::Aux(AuxVariable::NextPC), false),
+        (
+            4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
+            true,
+        ),
+    )
+    .with_label("PC update");
+
+    // If the current instruction is virtual, check that the next instruction
+    // in the trace is the next instruction in bytecode. Virtual sequences
+    // do not involve jumps or branches, so this should always hold,
+    // EXCEPT if we encounter a virtual instruction followed by a padding
+    // instruction. But that should never happen because the execution
+    // trace should always end with some return handling, which shouldn't involve
+    // any virtual sequences.
+    let virtual_sequence_constraint = OffsetEqConstraint::new(
+        (JoltR1CSInputs::OpFlags(CircuitFlags::Virtual), false),
+        (JoltR1CSInputs::Bytecode_A, true),
+        (JoltR1CSInputs::Bytecode_A + 1, false),
+    )
+    .with_label("virtual sequence");
+
+    vec![pc_constraint, virtual_sequence_constraint]
 }
@@ -0,0 +1,325 @@
+//! Export of the uniform constraint system in circom's binary formats, so that it
+//! can be consumed by external audit and constraint-analysis tooling (snarkjs etc.):
+//! - `.r1cs`: the constraints, see `write_r1cs`
+//! - `.wtns`: a sample witness, see `write_witness`
+//! - `.sym`: the name of each wire, see `write_symbols`
+//!
+//! The exported system covers a window of two consecutive steps. Wire 0 is the
+//! constant 1, wires `1..=n` are the `n` inputs of a step (in `ConstraintInput::flatten`
+//! order), and wires `n+1..=2n` are the same inputs at the next step. The uniform
+//! constraints are only applied to the first step; the cross-step constraints
+//! `condition * (a - b) == 0` relate the two.
+//!
+//! Product constraints (see `builder::ProductConstraint`) have degree greater than
+//! 2 and cannot be expressed in R1CS, so constraint systems containing them are
+//! rejected.
+
+use ark_serialize::CanonicalSerialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use super::builder::{CombinedUniformBuilder, OffsetLC};
+use super::inputs::ConstraintInput;
+use super::ops::{Variable, LC};
+use crate::field::JoltField;
+use crate::jolt::vm::JoltPolynomials;
+use crate::poly::multilinear_polynomial::MultilinearPolynomial;
+
+/// A row of one of the R1CS matrices, as (wire, coefficient) pairs sorted by wire
+type SparseRow = BTreeMap<u32, i128>;
+
+/// The bytes of the field modulus, little-endian
+pub(crate) fn modulus_bytes<F: JoltField>() -> Vec<u8> {
+    let mut bytes = field_bytes(-F::one());
+    for byte in bytes.iter_mut() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            break;
+        }
+    }
+    bytes
+}
+
+/// The canonical little-endian encoding of `value`, `F::NUM_BYTES` long
+fn field_bytes<F: JoltField>(value: F) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(F::NUM_BYTES);
+    value.serialize_uncompressed(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), F::NUM_BYTES);
+    bytes
+}
+
+fn add_terms(row: &mut SparseRow, lc: &LC, num_inputs: usize, next_step: bool, sign: i128) {
+    for term in lc.terms() {
+        let wire = match term.0 {
+            Variable::Input(index) | Variable::Auxiliary(index) => {
+                let offset = if next_step { num_inputs } else { 0 };
+                (1 + offset + index) as u32
+            }
+            Variable::Constant => 0,
+        };
+        *row.entry(wire).or_default() += sign * term.1 as i128;
+    }
+    row.retain(|_, coeff| *coeff != 0);
+}
+
+fn lc_row(lc: &LC, num_inputs: usize) -> SparseRow {
+    let mut row = SparseRow::new();
+    add_terms(&mut row, lc, num_inputs, false, 1);
+    row
+}
+
+fn offset_lc_row(lcs: &[(&OffsetLC, i128)], num_inputs: usize) -> SparseRow {
+    let mut row = SparseRow::new();
+    for ((next_step, lc), sign) in lcs {
+        add_terms(&mut row, lc, num_inputs, *next_step, *sign);
+    }
+    row
+}
+
+fn write_section(writer: &mut impl Write, section_type: u32, contents: &[u8]) -> io::Result<()> {
+    writer.write_all(&section_type.to_le_bytes())?;
+    writer.write_all(&(contents.len() as u64).to_le_bytes())?;
+    writer.write_all(contents)
+}
+
+/// Writes the constraint system in circom's `.r1cs` format (version 1). All wires
+/// other than the constant are declared as private inputs.
+pub fn write_r1cs<const C: usize, F: JoltField, I: ConstraintInput>(
+    builder: &CombinedUniformBuilder<C, F, I>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    if !builder.product_constraints().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "product constraints cannot be expressed in R1CS",
+        ));
+    }
+
+    let num_inputs = I::num_inputs::<C>();
+    let num_wires = 2 * num_inputs + 1;
+
+    let mut constraints: Vec<[SparseRow; 3]> = builder
+        .uniform_constraints()
+        .iter()
+        .map(|constraint| {
+            [
+                lc_row(&constraint.a, num_inputs),
+                lc_row(&constraint.b, num_inputs),
+                lc_row(&constraint.c, num_inputs),
+            ]
+        })
+        .collect();
+    constraints.extend(builder.offset_eq_constraints().iter().map(|constraint| {
+        [
+            offset_lc_row(&[(&constraint.cond, 1)], num_inputs),
+            offset_lc_row(&[(&constraint.a, 1), (&constraint.b, -1)], num_inputs),
+            SparseRow::new(),
+        ]
+    }));
+
+    let n8 = F::NUM_BYTES as u32;
+    let mut header = Vec::new();
+    header.extend(n8.to_le_bytes());
+    header.extend(modulus_bytes::<F>());
+    header.extend((num_wires as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes()); // public outputs
+    header.extend(0u32.to_le_bytes()); // public inputs
+    header.extend((num_wires as u32 - 1).to_le_bytes()); // private inputs
+    header.extend((num_wires as u64).to_le_bytes()); // labels
+    header.extend((constraints.len() as u32).to_le_bytes());
+
+    let mut constraint_section = Vec::new();
+    for constraint in constraints.iter() {
+        for row in constraint {
+            constraint_section.extend((row.len() as u32).to_le_bytes());
+            for (wire, coeff) in row {
+                constraint_section.extend(wire.to_le_bytes());
+                constraint_section.extend(field_bytes(F::from_i128(*coeff)));
+            }
+        }
+    }
+
+    let wire_to_label: Vec<u8> = (0..num_wires as u64)
+        .flat_map(|label| label.to_le_bytes())
+        .collect();
+
+    writer.write_all(b"r1cs")?;
+    writer.write_all(&1u32.to_le_bytes())?;
+    writer.write_all(&3u32.to_le_bytes())?;
+    write_section(writer, 1, &header)?;
+    write_section(writer, 2, &constraint_section)?;
+    write_section(writer, 3, &wire_to_label)
+}
+
+/// Writes the witness for the window starting at `step` in snarkjs' `.wtns`
+/// format (version 2). `step` may not be the last step of the trace.
+pub fn write_witness<const C: usize, F: JoltField, I: ConstraintInput>(
+    jolt_polynomials: &JoltPolynomials<F>,
+    step: usize,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let flattened_polys: Vec<&MultilinearPolynomial<F>> = I::flatten::<C>()
+        .iter()
+        .map(|var| var.get_ref(jolt_polynomials))
+        .collect();
+    let num_steps = flattened_polys[0].len();
+    if step + 1 >= num_steps {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("step {step} has no next step (trace has {num_steps} steps)"),
+        ));
+    }
+
+    let values: Vec<F> = std::iter::once(F::one())
+        .chain(flattened_polys.iter().map(|poly| poly.get_coeff(step)))
+        .chain(flattened_polys.iter().map(|poly| poly.get_coeff(step + 1)))
+        .collect();
+
+    let n8 = F::NUM_BYTES as u32;
+    let mut header = Vec::new();
+    header.extend(n8.to_le_bytes());
+    header.extend(modulus_bytes::<F>());
+    header.extend((values.len() as u32).to_le_bytes());
+
+    let witness_section: Vec<u8> = values.into_iter().flat_map(field_bytes).collect();
+
+    writer.write_all(b"wtns")?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    write_section(writer, 1, &header)?;
+    write_section(writer, 2, &witness_section)
+}
+
+/// Writes circom's `.sym` format, naming each wire after the input it holds,
+/// e.g. `step.RAM_Read` and `next.RAM_Read`.
+pub fn write_symbols<const C: usize, I: ConstraintInput>(
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let inputs = I::flatten::<C>();
+    let names = ["step", "next"];
+    for (i, (prefix, input)) in names
+        .iter()
+        .flat_map(|prefix| inputs.iter().map(move |input| (prefix, input)))
+        .enumerate()
+    {
+        let wire = i + 1;
+        writeln!(writer, "{wire},{wire},0,{prefix}.{input:?}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ark_bn254::Fr;
+    use ark_serialize::CanonicalDeserialize;
+
+    use super::*;
+    use crate::jolt::vm::rv32i_vm::C;
+    use crate::r1cs::constraints::{JoltRV32IMConstraints, R1CSConstraints};
+    use crate::r1cs::inputs::JoltR1CSInputs;
+
+    struct Reader<'a>(&'a [u8]);
+
+    impl Reader<'_> {
+        fn take(&mut self, n: usize) -> &[u8] {
+            let (head, tail) = self.0.split_at(n);
+            self.0 = tail;
+            head
+        }
+        fn u32(&mut self) -> u32 {
+            u32::from_le_bytes(self.take(4).try_into().unwrap())
+        }
+        fn u64(&mut self) -> u64 {
+            u64::from_le_bytes(self.take(8).try_into().unwrap())
+        }
+        fn field(&mut self) -> Fr {
+            Fr::deserialize_uncompressed(self.take(32)).unwrap()
+        }
+    }
+
+    /// Parses a `.r1cs` file, returning its wire count and constraints
+    pub(crate) fn parse_r1cs(bytes: &[u8]) -> (usize, Vec<[Vec<(usize, Fr)>; 3]>) {
+        let mut reader = Reader(bytes);
+        assert_eq!(reader.take(4), b"r1cs");
+        assert_eq!(reader.u32(), 1);
+        assert_eq!(reader.u32(), 3);
+
+        assert_eq!(reader.u32(), 1);
+        reader.u64();
+        assert_eq!(reader.u32(), 32);
+        assert_eq!(reader.take(32), modulus_bytes::<Fr>());
+        let num_wires = reader.u32() as usize;
+        reader.take(3 * 4 + 8);
+        let num_constraints = reader.u32() as usize;
+
+        assert_eq!(reader.u32(), 2);
+        reader.u64();
+        let constraints = (0..num_constraints)
+            .map(|_| {
+                [(); 3].map(|_| {
+                    let num_terms = reader.u32();
+                    (0..num_terms)
+                        .map(|_| (reader.u32() as usize, reader.field()))
+                        .collect()
+                })
+            })
+            .collect();
+        (num_wires, constraints)
+    }
+
+    /// Parses a `.wtns` file, returning the witness values
+    pub(crate) fn parse_wtns(bytes: &[u8]) -> Vec<Fr> {
+        let mut reader = Reader(bytes);
+        assert_eq!(reader.take(4), b"wtns");
+        assert_eq!(reader.u32(), 2);
+        assert_eq!(reader.u32(), 2);
+        assert_eq!(reader.u32(), 1);
+        reader.u64();
+        assert_eq!(reader.u32(), 32);
+        assert_eq!(reader.take(32), modulus_bytes::<Fr>());
+        let num_values = reader.u32();
+        assert_eq!(reader.u32(), 2);
+        reader.u64();
+        (0..num_values).map(|_| reader.field()).collect()
+    }
+
+    #[test]
+    fn modulus() {
+        let modulus = modulus_bytes::<Fr>();
+        assert_eq!(modulus.len(), 32);
+        // BN254 scalar field modulus, little-endian
+        assert_eq!(modulus[0], 0x01);
+        assert_eq!(modulus[31], 0x30);
+    }
+
+    #[test]
+    fn rv32im_r1cs() {
+        let builder = <JoltRV32IMConstraints as R1CSConstraints<C, Fr>>::construct_constraints(
+            1 << 10,
+            0x8000_0000,
+        );
+        let mut bytes = Vec::new();
+        write_r1cs(&builder, &mut bytes).unwrap();
+
+        let (num_wires, constraints) = parse_r1cs(&bytes);
+        assert_eq!(num_wires, 2 * JoltR1CSInputs::num_inputs::<C>() + 1);
+        assert_eq!(
+            constraints.len(),
+            builder.uniform_constraints().len() + builder.offset_eq_constraints().len()
+        );
+        for constraint in constraints.iter() {
+            for row in constraint {
+                assert!(row.iter().all(|(wire, _)| *wire < num_wires));
+                assert!(row.windows(2).all(|terms| terms[0].0 < terms[1].0));
+            }
+        }
+
+        let mut symbols = Vec::new();
+        write_symbols::<C, JoltR1CSInputs>(&mut symbols).unwrap();
+        assert_eq!(
+            String::from_utf8(symbols).unwrap().lines().count(),
+            num_wires - 1
+        );
+    }
+}
@@ -14,7 +14,9 @@ use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::poly::opening_proof::VerifierOpeningAccumulator;
 use crate::utils::transcript::Transcript;
 
+use super::constraints::CustomConstraints;
 use super::key::UniformSpartanKey;
+use super::ops::{Variable, LC};
 use super::spartan::{SpartanError, UniformSpartanProof};
 
 use crate::field::JoltField;
@@ -38,6 +40,9 @@ pub struct AuxVariableStuff<T: CanonicalSerialize + CanonicalDeserialize> {
     pub next_pc_jump: T,
     pub should_branch: T,
     pub next_pc: T,
+    /// Auxiliary variables allocated by user-defined constraints (see
+    /// `CustomConstraints`). Empty for Jolt's built-in constraints.
+    pub custom: Vec<T>,
 }
 
 impl<T: CanonicalSerialize + CanonicalDeserialize + Default> Initializable<T, usize>
@@ -68,6 +73,7 @@ impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
             &self.should_branch,
             &self.next_pc,
         ]);
+        values.extend(self.custom.iter());
         values
     }
 
@@ -85,6 +91,7 @@ impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
             &mut self.should_branch,
             &mut self.next_pc,
         ]);
+        values.extend(self.custom.iter_mut());
         values
     }
 }
@@ -189,6 +196,24 @@ impl<F: JoltField> R1CSPolynomials<F> {
             }
         }
 
+        let num_custom_aux = I::num_custom_aux();
+        let mut custom_aux = vec![vec![0u64; trace.len()]; num_custom_aux];
+        if num_custom_aux > 0 {
+            let mut columns = vec![0u64; num_custom_aux];
+            for (step_index, step) in trace.iter().enumerate() {
+                columns.fill(0);
+                I::custom_witness(step, &mut columns);
+                for (column, value) in custom_aux.iter_mut().zip(columns.iter()) {
+                    column[step_index] = *value;
+                }
+            }
+        }
+        let mut aux = AuxVariableStuff::initialize(&C);
+        aux.custom = custom_aux
+            .into_iter()
+            .map(MultilinearPolynomial::from)
+            .collect();
+
         Self {
             chunks_x: chunks_x
                 .into_iter()
@@ -205,7 +230,7 @@ impl<F: JoltField> R1CSPolynomials<F> {
                 .try_into()
                 .unwrap(),
             // Actual aux variable polynomials will be computed afterwards
-            aux: AuxVariableStuff::initialize(&C),
+            aux,
         }
     }
 }
@@ -267,6 +292,20 @@ pub trait ConstraintInput: Clone + Copy + Debug + PartialEq + Sync + Send + 'sta
         }
     }
 
+    /// The number of auxiliary variables allocated by user-defined constraints,
+    /// stored in `AuxVariableStuff::custom`.
+    fn num_custom_aux() -> usize {
+        0
+    }
+
+    /// Writes the values of the custom aux variables that are computed from the
+    /// execution trace rather than by the constraint builder, for one step.
+    fn custom_witness<InstructionSet: JoltInstructionSet>(
+        _step: &JoltTraceStep<InstructionSet>,
+        _columns: &mut [u64],
+    ) {
+    }
+
     /// Gets an immutable reference to a Jolt polynomial/commitment/opening
     /// corresponding to the given constraint input.
     fn get_ref<'a, T: CanonicalSerialize + CanonicalDeserialize + Sync>(
@@ -415,6 +454,110 @@ impl ConstraintInput for JoltR1CSInputs {
     }
 }
 
+/// Jolt's constraint inputs extended with the auxiliary variables of the
+/// user-defined constraints `E`. Jolt's own inputs keep their indices, so
+/// linear combinations of `JoltR1CSInputs` can be used unchanged in a builder
+/// over these inputs.
+pub enum CustomR1CSInputs<E> {
+    Jolt(JoltR1CSInputs),
+    Aux(usize, PhantomData<fn() -> E>),
+}
+
+impl<E> CustomR1CSInputs<E> {
+    /// The `index`-th auxiliary variable of the custom constraints.
+    pub fn aux(index: usize) -> Self {
+        Self::Aux(index, PhantomData)
+    }
+}
+
+// Implemented by hand so that `E` needn't implement these traits itself
+impl<E> Clone for CustomR1CSInputs<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for CustomR1CSInputs<E> {}
+
+impl<E> Debug for CustomR1CSInputs<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jolt(input) => write!(f, "{:?}", input),
+            Self::Aux(index, _) => write!(f, "CustomAux({})", index),
+        }
+    }
+}
+
+impl<E> PartialEq for CustomR1CSInputs<E> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Jolt(a), Self::Jolt(b)) => a == b,
+            (Self::Aux(a, _), Self::Aux(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<E> From<JoltR1CSInputs> for CustomR1CSInputs<E> {
+    fn from(input: JoltR1CSInputs) -> Self {
+        Self::Jolt(input)
+    }
+}
+
+// As in `impl_r1cs_input_lc_conversions!(JoltR1CSInputs, 4)`
+impl<E: CustomConstraints> From<CustomR1CSInputs<E>> for Variable {
+    fn from(input: CustomR1CSInputs<E>) -> Self {
+        Variable::Input(input.to_index::<4>())
+    }
+}
+
+impl<E: CustomConstraints> From<CustomR1CSInputs<E>> for LC {
+    fn from(input: CustomR1CSInputs<E>) -> Self {
+        Variable::from(input).into()
+    }
+}
+
+impl<E: CustomConstraints> ConstraintInput for CustomR1CSInputs<E> {
+    fn flatten<const C: usize>() -> Vec<Self> {
+        JoltR1CSInputs::flatten::<C>()
+            .into_iter()
+            .map(Self::Jolt)
+            .chain((0..E::NUM_AUX).map(Self::aux))
+            .collect()
+    }
+
+    fn num_custom_aux() -> usize {
+        E::NUM_AUX
+    }
+
+    fn custom_witness<InstructionSet: JoltInstructionSet>(
+        step: &JoltTraceStep<InstructionSet>,
+        columns: &mut [u64],
+    ) {
+        E::witness(step, columns)
+    }
+
+    fn get_ref<'a, T: CanonicalSerialize + CanonicalDeserialize + Sync>(
+        &self,
+        jolt: &'a JoltStuff<T>,
+    ) -> &'a T {
+        match self {
+            Self::Jolt(input) => input.get_ref(jolt),
+            Self::Aux(index, _) => &jolt.r1cs.aux.custom[*index],
+        }
+    }
+
+    fn get_ref_mut<'a, T: CanonicalSerialize + CanonicalDeserialize + Sync>(
+        &self,
+        jolt: &'a mut JoltStuff<T>,
+    ) -> &'a mut T {
+        match self {
+            Self::Jolt(input) => input.get_ref_mut(jolt),
+            Self::Aux(index, _) => &mut jolt.r1cs.aux.custom[*index],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ark_bn254::Fr;
@@ -437,6 +580,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn custom_inputs_indices() {
+        const C: usize = 4;
+        struct TwoAux;
+        impl CustomConstraints for TwoAux {
+            const NUM_AUX: usize = 2;
+            fn uniform_constraints<const N: usize, F: JoltField>(
+                _: &mut crate::r1cs::builder::R1CSBuilder<N, F, CustomR1CSInputs<Self>>,
+                _: u64,
+            ) {
+            }
+        }
+
+        let num_jolt_inputs = JoltR1CSInputs::num_inputs::<C>();
+        assert_eq!(
+            CustomR1CSInputs::<TwoAux>::num_inputs::<C>(),
+            num_jolt_inputs + 2
+        );
+        for var in JoltR1CSInputs::flatten::<C>() {
+            assert_eq!(
+                CustomR1CSInputs::<TwoAux>::from(var).to_index::<C>(),
+                var.to_index::<C>()
+            );
+        }
+        assert_eq!(
+            CustomR1CSInputs::<TwoAux>::aux(1).to_index::<C>(),
+            num_jolt_inputs + 1
+        );
+    }
+
     #[test]
     fn get_ref() {
         const C: usize = 4;
@@ -1,4 +1,7 @@
+use std::any::Any;
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use sha3::Sha3_256;
@@ -14,13 +17,16 @@ use sha3::Digest;
 
 use crate::utils::math::Math;
 
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct UniformSpartanKey<const C: usize, I: ConstraintInput, F: JoltField> {
     _inputs: PhantomData<I>,
     pub uniform_r1cs: UniformR1CS<F>,
 
     pub offset_eq_r1cs: CrossStepR1CS<F>,
 
+    /// Constraints of degree greater than 2, see `builder::ProductConstraint`
+    pub product_constraints: Vec<SparseProductConstraint<F>>,
+
     /// Number of constraints across all steps padded to nearest power of 2
     pub num_cons_total: usize,
 
@@ -35,7 +41,7 @@ pub struct UniformSpartanKey<const C: usize, I: ConstraintInput, F: JoltField> {
 pub type Coeff<F> = (usize, usize, F);
 
 /// Sparse representation of a single R1CS matrix.
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct SparseConstraints<F: JoltField> {
     /// Non-zero, non-constant coefficients
     pub vars: Vec<Coeff<F>>,
@@ -55,7 +61,7 @@ impl<F: JoltField> SparseConstraints<F> {
 
 /// Sparse representation of all 3 uniform R1CS matrices. Uniform matrices can be repeated over a number of steps
 /// and efficiently evaluated by taking advantage of the structure.
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct UniformR1CS<F: JoltField> {
     pub a: SparseConstraints<F>,
     pub b: SparseConstraints<F>,
@@ -71,7 +77,7 @@ pub struct UniformR1CS<F: JoltField> {
 /// CrossStepR1CSConstraint only supports a single additional equality constraint. 'a' holds the equality (something minus something),
 /// 'b' holds the condition. 'a' * 'b' == 0. Each SparseEqualityItem stores a uniform_column (pointing to a variable) and an offset
 /// suggesting which other step to point to.
-#[derive(Debug, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, Debug, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CrossStepR1CSConstraint<F: JoltField> {
     pub eq: SparseEqualityItem<F>,
     pub condition: SparseEqualityItem<F>,
@@ -91,7 +97,7 @@ impl<F: JoltField> CrossStepR1CSConstraint<F> {
 }
 
 /// CrossStepR1CS stores a vector of CrossStepR1CSConstraint
-#[derive(CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
 pub struct CrossStepR1CS<F: JoltField> {
     pub constraints: Vec<CrossStepR1CSConstraint<F>>,
 }
@@ -118,7 +124,7 @@ impl<F: JoltField> CrossStepR1CS<F> {
 
 /// Represents a single constraint row where the variables are either from the current step (offset = false)
 /// or from the proceeding step (offset = true).
-#[derive(CanonicalSerialize, CanonicalDeserialize, Debug, PartialEq)]
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug, PartialEq)]
 pub struct SparseEqualityItem<F: JoltField> {
     /// (uniform_col, offset, val)
     pub offset_vars: Vec<(usize, bool, F)>,
@@ -135,20 +141,61 @@ impl<F: JoltField> SparseEqualityItem<F> {
     }
 }
 
+/// Sparse representation of a linear combination of the variables of a single step.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug, PartialEq)]
+pub struct SparseLinearCombination<F: JoltField> {
+    /// (uniform_col, val)
+    pub vars: Vec<(usize, F)>,
+
+    pub constant: F,
+}
+
+impl<F: JoltField> SparseLinearCombination<F> {
+    pub fn empty() -> Self {
+        Self {
+            vars: vec![],
+            constant: F::zero(),
+        }
+    }
+
+    /// Evaluates the linear combination given the value of each uniform column.
+    pub fn evaluate(&self, column_value: impl Fn(usize) -> F) -> F {
+        self.vars
+            .iter()
+            .map(|(col, coeff)| *coeff * column_value(*col))
+            .sum::<F>()
+            + self.constant
+    }
+}
+
+/// A single constraint `factors[0] * factors[1] * ... == result`.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, Debug, PartialEq)]
+pub struct SparseProductConstraint<F: JoltField> {
+    pub factors: Vec<SparseLinearCombination<F>>,
+    pub result: SparseLinearCombination<F>,
+}
+
 impl<const C: usize, F: JoltField, I: ConstraintInput> UniformSpartanKey<C, I, F> {
     pub fn from_builder(constraint_builder: &CombinedUniformBuilder<C, F, I>) -> Self {
         let uniform_r1cs = constraint_builder.materialize_uniform();
         let offset_eq_r1cs = constraint_builder.materialize_offset_eq();
+        let product_constraints = constraint_builder.materialize_product_constraints();
 
         let total_rows = constraint_builder.constraint_rows().next_power_of_two();
         let num_steps = constraint_builder.uniform_repeat().next_power_of_two(); // TODO(JP): Number of steps no longer need to be padded.
 
-        let vk_digest = Self::digest(&uniform_r1cs, &offset_eq_r1cs, num_steps);
+        let vk_digest = Self::digest(
+            &uniform_r1cs,
+            &offset_eq_r1cs,
+            &product_constraints,
+            num_steps,
+        );
 
         Self {
             _inputs: PhantomData,
             uniform_r1cs,
             offset_eq_r1cs,
+            product_constraints,
             num_cons_total: total_rows,
             num_steps,
             vk_digest,
@@ -185,6 +232,28 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> UniformSpartanKey<C, I, F
         row_count.next_power_of_two().log_2()
     }
 
+    /// Degree of the highest-degree product constraint, or 0 if there are none.
+    pub fn product_constraint_degree(&self) -> usize {
+        self.product_constraints
+            .iter()
+            .map(|constraint| constraint.factors.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Uniform columns referenced by the product constraints, in ascending order.
+    pub fn product_constraint_columns(&self) -> Vec<usize> {
+        let mut columns: Vec<usize> = self
+            .product_constraints
+            .iter()
+            .flat_map(|constraint| constraint.factors.iter().chain([&constraint.result]))
+            .flat_map(|lc| lc.vars.iter().map(|(col, _)| *col))
+            .collect();
+        columns.sort_unstable();
+        columns.dedup();
+        columns
+    }
+
     /// (Prover) Evaluates RLC over A, B, C of: [A(r_x, y_var || r_x_step), A_shift(..)] for all y_var
     #[tracing::instrument(skip_all, name = "UniformSpartanKey::evaluate_r1cs_mle_rlc")]
     pub fn evaluate_matrix_mle_partial(&self, r_constr: &[F], r_step: &[F], r_rlc: F) -> Vec<F> {
@@ -406,7 +475,12 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> UniformSpartanKey<C, I, F
     }
 
     /// Returns the digest of the r1cs shape
-    fn digest(uniform_r1cs: &UniformR1CS<F>, offset_eq: &CrossStepR1CS<F>, num_steps: usize) -> F {
+    fn digest(
+        uniform_r1cs: &UniformR1CS<F>,
+        offset_eq: &CrossStepR1CS<F>,
+        product_constraints: &[SparseProductConstraint<F>],
+        num_steps: usize,
+    ) -> F {
         let mut hash_bytes = Vec::new();
         uniform_r1cs.serialize_compressed(&mut hash_bytes).unwrap();
         let mut offset_eq_bytes = Vec::new();
@@ -414,6 +488,12 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> UniformSpartanKey<C, I, F
             .serialize_compressed(&mut offset_eq_bytes)
             .unwrap();
         hash_bytes.extend(offset_eq_bytes);
+        // Only hashed when present, so that the digest of a plain R1CS is unchanged
+        if !product_constraints.is_empty() {
+            product_constraints
+                .serialize_compressed(&mut hash_bytes)
+                .unwrap();
+        }
         hash_bytes.extend(num_steps.to_be_bytes().to_vec());
         let mut hasher = Sha3_256::new();
         hasher.update(hash_bytes);
@@ -439,3 +519,43 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> UniformSpartanKey<C, I, F
         map_to_field(&hasher.finalize())
     }
 }
+
+/// Uniform Spartan keys by padded trace length and input address, so that
+/// proving (or verifying) many executions of a program derives each key once.
+/// The keys of any `ConstraintInput` and field share one cache; clones share
+/// the keys already derived.
+#[derive(Clone, Default)]
+pub struct SpartanKeyCache {
+    keys: Arc<Mutex<SpartanKeys>>,
+}
+
+type SpartanKeys = HashMap<(usize, u64), Arc<dyn Any + Send + Sync>>;
+
+impl SpartanKeyCache {
+    /// The key for `padded_num_steps` steps of a program reading its inputs from
+    /// `memory_start`, derived by `setup` unless it is cached.
+    pub fn get_or_insert<const C: usize, I: ConstraintInput, F: JoltField>(
+        &self,
+        padded_num_steps: usize,
+        memory_start: u64,
+        setup: impl FnOnce() -> UniformSpartanKey<C, I, F>,
+    ) -> Arc<UniformSpartanKey<C, I, F>> {
+        let index = (padded_num_steps, memory_start);
+        let cached = self.keys.lock().unwrap().get(&index).cloned();
+        if let Some(key) = cached.and_then(|key| key.downcast().ok()) {
+            return key;
+        }
+        let key = Arc::new(setup());
+        self.keys.lock().unwrap().insert(index, key.clone());
+        key
+    }
+
+    /// Number of keys cached
+    pub fn len(&self) -> usize {
+        self.keys.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
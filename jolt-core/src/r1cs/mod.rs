@@ -2,6 +2,8 @@ pub mod inputs;
 
 pub mod builder;
 pub mod constraints;
+pub mod export;
 pub mod key;
 pub mod ops;
+pub mod solidity;
 pub mod spartan;
@@ -0,0 +1,321 @@
+//! Generation of the Solidity library evaluating the R1CS matrix MLEs for the
+//! EVM verifier (see `jolt-evm-verifier`). The generated library mirrors
+//! `UniformSpartanKey::evaluate_matrix_mle_full`, with the constraints of the
+//! key unrolled into straight-line code, so it has to be regenerated whenever
+//! the constraints change.
+
+use alloy_primitives::hex;
+use ark_serialize::CanonicalSerialize;
+use std::io::{self, Write};
+
+use super::inputs::ConstraintInput;
+use super::key::{SparseConstraints, SparseEqualityItem, UniformSpartanKey};
+use crate::field::JoltField;
+use crate::utils::math::Math;
+
+/// Name of the generated Solidity library
+pub const MATRIX_LIBRARY_NAME: &str = "JoltR1CSMatrix";
+
+/// `value` as a Solidity `Fr` literal
+fn fr_literal<F: JoltField>(value: F) -> String {
+    let mut bytes = vec![];
+    value.serialize_uncompressed(&mut bytes).unwrap();
+    bytes.reverse();
+    format!("Fr.wrap(0x{})", hex::encode(bytes))
+}
+
+/// `coeff * rest`, omitting unit coefficients
+fn term<F: JoltField>(coeff: F, rest: &str) -> String {
+    if coeff == F::one() {
+        rest.to_string()
+    } else {
+        format!("{} * {}", fr_literal(coeff), rest)
+    }
+}
+
+/// Writes the function evaluating one matrix, returning its evaluation on the
+/// current step and the next step separately.
+fn write_matrix_function<F: JoltField>(
+    writer: &mut impl Write,
+    name: &str,
+    constraints: &SparseConstraints<F>,
+    cross_step: &[&SparseEqualityItem<F>],
+    first_cross_step_row: usize,
+) -> io::Result<()> {
+    writeln!(
+        writer,
+        "    function {name}(Fr[] memory row, Fr[] memory col, Fr col_eq_constant)"
+    )?;
+    writeln!(writer, "        private")?;
+    writeln!(writer, "        pure")?;
+    writeln!(writer, "        returns (Fr uniform, Fr cross_step)")?;
+    writeln!(writer, "    {{")?;
+    for (row, col, coeff) in constraints.vars.iter() {
+        let rest = format!("row[{row}] * col[{col}]");
+        writeln!(
+            writer,
+            "        uniform = uniform + {};",
+            term(*coeff, &rest)
+        )?;
+    }
+
+    writeln!(writer, "        Fr constants = Fr.wrap(0);")?;
+    for (row, coeff) in constraints.consts.iter() {
+        let rest = format!("row[{row}]");
+        writeln!(
+            writer,
+            "        constants = constants + {};",
+            term(*coeff, &rest)
+        )?;
+    }
+    for (i, item) in cross_step.iter().enumerate() {
+        if item.constant != F::zero() {
+            let rest = format!("row[{}]", first_cross_step_row + i);
+            writeln!(
+                writer,
+                "        constants = constants + {};",
+                term(item.constant, &rest)
+            )?;
+        }
+    }
+    writeln!(
+        writer,
+        "        uniform = uniform + constants * col_eq_constant;"
+    )?;
+
+    for (i, item) in cross_step.iter().enumerate() {
+        for (col, offset, coeff) in item.offset_vars.iter() {
+            let target = if *offset { "cross_step" } else { "uniform" };
+            let rest = format!("row[{}] * col[{col}]", first_cross_step_row + i);
+            writeln!(
+                writer,
+                "        {target} = {target} + {};",
+                term(*coeff, &rest)
+            )?;
+        }
+    }
+    writeln!(writer, "    }}")?;
+    writeln!(writer)
+}
+
+/// Writes a Solidity library evaluating the MLEs of the A, B and C matrices of
+/// `key` at the point of the inner Spartan sumcheck, see
+/// `UniformSpartanKey::evaluate_matrix_mle_full`. The library imports `Fr` from
+/// `./Fr.sol` and is meant to sit next to it in `jolt-evm-verifier/src/subprotocols`.
+///
+/// Product constraints are checked by a separate sumcheck that the EVM verifier
+/// does not support, so keys containing them are rejected.
+pub fn write_matrix_library<const C: usize, I: ConstraintInput, F: JoltField>(
+    key: &UniformSpartanKey<C, I, F>,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    if !key.product_constraints.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "product constraints are not supported by the EVM verifier",
+        ));
+    }
+
+    let constraint_row_bits = (key.uniform_r1cs.num_rows + 1).next_power_of_two().log_2();
+    let var_bits = key.num_vars_uniform_padded().log_2() + 1;
+    let first_cross_step_row = key.uniform_r1cs.num_rows;
+    let constraints = &key.offset_eq_r1cs.constraints;
+    let eq_items: Vec<_> = constraints
+        .iter()
+        .map(|constraint| &constraint.eq)
+        .collect();
+    let condition_items: Vec<_> = constraints
+        .iter()
+        .map(|constraint| &constraint.condition)
+        .collect();
+
+    writeln!(writer, "// SPDX-License-Identifier: MIT")?;
+    writeln!(
+        writer,
+        "// Generated by jolt_core::r1cs::solidity, do not edit."
+    )?;
+    writeln!(writer, "pragma solidity >=0.8.0;")?;
+    writeln!(writer)?;
+    writeln!(writer, "import {{Fr}} from \"./Fr.sol\";")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "/// Evaluates the MLEs of the R1CS matrices at the inner sumcheck point"
+    )?;
+    writeln!(writer, "library {MATRIX_LIBRARY_NAME} {{")?;
+    writeln!(
+        writer,
+        "    uint256 constant CONSTRAINT_ROW_BITS = {constraint_row_bits};"
+    )?;
+    writeln!(writer, "    uint256 constant VAR_BITS = {var_bits};")?;
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "    /// @param rx_constr The constraint part of the outer sumcheck point"
+    )?;
+    writeln!(
+        writer,
+        "    /// @param ry_var The variable part of the inner sumcheck point"
+    )?;
+    writeln!(
+        writer,
+        "    /// @param r_cross_step The first variable of the inner sumcheck point"
+    )?;
+    writeln!(
+        writer,
+        "    function evaluate(Fr[] memory rx_constr, Fr[] memory ry_var, Fr r_cross_step)"
+    )?;
+    writeln!(writer, "        internal")?;
+    writeln!(writer, "        pure")?;
+    writeln!(writer, "        returns (Fr, Fr, Fr)")?;
+    writeln!(writer, "    {{")?;
+    writeln!(
+        writer,
+        concat!(
+            "        require(rx_constr.length == CONSTRAINT_ROW_BITS, ",
+            "\"Incorrect row point length\");"
+        )
+    )?;
+    writeln!(
+        writer,
+        "        require(ry_var.length == VAR_BITS, \"Incorrect column point length\");"
+    )?;
+    writeln!(writer, "        Fr[] memory row = eq_evals(rx_constr);")?;
+    writeln!(writer, "        Fr[] memory col = eq_evals(ry_var);")?;
+    writeln!(
+        writer,
+        "        // The constant column is the first one past the padded variables"
+    )?;
+    writeln!(writer, "        Fr col_eq_constant = ry_var[0];")?;
+    writeln!(writer, "        for (uint256 i = 1; i < VAR_BITS; i++) {{")?;
+    writeln!(
+        writer,
+        "            col_eq_constant = col_eq_constant * (Fr.wrap(1) - ry_var[i]);"
+    )?;
+    writeln!(writer, "        }}")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "        (Fr a, Fr a_next) = A(row, col, col_eq_constant);"
+    )?;
+    writeln!(
+        writer,
+        "        (Fr b, Fr b_next) = B(row, col, col_eq_constant);"
+    )?;
+    writeln!(writer, "        (Fr c,) = C(row, col, col_eq_constant);")?;
+    writeln!(writer, "        Fr r_uniform = Fr.wrap(1) - r_cross_step;")?;
+    writeln!(
+        writer,
+        concat!(
+            "        return (r_uniform * a + r_cross_step * a_next, ",
+            "r_uniform * b + r_cross_step * b_next, r_uniform * c);"
+        )
+    )?;
+    writeln!(writer, "    }}")?;
+    writeln!(writer)?;
+
+    writeln!(
+        writer,
+        "    /// The evaluations of the eq polynomial at `r` over the boolean hypercube"
+    )?;
+    writeln!(
+        writer,
+        "    function eq_evals(Fr[] memory r) private pure returns (Fr[] memory evals) {{"
+    )?;
+    writeln!(writer, "        evals = new Fr[](1 << r.length);")?;
+    writeln!(writer, "        evals[0] = Fr.wrap(1);")?;
+    writeln!(writer, "        uint256 size = 1;")?;
+    writeln!(writer, "        for (uint256 j = 0; j < r.length; j++) {{")?;
+    writeln!(writer, "            for (uint256 i = size; i > 0; i--) {{")?;
+    writeln!(writer, "                Fr scalar = evals[i - 1];")?;
+    writeln!(writer, "                evals[2 * i - 1] = scalar * r[j];")?;
+    writeln!(
+        writer,
+        "                evals[2 * i - 2] = scalar - evals[2 * i - 1];"
+    )?;
+    writeln!(writer, "            }}")?;
+    writeln!(writer, "            size *= 2;")?;
+    writeln!(writer, "        }}")?;
+    writeln!(writer, "    }}")?;
+    writeln!(writer)?;
+
+    write_matrix_function(
+        writer,
+        "A",
+        &key.uniform_r1cs.a,
+        &eq_items,
+        first_cross_step_row,
+    )?;
+    write_matrix_function(
+        writer,
+        "B",
+        &key.uniform_r1cs.b,
+        &condition_items,
+        first_cross_step_row,
+    )?;
+    write_matrix_function(writer, "C", &key.uniform_r1cs.c, &[], first_cross_step_row)?;
+    writeln!(writer, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+
+    use super::*;
+    use crate::jolt::vm::rv32i_vm::C;
+    use crate::r1cs::constraints::{JoltRV32IMConstraints, R1CSConstraints};
+    use crate::r1cs::inputs::JoltR1CSInputs;
+
+    #[test]
+    fn rv32im_matrix_library() {
+        let builder = <JoltRV32IMConstraints as R1CSConstraints<C, Fr>>::construct_constraints(
+            1 << 10,
+            0x8000_0000,
+        );
+        let key = UniformSpartanKey::<C, JoltR1CSInputs, Fr>::from_builder(&builder);
+        let mut bytes = Vec::new();
+        write_matrix_library(&key, &mut bytes).unwrap();
+        let source = String::from_utf8(bytes).unwrap();
+
+        let constraint_row_bits = (key.uniform_r1cs.num_rows + 1).next_power_of_two().log_2();
+        assert!(source.contains(&format!(
+            "uint256 constant CONSTRAINT_ROW_BITS = {constraint_row_bits};"
+        )));
+        let var_bits = key.num_vars_uniform_padded().log_2() + 1;
+        assert!(source.contains(&format!("uint256 constant VAR_BITS = {var_bits};")));
+
+        // One line per non-zero entry of the matrices
+        let uniform_entries: usize = [
+            &key.uniform_r1cs.a,
+            &key.uniform_r1cs.b,
+            &key.uniform_r1cs.c,
+        ]
+        .iter()
+        .map(|matrix| matrix.vars.len())
+        .sum();
+        let cross_step_entries: usize = key
+            .offset_eq_r1cs
+            .constraints
+            .iter()
+            .map(|constraint| {
+                constraint.eq.offset_vars.len() + constraint.condition.offset_vars.len()
+            })
+            .sum();
+        let matrix_entries = source
+            .lines()
+            .filter(|line| line.contains("* col["))
+            .count();
+        assert_eq!(matrix_entries, uniform_entries + cross_step_entries);
+
+        // Every row and column index is in bounds
+        for line in source.lines() {
+            for (prefix, bits) in [("row[", constraint_row_bits), ("col[", var_bits)] {
+                for index in line.split(prefix).skip(1) {
+                    let index: usize = index[..index.find(']').unwrap()].parse().unwrap();
+                    assert!(index < 1 << bits);
+                }
+            }
+        }
+    }
+}
@@ -31,7 +31,7 @@ use crate::{
 use super::builder::CombinedUniformBuilder;
 use super::inputs::ConstraintInput;
 
-use rayon::prelude::*;
+use crate::utils::parallel::prelude::*;
 
 #[derive(Clone, Debug, Eq, PartialEq, Error)]
 pub enum SpartanError {
@@ -59,6 +59,14 @@ pub enum SpartanError {
     #[error("InvalidInnerSumcheckClaim")]
     InvalidInnerSumcheckClaim,
 
+    /// returned when the product constraint sumcheck proof fails, or is missing
+    #[error("InvalidProductSumcheckProof")]
+    InvalidProductSumcheckProof,
+
+    /// returned when the final product constraint sumcheck claim fails
+    #[error("InvalidProductSumcheckClaim")]
+    InvalidProductSumcheckClaim,
+
     /// returned if the supplied witness is not of the right length
     #[error("InvalidWitnessLength")]
     InvalidWitnessLength,
@@ -86,9 +94,166 @@ pub struct UniformSpartanProof<
     pub(crate) shift_sumcheck_claim: F,
     pub(crate) claimed_witness_evals: Vec<F>,
     pub(crate) shift_sumcheck_witness_evals: Vec<F>,
+    /// Only present if the constraint system has product constraints
+    pub(crate) product_proof: Option<ProductConstraintProof<F, ProofTranscript>>,
     _marker: PhantomData<ProofTranscript>,
 }
 
+/// Proves that every product constraint (see `builder::ProductConstraint`) holds at
+/// every step, via a zero-check over the steps:
+///   \sum_t eq(tau, t) * \sum_j gamma^j * (\prod_i L_ji(t) - R_j(t)) = 0
+/// The round polynomials have degree one more than the highest-degree constraint.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProductConstraintProof<F: JoltField, ProofTranscript: Transcript> {
+    pub(crate) sumcheck_proof: SumcheckInstanceProof<F, ProofTranscript>,
+    /// Evaluations of the columns referenced by the product constraints
+    /// (`UniformSpartanKey::product_constraint_columns`) at the sumcheck point
+    pub(crate) witness_evals: Vec<F>,
+}
+
+impl<F: JoltField, ProofTranscript: Transcript> ProductConstraintProof<F, ProofTranscript> {
+    #[tracing::instrument(skip_all, name = "ProductConstraintProof::prove")]
+    fn prove<const C: usize, I: ConstraintInput>(
+        constraint_builder: &CombinedUniformBuilder<C, F, I>,
+        key: &UniformSpartanKey<C, I, F>,
+        flattened_polys: &[&MultilinearPolynomial<F>],
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Self {
+        let num_rounds = key.num_steps.log_2();
+        let tau: Vec<F> = (0..num_rounds)
+            .map(|_i| transcript.challenge_scalar())
+            .collect();
+        let gamma: F = transcript.challenge_scalar();
+
+        // Polynomials: [eq(tau, t), L_00(t), L_01(t), ..., R_0(t), L_10(t), ...]
+        let num_steps = flattened_polys[0].len();
+        let mut polys = vec![MultilinearPolynomial::from(EqPolynomial::evals(&tau))];
+        for constraint in constraint_builder.product_constraints() {
+            for lc in constraint.factors.iter().chain([&constraint.result]) {
+                let evals: Vec<F> = (0..num_steps)
+                    .into_par_iter()
+                    .map(|step| F::from_i128(lc.evaluate_row(flattened_polys, step)))
+                    .collect();
+                polys.push(MultilinearPolynomial::from(evals));
+            }
+        }
+
+        let degrees: Vec<usize> = constraint_builder
+            .product_constraints()
+            .iter()
+            .map(|constraint| constraint.degree())
+            .collect();
+        let comb_func = |poly_evals: &[F]| -> F {
+            combine_product_constraints(&degrees, gamma, &poly_evals[1..]) * poly_evals[0]
+        };
+
+        let (sumcheck_proof, r, _claims) = SumcheckInstanceProof::prove_arbitrary(
+            &F::zero(),
+            num_rounds,
+            &mut polys,
+            comb_func,
+            key.product_constraint_degree() + 1,
+            transcript,
+        );
+        drop_in_background_thread(polys);
+
+        let columns: Vec<&MultilinearPolynomial<F>> = key
+            .product_constraint_columns()
+            .into_iter()
+            .map(|col| flattened_polys[col])
+            .collect();
+        let (witness_evals, chis) = MultilinearPolynomial::batch_evaluate(&columns, &r);
+        opening_accumulator.append(
+            &columns,
+            DensePolynomial::new(chis),
+            r,
+            &witness_evals,
+            transcript,
+        );
+
+        Self {
+            sumcheck_proof,
+            witness_evals,
+        }
+    }
+
+    fn verify<const C: usize, I: ConstraintInput, PCS>(
+        &self,
+        key: &UniformSpartanKey<C, I, F>,
+        flattened_commitments: &[&PCS::Commitment],
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), SpartanError>
+    where
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    {
+        let num_rounds = key.num_steps.log_2();
+        let tau: Vec<F> = (0..num_rounds)
+            .map(|_i| transcript.challenge_scalar())
+            .collect();
+        let gamma: F = transcript.challenge_scalar();
+
+        let (claim, r) = self
+            .sumcheck_proof
+            .verify(
+                F::zero(),
+                num_rounds,
+                key.product_constraint_degree() + 1,
+                transcript,
+            )
+            .map_err(|_| SpartanError::InvalidProductSumcheckProof)?;
+
+        let columns = key.product_constraint_columns();
+        if self.witness_evals.len() != columns.len() {
+            return Err(SpartanError::InvalidWitnessLength);
+        }
+        let column_eval = |col: usize| self.witness_evals[columns.binary_search(&col).unwrap()];
+
+        let mut degrees = Vec::with_capacity(key.product_constraints.len());
+        let mut lc_evals = vec![];
+        for constraint in key.product_constraints.iter() {
+            degrees.push(constraint.factors.len());
+            for lc in constraint.factors.iter().chain([&constraint.result]) {
+                lc_evals.push(lc.evaluate(column_eval));
+            }
+        }
+        let claim_expected = EqPolynomial::new(tau).evaluate(&r)
+            * combine_product_constraints(&degrees, gamma, &lc_evals);
+        if claim != claim_expected {
+            return Err(SpartanError::InvalidProductSumcheckClaim);
+        }
+
+        let column_commitments: Vec<_> = columns
+            .iter()
+            .map(|col| flattened_commitments[*col])
+            .collect();
+        opening_accumulator.append(
+            &column_commitments,
+            r,
+            &self.witness_evals.iter().collect::<Vec<_>>(),
+            transcript,
+        );
+
+        Ok(())
+    }
+}
+
+/// Computes \sum_j gamma^j * (\prod_i L_ji - R_j), where `lc_evals` holds the factors of
+/// each constraint followed by its result, and `degrees` the number of factors of each.
+fn combine_product_constraints<F: JoltField>(degrees: &[usize], gamma: F, lc_evals: &[F]) -> F {
+    let mut combined = F::zero();
+    let mut gamma_power = F::one();
+    let mut offset = 0;
+    for degree in degrees {
+        let product: F = lc_evals[offset..offset + degree].iter().copied().product();
+        combined += gamma_power * (product - lc_evals[offset + degree]);
+        gamma_power *= gamma;
+        offset += degree + 1;
+    }
+    combined
+}
+
 impl<const C: usize, I, F, ProofTranscript> UniformSpartanProof<C, I, F, ProofTranscript>
 where
     I: ConstraintInput,
@@ -317,6 +482,19 @@ where
             transcript,
         );
 
+        /* Sumcheck 4: Product constraints (only if there are any) */
+        let product_proof = if constraint_builder.product_constraints().is_empty() {
+            None
+        } else {
+            Some(ProductConstraintProof::prove(
+                constraint_builder,
+                key,
+                &flattened_polys,
+                opening_accumulator,
+                transcript,
+            ))
+        };
+
         // Outer sumcheck claims: [A(r_x), B(r_x), C(r_x)]
         let outer_sumcheck_claims = (
             outer_sumcheck_claims[0],
@@ -332,6 +510,7 @@ where
             shift_sumcheck_claim,
             claimed_witness_evals,
             shift_sumcheck_witness_evals,
+            product_proof,
             _marker: PhantomData,
         })
     }
@@ -468,6 +647,18 @@ where
             transcript,
         );
 
+        /* Sumcheck 4: Product constraints */
+        match (&self.product_proof, key.product_constraints.is_empty()) {
+            (None, true) => {}
+            (Some(product_proof), false) => product_proof.verify(
+                key,
+                &flattened_commitments,
+                opening_accumulator,
+                transcript,
+            )?,
+            _ => return Err(SpartanError::InvalidProductSumcheckProof),
+        }
+
         Ok(())
     }
 }
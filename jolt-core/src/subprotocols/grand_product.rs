@@ -7,11 +7,11 @@ use crate::poly::dense_mlpoly::DensePolynomial;
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::poly::split_eq_poly::SplitEqPolynomial;
 use crate::utils::math::Math;
+use crate::utils::parallel::prelude::*;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::Transcript;
 use ark_serialize::*;
 use itertools::Itertools;
-use rayon::prelude::*;
 
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
 pub struct BatchedGrandProductLayerProof<F: JoltField, ProofTranscript: Transcript> {
@@ -44,6 +44,25 @@ where
     pub quark_proof: Option<QuarkGrandProductProof<PCS, ProofTranscript>>,
 }
 
+impl<PCS, ProofTranscript> BatchedGrandProductProof<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    /// The sumchecks of the proof: one per GKR layer, then the Quarks sumcheck if any
+    pub(crate) fn sumcheck_proofs(
+        &self,
+    ) -> Vec<&SumcheckInstanceProof<PCS::Field, ProofTranscript>> {
+        let mut proofs: Vec<_> = self.gkr_layers.iter().map(|layer| &layer.proof).collect();
+        proofs.extend(
+            self.quark_proof
+                .as_ref()
+                .map(|proof| proof.sumcheck_proof()),
+        );
+        proofs
+    }
+}
+
 pub trait BatchedGrandProduct<F, PCS, ProofTranscript>: Sized
 where
     F: JoltField,
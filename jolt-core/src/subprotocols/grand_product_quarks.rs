@@ -11,11 +11,11 @@ use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvalu
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::subprotocols::QuarkHybridLayerDepth;
 use crate::utils::math::Math;
+use crate::utils::parallel::prelude::*;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use ark_serialize::*;
 use ark_std::{One, Zero};
 use itertools::Itertools;
-use rayon::prelude::*;
 use std::marker::PhantomData;
 use thiserror::Error;
 
@@ -300,6 +300,10 @@ where
     PCS: CommitmentScheme<ProofTranscript>,
     ProofTranscript: Transcript,
 {
+    pub(crate) fn sumcheck_proof(&self) -> &SumcheckInstanceProof<PCS::Field, ProofTranscript> {
+        &self.sumcheck_proof
+    }
+
     /// Computes a grand product proof using the Section 5 technique from Quarks Paper
     /// First - Extends the evals of v to create an f poly, then commits to it and evals
     /// Then - Constructs a g poly and preforms sumcheck proof that sum == 0
@@ -1,4 +1,5 @@
 use super::sumcheck::SumcheckInstanceProof;
+use crate::utils::parallel::{self, prelude::*};
 use crate::{
     field::JoltField,
     poly::{
@@ -16,7 +17,6 @@ use crate::{
         transcript::{AppendToTranscript, Transcript},
     },
 };
-use rayon::prelude::*;
 
 pub struct ShoutProof<F: JoltField, ProofTranscript: Transcript> {
     core_piop_sumcheck: SumcheckInstanceProof<F, ProofTranscript>,
@@ -49,7 +49,7 @@ impl<F: JoltField, ProofTranscript: Transcript> ShoutProof<F, ProofTranscript> {
         let span = tracing::span!(tracing::Level::INFO, "compute F");
         let _guard = span.enter();
 
-        let num_chunks = rayon::current_num_threads()
+        let num_chunks = parallel::current_num_threads()
             .next_power_of_two()
             .min(read_addresses.len());
         let chunk_size = (read_addresses.len() / num_chunks).max(1);
@@ -135,7 +135,7 @@ impl<F: JoltField, ProofTranscript: Transcript> ShoutProof<F, ProofTranscript> {
             previous_claim = univariate_poly.evaluate(&r_j);
 
             // Bind polynomials
-            rayon::join(
+            parallel::join(
                 || ra.bind_parallel(r_j, BindingOrder::LowToHigh),
                 || val.bind_parallel(r_j, BindingOrder::LowToHigh),
             );
@@ -275,7 +275,7 @@ pub fn prove_core_shout_piop<F: JoltField, ProofTranscript: Transcript>(
         previous_claim = univariate_poly.evaluate(&r_j);
 
         // Bind polynomials
-        rayon::join(
+        parallel::join(
             || ra.bind_parallel(r_j, BindingOrder::LowToHigh),
             || val.bind_parallel(r_j, BindingOrder::LowToHigh),
         );
@@ -523,7 +523,7 @@ pub fn prove_booleanity<F: JoltField, ProofTranscript: Transcript>(
         previous_claim = univariate_poly.evaluate(&r_j);
 
         // Bind polynomials
-        rayon::join(
+        parallel::join(
             || D.bind_parallel(r_j, BindingOrder::LowToHigh),
             || H.bind_parallel(r_j, BindingOrder::LowToHigh),
         );
@@ -664,7 +664,7 @@ pub fn prove_raf_evaluation<F: JoltField, ProofTranscript: Transcript>(
         previous_claim = univariate_poly.evaluate(&r_j);
 
         // Bind polynomials
-        rayon::join(
+        parallel::join(
             || ra.bind_parallel(r_j, BindingOrder::LowToHigh),
             || int.bind_parallel(r_j, BindingOrder::LowToHigh),
         );
@@ -14,9 +14,9 @@ use crate::poly::unipoly::UniPoly;
 use crate::subprotocols::grand_product_quarks::QuarkGrandProductBase;
 use crate::subprotocols::QuarkHybridLayerDepth;
 use crate::utils::math::Math;
+use crate::utils::parallel::prelude::*;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::Transcript;
-use rayon::prelude::*;
 
 /// A special bottom layer of a grand product, where boolean flags are used to
 /// toggle the other inputs (fingerprints) going into the rest of the tree.
@@ -11,10 +11,11 @@ use crate::poly::split_eq_poly::SplitEqPolynomial;
 use crate::poly::unipoly::{CompressedUniPoly, UniPoly};
 use crate::utils::errors::ProofVerifyError;
 use crate::utils::mul_0_optimized;
+use crate::utils::parallel::{self, prelude::*};
+use crate::utils::pool::ScratchPool;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use ark_serialize::*;
-use rayon::prelude::*;
 use std::marker::PhantomData;
 
 pub trait Bindable<F: JoltField>: Sync {
@@ -119,47 +120,48 @@ impl<F: JoltField, ProofTranscript: Transcript> SumcheckInstanceProof<F, ProofTr
             assert_eq!(&sum, claim, "Sumcheck claim is wrong");
         }
 
+        let pool = ScratchPool::global();
         for _round in 0..num_rounds {
-            // Vector storing evaluations of combined polynomials g(x) = P_0(x) * ... P_{num_polys} (x)
-            // for points {0, ..., |g(x)|}
-            let mut eval_points = vec![F::zero(); combined_degree];
-
             let mle_half = polys[0].len() / 2;
 
-            let accum: Vec<Vec<F>> = (0..mle_half)
+            // Vector storing evaluations of combined polynomials g(x) = P_0(x) * ... P_{num_polys} (x)
+            // for points {0, ..., |g(x)|}. Each rayon task sums its terms into a
+            // scratch vector from the pool, rather than allocating one per term.
+            let mut eval_points = (0..mle_half)
                 .into_par_iter()
-                .map(|poly_term_i| {
-                    let mut accum = vec![F::zero(); combined_degree];
-                    // TODO(moodlezoup): Optimize
-                    let evals: Vec<_> = polys
-                        .iter()
-                        .map(|poly| {
-                            poly.sumcheck_evals(
-                                poly_term_i,
-                                combined_degree,
-                                BindingOrder::HighToLow,
-                            )
-                        })
-                        .collect();
-                    for j in 0..combined_degree {
-                        let evals_j: Vec<_> = evals.iter().map(|x| x[j]).collect();
-                        accum[j] += comb_func(&evals_j);
-                    }
-
-                    accum
-                })
-                .collect();
-
-            eval_points
-                .par_iter_mut()
-                .enumerate()
-                .for_each(|(poly_i, eval_point)| {
-                    *eval_point = accum
-                        .par_iter()
-                        .take(mle_half)
-                        .map(|mle| mle[poly_i])
-                        .sum::<F>();
-                });
+                .fold(
+                    || pool.take(combined_degree, F::zero()),
+                    |mut accum, poly_term_i| {
+                        // TODO(moodlezoup): Optimize
+                        let evals: Vec<_> = polys
+                            .iter()
+                            .map(|poly| {
+                                poly.sumcheck_evals(
+                                    poly_term_i,
+                                    combined_degree,
+                                    BindingOrder::HighToLow,
+                                )
+                            })
+                            .collect();
+                        let mut evals_j = pool.take(evals.len(), F::zero());
+                        for j in 0..combined_degree {
+                            for (eval_j, eval) in evals_j.iter_mut().zip(evals.iter()) {
+                                *eval_j = eval[j];
+                            }
+                            accum[j] += comb_func(&evals_j);
+                        }
+                        accum
+                    },
+                )
+                .reduce(
+                    || pool.take(combined_degree, F::zero()),
+                    |mut sum, accum| {
+                        for (sum, eval) in sum.iter_mut().zip(accum.iter()) {
+                            *sum += *eval;
+                        }
+                        sum
+                    },
+                );
 
             eval_points.insert(1, previous_claim - eval_points[0]);
             let univariate_poly = UniPoly::from_evals(&eval_points);
@@ -298,7 +300,7 @@ impl<F: JoltField, ProofTranscript: Transcript> SumcheckInstanceProof<F, ProofTr
         claim_per_round = poly.evaluate(&r_i);
 
         // bound all tables to the verifier's challenge
-        let (_, mut poly_B) = rayon::join(
+        let (_, mut poly_B) = parallel::join(
             || poly_A.bound_poly_var_top_zero_optimized(&r_i),
             || {
                 // Simulates `poly_B.bound_poly_var_top(&r_i)` by
@@ -310,7 +312,7 @@ impl<F: JoltField, ProofTranscript: Transcript> SumcheckInstanceProof<F, ProofTr
                 let W_iter = (0..len).into_par_iter().map(witness_value);
                 let Z_iter = W_iter
                     .chain(one.into_par_iter())
-                    .chain(rayon::iter::repeatn(zero, len));
+                    .chain(parallel::repeatn(zero, len));
                 let left_iter = Z_iter.clone().take(len);
                 let right_iter = Z_iter.skip(len).take(len);
                 let B = left_iter
@@ -346,7 +348,7 @@ impl<F: JoltField, ProofTranscript: Transcript> SumcheckInstanceProof<F, ProofTr
             claim_per_round = poly.evaluate(&r_i);
 
             // bound all tables to the verifier's challenge
-            rayon::join(
+            parallel::join(
                 || poly_A.bound_poly_var_top_zero_optimized(&r_i),
                 || poly_B.bound_poly_var_top_zero_optimized(&r_i),
             );
@@ -1,4 +1,5 @@
 use super::sumcheck::SumcheckInstanceProof;
+use crate::utils::parallel::{self, prelude::*};
 use crate::{
     field::{JoltField, OptimizedMul},
     poly::{
@@ -15,7 +16,6 @@ use crate::{
         transcript::{AppendToTranscript, Transcript},
     },
 };
-use rayon::prelude::*;
 
 /// The Twist+Shout paper gives two different prover algorithms for the read-checking
 /// and write-checking algorithms in Twist, called the "local algorithm" and
@@ -250,7 +250,7 @@ fn prove_read_write_checking_local<F: JoltField, ProofTranscript: Transcript>(
     let mut r_cycle: Vec<F> = Vec::with_capacity(T.log_2());
     let mut r_address: Vec<F> = Vec::with_capacity(K.log_2());
 
-    let num_chunks = rayon::current_num_threads().next_power_of_two().min(T);
+    let num_chunks = parallel::current_num_threads().next_power_of_two().min(T);
     let chunk_size = T / num_chunks;
 
     #[cfg(test)]
@@ -724,7 +724,7 @@ fn prove_read_write_checking_local<F: JoltField, ProofTranscript: Transcript>(
         drop(_inner_guard);
         drop(inner_span);
 
-        rayon::join(
+        parallel::join(
             || wv.bind_parallel(r_j, BindingOrder::LowToHigh),
             || eq_r_prime.bind_parallel(r_j, BindingOrder::LowToHigh),
         );
@@ -1101,7 +1101,7 @@ pub fn prove_val_evaluation<F: JoltField, ProofTranscript: Transcript>(
         previous_claim = univariate_poly.evaluate(&r_j);
 
         // Bind polynomials
-        rayon::join(
+        parallel::join(
             || inc.bind_parallel(r_j, BindingOrder::LowToHigh),
             || lt.bind_parallel(r_j, BindingOrder::LowToHigh),
         );
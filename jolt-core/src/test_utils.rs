@@ -0,0 +1,285 @@
+//! Helpers for testing code built on Jolt, exported with the `test-utils`
+//! feature: random polynomials and commitments, proptest strategies for them,
+//! a transcript with predictable challenges, and tampered proofs for negative
+//! tests, e.g. that a service rejects proofs whose output was altered. The
+//! feature also exports `poly::commitment::mock::MockCommitScheme`, which
+//! "commits" to polynomials in the clear, for fast end-to-end tests.
+//!
+//! None of this is sound; it must not be used outside of tests.
+
+use std::borrow::Borrow;
+
+use ark_ec::CurveGroup;
+use ark_serialize::CanonicalSerialize;
+use proptest::prelude::*;
+use rand_core::RngCore;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::JoltInstructionSet;
+use crate::jolt::subtable::JoltSubtableSet;
+use crate::jolt::vm::read_write_memory::MemoryArgument;
+use crate::jolt::vm::JoltProof;
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::multilinear_polynomial::MultilinearPolynomial;
+use crate::r1cs::inputs::ConstraintInput;
+use crate::utils::transcript::Transcript;
+
+/// A multilinear polynomial in `num_vars` variables with random evaluations.
+pub fn random_polynomial<F: JoltField>(
+    num_vars: usize,
+    rng: &mut impl RngCore,
+) -> MultilinearPolynomial<F> {
+    MultilinearPolynomial::from(
+        (0..1 << num_vars)
+            .map(|_| F::random(rng))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// A random polynomial in `num_vars` variables and its commitment.
+pub fn random_commitment<PCS, ProofTranscript>(
+    setup: &PCS::Setup,
+    num_vars: usize,
+    rng: &mut impl RngCore,
+) -> (MultilinearPolynomial<PCS::Field>, PCS::Commitment)
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    let poly = random_polynomial(num_vars, rng);
+    let commitment = PCS::commit(&poly, setup);
+    (poly, commitment)
+}
+
+pub fn arb_field<F: JoltField>() -> impl Strategy<Value = F> {
+    prop::collection::vec(any::<u8>(), F::NUM_BYTES).prop_map(|bytes| F::from_bytes(&bytes))
+}
+
+pub fn arb_polynomial<F: JoltField>(
+    num_vars: usize,
+) -> impl Strategy<Value = MultilinearPolynomial<F>> {
+    prop::collection::vec(arb_field::<F>(), 1 << num_vars).prop_map(MultilinearPolynomial::from)
+}
+
+pub fn arb_point<F: JoltField>(num_vars: usize) -> impl Strategy<Value = Vec<F>> {
+    prop::collection::vec(arb_field::<F>(), num_vars)
+}
+
+pub fn arb_tamper() -> impl Strategy<Value = Tamper> {
+    prop::sample::select(Tamper::iter().collect::<Vec<_>>())
+}
+
+/// A transcript whose challenges are 1, 2, 3, ... whatever was absorbed, which
+/// records everything absorbed. Lets tests work out a protocol's messages by
+/// hand, or check what a prover absorbs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MockTranscript {
+    /// The absorbed messages, in order, in their compressed canonical encoding
+    pub absorbed: Vec<Vec<u8>>,
+    challenges: u64,
+}
+
+impl MockTranscript {
+    fn absorb(&mut self, value: &impl CanonicalSerialize) {
+        let mut bytes = vec![];
+        value.serialize_compressed(&mut bytes).unwrap();
+        self.absorbed.push(bytes);
+    }
+}
+
+impl Transcript for MockTranscript {
+    fn new(label: &'static [u8]) -> Self {
+        Self {
+            absorbed: vec![label.to_vec()],
+            challenges: 0,
+        }
+    }
+
+    #[cfg(test)]
+    fn compare_to(&mut self, _other: Self) {}
+
+    fn append_message(&mut self, msg: &'static [u8]) {
+        self.absorbed.push(msg.to_vec());
+    }
+
+    fn append_bytes(&mut self, bytes: &[u8]) {
+        self.absorbed.push(bytes.to_vec());
+    }
+
+    fn append_u64(&mut self, x: u64) {
+        self.absorbed.push(x.to_be_bytes().to_vec());
+    }
+
+    fn append_scalar<F: JoltField>(&mut self, scalar: &F) {
+        self.absorb(scalar);
+    }
+
+    fn append_scalars<F: JoltField>(&mut self, scalars: &[impl Borrow<F>]) {
+        for scalar in scalars {
+            self.append_scalar(scalar.borrow());
+        }
+    }
+
+    fn append_point<G: CurveGroup>(&mut self, point: &G) {
+        self.absorb(&point.into_affine());
+    }
+
+    fn append_points<G: CurveGroup>(&mut self, points: &[G]) {
+        for point in points {
+            self.append_point(point);
+        }
+    }
+
+    fn challenge_scalar<F: JoltField>(&mut self) -> F {
+        self.challenges += 1;
+        F::from_u64(self.challenges)
+    }
+
+    fn challenge_vector<F: JoltField>(&mut self, len: usize) -> Vec<F> {
+        (0..len).map(|_| self.challenge_scalar()).collect()
+    }
+
+    fn challenge_scalar_powers<F: JoltField>(&mut self, len: usize) -> Vec<F> {
+        let q: F = self.challenge_scalar();
+        let mut q_powers = vec![F::one(); len];
+        for i in 1..len {
+            q_powers[i] = q_powers[i - 1] * q;
+        }
+        q_powers
+    }
+}
+
+/// An alteration of a valid proof, which the verifier must reject.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum Tamper {
+    /// Claims a different output: flips a bit of the first output byte, or adds
+    /// an output byte if there is none
+    Outputs,
+    /// Claims a different input, as for `Outputs`
+    Inputs,
+    /// Claims the opposite panic flag
+    Panic,
+    /// Claims the proof is of another program
+    ProgramHash,
+    /// Claims the proof is for another verifier key
+    VerifierKey,
+    /// Removes the opening proof
+    OpeningProof,
+}
+
+impl Tamper {
+    pub fn apply<
+        const C: usize,
+        const M: usize,
+        I,
+        F,
+        PCS,
+        InstructionSet,
+        Subtables,
+        ProofTranscript,
+        MemoryProof,
+    >(
+        self,
+        proof: &mut JoltProof<
+            C,
+            M,
+            I,
+            F,
+            PCS,
+            InstructionSet,
+            Subtables,
+            ProofTranscript,
+            MemoryProof,
+        >,
+    ) where
+        I: ConstraintInput,
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        InstructionSet: JoltInstructionSet,
+        Subtables: JoltSubtableSet<F>,
+        ProofTranscript: Transcript,
+        MemoryProof: MemoryArgument<F, PCS, ProofTranscript>,
+    {
+        match self {
+            Tamper::Outputs => flip_first_byte(&mut proof.program_io.outputs),
+            Tamper::Inputs => flip_first_byte(&mut proof.program_io.inputs),
+            Tamper::Panic => proof.program_io.panic = !proof.program_io.panic,
+            Tamper::ProgramHash => proof.metadata.program_hash[0] ^= 1,
+            Tamper::VerifierKey => proof.metadata.verifier_key_digest[0] ^= 1,
+            Tamper::OpeningProof => proof.opening_proof = None,
+        }
+    }
+}
+
+fn flip_first_byte(bytes: &mut Vec<u8>) {
+    match bytes.first_mut() {
+        Some(byte) => *byte ^= 1,
+        None => bytes.push(1),
+    }
+}
+
+/// Flips bit `bit` (modulo the number of bits) of a serialized proof, key or
+/// commitment. The result may fail to deserialize, or deserialize into a value
+/// that fails to verify.
+pub fn flip_bit(bytes: &mut [u8], bit: usize) {
+    let bit = bit % (bytes.len() * 8);
+    bytes[bit / 8] ^= 1 << (bit % 8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::commitment::hyperkzg::{
+        HyperKZG, HyperKZGProof, HyperKZGProverKey, HyperKZGSRS, HyperKZGVerifierKey,
+    };
+    use crate::poly::multilinear_polynomial::PolynomialEvaluation;
+    use crate::utils::transcript::KeccakTranscript;
+    use ark_bn254::{Bn254, Fr};
+    use ark_serialize::CanonicalDeserialize;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn mock_transcript_challenges() {
+        let mut transcript = MockTranscript::new(b"test");
+        transcript.append_u64(7);
+        assert_eq!(transcript.challenge_scalar::<Fr>(), Fr::from(1u64));
+        assert_eq!(
+            transcript.challenge_scalar_powers::<Fr>(3),
+            vec![Fr::from(1u64), Fr::from(2u64), Fr::from(4u64)]
+        );
+        assert_eq!(transcript.absorbed.len(), 2);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn hyperkzg_rejects_flipped_bits(
+            poly in arb_polynomial::<Fr>(3),
+            point in arb_point::<Fr>(3),
+            bit in any::<usize>(),
+        ) {
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+            let srs = HyperKZGSRS::setup(&mut rng, 8);
+            let (pk, vk): (HyperKZGProverKey<Bn254>, HyperKZGVerifierKey<Bn254>) = srs.trim(8);
+            let commitment = HyperKZG::<_, KeccakTranscript>::commit(&pk, &poly).unwrap();
+            let eval = poly.evaluate(&point);
+            let proof =
+                HyperKZG::open(&pk, &poly, &point, &eval, &mut KeccakTranscript::new(b"test"))
+                    .unwrap();
+
+            let mut bytes = vec![];
+            proof.serialize_compressed(&mut bytes).unwrap();
+            flip_bit(&mut bytes, bit);
+            if let Ok(proof) = HyperKZGProof::<Bn254>::deserialize_compressed(&bytes[..]) {
+                let mut transcript = KeccakTranscript::new(b"test");
+                prop_assert!(
+                    HyperKZG::verify(&vk, &commitment, &point, &eval, &proof, &mut transcript)
+                        .is_err()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! serde support for types with a canonical (arkworks) encoding, for storing and
+//! transporting proofs, commitments and keys through serde-based APIs. A value is
+//! encoded as its compressed canonical bytes: a `0x`-prefixed hex string in
+//! human-readable formats (e.g. JSON), raw bytes otherwise (e.g. CBOR, postcard).
+//! Decoding validates the value, as `Serializable::deserialize_from_bytes` does.
+//!
+//! With the `canonical-serde` feature, Jolt's proofs, commitments and verifier
+//! keys implement `Serialize` and `Deserialize` this way. Other types can use the
+//! encoding with `#[serde(with = "jolt_core::utils::canonical_serde")]`.
+
+use std::fmt;
+
+use alloy_primitives::hex;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: CanonicalSerialize,
+    S: Serializer,
+{
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .map_err(serde::ser::Error::custom)?;
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode_prefixed(bytes))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: CanonicalDeserialize,
+    D: Deserializer<'de>,
+{
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_str(HexVisitor)?
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)?
+    };
+    let mut reader = bytes.as_slice();
+    let value = T::deserialize_compressed(&mut reader).map_err(de::Error::custom)?;
+    if !reader.is_empty() {
+        return Err(de::Error::invalid_length(
+            bytes.len(),
+            &"exactly the canonical encoding",
+        ));
+    }
+    Ok(value)
+}
+
+struct HexVisitor;
+
+impl<'de> Visitor<'de> for HexVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        hex::decode(v).map_err(E::custom)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    // Formats without a bytes type encode them as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` through `serialize`/`deserialize` for
+/// a type with the given generic parameters (in brackets) and where clause, if the
+/// `canonical-serde` feature is enabled.
+macro_rules! impl_canonical_serde {
+    ([$($generics:tt)*] $ty:ty $(where $($bounds:tt)*)?) => {
+        #[cfg(feature = "canonical-serde")]
+        impl<$($generics)*> serde::Serialize for $ty
+        where
+            $ty: ark_serialize::CanonicalSerialize,
+            $($($bounds)*)?
+        {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::utils::canonical_serde::serialize(self, serializer)
+            }
+        }
+
+        #[cfg(feature = "canonical-serde")]
+        impl<'de, $($generics)*> serde::Deserialize<'de> for $ty
+        where
+            $ty: ark_serialize::CanonicalDeserialize,
+            $($($bounds)*)?
+        {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                $crate::utils::canonical_serde::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_canonical_serde;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Affine};
+    use ark_ec::AffineRepr;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Point {
+        #[serde(with = "super")]
+        point: G1Affine,
+        #[serde(with = "super")]
+        scalar: Fr,
+    }
+
+    #[test]
+    fn round_trip() {
+        let value = Point {
+            point: G1Affine::generator(),
+            scalar: Fr::from(7u64),
+        };
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert!(json.contains("\"0x"));
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), value);
+
+        let bytes = postcard::to_allocvec(&value).unwrap();
+        assert_eq!(postcard::from_bytes::<Point>(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut json = serde_json::to_value(Point {
+            point: G1Affine::generator(),
+            scalar: Fr::from(7u64),
+        })
+        .unwrap();
+        let scalar = json["scalar"].as_str().unwrap().to_owned() + "00";
+        json["scalar"] = scalar.into();
+        assert!(serde_json::from_value::<Point>(json).is_err());
+    }
+}
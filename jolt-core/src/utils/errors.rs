@@ -1,3 +1,4 @@
+use ark_serialize::SerializationError;
 use core::fmt::Debug;
 use thiserror::Error;
 
@@ -18,4 +19,85 @@ pub enum ProofVerifyError {
     KeyLengthError(usize, usize),
     #[error("Invalid key length: {0}, expected power of 2")]
     InvalidKeyLength(usize),
+    #[error("Malformed proof: {0}")]
+    Serialization(#[from] SerializationError),
+    #[error("Dev-mode proofs cannot be verified")]
+    DevProof,
+    #[error("Proof is of a different program")]
+    ProgramMismatch,
+    #[error("Proof was made for a different verifier key")]
+    VerifierKeyMismatch,
+    #[error("Proof was made by Jolt {0}, expected {1}")]
+    VersionMismatch(String, String),
+    #[error("Proof was made with a different configuration")]
+    ConfigMismatch,
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Proving was cancelled")]
+pub struct ProvingCancelled;
+
+/// The guest panicked instead of returning a value. The proof of the execution
+/// attests to the panic.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("guest {}", .message.as_deref().unwrap_or("panicked"))]
+pub struct GuestPanic {
+    /// The panic message the guest wrote, if any (see `JoltDevice::panic_message`)
+    pub message: Option<String>,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SecurityError {
+    #[error("Challenges from an extension of degree {0} are not supported")]
+    UnsupportedExtensionDegree(u32),
+    #[error("{0} repetitions of the subprotocols are not supported")]
+    UnsupportedRepetitions(u32),
+    #[error("{0} bits of soundness is below the target of {1} bits")]
+    InsufficientSecurity(u32, u32),
+}
+
+#[derive(Error, Debug)]
+pub enum ProofFormatError {
+    #[error("Not a serialized Jolt proof")]
+    InvalidMagic,
+    #[error("Unsupported proof format version {0}, expected version {1}")]
+    UnsupportedVersion(u32, u32),
+    #[error("Proof is over a different field")]
+    FieldMismatch,
+    #[error("Proof uses commitment scheme {0}, expected {1}")]
+    CommitmentSchemeMismatch(String, String),
+    #[error("Proof is of a different program")]
+    ProgramMismatch,
+    #[error("Public I/O in the proof header does not match the proof")]
+    IoMismatch,
+    #[error("Invalid proof JSON: {0}")]
+    Json(String),
+    #[error("{0} unexpected bytes after the proof")]
+    TrailingBytes(usize),
+    #[error("Failed to (de)serialize proof: {0}")]
+    Serialization(#[from] SerializationError),
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ConfigError {
+    #[error("{0} must be nonzero")]
+    ZeroSize(&'static str),
+    #[error("Chunk size {0} is not a power of 2")]
+    ChunkSizeNotPowerOfTwo(usize),
+    #[error("Chunk size {0} is larger than the maximum trace length {1}")]
+    ChunkTooLarge(usize, usize),
+    #[error("Only one of a chunk size and a memory budget can be set")]
+    ChunkSizeAndMemoryBudget,
+    #[error("Zero-knowledge proofs are not supported")]
+    ZkUnsupported,
+    #[error(transparent)]
+    Security(#[from] SecurityError),
+    #[error("Config requires commitment scheme {0}, but proofs use {1}")]
+    PcsMismatch(String, String),
+    #[error("Trace of length {0} is longer than the maximum trace length {1}")]
+    TraceTooLong(usize, usize),
+    #[error("Proving needs an estimated {0} bytes, over the memory budget of {1} bytes")]
+    OverMemoryBudget(usize, usize),
+    #[error("No chunk size fits in the memory budget of {0} bytes")]
+    NoChunkFits(usize),
 }
@@ -0,0 +1,37 @@
+//! Loading large serialized artifacts, e.g. cached PCS setups and verifier keys,
+//! through memory maps, so that a prover starts without first copying them into
+//! buffers: the OS pages the file in as it is deserialized, and shares the pages
+//! between processes loading the same file.
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, Compress, Validate};
+use memmap2::Mmap;
+
+/// Maps the file at `path` into memory, read-only
+pub fn map_file(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // SAFETY: the map is only read, and the file must not be modified while it
+    // is mapped. Writes to it in place would be seen mid-deserialization, and
+    // truncating it makes reads past the new end raise SIGBUS, killing the
+    // process rather than failing with an error. The files this crate writes are
+    // replaced by renames, never in place, which leaves existing maps intact.
+    unsafe { Mmap::map(&file) }
+}
+
+/// Deserializes the file at `path` through a memory map. Validating the points
+/// and field elements is most of the time spent loading a large setup, so
+/// trusted local files can skip it with `Validate::No`. That skips the curve and
+/// subgroup checks of the points, so it must not be used on untrusted files: a
+/// point outside the prime-order subgroup can break the soundness of a verifier.
+pub fn deserialize_file<T: CanonicalDeserialize>(
+    path: &Path,
+    compress: Compress,
+    validate: Validate,
+) -> io::Result<T> {
+    let mmap = map_file(path)?;
+    T::deserialize_with_mode(&mmap[..], compress, validate)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -2,13 +2,19 @@
 use crate::field::JoltField;
 
 use ark_std::test_rng;
-use rayon::prelude::*;
+use parallel::prelude::*;
 
+pub mod canonical_serde;
 pub mod errors;
 pub mod gaussian_elimination;
 pub mod instruction_utils;
 pub mod math;
+pub mod mmap;
+pub mod parallel;
+pub mod pool;
 pub mod profiling;
+#[cfg(not(feature = "parallel"))]
+mod sequential;
 pub mod sol_types;
 pub mod thread;
 pub mod transcript;
@@ -69,7 +75,7 @@ macro_rules! join_conditional {
         }
         #[cfg(not(feature = "icicle"))]
         {
-            rayon::join($f1, $f2)
+            $crate::utils::parallel::join($f1, $f2)
         }
     }};
 }
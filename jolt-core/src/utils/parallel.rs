@@ -0,0 +1,19 @@
+//! The parallelism of the prover and verifier. With the `parallel` feature (on
+//! by default) this is rayon. Without it, the same API runs everything on the
+//! calling thread, in order (see `utils::sequential`): the crate then neither
+//! depends on rayon nor spawns threads, so it builds for targets without them,
+//! e.g. wasm without atomics, and schedules work deterministically, which helps
+//! debugging.
+//!
+//! Code using parallel iterators imports `utils::parallel::prelude::*` instead
+//! of rayon's prelude, and calls `parallel::join` etc. instead of rayon's.
+
+#[cfg(feature = "parallel")]
+pub use rayon::{
+    current_num_threads, current_thread_index, iter::repeatn, join, prelude, slice::Chunks,
+};
+
+#[cfg(not(feature = "parallel"))]
+pub use super::sequential::{
+    current_num_threads, current_thread_index, join, prelude, repeatn, Chunks,
+};
@@ -0,0 +1,181 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use super::parallel;
+
+type FreeLists = HashMap<TypeId, Vec<Box<dyn Any + Send>>>;
+
+/// A pool of scratch vectors for the prover's hot paths (sumcheck round
+/// evaluations, MSM buckets), which would otherwise allocate and free vectors
+/// of the same sizes over and over. A vector taken with `take` goes back to the
+/// pool when dropped, and later `take`s of the same element type reuse it.
+///
+/// Vectors of any element type share one pool, so it need not be generic over
+/// the field or group. Free vectors are kept per rayon thread, so threads don't
+/// contend for them, and at most `max_free` of each type per thread.
+pub struct ScratchPool {
+    shards: Vec<Mutex<FreeLists>>,
+    max_free: usize,
+    reused: AtomicUsize,
+    allocated: AtomicUsize,
+}
+
+/// How many `ScratchPool::take`s were served from the pool, and how many had to
+/// allocate a new vector
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolStats {
+    pub reused: usize,
+    pub allocated: usize,
+}
+
+static GLOBAL: LazyLock<ScratchPool> = LazyLock::new(|| ScratchPool::new(4));
+
+impl ScratchPool {
+    pub fn new(max_free: usize) -> Self {
+        Self {
+            shards: (0..=parallel::current_num_threads())
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            max_free,
+            reused: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    /// The pool used by the prover, cleared after each proof (see `clear`)
+    pub fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    /// A vector of `len` copies of `value`, reusing a free vector if there is one
+    pub fn take<T: Clone + Send + 'static>(&self, len: usize, value: T) -> Scratch<'_, T> {
+        let free = self
+            .shard()
+            .lock()
+            .unwrap()
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|free| free.pop());
+        let mut vec = match free {
+            Some(vec) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                *vec.downcast::<Vec<T>>().unwrap()
+            }
+            None => {
+                self.allocated.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(len)
+            }
+        };
+        vec.clear();
+        vec.resize(len, value);
+        Scratch { vec, pool: self }
+    }
+
+    /// Frees the vectors in the pool, e.g. once a proof is done, so that they
+    /// don't hold on to the peak memory of the last one
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.lock().unwrap().clear();
+        }
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            reused: self.reused.load(Ordering::Relaxed),
+            allocated: self.allocated.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The free lists of the current thread. Threads outside of rayon's pool
+    /// share the first one.
+    fn shard(&self) -> &Mutex<FreeLists> {
+        let index = parallel::current_thread_index().map_or(0, |index| index + 1);
+        &self.shards[index % self.shards.len()]
+    }
+
+    fn give<T: Send + 'static>(&self, vec: Vec<T>) {
+        if vec.capacity() == 0 {
+            return;
+        }
+        let mut shard = self.shard().lock().unwrap();
+        let free = shard.entry(TypeId::of::<T>()).or_default();
+        if free.len() < self.max_free {
+            free.push(Box::new(vec));
+        }
+    }
+}
+
+/// A vector taken from a `ScratchPool`, returned to it when dropped
+pub struct Scratch<'a, T: Send + 'static> {
+    vec: Vec<T>,
+    pool: &'a ScratchPool,
+}
+
+impl<T: Send + 'static> Scratch<'_, T> {
+    /// Takes the vector out of the pool for good
+    pub fn into_vec(mut self) -> Vec<T> {
+        std::mem::take(&mut self.vec)
+    }
+}
+
+impl<T: Send + 'static> Deref for Scratch<'_, T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.vec
+    }
+}
+
+impl<T: Send + 'static> DerefMut for Scratch<'_, T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.vec
+    }
+}
+
+impl<T: Send + 'static> Drop for Scratch<'_, T> {
+    fn drop(&mut self) {
+        self.pool.give(std::mem::take(&mut self.vec));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::{One, Zero};
+
+    #[test]
+    fn take_reuses_vectors() {
+        let pool = ScratchPool::new(2);
+        let mut scratch = pool.take(8, Fr::one());
+        scratch[0] = Fr::zero();
+        let ptr = scratch.as_ptr();
+        drop(scratch);
+
+        // The freed vector is reused and reinitialized
+        let scratch = pool.take(4, Fr::one());
+        assert_eq!(scratch.as_ptr(), ptr);
+        assert_eq!(*scratch, vec![Fr::one(); 4]);
+        // Vectors of other types are not
+        let other = pool.take(4, 0u64);
+        assert_eq!(
+            pool.stats(),
+            PoolStats {
+                reused: 1,
+                allocated: 2
+            }
+        );
+
+        // Vectors taken out for good are not returned
+        let _ = scratch.into_vec();
+        drop(other);
+        pool.take(4, Fr::one());
+        assert_eq!(pool.stats().allocated, 3);
+
+        pool.clear();
+        pool.take(4, 0u64);
+        assert_eq!(pool.stats().allocated, 4);
+    }
+}
@@ -0,0 +1,583 @@
+//! Sequential stand-ins for the parts of rayon's API the crate uses, in place
+//! of rayon when the `parallel` feature is disabled (see `utils::parallel`).
+//!
+//! A "parallel" iterator here is an `Iter` wrapping a standard iterator, with
+//! rayon's adapters and consumers as inherent methods. `Iter` deliberately does
+//! not implement `Iterator`, whose `fold` and `reduce` differ from rayon's; it
+//! implements `IntoIterator`, so it can be zipped, chained and extended with.
+
+use std::cmp::Ordering;
+use std::iter::{self, Once};
+use std::slice::{ChunkBy, ChunkByMut, ChunksExact, ChunksExactMut, ChunksMut, Windows};
+
+use itertools::{Either, Itertools, ZipEq};
+
+/// There is one thread, the caller's
+pub fn current_num_threads() -> usize {
+    1
+}
+
+/// There is no thread pool, so the caller is never one of its threads
+pub fn current_thread_index() -> Option<usize> {
+    None
+}
+
+/// Runs `oper_a` and then `oper_b`
+pub fn join<A, B, RA, RB>(oper_a: A, oper_b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA,
+    B: FnOnce() -> RB,
+{
+    let result_a = oper_a();
+    (result_a, oper_b())
+}
+
+/// `n` clones of `element`
+pub fn repeatn<T: Clone>(element: T, n: usize) -> Iter<iter::Take<iter::Repeat<T>>> {
+    Iter(iter::repeat(element).take(n))
+}
+
+pub type Chunks<'data, T> = Iter<std::slice::Chunks<'data, T>>;
+
+pub mod prelude {
+    pub use super::{
+        IntoParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelExtend,
+        ParallelSlice, ParallelSliceMut,
+    };
+}
+
+/// A "parallel" iterator, see the module documentation
+#[derive(Clone, Debug)]
+#[must_use = "iterators do nothing unless consumed"]
+pub struct Iter<I>(I);
+
+impl<I: Iterator> IntoIterator for Iter<I> {
+    type Item = I::Item;
+    type IntoIter = I;
+
+    fn into_iter(self) -> I {
+        self.0
+    }
+}
+
+pub trait IntoParallelIterator {
+    type Iter;
+    type Item;
+
+    fn into_par_iter(self) -> Self::Iter;
+}
+
+impl<T: IntoIterator> IntoParallelIterator for T {
+    type Iter = Iter<T::IntoIter>;
+    type Item = T::Item;
+
+    fn into_par_iter(self) -> Self::Iter {
+        Iter(self.into_iter())
+    }
+}
+
+pub trait IntoParallelRefIterator<'data> {
+    type Iter;
+
+    fn par_iter(&'data self) -> Self::Iter;
+}
+
+impl<'data, T: 'data + ?Sized> IntoParallelRefIterator<'data> for T
+where
+    &'data T: IntoIterator,
+{
+    type Iter = Iter<<&'data T as IntoIterator>::IntoIter>;
+
+    fn par_iter(&'data self) -> Self::Iter {
+        Iter(self.into_iter())
+    }
+}
+
+pub trait IntoParallelRefMutIterator<'data> {
+    type Iter;
+
+    fn par_iter_mut(&'data mut self) -> Self::Iter;
+}
+
+impl<'data, T: 'data + ?Sized> IntoParallelRefMutIterator<'data> for T
+where
+    &'data mut T: IntoIterator,
+{
+    type Iter = Iter<<&'data mut T as IntoIterator>::IntoIter>;
+
+    fn par_iter_mut(&'data mut self) -> Self::Iter {
+        Iter(self.into_iter())
+    }
+}
+
+pub trait ParallelExtend<T> {
+    fn par_extend<I: IntoIterator<Item = T>>(&mut self, iter: I);
+}
+
+impl<T, C: Extend<T>> ParallelExtend<T> for C {
+    fn par_extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend(iter)
+    }
+}
+
+pub trait ParallelSlice<T> {
+    fn par_chunks(&self, chunk_size: usize) -> Chunks<'_, T>;
+
+    fn par_chunks_exact(&self, chunk_size: usize) -> Iter<ChunksExact<'_, T>>;
+
+    fn par_chunk_by<F>(&self, pred: F) -> Iter<ChunkBy<'_, T, F>>
+    where
+        F: FnMut(&T, &T) -> bool;
+
+    fn par_windows(&self, window_size: usize) -> Iter<Windows<'_, T>>;
+}
+
+impl<T> ParallelSlice<T> for [T] {
+    fn par_chunks(&self, chunk_size: usize) -> Chunks<'_, T> {
+        Iter(self.chunks(chunk_size))
+    }
+
+    fn par_chunks_exact(&self, chunk_size: usize) -> Iter<ChunksExact<'_, T>> {
+        Iter(self.chunks_exact(chunk_size))
+    }
+
+    fn par_chunk_by<F>(&self, pred: F) -> Iter<ChunkBy<'_, T, F>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        Iter(self.chunk_by(pred))
+    }
+
+    fn par_windows(&self, window_size: usize) -> Iter<Windows<'_, T>> {
+        Iter(self.windows(window_size))
+    }
+}
+
+pub trait ParallelSliceMut<T> {
+    fn par_chunks_mut(&mut self, chunk_size: usize) -> Iter<ChunksMut<'_, T>>;
+
+    fn par_chunks_exact_mut(&mut self, chunk_size: usize) -> Iter<ChunksExactMut<'_, T>>;
+
+    fn par_chunk_by_mut<F>(&mut self, pred: F) -> Iter<ChunkByMut<'_, T, F>>
+    where
+        F: FnMut(&T, &T) -> bool;
+
+    fn par_sort(&mut self)
+    where
+        T: Ord;
+
+    fn par_sort_unstable(&mut self)
+    where
+        T: Ord;
+
+    fn par_sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F);
+}
+
+impl<T> ParallelSliceMut<T> for [T] {
+    fn par_chunks_mut(&mut self, chunk_size: usize) -> Iter<ChunksMut<'_, T>> {
+        Iter(self.chunks_mut(chunk_size))
+    }
+
+    fn par_chunks_exact_mut(&mut self, chunk_size: usize) -> Iter<ChunksExactMut<'_, T>> {
+        Iter(self.chunks_exact_mut(chunk_size))
+    }
+
+    fn par_chunk_by_mut<F>(&mut self, pred: F) -> Iter<ChunkByMut<'_, T, F>>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        Iter(self.chunk_by_mut(pred))
+    }
+
+    fn par_sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort()
+    }
+
+    fn par_sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_unstable()
+    }
+
+    fn par_sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, f: F) {
+        self.sort_by_key(f)
+    }
+}
+
+/// Adapters
+impl<I: Iterator> Iter<I> {
+    pub fn map<B, F: FnMut(I::Item) -> B>(self, f: F) -> Iter<iter::Map<I, F>> {
+        Iter(self.0.map(f))
+    }
+
+    /// Like `map`, with a state created once, whereas rayon creates one per
+    /// split of the work
+    pub fn map_init<T, B, INIT, F>(self, init: INIT, mut f: F) -> Iter<impl Iterator<Item = B>>
+    where
+        INIT: FnOnce() -> T,
+        F: FnMut(&mut T, I::Item) -> B,
+    {
+        let mut state = init();
+        Iter(self.0.map(move |item| f(&mut state, item)))
+    }
+
+    pub fn filter<P: FnMut(&I::Item) -> bool>(self, predicate: P) -> Iter<iter::Filter<I, P>> {
+        Iter(self.0.filter(predicate))
+    }
+
+    pub fn filter_map<B, F>(self, f: F) -> Iter<iter::FilterMap<I, F>>
+    where
+        F: FnMut(I::Item) -> Option<B>,
+    {
+        Iter(self.0.filter_map(f))
+    }
+
+    pub fn flat_map<U, F>(self, f: F) -> Iter<iter::FlatMap<I, U, F>>
+    where
+        U: IntoIterator,
+        F: FnMut(I::Item) -> U,
+    {
+        Iter(self.0.flat_map(f))
+    }
+
+    pub fn flat_map_iter<U, F>(self, f: F) -> Iter<iter::FlatMap<I, U, F>>
+    where
+        U: IntoIterator,
+        F: FnMut(I::Item) -> U,
+    {
+        Iter(self.0.flat_map(f))
+    }
+
+    pub fn flatten(self) -> Iter<iter::Flatten<I>>
+    where
+        I::Item: IntoIterator,
+    {
+        Iter(self.0.flatten())
+    }
+
+    pub fn flatten_iter(self) -> Iter<iter::Flatten<I>>
+    where
+        I::Item: IntoIterator,
+    {
+        Iter(self.0.flatten())
+    }
+
+    pub fn enumerate(self) -> Iter<iter::Enumerate<I>> {
+        Iter(self.0.enumerate())
+    }
+
+    pub fn zip<Z: IntoIterator>(self, other: Z) -> Iter<iter::Zip<I, Z::IntoIter>> {
+        Iter(self.0.zip(other))
+    }
+
+    /// Like `zip`, but panics if the lengths differ
+    pub fn zip_eq<Z: IntoIterator>(self, other: Z) -> Iter<ZipEq<I, Z::IntoIter>> {
+        Iter(self.0.zip_eq(other))
+    }
+
+    pub fn chain<C>(self, other: C) -> Iter<iter::Chain<I, C::IntoIter>>
+    where
+        C: IntoIterator<Item = I::Item>,
+    {
+        Iter(self.0.chain(other))
+    }
+
+    pub fn rev(self) -> Iter<iter::Rev<I>>
+    where
+        I: DoubleEndedIterator,
+    {
+        Iter(self.0.rev())
+    }
+
+    pub fn skip(self, n: usize) -> Iter<iter::Skip<I>> {
+        Iter(self.0.skip(n))
+    }
+
+    pub fn take(self, n: usize) -> Iter<iter::Take<I>> {
+        Iter(self.0.take(n))
+    }
+
+    pub fn step_by(self, step: usize) -> Iter<iter::StepBy<I>> {
+        Iter(self.0.step_by(step))
+    }
+
+    pub fn cloned<'a, T>(self) -> Iter<iter::Cloned<I>>
+    where
+        I: Iterator<Item = &'a T>,
+        T: 'a + Clone,
+    {
+        Iter(self.0.cloned())
+    }
+
+    pub fn copied<'a, T>(self) -> Iter<iter::Copied<I>>
+    where
+        I: Iterator<Item = &'a T>,
+        T: 'a + Copy,
+    {
+        Iter(self.0.copied())
+    }
+
+    pub fn inspect<F: FnMut(&I::Item)>(self, f: F) -> Iter<iter::Inspect<I, F>> {
+        Iter(self.0.inspect(f))
+    }
+
+    pub fn update<F>(self, mut f: F) -> Iter<impl Iterator<Item = I::Item>>
+    where
+        F: FnMut(&mut I::Item),
+    {
+        Iter(self.0.map(move |mut item| {
+            f(&mut item);
+            item
+        }))
+    }
+
+    /// Rayon's fold folds each split of the work into an accumulator; here
+    /// there is one split
+    pub fn fold<T, ID, F>(self, identity: ID, fold_op: F) -> Iter<Once<T>>
+    where
+        ID: FnOnce() -> T,
+        F: FnMut(T, I::Item) -> T,
+    {
+        Iter(iter::once(self.0.fold(identity(), fold_op)))
+    }
+
+    pub fn fold_with<T, F>(self, init: T, fold_op: F) -> Iter<Once<T>>
+    where
+        F: FnMut(T, I::Item) -> T,
+    {
+        Iter(iter::once(self.0.fold(init, fold_op)))
+    }
+
+    pub fn with_min_len(self, _min: usize) -> Self {
+        self
+    }
+
+    pub fn with_max_len(self, _max: usize) -> Self {
+        self
+    }
+}
+
+/// Consumers
+impl<I: Iterator> Iter<I> {
+    pub fn for_each<F: FnMut(I::Item)>(self, f: F) {
+        self.0.for_each(f)
+    }
+
+    pub fn for_each_init<T, INIT, F>(self, init: INIT, mut f: F)
+    where
+        INIT: FnOnce() -> T,
+        F: FnMut(&mut T, I::Item),
+    {
+        let mut state = init();
+        self.0.for_each(|item| f(&mut state, item))
+    }
+
+    pub fn try_for_each<E, F>(mut self, f: F) -> Result<(), E>
+    where
+        F: FnMut(I::Item) -> Result<(), E>,
+    {
+        self.0.try_for_each(f)
+    }
+
+    pub fn reduce<ID, OP>(self, identity: ID, op: OP) -> I::Item
+    where
+        ID: FnOnce() -> I::Item,
+        OP: FnMut(I::Item, I::Item) -> I::Item,
+    {
+        self.0.fold(identity(), op)
+    }
+
+    pub fn reduce_with<OP>(self, op: OP) -> Option<I::Item>
+    where
+        OP: FnMut(I::Item, I::Item) -> I::Item,
+    {
+        self.0.reduce(op)
+    }
+
+    pub fn sum<S: iter::Sum<I::Item>>(self) -> S {
+        self.0.sum()
+    }
+
+    pub fn product<P: iter::Product<I::Item>>(self) -> P {
+        self.0.product()
+    }
+
+    pub fn count(self) -> usize {
+        self.0.count()
+    }
+
+    pub fn len(&self) -> usize
+    where
+        I: ExactSizeIterator,
+    {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    where
+        I: ExactSizeIterator,
+    {
+        self.0.len() == 0
+    }
+
+    pub fn min(self) -> Option<I::Item>
+    where
+        I::Item: Ord,
+    {
+        self.0.min()
+    }
+
+    pub fn max(self) -> Option<I::Item>
+    where
+        I::Item: Ord,
+    {
+        self.0.max()
+    }
+
+    pub fn min_by<F>(self, compare: F) -> Option<I::Item>
+    where
+        F: FnMut(&I::Item, &I::Item) -> Ordering,
+    {
+        self.0.min_by(compare)
+    }
+
+    pub fn max_by<F>(self, compare: F) -> Option<I::Item>
+    where
+        F: FnMut(&I::Item, &I::Item) -> Ordering,
+    {
+        self.0.max_by(compare)
+    }
+
+    pub fn min_by_key<K: Ord, F: FnMut(&I::Item) -> K>(self, f: F) -> Option<I::Item> {
+        self.0.min_by_key(f)
+    }
+
+    pub fn max_by_key<K: Ord, F: FnMut(&I::Item) -> K>(self, f: F) -> Option<I::Item> {
+        self.0.max_by_key(f)
+    }
+
+    pub fn any<P: FnMut(I::Item) -> bool>(mut self, predicate: P) -> bool {
+        self.0.any(predicate)
+    }
+
+    pub fn all<P: FnMut(I::Item) -> bool>(mut self, predicate: P) -> bool {
+        self.0.all(predicate)
+    }
+
+    pub fn find_any<P: FnMut(&I::Item) -> bool>(mut self, predicate: P) -> Option<I::Item> {
+        self.0.find(predicate)
+    }
+
+    pub fn find_first<P: FnMut(&I::Item) -> bool>(mut self, predicate: P) -> Option<I::Item> {
+        self.0.find(predicate)
+    }
+
+    pub fn find_map_any<B, F>(mut self, f: F) -> Option<B>
+    where
+        F: FnMut(I::Item) -> Option<B>,
+    {
+        self.0.find_map(f)
+    }
+
+    pub fn find_map_first<B, F>(mut self, f: F) -> Option<B>
+    where
+        F: FnMut(I::Item) -> Option<B>,
+    {
+        self.0.find_map(f)
+    }
+
+    pub fn position_any<P: FnMut(I::Item) -> bool>(mut self, predicate: P) -> Option<usize> {
+        self.0.position(predicate)
+    }
+
+    pub fn position_first<P: FnMut(I::Item) -> bool>(mut self, predicate: P) -> Option<usize> {
+        self.0.position(predicate)
+    }
+
+    pub fn collect<C: FromIterator<I::Item>>(self) -> C {
+        self.0.collect()
+    }
+
+    pub fn collect_into_vec(self, target: &mut Vec<I::Item>) {
+        target.clear();
+        target.extend(self.0)
+    }
+
+    pub fn unzip<A, B, FromA, FromB>(self) -> (FromA, FromB)
+    where
+        I: Iterator<Item = (A, B)>,
+        FromA: Default + Extend<A>,
+        FromB: Default + Extend<B>,
+    {
+        self.0.unzip()
+    }
+
+    pub fn partition<A, B, P>(self, mut predicate: P) -> (A, B)
+    where
+        A: Default + Extend<I::Item>,
+        B: Default + Extend<I::Item>,
+        P: FnMut(&I::Item) -> bool,
+    {
+        let (mut left, mut right) = (A::default(), B::default());
+        for item in self.0 {
+            if predicate(&item) {
+                left.extend(Some(item));
+            } else {
+                right.extend(Some(item));
+            }
+        }
+        (left, right)
+    }
+
+    pub fn partition_map<A, B, L, R, P>(self, predicate: P) -> (A, B)
+    where
+        A: Default + Extend<L>,
+        B: Default + Extend<R>,
+        P: FnMut(I::Item) -> Either<L, R>,
+    {
+        self.0.partition_map(predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::*;
+    use super::*;
+
+    #[test]
+    fn matches_rayon_semantics() {
+        let v: Vec<u64> = (1..=10).collect();
+        // `fold` yields one accumulator per split, which `reduce` combines
+        let sum = v
+            .par_iter()
+            .fold(|| 0, |acc, x| acc + x)
+            .reduce(|| 0, |a, b| a + b);
+        assert_eq!(sum, 55);
+
+        let mut lens = vec![];
+        v.par_chunks(3)
+            .map(|chunk| chunk.len())
+            .collect_into_vec(&mut lens);
+        assert_eq!(lens, vec![3, 3, 3, 1]);
+
+        let mut w = v.clone();
+        w.par_iter_mut()
+            .zip_eq(v.par_iter())
+            .for_each(|(w, v)| *w += v);
+        assert_eq!(w[9], 20);
+
+        let (left, right): (Vec<_>, Vec<_>) = v.into_par_iter().partition_map(|x| {
+            if x % 2 == 0 {
+                Either::Left(x)
+            } else {
+                Either::Right(x)
+            }
+        });
+        assert_eq!((left.len(), right.len()), (5, 5));
+        assert_eq!(join(current_num_threads, || 2), (1, 2));
+    }
+}
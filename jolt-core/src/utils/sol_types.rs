@@ -11,7 +11,7 @@ use crate::subprotocols::grand_product::BatchedGrandProductProof;
 use crate::subprotocols::sumcheck::SumcheckInstanceProof;
 use crate::utils::transcript::Transcript;
 use alloy_primitives::U256;
-use alloy_sol_types::sol;
+use alloy_sol_types::{sol, SolValue};
 use ark_bn254::FrConfig;
 use ark_ff::Fp;
 use ark_ff::MontBackend;
@@ -158,7 +158,27 @@ pub fn into_uint256<F: JoltField>(from: F) -> U256 {
     U256::from_le_slice(&buf)
 }
 
+/// ABI encoding of `proof` as the `HyperKZGProof` argument of `HyperKZG.sol`
+pub fn hyperkzg_proof_calldata(proof: &HyperKZGProof<Bn254>) -> Vec<u8> {
+    let proof: HyperKZGProofSol = proof.into();
+    proof.abi_encode()
+}
+
 const C: usize = 4;
+
+/// ABI encoding of `proof` as the `SpartanProof` argument of `SpartanVerifier.sol`
+pub fn spartan_proof_calldata<ProofTranscript: Transcript>(
+    proof: &UniformSpartanProof<
+        C,
+        JoltR1CSInputs,
+        Fp<MontBackend<FrConfig, 4>, 4>,
+        ProofTranscript,
+    >,
+) -> Vec<u8> {
+    let proof: SpartanProof = proof.into();
+    proof.abi_encode()
+}
+
 impl<ProofTranscript: Transcript> Into<SpartanProof>
     for &UniformSpartanProof<C, JoltR1CSInputs, Fp<MontBackend<FrConfig, 4>, 4>, ProofTranscript>
 {
@@ -1,16 +1,47 @@
-use rayon::prelude::*;
+use crate::utils::parallel::{self, prelude::*};
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
 use std::thread::{self, JoinHandle};
 
 use crate::field::JoltField;
 
+/// The chunks per thread tuned for the reference machine
+pub const DEFAULT_CHUNKS_PER_THREAD: usize = 4;
+
+static CHUNKS_PER_THREAD: AtomicUsize = AtomicUsize::new(DEFAULT_CHUNKS_PER_THREAD);
+
+/// Sets how many chunks per thread the parallel loops over unevenly sparse
+/// rows (the R1CS witness and Spartan's first sumcheck) are split into in this
+/// process, e.g. to the fastest on this machine (see `CalibrationProfile`).
+/// More chunks balance the load better, at the cost of more overhead.
+pub fn set_chunks_per_thread(chunks: usize) {
+    CHUNKS_PER_THREAD.store(chunks.max(1), Ordering::Relaxed);
+}
+
+pub fn chunks_per_thread() -> usize {
+    CHUNKS_PER_THREAD.load(Ordering::Relaxed)
+}
+
+/// The number of chunks to split a parallel loop over unevenly sparse rows into,
+/// see `set_chunks_per_thread`
+pub fn num_parallel_chunks() -> usize {
+    parallel::current_num_threads().next_power_of_two() * chunks_per_thread()
+}
+
+/// Drops `data` on another thread, so that freeing large polynomials does not
+/// hold up the prover. Without the `parallel` feature, drops it right away.
 pub fn drop_in_background_thread<T>(data: T)
 where
     T: Send + 'static,
 {
     // h/t https://abrams.cc/rust-dropping-things-in-another-thread
+    #[cfg(feature = "parallel")]
     rayon::spawn(move || drop(data));
+    #[cfg(not(feature = "parallel"))]
+    drop(data);
 }
 
+#[cfg(feature = "parallel")]
 pub fn allocate_vec_in_background<T: Clone + Send + 'static>(
     value: T,
     size: usize,
@@ -132,6 +163,6 @@ where
     RB: Send,
     RC: Send,
 {
-    let (res_a, (res_b, res_c)) = rayon::join(oper_a, || rayon::join(oper_b, oper_c));
+    let (res_a, (res_b, res_c)) = parallel::join(oper_a, || parallel::join(oper_b, oper_c));
     (res_a, res_b, res_c)
 }
@@ -5,6 +5,13 @@ use sha3::{Digest, Keccak256};
 use std::borrow::Borrow;
 
 /// Represents the current state of the protocol's Fiat-Shamir transcript.
+///
+/// Every absorption is one Keccak-256 hash of the running state, the round and
+/// the message, laid out in 32-byte EVM words so that the Solidity verifier can
+/// recompute it. The messages are packed on the stack rather than in fresh
+/// vectors, since there are millions of absorptions per proof; on ARMv8 the
+/// Keccak permutation uses the SHA3 instructions when the CPU has them. Both
+/// give the same outputs as the plain implementation, which the tests pin.
 #[derive(Clone)]
 pub struct KeccakTranscript {
     /// Ethereum-compatible 256-bit running state
@@ -26,12 +33,12 @@ impl KeccakTranscript {
     /// Gives the hasher object with the running seed and index added
     /// To load hash you must call finalize, after appending u8 vectors
     fn hasher(&self) -> Keccak256 {
-        let mut packed = [0_u8; 28].to_vec();
-        packed.append(&mut self.n_rounds.to_be_bytes().to_vec());
+        let mut packed = [0_u8; 32];
+        packed[28..].copy_from_slice(&self.n_rounds.to_be_bytes());
         // Note we add the extra memory here to improve the ease of eth integrations
         Keccak256::new()
             .chain_update(self.state)
-            .chain_update(&packed)
+            .chain_update(packed)
     }
 
     // Loads arbitrary byte lengths using ceil(out/32) invocations of 32 byte randoms
@@ -45,7 +52,7 @@ impl KeccakTranscript {
             remaining_len -= 32;
         }
         // We load a full 32 byte random region
-        let mut full_rand = vec![0_u8; 32];
+        let mut full_rand = [0_u8; 32];
         self.challenge_bytes32(&mut full_rand);
         // Then only clone the first bits of this random region to perfectly fill out
         out[start..start + remaining_len].clone_from_slice(&full_rand[0..remaining_len]);
@@ -79,13 +86,9 @@ impl Transcript for KeccakTranscript {
     fn new(label: &'static [u8]) -> Self {
         // Hash in the label
         assert!(label.len() < 33);
-        let hasher = if label.len() == 32 {
-            Keccak256::new().chain_update(label)
-        } else {
-            let zeros = vec![0_u8; 32 - label.len()];
-            Keccak256::new().chain_update(label).chain_update(zeros)
-        };
-        let out = hasher.finalize();
+        let mut packed = [0_u8; 32];
+        packed[..label.len()].copy_from_slice(label);
+        let out = Keccak256::new().chain_update(packed).finalize();
 
         Self {
             state: out.into(),
@@ -108,14 +111,10 @@ impl Transcript for KeccakTranscript {
         // We require all messages to fit into one evm word and then right pad them
         // right padding matches the format of the strings when cast to bytes 32 in solidity
         assert!(msg.len() < 33);
-        let hasher = if msg.len() == 32 {
-            self.hasher().chain_update(msg)
-        } else {
-            let mut packed = msg.to_vec();
-            packed.append(&mut vec![0_u8; 32 - msg.len()]);
-            self.hasher().chain_update(packed)
-        };
+        let mut packed = [0_u8; 32];
+        packed[..msg.len()].copy_from_slice(msg);
         // Instantiate hasher add our seed, position and msg
+        let hasher = self.hasher().chain_update(packed);
         self.update_state(hasher.finalize().into());
     }
 
@@ -127,19 +126,19 @@ impl Transcript for KeccakTranscript {
 
     fn append_u64(&mut self, x: u64) {
         // Allocate into a 32 byte region
-        let mut packed = [0_u8; 24].to_vec();
-        packed.append(&mut x.to_be_bytes().to_vec());
-        let hasher = self.hasher().chain_update(packed.clone());
+        let mut packed = [0_u8; 32];
+        packed[24..].copy_from_slice(&x.to_be_bytes());
+        let hasher = self.hasher().chain_update(packed);
         self.update_state(hasher.finalize().into());
     }
 
     fn append_scalar<F: JoltField>(&mut self, scalar: &F) {
-        let mut buf = vec![];
+        let mut buf = Vec::with_capacity(F::NUM_BYTES);
         scalar.serialize_uncompressed(&mut buf).unwrap();
         // Serialize uncompressed gives the scalar in LE byte order which is not
         // a natural representation in the EVM for scalar math so we reverse
         // to get an EVM compatible version.
-        buf = buf.into_iter().rev().collect();
+        buf.reverse();
         self.append_bytes(&buf);
     }
 
@@ -165,10 +164,10 @@ impl Transcript for KeccakTranscript {
         // can lead to errors so we extract the affine coordinates and the encode them be before writing
         let x = aff.x().unwrap();
         x.serialize_compressed(&mut x_bytes).unwrap();
-        x_bytes = x_bytes.into_iter().rev().collect();
+        x_bytes.reverse();
         let y = aff.y().unwrap();
         y.serialize_compressed(&mut y_bytes).unwrap();
-        y_bytes = y_bytes.into_iter().rev().collect();
+        y_bytes.reverse();
 
         let hasher = self.hasher().chain_update(x_bytes).chain_update(y_bytes);
         self.update_state(hasher.finalize().into());
@@ -187,7 +186,7 @@ impl Transcript for KeccakTranscript {
         self.challenge_bytes(&mut buf);
         // Because onchain we don't want to do the bit reversal to get the LE ordering
         // we reverse here so that the random is BE ordering.
-        buf = buf.into_iter().rev().collect();
+        buf.reverse();
         F::from_bytes(&buf)
     }
 
@@ -228,3 +227,30 @@ pub trait Transcript: Clone + Sync + Send + 'static {
 pub trait AppendToTranscript {
     fn append_to_transcript<ProofTranscript: Transcript>(&self, transcript: &mut ProofTranscript);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective};
+    use ark_std::Zero;
+
+    #[test]
+    fn keccak_transcript_known_answer() {
+        let mut transcript = KeccakTranscript::new(b"Jolt transcript");
+        assert_eq!(
+            alloy_primitives::hex::encode(transcript.state),
+            "a8217854510dbd9c6603f7e98c8044c3b1c3822dadbfb1aba703d665425a7d31"
+        );
+        transcript.append_message(b"message");
+        transcript.append_u64(42);
+        transcript.append_bytes(&(0..100).collect::<Vec<u8>>());
+        transcript.append_scalar(&Fr::from(7u64));
+        transcript.append_point(&G1Projective::zero());
+        let _: Fr = transcript.challenge_scalar();
+        assert_eq!(transcript.n_rounds, 6);
+        assert_eq!(
+            alloy_primitives::hex::encode(transcript.state),
+            "88ba137f8b289f34ba9051108c51c263a6a5365dd76eea4e2bb0506a2fa24c71"
+        );
+    }
+}
@@ -1,71 +1,24 @@
-use alloy_primitives::hex;
-use jolt_core::field::JoltField;
-use jolt_core::r1cs::key::SparseConstraints;
-use jolt_core::r1cs::key::SparseEqualityItem;
-
-// This code is provided as an example of how to turn the sparse constraints into solidity, and was the base
-// for the code in R1CSMatrix, however because the key is not expected to change often full auto-generation is not
-// provided and some tweaking is needed to produce sol from this.
-#[allow(dead_code)]
-fn partial_autogenerate<F: JoltField>(
-    constraints: &SparseConstraints<F>,
-    cross_step: Option<&SparseEqualityItem<F>>,
-) {
-    let bytes = |from: F| -> Vec<u8> {
-        let mut buf = vec![];
-        from.serialize_uncompressed(&mut buf).unwrap();
-        buf.into_iter().rev().collect()
-    };
-
-    print!("Fr running = Fr.wrap(0);\nFr rv = Fr.wrap(0);\n");
-
-    for (row, col, coeff) in constraints.vars.iter() {
-        println!(
-            "rv += Fr.wrap(0x{})*row[{:?}]*col[{:?}];",
-            hex::encode(bytes(*coeff)),
-            row,
-            col
-        );
-    }
-
-    print!("running += rv*eq_rx_ry_step;\n// then we do constant col\nFr rc = Fr.wrap(0);");
-
-    for (row, coeff) in constraints.consts.iter() {
-        println!(
-            "rc += Fr.wrap(0x{})*row[{:?}];",
-            hex::encode(bytes(*coeff)),
-            row
-        );
-    }
-    println!("running += rc*col_eq_constant;");
-
-    if cross_step.is_some() {
-        println!("Fr rnu = Fr.wrap(0);");
-
-        for (col, offset, coeff) in cross_step.unwrap().offset_vars.iter() {
-            if *offset {
-                println!(
-                    "rnu += Fr.wrap(0x{})*col[{:?}]*eq_step_offset_1;",
-                    hex::encode(bytes(*coeff)),
-                    col
-                );
-            } else {
-                println!(
-                    "rnu += Fr.wrap(0x{})*col[{:?}]*eq_rx_ry_step;",
-                    hex::encode(bytes(*coeff)),
-                    col
-                );
-            }
-        }
-        println!(
-            "rnu += col_eq_const*Fr.wrap(0x{});",
-            hex::encode(bytes(cross_step.unwrap().constant))
-        );
-        println!("running += rnu * row_constr_eq_cross_step;");
-    }
-    println!("return(running);");
-}
-
+use ark_bn254::Fr;
+use jolt_core::jolt::vm::rv32i_vm::C;
+use jolt_core::r1cs::constraints::{JoltRV32IMConstraints, R1CSConstraints};
+use jolt_core::r1cs::inputs::JoltR1CSInputs;
+use jolt_core::r1cs::key::UniformSpartanKey;
+use jolt_core::r1cs::solidity::write_matrix_library;
+
+// Prints the Solidity library evaluating the RV32IM constraint matrices, to be
+// saved as src/subprotocols/JoltR1CSMatrix.sol. The constraints depend on the
+// start of the program's input region, which is passed as the first argument.
 fn main() {
-    // TODO - Have example code here rather than in the verifier
+    let memory_start = std::env::args()
+        .nth(1)
+        .map(|arg| u64::from_str_radix(arg.trim_start_matches("0x"), 16).unwrap())
+        .unwrap_or(0x8000_0000);
+    // The matrices are the same for every step, so the trace length does not
+    // affect the generated code
+    let builder = <JoltRV32IMConstraints as R1CSConstraints<C, Fr>>::construct_constraints(
+        1 << 10,
+        memory_start,
+    );
+    let key = UniformSpartanKey::<C, JoltR1CSInputs, Fr>::from_builder(&builder);
+    write_matrix_library(&key, &mut std::io::stdout()).unwrap();
 }
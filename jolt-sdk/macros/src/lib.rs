@@ -4,7 +4,7 @@ extern crate proc_macro;
 
 use core::panic;
 
-use common::{attributes::parse_attributes, rv_trace::MemoryLayout};
+use common::{attributes::parse_attributes, constants::RNG_SEED_SIZE, rv_trace::MemoryLayout};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
@@ -35,6 +35,16 @@ pub fn provable(attr: TokenStream, item: TokenStream) -> TokenStream {
     token_stream
 }
 
+/// A guest unit test, run in the emulator by `jolt test` (see `Program::set_tests`)
+/// and natively by `cargo test`. Tests take no arguments and fail by panicking;
+/// their names must be unique within the guest.
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attr = parse_macro_input!(attr as AttributeArgs);
+    let func = parse_macro_input!(item as ItemFn);
+    MacroBuilder::new(attr, func).make_test().into()
+}
+
 struct MacroBuilder {
     attr: AttributeArgs,
     func: ItemFn,
@@ -64,15 +74,16 @@ impl MacroBuilder {
         let analyze_fn = self.make_analyze_function();
         let preprocess_fn = self.make_preprocess_func();
         let prove_fn = self.make_prove_func();
-
-        let main_fn = if let Some(func) = self.get_func_selector() {
-            if *self.get_func_name() == func {
-                self.make_main_func()
-            } else {
-                quote! {}
-            }
-        } else {
-            self.make_main_func()
+        let prove_async_fn = self.make_prove_async_func();
+        let prove_dev_fn = self.make_prove_dev_func();
+
+        let main_fn = match self.get_func_selector() {
+            // The `main` of test builds runs the selected `#[jolt::test]` function
+            _ if self.get_test_selector().is_some() => quote! {},
+            Some(funcs) if funcs.len() > 1 => self.make_shared_main_func(&funcs),
+            Some(funcs) if *self.get_func_name() == funcs[0] => self.make_main_func(),
+            Some(_) => quote! {},
+            None => self.make_main_func(),
         };
 
         quote! {
@@ -81,6 +92,8 @@ impl MacroBuilder {
             #analyze_fn
             #preprocess_fn
             #prove_fn
+            #prove_async_fn
+            #prove_dev_fn
             #main_fn
         }
         .into()
@@ -154,11 +167,7 @@ impl MacroBuilder {
         let fn_name_str = fn_name.to_string();
         let analyze_fn_name = Ident::new(&format!("analyze_{}", fn_name), fn_name.span());
         let inputs = &self.func.sig.inputs;
-        let set_program_args = self.func_args.iter().map(|(name, _)| {
-            quote! {
-                program.set_input(&#name);
-            }
-        });
+        let set_program_args = self.make_set_program_args();
 
         quote! {
              #[cfg(not(target_arch = "wasm32"))]
@@ -178,9 +187,6 @@ impl MacroBuilder {
     }
 
     fn make_preprocess_func(&self) -> TokenStream2 {
-        let attributes = parse_attributes(&self.attr);
-        let max_input_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_input_size);
-        let max_output_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_output_size);
         let set_mem_size = self.make_set_linker_parameters();
         let guest_name = self.get_guest_name();
         let imports = self.make_imports();
@@ -189,31 +195,41 @@ impl MacroBuilder {
         let fn_name = self.get_func_name();
         let fn_name_str = fn_name.to_string();
         let preprocess_fn_name = Ident::new(&format!("preprocess_{}", fn_name), fn_name.span());
+        let preprocess_shared_fn_name =
+            Ident::new(&format!("preprocess_{}_shared", fn_name), fn_name.span());
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #preprocess_fn_name() -> (
                 jolt::host::Program,
                 jolt::JoltPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>
+            ) {
+                #preprocess_shared_fn_name(&[#fn_name_str])
+            }
+
+            /// Builds a single ELF exporting all of `funcs`, which must include this
+            /// function, and preprocesses it. The program and preprocessing can be
+            /// passed to the `prove_*` function of any of `funcs`.
+            #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
+            pub fn #preprocess_shared_fn_name(funcs: &[&str]) -> (
+                jolt::host::Program,
+                jolt::JoltPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>
             ) {
                 #imports
 
                 let mut program = Program::new(#guest_name);
-                program.set_func(#fn_name_str);
+                program.set_funcs(funcs);
                 #set_std
                 #set_mem_size
-                let (bytecode, memory_init) = program.decode();
-                let memory_layout = MemoryLayout::new(#max_input_size, #max_output_size);
+                // Reused from the build cache if the guest is unchanged
+                let program_preprocessing = program.program_preprocessing();
 
                 // TODO(moodlezoup): Feed in size parameters via macro
                 let preprocessing: JoltPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
-                    RV32IJoltVM::preprocess(
-                        bytecode,
-                        memory_layout,
-                        memory_init,
-                        1 << 20,
-                        1 << 20,
-                        1 << 24
-                    );
+                    RV32IJoltVM::preprocess_with_config(
+                        program_preprocessing,
+                        &jolt::ProverConfig::default(),
+                    )
+                    .unwrap();
 
                 (program, preprocessing)
             }
@@ -223,25 +239,15 @@ impl MacroBuilder {
     fn make_prove_func(&self) -> TokenStream2 {
         let prove_output_ty = self.get_prove_output_type();
 
-        let handle_return = match &self.func.sig.output {
-            ReturnType::Default => quote! {
-                let ret_val = ();
-            },
-            ReturnType::Type(_, ty) => quote! {
-                let ret_val = jolt::postcard::from_bytes::<#ty>(&output_bytes).unwrap();
-            },
-        };
+        let handle_return = self.make_handle_return(quote! { jolt_proof.program_io });
 
-        let set_program_args = self.func_args.iter().map(|(name, _)| {
-            quote! {
-                program.set_input(&#name);
-            }
-        });
+        let set_program_args = self.make_set_program_args();
 
         let fn_name = self.get_func_name();
         let inputs = &self.func.sig.inputs;
         let imports = self.make_imports();
 
+        let fn_name_str = fn_name.to_string();
         let prove_fn_name = syn::Ident::new(&format!("prove_{}", fn_name), fn_name.span());
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
@@ -252,17 +258,26 @@ impl MacroBuilder {
             ) -> #prove_output_ty {
                 #imports
 
+                program.select_func(#fn_name_str);
                 #(#set_program_args;)*
 
-                let (io_device, trace) = program.trace();
-
-                let output_bytes = io_device.outputs.clone();
-
-                let (jolt_proof, jolt_commitments, _) = RV32IJoltVM::prove(
-                    io_device,
-                    trace,
-                    preprocessing,
-                );
+                let (jolt_proof, jolt_commitments) = match jolt::host::RemoteProver::from_env() {
+                    Some(remote) => {
+                        let envelope = remote
+                            .prove::<jolt::F, jolt::PCS, jolt::ProofTranscript>(&mut program)
+                            .unwrap_or_else(|err| panic!("{}", err));
+                        (envelope.proof, envelope.commitments)
+                    }
+                    None => {
+                        let (io_device, trace) = program.trace();
+                        let (jolt_proof, jolt_commitments, _) = RV32IJoltVM::prove(
+                            io_device,
+                            trace,
+                            preprocessing,
+                        );
+                        (jolt_proof, jolt_commitments)
+                    }
+                };
 
                 #handle_return
 
@@ -276,50 +291,352 @@ impl MacroBuilder {
         }
     }
 
+    fn make_prove_async_func(&self) -> TokenStream2 {
+        let prove_output_ty = self.get_prove_output_type();
+
+        let handle_return = self.make_handle_return(quote! { io_device });
+
+        let set_program_args = self.make_set_program_args();
+
+        let fn_name = self.get_func_name();
+        let inputs = &self.func.sig.inputs;
+        let imports = self.make_imports();
+
+        let fn_name_str = fn_name.to_string();
+        let prove_async_fn_name =
+            syn::Ident::new(&format!("prove_{}_async", fn_name), fn_name.span());
+        quote! {
+            #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
+            pub fn #prove_async_fn_name(
+                mut program: jolt::host::Program,
+                preprocessing: jolt::JoltPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+                progress: jolt::ProverProgress,
+                #inputs
+            ) -> jolt::ProvingTask<Result<#prove_output_ty, jolt::ProvingCancelled>> {
+                jolt::ProvingTask::spawn(move || {
+                    #imports
+
+                    program.select_func(#fn_name_str);
+                    #(#set_program_args;)*
+
+                    progress.enter(jolt::ProverStage::Tracing)?;
+                    let (io_device, trace) = program.trace();
+                    #handle_return
+
+                    let (jolt_proof, jolt_commitments, _, _) = RV32IJoltVM::prove_with_progress(
+                        io_device,
+                        trace,
+                        preprocessing,
+                        None,
+                        &progress,
+                    )?;
+
+                    let proof = jolt::JoltHyperKZGProof {
+                        proof: jolt_proof,
+                        commitments: jolt_commitments,
+                    };
+
+                    Ok((ret_val, proof))
+                })
+            }
+        }
+    }
+
+    fn make_prove_dev_func(&self) -> TokenStream2 {
+        let set_mem_size = self.make_set_linker_parameters();
+        let guest_name = self.get_guest_name();
+        let imports = self.make_imports();
+        let set_std = self.make_set_std();
+        let handle_return = self.make_handle_return(quote! { proof.program_io });
+        let set_program_args = self.make_set_program_args();
+
+        let fn_name = self.get_func_name();
+        let fn_name_str = fn_name.to_string();
+        let prove_dev_fn_name = Ident::new(&format!("prove_dev_{}", fn_name), fn_name.span());
+        let inputs = &self.func.sig.inputs;
+        let ret_ty = match &self.func.sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! { #ty },
+        };
+
+        quote! {
+            /// Runs the function in dev mode (see `Jolt::prove_dev`): checks the
+            /// execution against the constraints without proving it, and returns
+            /// a `DevProof`, which cannot be verified. Needs no preprocessing.
+            #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
+            pub fn #prove_dev_fn_name(
+                #inputs
+            ) -> (Result<#ret_ty, jolt::GuestPanic>, jolt::DevProof) {
+                #imports
+
+                let mut program = Program::new(#guest_name);
+                program.set_func(#fn_name_str);
+                #set_std
+                #set_mem_size
+                #(#set_program_args;)*
+
+                let preprocessing: JoltPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
+                    RV32IJoltVM::preprocess_dev(program.program_preprocessing());
+                let (io_device, trace) = program.trace();
+                let proof = RV32IJoltVM::prove_dev(io_device, trace, &preprocessing)
+                    .unwrap_or_else(|violation| panic!("{}", violation));
+
+                #handle_return
+
+                (ret_val, proof)
+            }
+        }
+    }
+
     fn make_main_func(&self) -> TokenStream2 {
+        let get_input_slice = self.make_get_input_slice();
+        let set_panic_hook = self.make_set_panic_hook();
+        let body = self.make_entry_body();
+        let start = self.make_start();
+        let terminate = self.make_terminate();
+
+        quote! {
+            #start
+
+            #[cfg(feature = "guest")]
+            #[no_mangle]
+            pub extern "C" fn main() {
+                #set_panic_hook
+                let mut offset = 0;
+                #get_input_slice
+                #body
+                #terminate
+            }
+        }
+    }
+
+    /// For a shared build of `funcs` (see `Program::set_funcs`): an entry point
+    /// running this function and, if it is the first of `funcs`, a `main` that
+    /// calls the entry point selected by the host. The functions of a shared
+    /// build must live in the same module and have the same memory attributes.
+    fn make_shared_main_func(&self, funcs: &[String]) -> TokenStream2 {
+        let fn_name = self.get_func_name();
+        if !funcs.iter().any(|func| fn_name == func) {
+            return quote! {};
+        }
+        let entry_fn_name = Ident::new(&format!("jolt_entry_{}", fn_name), fn_name.span());
+        let body = self.make_entry_body();
+        let entry_fn = quote! {
+            #[cfg(feature = "guest")]
+            fn #entry_fn_name(input_slice: &[u8]) {
+                #body
+            }
+        };
+        if *fn_name != funcs[0] {
+            return entry_fn;
+        }
+
+        let get_input_slice = self.make_get_input_slice();
+        let set_panic_hook = self.make_set_panic_hook();
+        let start = self.make_start();
+        let terminate = self.make_terminate();
+        let entries = funcs.iter().enumerate().map(|(index, func)| {
+            let index = index as u32;
+            let entry_fn_name = Ident::new(&format!("jolt_entry_{}", func), fn_name.span());
+            quote! {
+                #index => #entry_fn_name(input_slice),
+            }
+        });
+
+        quote! {
+            #entry_fn
+
+            #start
+
+            #[cfg(feature = "guest")]
+            #[no_mangle]
+            pub extern "C" fn main() {
+                #set_panic_hook
+                #get_input_slice
+                let (entry, input_slice) = input_slice.split_at(4);
+                match u32::from_le_bytes(entry.try_into().unwrap()) {
+                    #(#entries)*
+                    _ => panic!("unknown entry point"),
+                }
+                #terminate
+            }
+        }
+    }
+
+    /// A `#[jolt::test]` function: a native test, and in test builds of the guest
+    /// an entry point running it, plus, if it is the first of the tests, a `main`
+    /// calling the entry point of the test selected by the host. Like in shared
+    /// builds, the index of the test follows the RNG seed in the inputs.
+    fn make_test(&self) -> TokenStream2 {
+        let fn_name = self.get_func_name();
+        let attrs = &self.func.attrs;
+        let block = &self.func.block;
+        let entry_fn_name = Ident::new(&format!("jolt_test_{}", fn_name), fn_name.span());
+        let test_fn = quote! {
+            #[cfg(not(feature = "guest"))]
+            #[test]
+            #(#attrs)*
+            fn #fn_name() #block
+
+            #[cfg(feature = "guest")]
+            #[no_mangle]
+            pub extern "C" fn #entry_fn_name() #block
+        };
+
+        let tests = self.get_test_selector().unwrap_or_default();
+        if tests.first().map_or(true, |first| fn_name != first) {
+            return test_fn;
+        }
+
+        let get_input_slice = self.make_get_input_slice();
+        let set_panic_hook = self.make_set_panic_hook();
+        let start = self.make_start();
+        let terminate = self.make_terminate();
+        // The tests may live in other modules, so they are called through their symbols
+        let entry_fn_names: Vec<_> = tests
+            .iter()
+            .map(|test| Ident::new(&format!("jolt_test_{}", test), fn_name.span()))
+            .collect();
+        let entries = entry_fn_names
+            .iter()
+            .enumerate()
+            .map(|(index, entry_fn_name)| {
+                let index = index as u32;
+                quote! {
+                    #index => unsafe { #entry_fn_name() },
+                }
+            });
+
+        quote! {
+            #test_fn
+
+            #start
+
+            #[cfg(feature = "guest")]
+            mod jolt_test_main {
+                extern "C" {
+                    #(fn #entry_fn_names();)*
+                }
+
+                #[no_mangle]
+                pub extern "C" fn main() {
+                    #set_panic_hook
+                    #get_input_slice
+                    let (entry, _) = input_slice.split_at(4);
+                    match u32::from_le_bytes(entry.try_into().unwrap()) {
+                        #(#entries)*
+                        _ => panic!("unknown test"),
+                    }
+                    #terminate
+                }
+            }
+        }
+    }
+
+    /// Sets `input_slice` to the guest's inputs following the RNG seed, which
+    /// it consumes
+    fn make_get_input_slice(&self) -> TokenStream2 {
         let attributes = parse_attributes(&self.attr);
         let memory_layout =
             MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
         let input_start = memory_layout.input_start;
-        let output_start = memory_layout.output_start;
         let max_input_len = attributes.max_input_size as usize;
-        let max_output_len = attributes.max_output_size as usize;
-        let termination_bit = memory_layout.termination as usize;
 
-        let get_input_slice = quote! {
+        // The host prefixes the inputs with the seed of the guest's prover-seeded RNG
+        quote! {
             let input_ptr = #input_start as *const u8;
             let input_slice = unsafe {
                 core::slice::from_raw_parts(input_ptr, #max_input_len)
             };
-        };
+            let (seed, input_slice) = input_slice.split_at(#RNG_SEED_SIZE);
+            jolt::prover_rand::set_seed(seed.try_into().unwrap());
+        }
+    }
 
+    /// Runs the function on the arguments read from `input_slice` and writes
+    /// its output
+    fn make_entry_body(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let memory_layout =
+            MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
+        let output_start = memory_layout.output_start;
+        let max_output_len = attributes.max_output_size as usize;
+
+        let codec = self.get_codec();
         let args = &self.func_args;
         let args_fetch = args.iter().map(|(name, ty)| {
             quote! {
                 let (#name, input_slice) =
-                    jolt::postcard::take_from_bytes::<#ty>(input_slice).unwrap();
+                    <#codec as jolt::Decode<#ty>>::decode(input_slice).unwrap();
             }
         });
 
         // TODO: ensure that input slice hasn't overflown
         let check_input_len = quote! {};
 
+        // Any remaining input is length-prefixed raw input read via `jolt::raw_input()`.
+        // The prefix is untrusted, and must not point the reader past the input region.
+        let raw_input_fetch = quote! {
+            let (raw_input_len, input_slice) = input_slice.split_at(4);
+            let raw_input_len = u32::from_le_bytes(raw_input_len.try_into().unwrap()) as usize;
+            assert!(
+                raw_input_len <= input_slice.len(),
+                "raw input length exceeds the input region"
+            );
+            unsafe {
+                jolt::io::set_raw_input(input_slice.as_ptr(), raw_input_len);
+            }
+        };
+
         let block = &self.func.block;
         let block = quote! {let to_return = (|| -> _ { #block })();};
 
+        // The output region holds the journal (see `jolt::commit`), then the return value
+        let set_journal = quote! {
+            unsafe {
+                jolt::journal::set_output(#output_start as *mut u8, #max_output_len);
+            }
+        };
+
         let handle_return = match &self.func.sig.output {
             ReturnType::Default => quote! {},
             ReturnType::Type(_, ty) => quote! {
-                let output_ptr = #output_start as *mut u8;
-                let output_slice = unsafe {
-                    core::slice::from_raw_parts_mut(output_ptr, #max_output_len)
-                };
+                let output_slice = unsafe { jolt::journal::output_after_journal() };
 
-                jolt::postcard::to_slice::<#ty>(&to_return, output_slice).unwrap();
+                <#codec as jolt::Encode<#ty>>::encode(&to_return, output_slice).unwrap();
             },
         };
 
-        let panic_fn = self.make_panic(memory_layout.panic);
+        quote! {
+            #(#args_fetch;)*
+            #raw_input_fetch
+            #check_input_len
+            #set_journal
+            #block
+            #handle_return
+        }
+    }
+
+    /// Signals to the host that the guest has returned
+    fn make_terminate(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let memory_layout =
+            MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
+        let termination_bit = memory_layout.termination as usize;
+
+        quote! {
+            unsafe {
+                core::ptr::write_volatile(#termination_bit as *mut u8, 1);
+            }
+        }
+    }
+
+    /// The guest's entry point, allocator and panic handler, which `main` relies on
+    fn make_start(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let memory_layout =
+            MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
+        let panic_fn = self.make_panic(&memory_layout);
         let declare_alloc = self.make_allocator();
 
         quote! {
@@ -338,25 +655,15 @@ impl MacroBuilder {
 
             #declare_alloc
 
-            #[cfg(feature = "guest")]
-            #[no_mangle]
-            pub extern "C" fn main() {
-                let mut offset = 0;
-                #get_input_slice
-                #(#args_fetch;)*
-                #check_input_len
-                #block
-                #handle_return
-                unsafe {
-                    core::ptr::write_volatile(#termination_bit as *mut u8, 1);
-                }
-            }
-
             #panic_fn
         }
     }
 
-    fn make_panic(&self, panic_address: u64) -> TokenStream2 {
+    fn make_panic(&self, memory_layout: &MemoryLayout) -> TokenStream2 {
+        let panic_address = memory_layout.panic;
+        let output_start = memory_layout.output_start;
+        let max_output_len = memory_layout.max_output_size as usize;
+
         if self.std {
             quote! {
                 #[cfg(feature = "guest")]
@@ -376,7 +683,14 @@ impl MacroBuilder {
 
                 #[cfg(feature = "guest")]
                 #[panic_handler]
-                fn panic(_info: &PanicInfo) -> ! {
+                fn panic(info: &PanicInfo) -> ! {
+                    // The output region is reused to report the panic message to the host
+                    let output_ptr = #output_start as *mut u8;
+                    let output_slice = unsafe {
+                        core::slice::from_raw_parts_mut(output_ptr, #max_output_len)
+                    };
+                    jolt::write_panic_message(info, output_slice);
+
                     unsafe {
                         core::ptr::write_volatile(#panic_address as *mut u8, 1);
                     }
@@ -387,6 +701,31 @@ impl MacroBuilder {
         }
     }
 
+    /// In std guests, installs the panic hook that reports the panic message to
+    /// the host, as the panic handler of no_std guests does. std panics run the
+    /// hook and then abort through `jolt_panic`.
+    fn make_set_panic_hook(&self) -> TokenStream2 {
+        if !self.std {
+            return quote! {};
+        }
+        let attributes = parse_attributes(&self.attr);
+        let memory_layout =
+            MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
+        let output_start = memory_layout.output_start;
+        let max_output_len = memory_layout.max_output_size as usize;
+
+        quote! {
+            std::panic::set_hook(std::boxed::Box::new(|info| {
+                // The output region is reused to report the panic message to the host
+                let output_ptr = #output_start as *mut u8;
+                let output_slice = unsafe {
+                    core::slice::from_raw_parts_mut(output_ptr, #max_output_len)
+                };
+                jolt::write_panic_message(info, output_slice);
+            }));
+        }
+    }
+
     fn make_allocator(&self) -> TokenStream2 {
         if self.std {
             quote! {}
@@ -429,26 +768,15 @@ impl MacroBuilder {
             #[cfg(target_arch = "wasm32")]
             use std::vec::Vec;
             #[cfg(target_arch = "wasm32")]
-            use rmp_serde::Deserializer;
-            #[cfg(target_arch = "wasm32")]
-            use serde::{Deserialize, Serialize};
+            use serde::Serialize;
 
+            /// Public I/O of a verified proof, as returned to JS
             #[cfg(all(target_arch = "wasm32", not(feature = "guest")))]
-            use jolt::host::ELFInstruction;
-
-            #[cfg(all(target_arch = "wasm32", not(feature = "guest")))]
-            #[derive(Serialize, Deserialize)]
-            struct DecodedData {
-                bytecode: Vec<ELFInstruction>,
-                memory_init: Vec<(u64, u8)>,
-            }
-
-            #[cfg(target_arch = "wasm32")]
-            fn deserialize_from_bin<'a, T: Deserialize<'a>>(
-                data: &'a [u8],
-            ) -> Result<T, rmp_serde::decode::Error> {
-                let mut de = Deserializer::new(data);
-                Deserialize::deserialize(&mut de)
+            #[derive(Serialize)]
+            struct VerifiedIo {
+                inputs: Vec<u8>,
+                outputs: Vec<u8>,
+                panic: bool,
             }
         }
     }
@@ -494,13 +822,69 @@ impl MacroBuilder {
         }
     }
 
+    /// The codec of the function's arguments and return value, see `jolt::codec`
+    fn get_codec(&self) -> syn::Path {
+        let codec = parse_attributes(&self.attr)
+            .codec
+            .unwrap_or_else(|| "jolt::Postcard".to_string());
+        syn::parse_str(&codec).expect("expected a path to a codec")
+    }
+
+    /// Encodes each argument into the program's inputs
+    fn make_set_program_args(&self) -> Vec<TokenStream2> {
+        let codec = self.get_codec();
+        let max_input_len = parse_attributes(&self.attr).max_input_size as usize;
+        self.func_args
+            .iter()
+            .map(|(name, ty)| {
+                quote! {
+                    {
+                        let mut buf = vec![0; #max_input_len];
+                        let len = <#codec as jolt::Encode<#ty>>::encode(&#name, &mut buf)
+                            .expect("inputs exceed max_input_size");
+                        program.set_input_bytes(&buf[..len]);
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Sets `ret_val` to the function's return value, decoded from the outputs of
+    /// the final I/O device state `program_io`, where it follows the journal. A
+    /// guest that panicked wrote its panic message rather than a return value, so
+    /// `ret_val` is then a `GuestPanic` carrying the message; the proof of the
+    /// panic is still returned.
+    fn make_handle_return(&self, program_io: TokenStream2) -> TokenStream2 {
+        let codec = self.get_codec();
+        let decode = match &self.func.sig.output {
+            ReturnType::Default => quote! { () },
+            ReturnType::Type(_, ty) => quote! {
+                {
+                    let (_, output_bytes) = jolt::Journal::split(&#program_io.outputs).unwrap();
+                    let (ret_val, _) =
+                        <#codec as jolt::Decode<#ty>>::decode(output_bytes).unwrap();
+                    ret_val
+                }
+            },
+        };
+        quote! {
+            let ret_val = if #program_io.panic {
+                Err(jolt::GuestPanic {
+                    message: #program_io.panic_message(),
+                })
+            } else {
+                Ok(#decode)
+            };
+        }
+    }
+
     fn get_prove_output_type(&self) -> TokenStream2 {
         match &self.func.sig.output {
             ReturnType::Default => quote! {
-                ((), jolt::JoltHyperKZGProof)
+                (Result<(), jolt::GuestPanic>, jolt::JoltHyperKZGProof)
             },
             ReturnType::Type(_, ty) => quote! {
-                (#ty, jolt::JoltHyperKZGProof)
+                (Result<#ty, jolt::GuestPanic>, jolt::JoltHyperKZGProof)
             },
         }
     }
@@ -530,8 +914,17 @@ impl MacroBuilder {
         proc_macro::tracked_env::var("CARGO_PKG_NAME").unwrap()
     }
 
-    fn get_func_selector(&self) -> Option<String> {
-        proc_macro::tracked_env::var("JOLT_FUNC_NAME").ok()
+    /// The functions the guest is being built for: one, or several for a shared
+    /// build (see `Program::set_funcs`)
+    fn get_func_selector(&self) -> Option<Vec<String>> {
+        let funcs = proc_macro::tracked_env::var("JOLT_FUNC_NAME").ok()?;
+        Some(funcs.split(',').map(str::to_string).collect())
+    }
+
+    /// The guest's tests, in a test build
+    fn get_test_selector(&self) -> Option<Vec<String>> {
+        let tests = proc_macro::tracked_env::var("JOLT_TEST_NAMES").ok()?;
+        Some(tests.split(',').map(str::to_string).collect())
     }
 
     fn has_wasm_attr(&self) -> bool {
@@ -541,26 +934,60 @@ impl MacroBuilder {
     fn make_wasm_function(&self) -> TokenStream2 {
         let fn_name = self.get_func_name();
         let verify_wasm_fn_name = Ident::new(&format!("verify_{}", fn_name), fn_name.span());
+        let verify_io_wasm_fn_name =
+            Ident::new(&format!("verify_{}_with_io", fn_name), fn_name.span());
 
         quote! {
             #[wasm_bindgen]
             #[cfg(all(target_arch = "wasm32", not(feature = "guest")))]
             pub fn #verify_wasm_fn_name(preprocessing_data: &[u8], proof_bytes: &[u8]) -> bool {
-                use jolt::{Jolt, JoltHyperKZGProof, RV32IJoltVM, ProofTranscript};
+                #verify_io_wasm_fn_name(preprocessing_data, proof_bytes).is_ok()
+            }
 
-                let decoded_preprocessing_data: DecodedData = deserialize_from_bin(preprocessing_data).unwrap();
-                let proof = JoltHyperKZGProof::deserialize_from_bytes(proof_bytes).unwrap();
+            /// Verifies the proof and returns the public I/O it attests to, as an
+            /// object with the serialized `inputs` and `outputs` and the `panic` flag.
+            /// Throws if the data cannot be decoded or the proof is invalid.
+            #[wasm_bindgen]
+            #[cfg(all(target_arch = "wasm32", not(feature = "guest")))]
+            pub fn #verify_io_wasm_fn_name(
+                preprocessing_data: &[u8],
+                proof_bytes: &[u8],
+            ) -> Result<JsValue, JsError> {
+                use jolt::{
+                    Jolt, JoltHyperKZGProof, ProgramPreprocessing, ProverConfig, RV32IJoltVM,
+                    Serializable,
+                };
 
-                let preprocessing = RV32IJoltVM::preprocess(
-                    decoded_preprocessing_data.bytecode,
-                    decoded_preprocessing_data.memory_init,
-                    1 << 20,
-                    1 << 20,
-                    1 << 24,
-                );
+                // Checked against its program hash when deserialized
+                let program = ProgramPreprocessing::deserialize_from_bytes(preprocessing_data)
+                    .map_err(|e| JsError::new(&format!("Failed to decode preprocessing: {e}")))?;
+                let proof = JoltHyperKZGProof::deserialize_from_bytes(proof_bytes)
+                    .map_err(|e| JsError::new(&format!("Failed to decode proof: {e}")))?;
+
+                // The maximum sizes `preprocess_*` uses
+                let config = ProverConfig::default();
+                let preprocessing: jolt::JoltPreprocessing<
+                    4,
+                    jolt::F,
+                    jolt::PCS,
+                    jolt::ProofTranscript,
+                > = RV32IJoltVM::preprocess_program(
+                        program,
+                        config.max_bytecode_size(),
+                        config.max_memory_address(),
+                        config.max_trace_length(),
+                    );
+
+                let program_io = proof.proof.program_io.clone();
+                RV32IJoltVM::verify(preprocessing, proof.proof, proof.commitments, None)
+                    .map_err(|e| JsError::new(&format!("Invalid proof: {e}")))?;
 
-                let result = RV32IJoltVM::verify(preprocessing, proof.proof, proof.commitments);
-                result.is_ok()
+                let io = VerifiedIo {
+                    inputs: program_io.inputs,
+                    outputs: program_io.outputs,
+                    panic: program_io.panic,
+                };
+                serde_wasm_bindgen::to_value(&io).map_err(|e| JsError::new(&e.to_string()))
             }
         }
     }
@@ -0,0 +1,38 @@
+//! Untrusted advice (hints) from the host.
+//!
+//! Bytes passed to the host's `Program::set_advice` can be read by the guest
+//! with [`read_word`] and [`read`]. Advice is not part of the program's public
+//! inputs and nothing about it is enforced by the proof: the prover may supply
+//! any values at all. It is meant for things that are expensive to compute but
+//! cheap to check, e.g. inverses, square roots or a sorted permutation, so the
+//! guest must always verify what it reads.
+//!
+//! Each word is read by a single `ADVICE` instruction (the RISC-V custom-0
+//! opcode), which writes it directly to a register; the advice never passes
+//! through the memory argument. Reads past the end of the advice return zero.
+
+/// Reads the next little-endian word of advice.
+#[cfg(target_arch = "riscv32")]
+pub fn read_word() -> u32 {
+    let word: u32;
+    unsafe {
+        core::arch::asm!(".insn i 0x0b, 0, {rd}, x0, 0", rd = out(reg) word);
+    }
+    word
+}
+
+/// Reads the next little-endian word of advice.
+#[cfg(not(target_arch = "riscv32"))]
+pub fn read_word() -> u32 {
+    panic!("advice is only available to guests running in the Jolt VM")
+}
+
+/// Fills `buf` with advice bytes. Advice is consumed a word at a time, so if
+/// `buf.len()` is not a multiple of 4 the remaining bytes of the last word are
+/// discarded.
+pub fn read(buf: &mut [u8]) {
+    for chunk in buf.chunks_mut(4) {
+        let word = read_word().to_le_bytes();
+        chunk.copy_from_slice(&word[..chunk.len()]);
+    }
+}
@@ -0,0 +1,92 @@
+//! Encodings of the arguments and return values of provable functions.
+//!
+//! A codec is a marker type implementing [`Decode`] and [`Encode`] for the
+//! types it supports, chosen with the `codec` attribute of `provable`, e.g.
+//! `#[jolt::provable(codec = "jolt::Raw")]`. The host encodes the arguments into
+//! the guest's input region and decodes the return value from its output region
+//! with the same codec, so the public I/O committed to by a proof is in that
+//! encoding. The default is [`Postcard`]. Other serialization formats, e.g.
+//! bincode or rkyv, can be used by implementing both traits for a new codec.
+
+use serde::{Deserialize, Serialize};
+
+/// A value could not be encoded or decoded, e.g. because it does not fit in
+/// the buffer or the bytes are malformed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CodecError;
+
+/// Decodes values of type `T` with the codec `Self`
+pub trait Decode<'a, T> {
+    /// Decodes a value from the start of `bytes`, and returns it along with the
+    /// bytes following it.
+    fn decode(bytes: &'a [u8]) -> Result<(T, &'a [u8]), CodecError>;
+}
+
+/// Encodes values of type `T` with the codec `Self`
+pub trait Encode<T: ?Sized> {
+    /// Encodes `value` at the start of `buf`, and returns the number of bytes
+    /// written.
+    fn encode(value: &T, buf: &mut [u8]) -> Result<usize, CodecError>;
+}
+
+/// The `postcard` format, for any type implementing `serde`'s traits
+pub struct Postcard;
+
+impl<'a, T: Deserialize<'a>> Decode<'a, T> for Postcard {
+    fn decode(bytes: &'a [u8]) -> Result<(T, &'a [u8]), CodecError> {
+        postcard::take_from_bytes(bytes).map_err(|_| CodecError)
+    }
+}
+
+impl<T: Serialize + ?Sized> Encode<T> for Postcard {
+    fn encode(value: &T, buf: &mut [u8]) -> Result<usize, CodecError> {
+        postcard::to_slice(value, buf)
+            .map(|bytes| bytes.len())
+            .map_err(|_| CodecError)
+    }
+}
+
+/// Bytes as they are: byte arrays verbatim, and byte slices prefixed with their
+/// length as a little-endian `u32`. Guests reading large byte inputs avoid any
+/// decoding cost, and the committed I/O is readable without a deserializer.
+pub struct Raw;
+
+impl<'a> Decode<'a, &'a [u8]> for Raw {
+    fn decode(bytes: &'a [u8]) -> Result<(&'a [u8], &'a [u8]), CodecError> {
+        let (len, rest) = <Raw as Decode<[u8; 4]>>::decode(bytes)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if rest.len() < len {
+            return Err(CodecError);
+        }
+        Ok(rest.split_at(len))
+    }
+}
+
+impl<'a, const N: usize> Decode<'a, [u8; N]> for Raw {
+    fn decode(bytes: &'a [u8]) -> Result<([u8; N], &'a [u8]), CodecError> {
+        if bytes.len() < N {
+            return Err(CodecError);
+        }
+        let (value, rest) = bytes.split_at(N);
+        Ok((value.try_into().unwrap(), rest))
+    }
+}
+
+impl Encode<&[u8]> for Raw {
+    fn encode(value: &&[u8], buf: &mut [u8]) -> Result<usize, CodecError> {
+        let len = u32::try_from(value.len()).map_err(|_| CodecError)?;
+        let prefix_len = <Raw as Encode<[u8; 4]>>::encode(&len.to_le_bytes(), buf)?;
+        let end = prefix_len + value.len();
+        buf.get_mut(prefix_len..end)
+            .ok_or(CodecError)?
+            .copy_from_slice(value);
+        Ok(end)
+    }
+}
+
+impl<const N: usize> Encode<[u8; N]> for Raw {
+    fn encode(value: &[u8; N], buf: &mut [u8]) -> Result<usize, CodecError> {
+        buf.get_mut(..N).ok_or(CodecError)?.copy_from_slice(value);
+        Ok(N)
+    }
+}
@@ -0,0 +1,42 @@
+//! Untrusted data computed by the host on the guest's request.
+//!
+//! Unlike advice, which the host must supply before the guest runs, a hint is
+//! asked for at runtime: the guest sends a request, e.g. a key whose Merkle path
+//! it needs, to the handler the host registered with `Program::set_hint`, and
+//! reads back the handler's response. The response is delivered as advice, so
+//! it is just as unconstrained by the proof: the guest must verify it.
+//!
+//! A request is an `ADVICE` instruction with immediate 1 and the request in
+//! `a0`-`a2`. The tracer inserts the response, prefixed with its length, at the
+//! front of the remaining advice, and replays recorded responses when the
+//! program is traced again, so proving sees the same hints as execution.
+
+use crate::advice;
+
+/// Sends `request` to the host's hint handler `id`, reads its response into
+/// `buf` and returns the length of the response. Panics if `buf` is too short
+/// for the response.
+pub fn request(id: u32, request: &[u8], buf: &mut [u8]) -> usize {
+    send(id, request);
+    let len = advice::read_word() as usize;
+    assert!(len <= buf.len(), "hint response does not fit in the buffer");
+    advice::read(&mut buf[..len]);
+    len
+}
+
+#[cfg(target_arch = "riscv32")]
+fn send(id: u32, request: &[u8]) {
+    unsafe {
+        core::arch::asm!(
+            ".insn i 0x0b, 0, x0, x0, 1",
+            in("a0") request.as_ptr(),
+            in("a1") request.len(),
+            in("a2") id,
+        );
+    }
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+fn send(_id: u32, _request: &[u8]) {
+    panic!("hints are only available to guests running in the Jolt VM")
+}
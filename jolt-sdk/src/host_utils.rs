@@ -10,9 +10,19 @@ pub use jolt_core::host;
 pub use jolt_core::jolt::instruction;
 pub use jolt_core::jolt::vm::{
     bytecode::BytecodeRow,
+    calibration::CalibrationProfile,
+    config::{PcsBackend, ProverConfig, ProverConfigBuilder, ThreadPriority},
+    envelope::{ProofHeader, PROOF_FORMAT_VERSION},
+    memory_tracker::{MemoryTracker, StageMemory, TrackingAllocator},
+    progress::{CancellationToken, ProverProgress, ProverStage, ProvingTask},
     rv32i_vm::{
-        JoltHyperKZGProof, ProofTranscript, RV32IJoltProof, RV32IJoltVM, Serializable, PCS, RV32I,
+        JoltHyperKZGProof, ProofTranscript, RV32IJoltProof, RV32IJoltVM, RV32IProofEnvelope,
+        Serializable, PCS, RV32I,
     },
-    Jolt, JoltCommitments, JoltPreprocessing, JoltProof,
+    stage_summary::{StageSummary, StageTiming},
+    DevProof, InputCommitment, Jolt, JoltCommitments, JoltPreprocessing, JoltProof,
+    ProgramCommitment, ProgramPreprocessing, VerifierKey,
 };
+pub use jolt_core::poly::commitment::setup_cache::SetupCache;
+pub use jolt_core::utils::errors::{ConfigError, GuestPanic, ProvingCancelled};
 pub use tracer;
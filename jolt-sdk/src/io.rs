@@ -0,0 +1,76 @@
+//! Reading raw guest input.
+//!
+//! Bytes passed to the host's `Program::set_raw_input` are placed in the
+//! guest's input region after the serialized function arguments, and are loaded
+//! into guest memory with the rest of the inputs before the guest starts. Rather
+//! than deserializing them into a value, guests can read them in chunks through
+//! [`raw_input`], which copies out of the input region. Since the input region
+//! holds all of them, the bytes count towards `max_input_size`.
+
+static mut RAW_INPUT_PTR: usize = 0;
+static mut RAW_INPUT_LEN: usize = 0;
+
+/// Reader over the guest's raw input, in the input region.
+pub struct RawInput {
+    _private: (),
+}
+
+/// Returns a reader over the guest's raw input. This is not a streaming reader:
+/// all of the raw input is in guest memory from the start, so it is bounded by
+/// the function's `max_input_size`, less the other inputs.
+pub fn raw_input() -> RawInput {
+    RawInput { _private: () }
+}
+
+/// Points the raw input at `len` bytes starting at `ptr`. Called by the
+/// `provable` entrypoint; guests should not need to call this directly.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` bytes for the rest of the program.
+pub unsafe fn set_raw_input(ptr: *const u8, len: usize) {
+    RAW_INPUT_PTR = ptr as usize;
+    RAW_INPUT_LEN = len;
+}
+
+impl RawInput {
+    /// Reads up to `buf.len()` bytes into `buf`, returning the number of bytes
+    /// read. Returns 0 once all of the raw input has been read.
+    pub fn read(&mut self, buf: &mut [u8]) -> usize {
+        let (ptr, len) = unsafe { (RAW_INPUT_PTR, RAW_INPUT_LEN) };
+        let n = buf.len().min(len);
+        if n == 0 {
+            return 0;
+        }
+
+        let src = unsafe { core::slice::from_raw_parts(ptr as *const u8, n) };
+        buf[..n].copy_from_slice(src);
+        unsafe {
+            RAW_INPUT_PTR = ptr + n;
+            RAW_INPUT_LEN = len - n;
+        }
+        n
+    }
+
+    /// Fills `buf` entirely, returning `false` (and reading nothing) if fewer
+    /// than `buf.len()` bytes of raw input remain.
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> bool {
+        if self.remaining() < buf.len() {
+            return false;
+        }
+        self.read(buf);
+        true
+    }
+
+    /// Number of bytes of raw input left to read.
+    pub fn remaining(&self) -> usize {
+        unsafe { RAW_INPUT_LEN }
+    }
+}
+
+#[cfg(feature = "host")]
+impl std::io::Read for RawInput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(RawInput::read(self, buf))
+    }
+}
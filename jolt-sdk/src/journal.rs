@@ -0,0 +1,122 @@
+//! A public journal of values committed by the guest.
+//!
+//! Besides its return value, a provable function can make any number of values
+//! public with [`commit`]. They are appended to the journal, which the guest
+//! writes to the start of its output region, before the return value: a
+//! little-endian `u32` length followed by the postcard encodings of the
+//! committed values. Since the output region is part of the public I/O a proof
+//! attests to, a verified proof authenticates the journal as much as the
+//! return value. The host reads it back with [`Journal`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::codec::{CodecError, Decode, Encode, Postcard};
+
+/// Size of the journal's length prefix
+pub const JOURNAL_LEN_SIZE: usize = 4;
+
+static mut OUTPUT_PTR: usize = 0;
+static mut OUTPUT_LEN: usize = 0;
+static mut JOURNAL_LEN: usize = 0;
+
+/// Points the journal at the output region of `len` bytes starting at `ptr`.
+/// Called by the `provable` entrypoint; guests should not need to call this
+/// directly.
+///
+/// # Safety
+///
+/// `ptr` must be valid for writes of `len` bytes for the rest of the program,
+/// and nothing else may write there.
+pub unsafe fn set_output(ptr: *mut u8, len: usize) {
+    OUTPUT_PTR = ptr as usize;
+    OUTPUT_LEN = len;
+    JOURNAL_LEN = 0;
+}
+
+/// The output region after the journal, where the `provable` entrypoint writes
+/// the return value.
+///
+/// # Safety
+///
+/// The output region must have been set with `set_output`, and the returned
+/// slice must be dropped before the next `commit`.
+pub unsafe fn output_after_journal() -> &'static mut [u8] {
+    let start = JOURNAL_LEN_SIZE + JOURNAL_LEN;
+    let output = core::slice::from_raw_parts_mut(OUTPUT_PTR as *mut u8, OUTPUT_LEN);
+    &mut output[start.min(OUTPUT_LEN)..]
+}
+
+/// Appends `value` to the public journal. Panics if the journal no longer fits
+/// in the output region (see `max_output_size`). Outside the Jolt VM, e.g. when
+/// the function is run natively on the host, the value is discarded.
+pub fn commit<T: Serialize + ?Sized>(value: &T) {
+    if unsafe { OUTPUT_PTR } == 0 {
+        return;
+    }
+    let (output, journal_len) = unsafe {
+        (
+            core::slice::from_raw_parts_mut(OUTPUT_PTR as *mut u8, OUTPUT_LEN),
+            JOURNAL_LEN,
+        )
+    };
+    let start = JOURNAL_LEN_SIZE + journal_len;
+    let written = output
+        .get_mut(start..)
+        .and_then(|buf| Postcard::encode(value, buf).ok())
+        .expect("journal exceeds max_output_size");
+    let journal_len = journal_len + written;
+    output[..JOURNAL_LEN_SIZE].copy_from_slice(&(journal_len as u32).to_le_bytes());
+    unsafe {
+        JOURNAL_LEN = journal_len;
+    }
+}
+
+/// The values a guest committed, read back from its outputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Journal<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Journal<'a> {
+    /// Splits a guest's `outputs` (e.g. `program_io.outputs` of a proof) into
+    /// its journal and the encoded return value following it.
+    pub fn split(outputs: &'a [u8]) -> Result<(Self, &'a [u8]), CodecError> {
+        // Outputs are only as long as the last nonzero byte written
+        if outputs.len() < JOURNAL_LEN_SIZE {
+            if outputs.iter().any(|byte| *byte != 0) {
+                return Err(CodecError);
+            }
+            return Ok((Self { bytes: &[] }, &[]));
+        }
+        let (len, rest) = <crate::Raw as Decode<[u8; 4]>>::decode(outputs)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if rest.len() < len {
+            return Err(CodecError);
+        }
+        let (bytes, rest) = rest.split_at(len);
+        Ok((Self { bytes }, rest))
+    }
+
+    /// The journal attested to by `proof`. It is authenticated once the proof
+    /// verifies.
+    #[cfg(feature = "host")]
+    pub fn from_proof(proof: &'a crate::JoltHyperKZGProof) -> Result<Self, CodecError> {
+        Self::split(&proof.proof.program_io.outputs).map(|(journal, _)| journal)
+    }
+
+    /// Decodes the next committed value, in the order the guest committed them
+    pub fn read<T: Deserialize<'a>>(&mut self) -> Result<T, CodecError> {
+        let (value, rest) = <Postcard as Decode<T>>::decode(self.bytes)?;
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    /// The encoded values not read yet
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
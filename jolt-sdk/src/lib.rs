@@ -2,9 +2,12 @@
 
 extern crate jolt_sdk_macros;
 
-pub use jolt_sdk_macros::provable;
+pub use jolt_sdk_macros::{provable, test};
 pub use postcard;
 
+pub mod codec;
+pub use codec::{CodecError, Decode, Encode, Postcard, Raw};
+
 #[cfg(feature = "host")]
 pub mod host_utils;
 #[cfg(feature = "host")]
@@ -12,3 +15,21 @@ pub use host_utils::*;
 
 pub mod alloc;
 pub use alloc::*;
+
+pub mod panic;
+pub use panic::*;
+
+pub mod prover_rand;
+pub use prover_rand::prover_rand_bytes;
+
+pub mod io;
+pub use io::raw_input;
+
+pub mod advice;
+
+pub mod journal;
+pub use journal::{commit, Journal};
+
+pub mod hint;
+
+pub mod log;
@@ -0,0 +1,71 @@
+//! Debug logging from the guest.
+//!
+//! [`log!`](crate::log!) formats a message like `println!` and sends it to the
+//! host, which records it with the cycle it was logged at (see
+//! `Program::logs`). Guests can thus be debugged while they are traced or
+//! proven, without a separate emulator run.
+//!
+//! A message is an `ADVICE` instruction with immediate 2 and the message's
+//! address and length in `a0` and `a1`. Like a hint request, it writes zero to
+//! `x0`, so it is proven as a no-op: the messages are not part of the proof,
+//! and a guest's proof is the same whether or not it logs.
+
+use core::fmt::{self, Write};
+
+/// Longest message `log!` sends; longer ones are truncated
+pub const MAX_MESSAGE_SIZE: usize = 256;
+
+/// Formats its arguments like `println!` and logs the message to the host.
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => {
+        $crate::log::log_fmt(format_args!($($arg)*))
+    };
+}
+
+/// Logs the message `args`, see `log!`.
+pub fn log_fmt(args: fmt::Arguments) {
+    let mut message = Message {
+        buf: [0; MAX_MESSAGE_SIZE],
+        len: 0,
+    };
+    let _ = message.write_fmt(args);
+    write(&message.buf[..message.len]);
+}
+
+/// Logs `message` to the host as is.
+#[cfg(target_arch = "riscv32")]
+pub fn write(message: &[u8]) {
+    unsafe {
+        core::arch::asm!(
+            ".insn i 0x0b, 0, x0, x0, 2",
+            in("a0") message.as_ptr(),
+            in("a1") message.len(),
+        );
+    }
+}
+
+/// Logs `message` to the host as is. Outside the Jolt VM, e.g. when the guest
+/// is unit tested natively, the message is printed to stderr instead.
+#[cfg(not(target_arch = "riscv32"))]
+pub fn write(message: &[u8]) {
+    #[cfg(feature = "host")]
+    eprintln!("{}", String::from_utf8_lossy(message));
+    #[cfg(not(feature = "host"))]
+    let _ = message;
+}
+
+/// A message being formatted, truncated to `MAX_MESSAGE_SIZE` bytes
+struct Message {
+    buf: [u8; MAX_MESSAGE_SIZE],
+    len: usize,
+}
+
+impl Write for Message {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = s.len().min(MAX_MESSAGE_SIZE - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use core::fmt::{self, Write};
+
+/// Writes the panic message and location into `out` as NUL-terminated UTF-8 so
+/// that the host can recover it from the guest's output region. `info` is the
+/// `PanicInfo` of the panic handler or, in std guests, the `PanicHookInfo` of
+/// the panic hook. Messages that don't fit are truncated.
+pub fn write_panic_message(info: &impl fmt::Display, out: &mut [u8]) {
+    if out.is_empty() {
+        return;
+    }
+
+    // Reserve the last byte for the NUL terminator
+    let capacity = out.len() - 1;
+    let mut writer = SliceWriter {
+        buf: &mut out[..capacity],
+        pos: 0,
+    };
+    let _ = write!(writer, "{}", info);
+    let end = writer.pos;
+
+    unsafe {
+        core::ptr::write_volatile(&mut out[end], 0);
+    }
+}
+
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            if self.pos == self.buf.len() {
+                return Err(fmt::Error);
+            }
+            // Volatile writes ensure the stores reach memory-mapped I/O
+            unsafe {
+                core::ptr::write_volatile(&mut self.buf[self.pos], byte);
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+//! Deterministic, prover-seeded pseudorandomness for guest programs.
+//!
+//! **The output is chosen by the prover, and is not random to the verifier.**
+//! The generator is ChaCha20 keyed by a 32-byte seed which the host places at
+//! the start of the guest's input region. The seed is a public input like any
+//! other: the proof shows which seed was used, but nothing constrains how it
+//! was picked, so a prover can try seeds until the guest's output suits them.
+//! Use it where reproducibility matters and unpredictability doesn't, e.g.
+//! randomized algorithms whose result the guest checks itself, and never where
+//! soundness or fairness depends on the bytes being unpredictable to the prover.
+
+extern crate alloc as rust_alloc;
+
+use core::cell::Cell;
+use rust_alloc::vec::Vec;
+
+pub use common::constants::RNG_SEED_SIZE as SEED_SIZE;
+
+/// The generator's ChaCha20 key and block counter
+struct Generator {
+    key: Cell<[u32; 8]>,
+    counter: Cell<u64>,
+}
+
+impl Generator {
+    const fn new() -> Self {
+        Self {
+            key: Cell::new([0; 8]),
+            counter: Cell::new(0),
+        }
+    }
+}
+
+// SAFETY: guests run on a single thread, so the generator is never shared
+// between threads
+#[cfg(not(feature = "host"))]
+unsafe impl Sync for Generator {}
+
+#[cfg(not(feature = "host"))]
+static GENERATOR: Generator = Generator::new();
+
+// Host builds may run the guest's functions natively, on any thread
+#[cfg(feature = "host")]
+std::thread_local! {
+    static GENERATOR: Generator = const { Generator::new() };
+}
+
+fn with_generator<T>(f: impl FnOnce(&Generator) -> T) -> T {
+    #[cfg(not(feature = "host"))]
+    {
+        f(&GENERATOR)
+    }
+    #[cfg(feature = "host")]
+    {
+        GENERATOR.with(f)
+    }
+}
+
+/// Seeds the generator. Called by the `provable` entrypoint before the guest
+/// function runs; guests should not need to call this directly.
+pub fn set_seed(seed: &[u8; SEED_SIZE]) {
+    let mut key = [0u32; 8];
+    for (word, chunk) in key.iter_mut().zip(seed.chunks_exact(4)) {
+        *word = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    with_generator(|generator| {
+        generator.key.set(key);
+        generator.counter.set(0);
+    });
+}
+
+/// Returns `n` bytes drawn from the prover-seeded generator. See the module
+/// documentation: the prover controls these bytes.
+pub fn prover_rand_bytes(n: usize) -> Vec<u8> {
+    let mut bytes = rust_alloc::vec![0u8; n];
+    fill_bytes(&mut bytes);
+    bytes
+}
+
+/// Fills `dest` with bytes drawn from the prover-seeded generator.
+pub fn fill_bytes(dest: &mut [u8]) {
+    with_generator(|generator| {
+        let key = generator.key.get();
+        let mut counter = generator.counter.get();
+        for chunk in dest.chunks_mut(64) {
+            let block = chacha20_block(&key, counter);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            counter += 1;
+        }
+        generator.counter.set(counter);
+    });
+}
+
+fn chacha20_block(key: &[u32; 8], counter: u64) -> [u8; 64] {
+    let mut initial = [0u32; 16];
+    // "expand 32-byte k"
+    initial[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]);
+    initial[4..12].copy_from_slice(key);
+    initial[12] = counter as u32;
+    initial[13] = (counter >> 32) as u32;
+
+    let mut state = initial;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut out = [0u8; 64];
+    for (i, chunk) in out.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&state[i].wrapping_add(initial[i]).to_le_bytes());
+    }
+    out
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
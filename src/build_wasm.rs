@@ -7,24 +7,19 @@ use std::{
 };
 
 use eyre::Result;
-use jolt_core::host::{ELFInstruction, Program};
-use rmp_serde::Serializer;
-use serde::{Deserialize, Serialize};
+use jolt_core::host::Program;
+use jolt_core::jolt::vm::rv32i_vm::Serializable;
 use syn::{Attribute, ItemFn, Meta, PathSegment};
 use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
 
-#[derive(Serialize, Deserialize)]
-struct DecodedData {
-    bytecode: Vec<ELFInstruction>,
-    memory_init: Vec<(u64, u8)>,
-}
-
-struct FunctionAttributes {
+pub(crate) struct FunctionAttributes {
     pub func_name: String,
     pub attributes: Attributes,
 }
 
-fn preprocess_and_save(func_name: &str, attributes: &Attributes, is_std: bool) -> Result<()> {
+/// The guest program of the provable function `func_name`, configured by its
+/// attributes
+pub(crate) fn guest_program(func_name: &str, attributes: &Attributes, is_std: bool) -> Program {
     let mut program = Program::new("guest");
 
     program.set_func(func_name);
@@ -33,15 +28,12 @@ fn preprocess_and_save(func_name: &str, attributes: &Attributes, is_std: bool) -
     program.set_stack_size(attributes.stack_size);
     program.set_max_input_size(attributes.max_input_size);
     program.set_max_output_size(attributes.max_output_size);
+    program
+}
 
-    let (bytecode, memory_init) = program.decode();
-    let decoded_data = DecodedData {
-        bytecode,
-        memory_init,
-    };
-
-    let mut buf = Vec::new();
-    decoded_data.serialize(&mut Serializer::new(&mut buf))?;
+fn preprocess_and_save(func_name: &str, attributes: &Attributes, is_std: bool) -> Result<()> {
+    let mut program = guest_program(func_name, attributes, is_std);
+    let buf = program.program_preprocessing().serialize_to_bytes()?;
 
     let target_dir = Path::new("target/wasm32-unknown-unknown/release");
     fs::create_dir_all(target_dir)?;
@@ -52,7 +44,7 @@ fn preprocess_and_save(func_name: &str, attributes: &Attributes, is_std: bool) -
     Ok(())
 }
 
-fn extract_provable_functions() -> Vec<FunctionAttributes> {
+pub(crate) fn extract_provable_functions() -> Vec<FunctionAttributes> {
     let guest_path = Path::new("guest/src/lib.rs");
     let content = fs::read_to_string(guest_path)
         .unwrap_or_else(|_| panic!("Unable to read file: {:?}", guest_path));
@@ -102,7 +94,7 @@ fn get_project_name() -> Option<String> {
     })
 }
 
-fn is_std() -> Option<bool> {
+pub(crate) fn is_std() -> Option<bool> {
     let content = fs::read_to_string("guest/Cargo.toml").expect("Failed to read Cargo.toml");
     let doc = content
         .parse::<DocumentMut>()
@@ -133,7 +125,7 @@ fn is_std() -> Option<bool> {
 fn create_index_html(func_names: Vec<String>) -> Result<()> {
     let func_names_with_verify_prefix: Vec<String> = func_names
         .iter()
-        .map(|name| format!("verify_{}", name))
+        .map(|name| format!("verify_{}_with_io", name))
         .collect();
 
     let mut html_content = String::from(HTML_HEAD);
@@ -184,8 +176,12 @@ fn create_index_html(func_names: Vec<String>) -> Result<()> {
                     const wasmBinary = await response.arrayBuffer();
                     const wasmData = new Uint8Array(wasmBinary);
 
-                    const result = verify_{0}(wasmData, proofData);
-                    alert(result ? "Proof is valid!" : "Proof is invalid.");
+                    try {{
+                        const io = verify_{0}_with_io(wasmData, proofData);
+                        alert(`Proof is valid! Serialized outputs: [${{io.outputs}}]`);
+                    }} catch (e) {{
+                        alert(`Proof is invalid: ${{e}}`);
+                    }}
                 }};
 
                 reader.readAsArrayBuffer(file);
@@ -215,7 +211,6 @@ pub fn modify_cargo_toml(name: &str) -> Result<()> {
         });
         dependencies.insert("serde_json", toml_edit::value("1.0"));
         dependencies.insert("serde-wasm-bindgen", toml_edit::value("=0.6.5"));
-        dependencies.insert("rmp-serde", toml_edit::value("1.3.0"));
     }
 
     {
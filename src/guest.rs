@@ -0,0 +1,55 @@
+use std::{fs, path::PathBuf};
+
+use clap::Args;
+use eyre::{eyre, Result};
+use jolt_core::host::Program;
+
+use crate::build_wasm::{extract_provable_functions, guest_program, is_std};
+
+/// A provable function of the guest in `guest/` and the inputs to run it on
+#[derive(Args)]
+pub struct GuestInput {
+    /// Name of the provable function
+    func: String,
+    /// File with the function's encoded arguments, as the host would pass them
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// File with the guest's raw input (see `jolt::raw_input`)
+    #[arg(long)]
+    raw_input: Option<PathBuf>,
+    /// File with the guest's advice (see `jolt::advice`)
+    #[arg(long)]
+    advice: Option<PathBuf>,
+}
+
+impl GuestInput {
+    /// The function's program, configured by its attributes, with the inputs set
+    pub fn program(&self) -> Result<Program> {
+        let mut program = function_program(&self.func)?;
+        if let Some(input) = &self.input {
+            program.set_input_bytes(&fs::read(input)?);
+        }
+        if let Some(raw_input) = &self.raw_input {
+            program.set_raw_input(&fs::read(raw_input)?);
+        }
+        if let Some(advice) = &self.advice {
+            program.set_advice(&fs::read(advice)?);
+        }
+        Ok(program)
+    }
+}
+
+/// The program of the provable function `func` of the guest in `guest/`,
+/// configured by its attributes
+pub(crate) fn function_program(func: &str) -> Result<Program> {
+    let function = extract_provable_functions()
+        .into_iter()
+        .find(|function| function.func_name == func)
+        .ok_or_else(|| eyre!("no provable function {} in guest/src/lib.rs", func))?;
+    let is_std = is_std().ok_or_else(|| eyre!("could not read the guest's jolt dependency"))?;
+    Ok(guest_program(
+        &function.func_name,
+        &function.attributes,
+        is_std,
+    ))
+}
@@ -1,4 +1,9 @@
 mod build_wasm;
+mod guest;
+mod profile;
+mod proof;
+mod server;
+mod test;
 
 use std::{
     fs::{self, File},
@@ -12,6 +17,11 @@ use sysinfo::System;
 
 use build_wasm::{build_wasm, modify_cargo_toml};
 use jolt_core::host::toolchain;
+use jolt_core::jolt::vm::{calibration::CalibrationProfile, config::ProverConfig};
+use profile::{profile, ProfileOptions};
+use proof::{prove, verify, ProveOptions, VerifyOptions};
+use server::{serve, ServeOptions};
+use test::{test, TestOptions};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -36,6 +46,27 @@ enum Command {
     UninstallToolchain,
     /// Handles preprocessing and generates WASM compatible files
     BuildWasm,
+    /// Runs a provable function without proving it, and reports its cycle count,
+    /// the cycles of each function, and the projected costs of proving it
+    Profile(ProfileOptions),
+    /// Proves a provable function and writes the proof, the program's verifier
+    /// key and the public I/O to files
+    Prove(ProveOptions),
+    /// Verifies a proof written by `prove` with a verifier key, without the guest
+    Verify(VerifyOptions),
+    /// Runs the guest's `#[jolt::test]` functions in the emulator, without proving
+    /// them, and reports which of them passed
+    Test(TestOptions),
+    /// Serves an HTTP API to submit proving jobs for the guest's provable
+    /// functions, poll their status and fetch their proofs
+    Serve(ServeOptions),
+    /// Measures the prover's kernels on this machine and saves the profile,
+    /// which `prove` and `serve` then tune the prover to
+    Calibrate {
+        /// Number of threads to measure on. Defaults to all cores.
+        #[arg(long)]
+        threads: Option<usize>,
+    },
 }
 
 fn main() {
@@ -45,9 +76,39 @@ fn main() {
         Command::InstallToolchain => install_toolchain(),
         Command::UninstallToolchain => uninstall_toolchain(),
         Command::BuildWasm => build_wasm(),
+        Command::Profile(options) => profile(options).expect("profiling failed"),
+        Command::Prove(options) => prove(options).expect("proving failed"),
+        Command::Verify(options) => {
+            if let Err(err) = verify(options) {
+                eprintln!("invalid proof: {}", err);
+                std::process::exit(1);
+            }
+        }
+        Command::Serve(options) => serve(options).expect("server failed"),
+        Command::Calibrate { threads } => calibrate(threads).expect("calibration failed"),
+        Command::Test(options) => {
+            if let Err(err) = test(options) {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
     }
 }
 
+fn calibrate(threads: Option<usize>) -> Result<()> {
+    let mut builder = ProverConfig::builder();
+    if let Some(threads) = threads {
+        builder = builder.with_threads(threads);
+    }
+    let profile = builder.build()?.install(CalibrationProfile::measure);
+    let path = CalibrationProfile::default_path()
+        .ok_or_else(|| eyre::eyre!("no cache directory to save the calibration in"))?;
+    profile.save(&path)?;
+    println!("{:#?}", profile);
+    println!("saved to {}", path.display());
+    Ok(())
+}
+
 fn create_project(name: String, wasm: bool) {
     create_folder_structure(&name).expect("could not create directory");
     create_host_files(&name).expect("file creation failed");
@@ -58,7 +119,9 @@ fn create_project(name: String, wasm: bool) {
 }
 
 fn install_toolchain() {
-    if let Err(err) = toolchain::install_toolchain() {
+    if let Err(err) =
+        toolchain::install_toolchain().and_then(|_| toolchain::install_no_std_toolchain())
+    {
         panic!("toolchain install failed: {}", err);
     }
     display_welcome();
@@ -202,6 +265,7 @@ const HOST_MAIN: &str = r#"pub fn main() {
     let (prove_fib, verify_fib) = guest::build_fib();
 
     let (output, proof) = prove_fib(50);
+    let output = output.expect("the guest panicked");
     let is_valid = verify_fib(proof);
 
     println!("output: {}", output);
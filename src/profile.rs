@@ -0,0 +1,47 @@
+use clap::Args;
+use eyre::Result;
+use jolt_core::jolt::vm::rv32i_vm::{ProofTranscript, PCS};
+
+use crate::guest::GuestInput;
+use crate::proof::F;
+
+#[derive(Args)]
+pub struct ProfileOptions {
+    #[command(flatten)]
+    guest: GuestInput,
+    /// Number of functions to list
+    #[arg(long, default_value_t = 20)]
+    top: usize,
+    /// Prover throughput assumed for the projected proving time
+    #[arg(long, default_value_t = 1_000_000)]
+    steps_per_second: usize,
+}
+
+/// Runs a provable function of the guest in `guest/` without proving it, and
+/// prints its cycle counts and projected proving costs.
+pub fn profile(options: ProfileOptions) -> Result<()> {
+    let mut program = options.guest.program()?;
+    let report = program.profile::<F, PCS, ProofTranscript>();
+    if let Some(guest_panic) = program.guest_panic() {
+        println!("{}", guest_panic);
+    }
+
+    println!("{}", report);
+    let proving_time = report.proving_time(options.steps_per_second);
+    println!(
+        "proving time (est.):    {:.1} s at {} steps/s",
+        proving_time.as_secs_f64(),
+        options.steps_per_second
+    );
+
+    println!("\ncycles by function:");
+    for (name, cycles) in report.functions.iter().take(options.top) {
+        println!(
+            "{:>12} {:>5.1}%  {}",
+            cycles,
+            100.0 * *cycles as f64 / report.cycles as f64,
+            name.as_deref().unwrap_or("<unknown>")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use clap::Args;
+use common::rv_trace::JoltDevice;
+use eyre::{eyre, Result};
+use jolt_core::{
+    host::Program,
+    jolt::vm::{
+        calibration::CalibrationProfile,
+        config::ProverConfig,
+        kat::RecordingPCS,
+        rv32i_vm::{ProofTranscript, RV32IJoltVM, RV32IProofEnvelope, Serializable, C, PCS},
+        Jolt, JoltPreprocessing, VerifierKey,
+    },
+    poly::commitment::commitment_scheme::CommitmentScheme,
+};
+
+use crate::guest::GuestInput;
+
+pub(crate) type F = <PCS as CommitmentScheme<ProofTranscript>>::Field;
+
+/// File names of the proof, verifier key and public I/O written by `jolt prove`
+const PROOF_FILE: &str = "proof.bin";
+const VERIFIER_KEY_FILE: &str = "vk.bin";
+const IO_FILE: &str = "io.bin";
+
+#[derive(Args)]
+pub struct ProveOptions {
+    #[command(flatten)]
+    guest: GuestInput,
+    /// Directory to write the proof, verifier key and public I/O to
+    #[arg(long, default_value = "proof")]
+    out: PathBuf,
+    /// Maximum trace length the proof may have. Sizes the PCS setup, and must be
+    /// the same for every proof verified with the same key.
+    #[arg(long, default_value_t = 1 << 24)]
+    max_trace_length: usize,
+    /// Number of threads to prove on. Defaults to all cores.
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Directory to cache the PCS setup in, so that later proofs of the same
+    /// maximum trace length skip computing it. Defaults to `$JOLT_SETUP_CACHE`.
+    #[arg(long)]
+    setup_cache: Option<PathBuf>,
+    /// Also writes known-answer test vectors of a proof of the execution to this
+    /// file, as JSON: every transcript state, challenge, commitment and sumcheck
+    /// message, for validating other verifier implementations. For tiny guests.
+    #[arg(long, value_name = "FILE")]
+    known_answer_vectors: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct VerifyOptions {
+    /// Proof written by `jolt prove`
+    proof: PathBuf,
+    /// Verifier key written by `jolt prove`
+    vk: PathBuf,
+    /// Public I/O the proof must attest to, e.g. written by `jolt prove`. By
+    /// default, the proof is verified against the I/O it carries, which is printed.
+    #[arg(long)]
+    io: Option<PathBuf>,
+}
+
+/// The profile saved by `jolt calibrate`, if any
+pub(crate) fn saved_calibration() -> Option<CalibrationProfile> {
+    CalibrationProfile::load(&CalibrationProfile::default_path()?).ok()
+}
+
+/// Proves a provable function of the guest in `guest/` and writes the proof,
+/// the verifier key of the program and the public I/O of the execution to
+/// `options.out`. The proof can then be checked with `jolt verify`, without the
+/// guest.
+pub fn prove(options: ProveOptions) -> Result<()> {
+    let mut builder = ProverConfig::builder().with_max_trace_length(options.max_trace_length);
+    if let Some(threads) = options.threads {
+        builder = builder.with_threads(threads);
+    }
+    if let Some(setup_cache) = options.setup_cache {
+        builder = builder.with_setup_cache(setup_cache);
+    }
+    if let Some(calibration) = saved_calibration() {
+        builder = builder.with_calibration(calibration);
+    }
+    let config = builder.build()?;
+    let mut program = options.guest.program()?;
+    let (preprocessing, verifier_key) = preprocess(&mut program, &config)?;
+
+    let (io_device, trace) = program.trace();
+    if let Some(guest_panic) = program.guest_panic() {
+        println!("{}", guest_panic);
+    }
+    let (proof, commitments, _) =
+        RV32IJoltVM::prove_with_config(io_device.clone(), trace, preprocessing.clone(), &config)?;
+    let envelope = RV32IProofEnvelope::new(proof, commitments, &preprocessing);
+
+    fs::create_dir_all(&options.out)?;
+    fs::write(options.out.join(PROOF_FILE), envelope.serialize_to_bytes()?)?;
+    verifier_key.save_to_file(options.out.join(VERIFIER_KEY_FILE))?;
+    io_device.serialize_compressed(File::create(options.out.join(IO_FILE))?)?;
+
+    println!("program: {}", hex(&verifier_key.program_hash()));
+    print_io(&io_device);
+    println!("wrote {}", options.out.display());
+
+    if let Some(path) = &options.known_answer_vectors {
+        program
+            .known_answer_vectors::<F, RecordingPCS>()?
+            .save(path)?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// Preprocesses `program` as configured by `config`, and binds it to the returned
+/// key. Proofs made with the preprocessing can then be verified from the key alone.
+pub(crate) fn preprocess(
+    program: &mut Program,
+    config: &ProverConfig,
+) -> Result<(
+    JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    VerifierKey<PCS, ProofTranscript>,
+)> {
+    let mut preprocessing =
+        RV32IJoltVM::preprocess_with_config(program.program_preprocessing(), config)?;
+    let verifier_key = RV32IJoltVM::bind_verifier_key_with_config(&mut preprocessing, config);
+    Ok((preprocessing, verifier_key))
+}
+
+/// Verifies a proof written by `jolt prove` with the program's verifier key.
+/// Fails if the proof is invalid, is not of the key's program, or does not
+/// attest to the expected I/O.
+pub fn verify(options: VerifyOptions) -> Result<()> {
+    let verifier_key = VerifierKey::<PCS, ProofTranscript>::from_file(&options.vk)?;
+    let envelope = RV32IProofEnvelope::<F, PCS, ProofTranscript>::deserialize_from_bytes(
+        &fs::read(&options.proof)?,
+    )?;
+    if envelope.header.program_hash != verifier_key.program_hash() {
+        return Err(eyre!("the proof is not of the verifier key's program"));
+    }
+
+    let preprocessing: JoltPreprocessing<C, F, PCS, ProofTranscript> =
+        RV32IJoltVM::preprocess_verifier(verifier_key.clone());
+    match &options.io {
+        Some(io) => {
+            let expected = JoltDevice::deserialize_compressed(File::open(io)?)?;
+            RV32IJoltVM::verify_with_io(
+                preprocessing,
+                envelope.proof,
+                envelope.commitments,
+                &expected,
+            )?;
+        }
+        None => {
+            print_io(&envelope.header.program_io);
+            RV32IJoltVM::verify(preprocessing, envelope.proof, envelope.commitments, None)?;
+        }
+    }
+
+    println!("program: {}", hex(&verifier_key.program_hash()));
+    println!("valid");
+    Ok(())
+}
+
+fn print_io(io: &JoltDevice) {
+    println!("inputs:  {}", hex(&io.inputs));
+    println!("outputs: {}", hex(&io.outputs));
+    println!("panic:   {}", io.panic);
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,578 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+};
+
+use clap::Args;
+use eyre::{bail, eyre, Result};
+use jolt_core::{
+    host::Program,
+    jolt::vm::{
+        config::{ProverConfig, ThreadPriority},
+        rv32i_vm::{ProofTranscript, RV32IJoltVM, RV32IProofEnvelope, Serializable, C, PCS},
+        Jolt, JoltPreprocessing, VerifierKey,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::guest::function_program;
+use crate::proof::{hex, preprocess, saved_calibration, F};
+
+/// Where programs uploaded with `POST /programs` are written
+const PROGRAMS_DIR: &str = "/tmp/jolt-server-programs";
+
+#[derive(Args)]
+pub struct ServeOptions {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:3030")]
+    addr: String,
+    /// Number of jobs proven at once. Each proof uses all of the prover's
+    /// threads, so more than one worker mostly helps with many small jobs.
+    #[arg(long, default_value_t = 1)]
+    workers: usize,
+    /// Maximum number of queued jobs, beyond which submissions are rejected
+    #[arg(long, default_value_t = 64)]
+    max_queue: usize,
+    /// Maximum trace length of a job. Sizes the PCS setup, as with `jolt prove`.
+    #[arg(long, default_value_t = 1 << 24)]
+    max_trace_length: usize,
+    /// Maximum estimated prover memory of a job in bytes (see `jolt profile`)
+    #[arg(long)]
+    max_memory: Option<usize>,
+    /// Maximum size of a request body in bytes
+    #[arg(long, default_value_t = 1 << 26)]
+    max_request_size: usize,
+    /// Number of threads jobs are proven on, shared by all workers. Defaults to
+    /// all cores.
+    #[arg(long)]
+    prover_threads: Option<usize>,
+    /// Prove at low scheduling priority, so that proving does not starve the
+    /// server's other threads or other processes on the machine
+    #[arg(long)]
+    low_priority: bool,
+}
+
+/// A job submitted with `POST /jobs`: a provable function of the guest, or a
+/// program uploaded with `POST /programs` by its hash, and its inputs,
+/// hex-encoded as `jolt prove` reads them from files. The input of an uploaded
+/// program is its whole input region, passed to it verbatim.
+#[derive(Deserialize)]
+struct JobRequest {
+    func: Option<String>,
+    program: Option<String>,
+    #[serde(default)]
+    input: String,
+    #[serde(default)]
+    raw_input: String,
+    #[serde(default)]
+    advice: String,
+}
+
+/// What a job proves
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum JobTarget {
+    Function(String),
+    /// An uploaded program, by its hex-encoded program hash
+    Program(String),
+}
+
+/// A job's inputs, decoded
+#[derive(Clone)]
+struct JobInputs {
+    target: JobTarget,
+    input: Vec<u8>,
+    raw_input: Vec<u8>,
+    advice: Vec<u8>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum JobStatus {
+    Queued,
+    Running,
+    Done { cycles: usize },
+    Failed { error: String },
+}
+
+#[derive(Serialize)]
+struct JobInfo {
+    id: u64,
+    #[serde(flatten)]
+    status: JobStatus,
+}
+
+struct Job {
+    inputs: JobInputs,
+    status: JobStatus,
+    /// The serialized `RV32IProofEnvelope`, once done
+    proof: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    queue: VecDeque<u64>,
+    jobs: HashMap<u64, Job>,
+}
+
+/// The preprocessing of a job target, and the verifier key of the guest's
+/// functions. Uploaded programs are not committed to, so their proofs verify
+/// with the preprocessing of their uploader (see `jolt_core::host::RemoteProver`).
+type Preprocessed = (
+    JoltPreprocessing<C, F, PCS, ProofTranscript>,
+    Option<VerifierKey<PCS, ProofTranscript>>,
+);
+
+/// A proving service for the guest in `guest/` and for uploaded programs. Jobs
+/// are queued and proven by a fixed number of workers; jobs are rejected up
+/// front if the queue is full, and fail if they exceed the trace length or
+/// prover memory limits. The preprocessing of each function or program is
+/// computed by its first job and reused.
+struct Server {
+    options: ServeOptions,
+    jobs: Mutex<Jobs>,
+    queued: Condvar,
+    preprocessing: Mutex<HashMap<JobTarget, Arc<Preprocessed>>>,
+    /// Uploaded programs by their hex-encoded program hash
+    programs: Mutex<HashMap<String, Program>>,
+    uploads: AtomicU64,
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_vec(value).unwrap(),
+        }
+    }
+
+    fn error(status: u16, message: impl ToString) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message.to_string() }))
+    }
+
+    fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body,
+        }
+    }
+
+    fn write_to(&self, mut stream: TcpStream) -> Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            202 => "Accepted",
+            400 => "Bad Request",
+            404 => "Not Found",
+            409 => "Conflict",
+            413 => "Payload Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        )?;
+        stream.write_all(&self.body)?;
+        Ok(())
+    }
+}
+
+/// Serves a proving API for the provable functions of the guest in `guest/`:
+///
+/// - `POST /programs` uploads an ELF, configured by the `max_input_size`,
+///   `max_output_size` and `memory_size` query parameters, and returns its hash
+/// - `POST /jobs` submits a job (see `JobRequest`) and returns its id
+/// - `GET /jobs/<id>` returns its status, and `DELETE /jobs/<id>` discards it
+/// - `GET /jobs/<id>/proof` returns its proof once done, as written by `jolt prove`
+/// - `GET /functions/<func>/vk` returns the verifier key of a function's proofs
+pub fn serve(options: ServeOptions) -> Result<()> {
+    let listener = TcpListener::bind(&options.addr)?;
+    let server = Arc::new(Server {
+        options,
+        jobs: Mutex::new(Jobs::default()),
+        queued: Condvar::new(),
+        preprocessing: Mutex::new(HashMap::new()),
+        programs: Mutex::new(HashMap::new()),
+        uploads: AtomicU64::new(0),
+    });
+    for _ in 0..server.options.workers.max(1) {
+        let server = server.clone();
+        thread::spawn(move || server.work());
+    }
+
+    println!("listening on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let server = server.clone();
+        thread::spawn(move || {
+            if let Err(err) = server.handle(stream) {
+                eprintln!("request failed: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+impl Server {
+    fn handle(&self, stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse()?;
+                }
+            }
+        }
+
+        let response = if content_length > self.options.max_request_size {
+            Response::error(413, "request too large")
+        } else {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            self.route(method, path, &body)
+        };
+        response.write_to(stream)
+    }
+
+    fn route(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let id = |id: &str| id.parse::<u64>().ok();
+        match (method, segments.as_slice()) {
+            ("POST", ["programs"]) => self.upload(query, body),
+            ("POST", ["jobs"]) => self.submit(body),
+            ("GET", ["jobs", job]) => match id(job) {
+                Some(id) => self.status(id),
+                None => Response::error(404, "no such job"),
+            },
+            ("DELETE", ["jobs", job]) => match id(job) {
+                Some(id) => self.discard(id),
+                None => Response::error(404, "no such job"),
+            },
+            ("GET", ["jobs", job, "proof"]) => match id(job) {
+                Some(id) => self.proof(id),
+                None => Response::error(404, "no such job"),
+            },
+            ("GET", ["functions", func, "vk"]) => self.verifier_key(func),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn upload(&self, query: &str, elf: &[u8]) -> Response {
+        match self.add_program(query, elf) {
+            Ok(program_hash) => {
+                Response::json(200, &serde_json::json!({ "program": program_hash }))
+            }
+            Err(err) => Response::error(400, err),
+        }
+    }
+
+    /// Builds an uploaded program and returns its hex-encoded program hash
+    fn add_program(&self, query: &str, elf: &[u8]) -> Result<String> {
+        fs::create_dir_all(PROGRAMS_DIR)?;
+        let upload = self.uploads.fetch_add(1, Ordering::Relaxed);
+        let path = Path::new(PROGRAMS_DIR).join(format!("{}-{}.elf", process::id(), upload));
+        fs::write(&path, elf)?;
+
+        let mut program = Program::from_elf(path);
+        for (name, value) in query.split('&').filter_map(|param| param.split_once('=')) {
+            let value: u64 = value
+                .parse()
+                .map_err(|_| eyre!("invalid value of {}: {}", name, value))?;
+            match name {
+                "max_input_size" => program.set_max_input_size(value),
+                "max_output_size" => program.set_max_output_size(value),
+                "memory_size" => program.set_memory_size(value),
+                _ => bail!("unknown parameter {}", name),
+            }
+        }
+        // Decoding a malformed ELF panics
+        let program_hash = panic::catch_unwind(AssertUnwindSafe(|| {
+            hex(&program.program_preprocessing().program_hash)
+        }))
+        .map_err(|_| eyre!("could not decode the program"))?;
+        self.programs
+            .lock()
+            .unwrap()
+            .entry(program_hash.clone())
+            .or_insert(program);
+        Ok(program_hash)
+    }
+
+    fn submit(&self, body: &[u8]) -> Response {
+        let request = match serde_json::from_slice::<JobRequest>(body) {
+            Ok(request) => request,
+            Err(err) => return Response::error(400, err),
+        };
+        // Fail unknown targets now rather than once the job is run
+        let target = match (request.func, request.program) {
+            (Some(func), None) => match function_program(&func) {
+                Ok(_) => JobTarget::Function(func),
+                Err(err) => return Response::error(400, err),
+            },
+            (None, Some(program_hash)) => {
+                let program_hash = program_hash.trim_start_matches("0x").to_lowercase();
+                if !self.programs.lock().unwrap().contains_key(&program_hash) {
+                    return Response::error(404, "no such program");
+                }
+                JobTarget::Program(program_hash)
+            }
+            _ => return Response::error(400, "a job needs either a func or a program"),
+        };
+        let inputs = match (
+            from_hex(&request.input),
+            from_hex(&request.raw_input),
+            from_hex(&request.advice),
+        ) {
+            (Ok(input), Ok(raw_input), Ok(advice)) => JobInputs {
+                target,
+                input,
+                raw_input,
+                advice,
+            },
+            (Err(err), ..) | (_, Err(err), _) | (.., Err(err)) => return Response::error(400, err),
+        };
+
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.queue.len() >= self.options.max_queue {
+            return Response::error(503, "the queue is full");
+        }
+        let id = jobs.next_id;
+        jobs.next_id += 1;
+        jobs.jobs.insert(
+            id,
+            Job {
+                inputs,
+                status: JobStatus::Queued,
+                proof: None,
+            },
+        );
+        jobs.queue.push_back(id);
+        self.queued.notify_one();
+        Response::json(
+            202,
+            &JobInfo {
+                id,
+                status: JobStatus::Queued,
+            },
+        )
+    }
+
+    fn status(&self, id: u64) -> Response {
+        match self.jobs.lock().unwrap().jobs.get(&id) {
+            Some(job) => Response::json(
+                200,
+                &JobInfo {
+                    id,
+                    status: job.status.clone(),
+                },
+            ),
+            None => Response::error(404, "no such job"),
+        }
+    }
+
+    fn discard(&self, id: u64) -> Response {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.jobs.get(&id).map(|job| &job.status) {
+            None => Response::error(404, "no such job"),
+            Some(JobStatus::Running) => Response::error(409, "the job is running"),
+            Some(_) => {
+                jobs.jobs.remove(&id);
+                jobs.queue.retain(|queued| *queued != id);
+                Response::json(200, &serde_json::json!({ "id": id }))
+            }
+        }
+    }
+
+    fn proof(&self, id: u64) -> Response {
+        match self.jobs.lock().unwrap().jobs.get(&id) {
+            Some(Job {
+                proof: Some(proof), ..
+            }) => Response::bytes(proof.clone()),
+            Some(_) => Response::error(409, "the job is not done"),
+            None => Response::error(404, "no such job"),
+        }
+    }
+
+    fn verifier_key(&self, func: &str) -> Response {
+        match self
+            .preprocessed(&JobTarget::Function(func.to_string()))
+            .and_then(|preprocessed| preprocessed.1.as_ref().unwrap().serialize_to_bytes())
+        {
+            Ok(bytes) => Response::bytes(bytes),
+            Err(err) => Response::error(404, err),
+        }
+    }
+
+    /// Proves queued jobs, one at a time
+    fn work(&self) {
+        loop {
+            let (id, inputs) = {
+                let mut jobs = self.jobs.lock().unwrap();
+                let id = loop {
+                    match jobs.queue.pop_front() {
+                        Some(id) => break id,
+                        None => jobs = self.queued.wait(jobs).unwrap(),
+                    }
+                };
+                let job = jobs.jobs.get_mut(&id).unwrap();
+                job.status = JobStatus::Running;
+                (id, job.inputs.clone())
+            };
+
+            // A panicking job, e.g. one whose guest fails to build, only fails itself
+            let result = panic::catch_unwind(AssertUnwindSafe(|| self.prove(&inputs)))
+                .unwrap_or_else(|_| Err(eyre!("the prover panicked")));
+            let mut jobs = self.jobs.lock().unwrap();
+            let job = jobs.jobs.get_mut(&id).unwrap();
+            match result {
+                Ok((proof, cycles)) => {
+                    job.status = JobStatus::Done { cycles };
+                    job.proof = Some(proof);
+                }
+                Err(err) => {
+                    job.status = JobStatus::Failed {
+                        error: err.to_string(),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Proves a job, checking its limits, and returns the serialized proof and
+    /// the job's trace length
+    fn prove(&self, inputs: &JobInputs) -> Result<(Vec<u8>, usize)> {
+        let mut program = self.program(&inputs.target)?;
+        program.set_input_bytes(&inputs.input);
+        program.set_raw_input(&inputs.raw_input);
+        program.set_advice(&inputs.advice);
+        let preprocessed = self.preprocessed(&inputs.target)?;
+        let (preprocessing, _) = &*preprocessed;
+
+        let (io_device, trace) = program.trace();
+        let cycles = trace.len();
+        if cycles > self.options.max_trace_length {
+            bail!(
+                "the trace has {} steps, more than the limit of {}",
+                cycles,
+                self.options.max_trace_length
+            );
+        }
+        if let Some(max_memory) = self.options.max_memory {
+            let cost = RV32IJoltVM::cost_report(preprocessing, cycles, program.max_memory_words());
+            if cost.prover_memory > max_memory {
+                bail!(
+                    "the job needs an estimated {} bytes of prover memory, more than the limit \
+                     of {}",
+                    cost.prover_memory,
+                    max_memory
+                );
+            }
+        }
+
+        let (proof, commitments, _) = self
+            .config()?
+            .install(|| RV32IJoltVM::prove(io_device, trace, preprocessing.clone()));
+        let envelope = RV32IProofEnvelope::new(proof, commitments, preprocessing);
+        Ok((envelope.serialize_to_bytes()?, cycles))
+    }
+
+    /// The config jobs are preprocessed and proven with. Its thread pool is
+    /// built by the first job and shared by the rest.
+    fn config(&self) -> Result<ProverConfig> {
+        let mut builder =
+            ProverConfig::builder().with_max_trace_length(self.options.max_trace_length);
+        if let Some(threads) = self.options.prover_threads {
+            builder = builder.with_threads(threads);
+        }
+        if self.options.low_priority {
+            builder = builder.with_priority(ThreadPriority::Low);
+        }
+        if let Some(calibration) = saved_calibration() {
+            builder = builder.with_calibration(calibration);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// The program proven by jobs of `target`, without inputs
+    fn program(&self, target: &JobTarget) -> Result<Program> {
+        match target {
+            JobTarget::Function(func) => function_program(func),
+            JobTarget::Program(program_hash) => self
+                .programs
+                .lock()
+                .unwrap()
+                .get(program_hash)
+                .cloned()
+                .ok_or_else(|| eyre!("no such program")),
+        }
+    }
+
+    /// The preprocessing of `target`, computed on first use
+    fn preprocessed(&self, target: &JobTarget) -> Result<Arc<Preprocessed>> {
+        let mut preprocessing = self.preprocessing.lock().unwrap();
+        if let Some(preprocessed) = preprocessing.get(target) {
+            return Ok(preprocessed.clone());
+        }
+        let mut program = self.program(target)?;
+        let config = self.config()?;
+        let preprocessed = Arc::new(match target {
+            JobTarget::Function(_) => {
+                let (preprocessing, verifier_key) = preprocess(&mut program, &config)?;
+                (preprocessing, Some(verifier_key))
+            }
+            JobTarget::Program(_) => {
+                let preprocessing =
+                    RV32IJoltVM::preprocess_with_config(program.program_preprocessing(), &config)?;
+                (preprocessing, None)
+            }
+        });
+        preprocessing.insert(target.clone(), preprocessed.clone());
+        Ok(preprocessed)
+    }
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.is_ascii() || hex.len() % 2 != 0 {
+        bail!("invalid hex string");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| eyre!("invalid hex string")))
+        .collect()
+}
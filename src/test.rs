@@ -0,0 +1,125 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use eyre::{eyre, Result};
+use jolt_core::host::Program;
+use syn::{Attribute, Item};
+
+use crate::build_wasm::is_std;
+
+#[derive(Args)]
+pub struct TestOptions {
+    /// Only run the tests whose names contain this string
+    filter: Option<String>,
+}
+
+/// Builds the `#[jolt::test]` functions of the guest in `guest/` for the guest's
+/// target and runs each of them in the emulator, without proving them. Fails if
+/// any of them panicked.
+pub fn test(options: TestOptions) -> Result<()> {
+    let mut tests = vec![];
+    for file in rust_files(Path::new("guest/src"))? {
+        let syntax = syn::parse_file(&fs::read_to_string(&file)?)
+            .map_err(|err| eyre!("could not parse {:?}: {}", file, err))?;
+        collect_tests(&syntax.items, &mut tests);
+    }
+    let is_std = is_std().ok_or_else(|| eyre!("could not read the guest's jolt dependency"))?;
+
+    let selected: Vec<&str> = tests
+        .iter()
+        .map(String::as_str)
+        .filter(|test| {
+            options
+                .filter
+                .as_ref()
+                .map_or(true, |filter| test.contains(filter))
+        })
+        .collect();
+    println!("running {} tests", selected.len());
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    // All tests are built into one ELF, whatever the filter, so it is built only once
+    let mut program = Program::new("guest");
+    program.set_std(is_std);
+    program.set_tests(&tests.iter().map(String::as_str).collect::<Vec<_>>());
+
+    let mut failures = vec![];
+    for test in &selected {
+        let outcome = program.run_test(test);
+        match &outcome.panic {
+            None => println!("test {} ... ok ({} cycles)", test, outcome.cycles),
+            Some(panic) => {
+                println!("test {} ... FAILED", test);
+                failures.push((test, panic.clone()));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (test, panic) in &failures {
+            println!("\n---- {} ----\n{}", test, panic);
+        }
+    }
+    let passed = selected.len() - failures.len();
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        passed,
+        failures.len()
+    );
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!("{} guest tests failed", failures.len()))
+    }
+}
+
+/// The names of the `#[jolt::test]` functions among `items`, including inline
+/// modules
+fn collect_tests(items: &[Item], tests: &mut Vec<String>) {
+    for item in items {
+        match item {
+            Item::Fn(func) if func.attrs.iter().any(is_jolt_test) => {
+                tests.push(func.sig.ident.to_string());
+            }
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    collect_tests(items, tests);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_jolt_test(attr: &Attribute) -> bool {
+    let segments: Vec<String> = attr
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    segments == ["jolt", "test"]
+}
+
+/// The Rust source files under `dir`, in a stable order
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            files.extend(rust_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use common::rv_trace::{JoltDevice, RVTraceRow, RV32IM};
+use object::{Object, ObjectSymbol, SymbolKind};
+
+/// Return address register (x1) per the RISC-V calling convention.
+const RA: u64 = 1;
+
+/// A single frame of a guest backtrace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StackFrame {
+    pub address: u64,
+    pub symbol: Option<String>,
+}
+
+/// A guest panic recovered from an execution trace: the panic message written
+/// by the guest's panic handler, and the call stack at the time of the panic
+/// (innermost frame first).
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuestPanic {
+    pub message: Option<String>,
+    pub backtrace: Vec<StackFrame>,
+}
+
+impl fmt::Display for GuestPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            // The message is the guest's `PanicInfo`, which reads "panicked at ..."
+            Some(message) => writeln!(f, "guest {}", message)?,
+            None => writeln!(f, "guest panicked")?,
+        }
+        writeln!(f, "guest backtrace:")?;
+        for (i, frame) in self.backtrace.iter().enumerate() {
+            match &frame.symbol {
+                Some(symbol) => writeln!(f, "{:>4}: {:#010x} - {}", i, frame.address, symbol)?,
+                None => writeln!(f, "{:>4}: {:#010x}", i, frame.address)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a report of the guest panic if the traced program panicked.
+///
+/// # Arguments
+///
+/// * `elf` ELF file contents, used to symbolicate the backtrace if symbols are present
+/// * `rows` Execution trace of the program
+/// * `device` I/O device state at the end of execution
+pub fn guest_panic(elf: &[u8], rows: &[RVTraceRow], device: &JoltDevice) -> Option<GuestPanic> {
+    if !device.panic {
+        return None;
+    }
+
+    let symbols = function_symbols(elf);
+    let backtrace = call_stack(rows)
+        .into_iter()
+        .map(|address| StackFrame {
+            address,
+            symbol: symbolicate(&symbols, address),
+        })
+        .collect();
+
+    Some(GuestPanic {
+        message: device.panic_message(),
+        backtrace,
+    })
+}
+
+/// Reconstructs the call stack at the last row of the trace by replaying
+/// calls (`jal`/`jalr` linking `ra`) and returns (`jalr x0, ra`). Returns the
+/// address of the last executed instruction followed by the call sites of
+/// each active frame, innermost first.
+fn call_stack(rows: &[RVTraceRow]) -> Vec<u64> {
+    let mut call_sites = Vec::new();
+    for row in rows {
+        let instruction = &row.instruction;
+        match instruction.opcode {
+            RV32IM::JAL | RV32IM::JALR if instruction.rd == Some(RA) => {
+                call_sites.push(instruction.address);
+            }
+            RV32IM::JALR if instruction.rd == Some(0) && instruction.rs1 == Some(RA) => {
+                call_sites.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut frames = Vec::with_capacity(call_sites.len() + 1);
+    if let Some(last) = rows.last() {
+        frames.push(last.instruction.address);
+    }
+    frames.extend(call_sites.into_iter().rev());
+    frames
+}
+
+/// Function symbols of the ELF, sorted by address.
+fn function_symbols(elf: &[u8]) -> Vec<(u64, String)> {
+    let obj = match object::File::parse(elf) {
+        Ok(obj) => obj,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut symbols: Vec<(u64, String)> = obj
+        .symbols()
+        .filter(|symbol| symbol.kind() == SymbolKind::Text)
+        .filter_map(|symbol| {
+            let name = symbol.name().ok()?;
+            Some((symbol.address(), name.to_string()))
+        })
+        .collect();
+    symbols.sort();
+    symbols
+}
+
+/// The last symbol starting at or before `address`.
+fn containing_symbol(symbols: &[(u64, String)], address: u64) -> Option<&(u64, String)> {
+    let index = symbols.partition_point(|(start, _)| *start <= address);
+    index.checked_sub(1).map(|index| &symbols[index])
+}
+
+fn symbolicate(symbols: &[(u64, String)], address: u64) -> Option<String> {
+    let (start, name) = containing_symbol(symbols, address)?;
+    Some(format!("{}+{:#x}", name, address - start))
+}
+
+/// Attributes each row of the trace to the function containing its instruction
+/// and returns the number of rows of each function, most first. Rows outside any
+/// function symbol of the ELF are counted under `None`.
+///
+/// # Arguments
+///
+/// * `elf` ELF file contents
+/// * `rows` Execution trace of the program
+pub fn cycles_by_function(elf: &[u8], rows: &[RVTraceRow]) -> Vec<(Option<String>, usize)> {
+    count_by_function(&function_symbols(elf), rows)
+}
+
+fn count_by_function(
+    symbols: &[(u64, String)],
+    rows: &[RVTraceRow],
+) -> Vec<(Option<String>, usize)> {
+    let mut counts = HashMap::<Option<&str>, usize>::new();
+    for row in rows {
+        let symbol = containing_symbol(symbols, row.instruction.address);
+        *counts
+            .entry(symbol.map(|(_, name)| name.as_str()))
+            .or_default() += 1;
+    }
+
+    let mut counts: Vec<_> = counts
+        .into_iter()
+        .map(|(name, count)| (name.map(str::to_string), count))
+        .collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+#[cfg(test)]
+mod test_backtrace {
+    use super::*;
+    use common::rv_trace::{ELFInstruction, RegisterState};
+
+    fn row(address: u64, opcode: RV32IM, rs1: Option<u64>, rd: Option<u64>) -> RVTraceRow {
+        RVTraceRow {
+            instruction: ELFInstruction {
+                address,
+                opcode,
+                rs1,
+                rs2: None,
+                rd,
+                imm: None,
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: None,
+                rs2_val: None,
+                rd_post_val: None,
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        }
+    }
+
+    #[test]
+    fn call_stack_tracks_calls_and_returns() {
+        let rows = vec![
+            row(0x100, RV32IM::JAL, None, Some(RA)),
+            row(0x200, RV32IM::JAL, None, Some(RA)),
+            row(0x300, RV32IM::JALR, Some(RA), Some(0)),
+            row(0x104, RV32IM::JALR, Some(5), Some(RA)),
+            row(0x400, RV32IM::ADD, Some(2), Some(3)),
+        ];
+        assert_eq!(call_stack(&rows), vec![0x400, 0x104, 0x100]);
+    }
+
+    #[test]
+    fn display_does_not_repeat_panicked_at() {
+        let guest_panic = GuestPanic {
+            message: Some("panicked at src/lib.rs:3:5:\noops".to_string()),
+            backtrace: vec![StackFrame {
+                address: 0x104,
+                symbol: Some("main+0x4".to_string()),
+            }],
+        };
+        assert_eq!(
+            guest_panic.to_string(),
+            concat!(
+                "guest panicked at src/lib.rs:3:5:\noops\n",
+                "guest backtrace:\n",
+                "   0: 0x00000104 - main+0x4\n",
+            )
+        );
+    }
+
+    #[test]
+    fn symbolicate_uses_nearest_preceding_symbol() {
+        let symbols = vec![(0x100, "main".to_string()), (0x200, "foo".to_string())];
+        assert_eq!(symbolicate(&symbols, 0x80), None);
+        assert_eq!(symbolicate(&symbols, 0x104), Some("main+0x4".to_string()));
+        assert_eq!(symbolicate(&symbols, 0x200), Some("foo+0x0".to_string()));
+    }
+
+    #[test]
+    fn count_by_function_attributes_rows_to_symbols() {
+        let symbols = vec![(0x100, "main".to_string()), (0x200, "foo".to_string())];
+        let rows: Vec<_> = [0x80, 0x100, 0x200, 0x204, 0x208]
+            .into_iter()
+            .map(|address| row(address, RV32IM::ADD, Some(2), Some(3)))
+            .collect();
+        assert_eq!(
+            count_by_function(&symbols, &rows),
+            vec![
+                (Some("foo".to_string()), 3),
+                (None, 1),
+                (Some("main".to_string()), 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use common::rv_trace::RVTraceRow;
+
+/// What differs between two traces at the first point of divergence.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DivergenceKind {
+    /// The two executions reached different program counters.
+    Pc,
+    /// The same program counter holds different instructions.
+    Instruction,
+    /// The instruction read different source register values, or wrote a
+    /// different value to its destination register.
+    Register,
+    /// The instruction accessed memory differently (address or value).
+    Memory,
+    /// One execution terminated before the other.
+    Length,
+}
+
+/// The first step at which two execution traces disagree. `left` and `right`
+/// are the rows of each trace at that step, or `None` if that trace had
+/// already terminated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceDivergence {
+    pub step: usize,
+    pub kind: DivergenceKind,
+    pub left: Option<RVTraceRow>,
+    pub right: Option<RVTraceRow>,
+}
+
+impl fmt::Display for TraceDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "traces diverge at step {} ({:?})", self.step, self.kind)?;
+        writeln!(f, "  left:  {}", describe(&self.left))?;
+        write!(f, "  right: {}", describe(&self.right))
+    }
+}
+
+fn describe(row: &Option<RVTraceRow>) -> String {
+    match row {
+        None => "<terminated>".to_string(),
+        Some(row) => format!(
+            "{:#010x} {:?} {:?} {:?}",
+            row.instruction.address, row.instruction.opcode, row.register_state, row.memory_state,
+        ),
+    }
+}
+
+/// Compares two execution traces step by step and returns the first point at
+/// which they diverge in PC, instruction, register values or memory accesses,
+/// or `None` if they are identical.
+pub fn first_divergence(left: &[RVTraceRow], right: &[RVTraceRow]) -> Option<TraceDivergence> {
+    let divergence = |step: usize, kind: DivergenceKind| TraceDivergence {
+        step,
+        kind,
+        left: left.get(step).cloned(),
+        right: right.get(step).cloned(),
+    };
+
+    for (step, (l, r)) in left.iter().zip(right.iter()).enumerate() {
+        if l.instruction.address != r.instruction.address {
+            return Some(divergence(step, DivergenceKind::Pc));
+        }
+        if l.instruction != r.instruction {
+            return Some(divergence(step, DivergenceKind::Instruction));
+        }
+        if l.register_state != r.register_state {
+            return Some(divergence(step, DivergenceKind::Register));
+        }
+        if l.memory_state != r.memory_state {
+            return Some(divergence(step, DivergenceKind::Memory));
+        }
+    }
+
+    if left.len() != right.len() {
+        return Some(divergence(
+            left.len().min(right.len()),
+            DivergenceKind::Length,
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test_diff {
+    use super::*;
+    use common::rv_trace::{ELFInstruction, RegisterState, RV32IM};
+
+    fn row(address: u64, rd_post_val: u64) -> RVTraceRow {
+        RVTraceRow {
+            instruction: ELFInstruction {
+                address,
+                opcode: RV32IM::ADDI,
+                rs1: Some(0),
+                rs2: None,
+                rd: Some(5),
+                imm: Some(0),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(0),
+                rs2_val: None,
+                rd_post_val: Some(rd_post_val),
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        }
+    }
+
+    #[test]
+    fn identical_traces() {
+        let trace = vec![row(0x100, 1), row(0x104, 2)];
+        assert_eq!(first_divergence(&trace, &trace), None);
+    }
+
+    #[test]
+    fn register_divergence() {
+        let left = vec![row(0x100, 1), row(0x104, 2)];
+        let right = vec![row(0x100, 1), row(0x104, 3)];
+        let divergence = first_divergence(&left, &right).unwrap();
+        assert_eq!(divergence.step, 1);
+        assert_eq!(divergence.kind, DivergenceKind::Register);
+    }
+
+    #[test]
+    fn pc_and_length_divergence() {
+        let left = vec![row(0x100, 1), row(0x104, 2)];
+        let right = vec![row(0x100, 1), row(0x108, 2)];
+        assert_eq!(
+            first_divergence(&left, &right).unwrap().kind,
+            DivergenceKind::Pc
+        );
+
+        let divergence = first_divergence(&left, &left[..1]).unwrap();
+        assert_eq!(divergence.step, 1);
+        assert_eq!(divergence.kind, DivergenceKind::Length);
+        assert_eq!(divergence.right, None);
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt;
+
+use common::rv_trace::{MemoryState, RVTraceRow};
+
+use crate::reference::ReferenceModel;
+
+/// What the emulator and the reference model disagree on.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Mismatch {
+    /// They execute instructions at different addresses.
+    Pc { emulator: u64, reference: u64 },
+    /// A register differs after the instruction.
+    Register {
+        index: usize,
+        emulator: u64,
+        reference: u64,
+    },
+    /// The word written by a store differs.
+    Memory {
+        address: u64,
+        emulator: u64,
+        reference: u64,
+    },
+    /// The reference model does not implement the instruction.
+    Unsupported { word: u32 },
+}
+
+/// The first instruction after which the emulator's architectural state (PC,
+/// registers, stored memory) differs from the reference model's, see
+/// `trace_differential`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferenceDivergence {
+    /// Index of the instruction in the trace
+    pub step: usize,
+    /// The emulator's execution of the instruction
+    pub row: RVTraceRow,
+    pub mismatch: Mismatch,
+}
+
+impl fmt::Display for ReferenceDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "emulator diverges from the reference at step {} ({:#010x} {:?}): ",
+            self.step, self.row.instruction.address, self.row.instruction.opcode,
+        )?;
+        match &self.mismatch {
+            Mismatch::Pc {
+                emulator,
+                reference,
+            } => write!(f, "pc {emulator:#010x}, reference {reference:#010x}"),
+            Mismatch::Register {
+                index,
+                emulator,
+                reference,
+            } => write!(f, "x{index} = {emulator:#x}, reference {reference:#x}"),
+            Mismatch::Memory {
+                address,
+                emulator,
+                reference,
+            } => write!(
+                f,
+                "stored {emulator:#x} at {address:#010x}, reference {reference:#x}"
+            ),
+            Mismatch::Unsupported { word } => {
+                write!(
+                    f,
+                    "instruction {word:#010x} not implemented by the reference"
+                )
+            }
+        }
+    }
+}
+
+/// Executes the instruction of `row` on `reference`, and compares the resulting
+/// state with the emulator's, whose registers after the instruction are
+/// `registers`.
+pub(crate) fn check_step(
+    reference: &mut dyn ReferenceModel,
+    row: &RVTraceRow,
+    registers: &[i64; 32],
+) -> Option<Mismatch> {
+    if reference.pc() != row.instruction.address {
+        return Some(Mismatch::Pc {
+            emulator: row.instruction.address,
+            reference: reference.pc(),
+        });
+    }
+    if let Err(word) = reference.step(row.advice_value) {
+        return Some(Mismatch::Unsupported { word });
+    }
+    for (index, value) in registers.iter().enumerate().skip(1) {
+        let emulator = *value as u32 as u64;
+        if reference.register(index) != emulator {
+            return Some(Mismatch::Register {
+                index,
+                emulator,
+                reference: reference.register(index),
+            });
+        }
+    }
+    if let Some(MemoryState::Write {
+        address,
+        post_value,
+        ..
+    }) = row.memory_state
+    {
+        if reference.load_word(address) != post_value {
+            return Some(Mismatch::Memory {
+                address,
+                emulator: post_value,
+                reference: reference.load_word(address),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test_differential {
+    use super::*;
+    use crate::reference::ReferenceInterpreter;
+    use common::constants::RAM_START_ADDRESS;
+    use common::rv_trace::{ELFInstruction, RegisterState, RV32IM};
+
+    const STORE_ADDRESS: u64 = RAM_START_ADDRESS + 0x100;
+
+    /// A reference model about to execute `sw x1, 0(x2)`, with x1 = 5 and x2 =
+    /// `STORE_ADDRESS`, and the emulator's registers at that point.
+    fn setup() -> (ReferenceInterpreter, [i64; 32]) {
+        let program = [
+            0x80000137u32, // lui x2, 0x80000
+            0x10010113,    // addi x2, x2, 0x100
+            0x00500093,    // addi x1, x0, 5
+            0x00112023,    // sw x1, 0(x2)
+        ];
+        let bytes = program.iter().flat_map(|word| word.to_le_bytes());
+        let mut reference =
+            ReferenceInterpreter::new(RAM_START_ADDRESS, (RAM_START_ADDRESS..).zip(bytes));
+        for _ in 0..3 {
+            reference.step(None).unwrap();
+        }
+        let mut registers = [0i64; 32];
+        registers[1] = 5;
+        // The emulator keeps 32-bit values sign-extended
+        registers[2] = STORE_ADDRESS as i32 as i64;
+        (reference, registers)
+    }
+
+    /// The emulator's row for the store, writing `post_value`
+    fn store_row(post_value: u64) -> RVTraceRow {
+        RVTraceRow {
+            instruction: ELFInstruction {
+                address: RAM_START_ADDRESS + 12,
+                opcode: RV32IM::SW,
+                rs1: Some(2),
+                rs2: Some(1),
+                rd: None,
+                imm: Some(0),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(STORE_ADDRESS),
+                rs2_val: Some(5),
+                rd_post_val: None,
+            },
+            memory_state: Some(MemoryState::Write {
+                address: STORE_ADDRESS,
+                pre_value: 0,
+                post_value,
+            }),
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        }
+    }
+
+    #[test]
+    fn matching_step() {
+        let (mut reference, registers) = setup();
+        assert_eq!(check_step(&mut reference, &store_row(5), &registers), None);
+    }
+
+    #[test]
+    fn mismatches() {
+        let (mut reference, registers) = setup();
+        assert_eq!(
+            check_step(&mut reference, &store_row(6), &registers),
+            Some(Mismatch::Memory {
+                address: STORE_ADDRESS,
+                emulator: 6,
+                reference: 5,
+            })
+        );
+
+        let (mut reference, mut registers) = setup();
+        registers[1] = 4;
+        assert_eq!(
+            check_step(&mut reference, &store_row(5), &registers),
+            Some(Mismatch::Register {
+                index: 1,
+                emulator: 4,
+                reference: 5,
+            })
+        );
+
+        let (mut reference, registers) = setup();
+        let mut row = store_row(5);
+        row.instruction.address += 4;
+        assert_eq!(
+            check_step(&mut reference, &row, &registers),
+            Some(Mismatch::Pc {
+                emulator: RAM_START_ADDRESS + 16,
+                reference: RAM_START_ADDRESS + 12,
+            })
+        );
+    }
+}
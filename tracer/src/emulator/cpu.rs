@@ -6,6 +6,7 @@ use std::convert::TryInto;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use crate::hints::Hints;
 use crate::trace::Tracer;
 use common::rv_trace::*;
 
@@ -65,6 +66,20 @@ pub const MIP_SEIP: u64 = 0x200;
 const MIP_STIP: u64 = 0x020;
 const MIP_SSIP: u64 = 0x002;
 
+/// Immediate of the `ADVICE` instruction requesting a hint
+const HINT_REQUEST: i64 = 1;
+/// Immediate of the `ADVICE` instruction logging a message
+const LOG_MESSAGE: i64 = 2;
+
+/// Splits advice bytes into little-endian words, zero-padding the last one
+fn advice_words(bytes: &[u8]) -> impl Iterator<Item = u32> + '_ {
+    bytes.chunks(4).map(|chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        u32::from_le_bytes(word)
+    })
+}
+
 /// Emulates a RISC-V CPU core
 pub struct Cpu {
     clock: u64,
@@ -84,6 +99,15 @@ pub struct Cpu {
     decode_cache: DecodeCache,
     unsigned_data_mask: u64,
     pub tracer: Rc<Tracer>,
+    /// Whether executed instructions are recorded by `tracer`
+    tracing: bool,
+    /// Untrusted advice words handed out by the `ADVICE` instruction
+    advice: Vec<u32>,
+    advice_position: usize,
+    /// Answers the hint requests of the `ADVICE` instruction
+    hints: Hints,
+    /// Number of hints requested so far
+    hint_count: usize,
 }
 
 #[derive(Clone)]
@@ -245,6 +269,11 @@ impl Cpu {
             decode_cache: DecodeCache::new(),
             unsigned_data_mask: 0xffffffffffffffff,
             tracer,
+            tracing: true,
+            advice: Vec::new(),
+            advice_position: 0,
+            hints: Hints::new(),
+            hint_count: 0,
         };
         cpu.x[0xb] = 0x1020; // I don't know why but Linux boot seems to require this initialization
         cpu.write_csr_raw(CSR_MISA_ADDRESS, 0x800000008014312f);
@@ -259,6 +288,67 @@ impl Cpu {
         self.pc = value;
     }
 
+    /// Enables or disables recording of executed instructions. With recording
+    /// disabled, instructions are executed as usual but no trace rows are built,
+    /// for passes that only need the program's outputs or cycle count.
+    ///
+    /// # Arguments
+    /// * `enabled`
+    pub fn set_tracing(&mut self, enabled: bool) {
+        self.tracing = enabled;
+    }
+
+    /// Sets the untrusted advice returned, one little-endian word at a time, by
+    /// the `ADVICE` instruction. The last word is zero-padded.
+    ///
+    /// # Arguments
+    /// * `advice`
+    pub fn set_advice(&mut self, advice: &[u8]) {
+        self.advice = advice_words(advice).collect();
+        self.advice_position = 0;
+    }
+
+    /// Sets the host functions answering the guest's hint requests
+    ///
+    /// # Arguments
+    /// * `hints`
+    pub fn set_hints(&mut self, hints: Hints) {
+        hints.clear_logs();
+        self.hints = hints;
+        self.hint_count = 0;
+    }
+
+    /// Answers a hint request: `a1` bytes at address `a0` for the hint `a2`. The
+    /// response is inserted in the advice, prefixed with its length in bytes, so
+    /// that the guest reads it next.
+    fn request_hint(&mut self) {
+        let address = self.x[10] as u64 & self.unsigned_data_mask;
+        let len = (self.x[11] as u64 & self.unsigned_data_mask) as usize;
+        let id = self.x[12] as u32;
+        let request = self.mmu.load_bytes_raw(address, len);
+        let response = self.hints.respond(self.hint_count, id, &request);
+        self.hint_count += 1;
+
+        let words = std::iter::once(response.len() as u32).chain(advice_words(&response));
+        let position = self.advice_position.min(self.advice.len());
+        self.advice.splice(position..position, words);
+    }
+
+    /// Logs the `a1` bytes at address `a0` at the current cycle.
+    fn log_message(&mut self) {
+        let address = self.x[10] as u64 & self.unsigned_data_mask;
+        let len = (self.x[11] as u64 & self.unsigned_data_mask) as usize;
+        let message = self.mmu.load_bytes_raw(address, len);
+        self.hints.log(self.clock, &message);
+    }
+
+    /// Returns the next advice word, or 0 once the advice is exhausted.
+    fn next_advice(&mut self) -> u32 {
+        let word = self.advice.get(self.advice_position).copied().unwrap_or(0);
+        self.advice_position += 1;
+        word
+    }
+
     /// Updates XLEN, 32-bit or 64-bit
     ///
     /// # Arguments
@@ -284,6 +374,18 @@ impl Cpu {
         }
     }
 
+    /// Writes integer register content. Writes to the zero register are ignored.
+    ///
+    /// # Arguments
+    /// * `reg` Register number. Must be 0-31
+    /// * `value`
+    pub fn write_register(&mut self, reg: u8, value: i64) {
+        debug_assert!(reg <= 31, "reg must be 0-31. {}", reg);
+        if reg != 0 {
+            self.x[reg as usize] = value;
+        }
+    }
+
     /// Reads Program counter content
     pub fn read_pc(&self) -> u64 {
         self.pc
@@ -332,6 +434,11 @@ impl Cpu {
         };
 
         match self.decode(word).cloned() {
+            Ok(inst) if !self.tracing => {
+                let result = (inst.operation)(self, word, instruction_address);
+                self.x[0] = 0; // hardwired zero
+                result
+            }
             Ok(inst) => {
                 // setup trace
                 let trace_inst = inst.trace.unwrap()(&inst, &self.xlen, word, instruction_address);
@@ -1898,7 +2005,21 @@ fn trace_j(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstr
     }
 }
 
-const INSTRUCTION_NUM: usize = 116;
+// (custom-0) Only rd is used
+fn trace_advice(_inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let f = parse_format_i(word);
+    ELFInstruction {
+        opcode: RV32IM::VIRTUAL_ADVICE,
+        address: normalize_u64(address, xlen),
+        imm: None,
+        rs1: None,
+        rs2: None,
+        rd: Some(normalize_register(f.rd)),
+        virtual_sequence_remaining: None,
+    }
+}
+
+const INSTRUCTION_NUM: usize = 117;
 
 // @TODO: Reorder in often used order as
 pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
@@ -2649,6 +2770,32 @@ pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
         disassemble: dump_empty,
         trace: Some(trace_i),
     },
+    Instruction {
+        mask: 0x0000707f,
+        data: 0x0000000b,
+        name: "ADVICE",
+        operation: |cpu, word, _address| {
+            let f = parse_format_i(word);
+            // Hint requests (see `Cpu::request_hint`) and log messages read 0
+            // into `rd`, so they are proven as no-ops
+            let value = match f.imm {
+                HINT_REQUEST => {
+                    cpu.request_hint();
+                    0
+                }
+                LOG_MESSAGE => {
+                    cpu.log_message();
+                    0
+                }
+                _ => cpu.next_advice(),
+            };
+            cpu.x[f.rd] = cpu.sign_extend(value as i32 as i64);
+            cpu.tracer.push_advice(value as u64);
+            Ok(())
+        },
+        disassemble: dump_format_i,
+        trace: Some(trace_advice),
+    },
     Instruction {
         mask: 0x0000707f,
         data: 0x0000100f,
@@ -3937,6 +4084,7 @@ mod test_cpu {
     use super::*;
     use crate::emulator::mmu::DRAM_BASE;
     use crate::emulator::terminal::DummyTerminal;
+    use crate::hints::LogRecord;
 
     fn create_cpu() -> Cpu {
         Cpu::new(Box::new(DummyTerminal::new()))
@@ -4009,6 +4157,15 @@ mod test_cpu {
         // greater than 32?
     }
 
+    #[test]
+    fn write_register() {
+        let mut cpu = create_cpu();
+        cpu.write_register(0, 1);
+        assert_eq!(0, cpu.read_register(0));
+        cpu.write_register(5, -3);
+        assert_eq!(-3, cpu.read_register(5));
+    }
+
     #[test]
     fn tick() {
         let mut cpu = create_cpu();
@@ -4037,6 +4194,134 @@ mod test_cpu {
         assert_eq!(8, cpu.read_register(8));
     }
 
+    #[test]
+    fn tick_without_tracing() {
+        let mut cpu = create_cpu();
+        cpu.get_mut_mmu().init_memory(4);
+        cpu.update_pc(DRAM_BASE);
+        cpu.set_tracing(false);
+        // write non-compressed "addi a0, a0, 12" instruction
+        match cpu.get_mut_mmu().store_word(DRAM_BASE, 0xc50513) {
+            Ok(()) => {}
+            Err(_e) => panic!("Failed to store"),
+        };
+        cpu.tick();
+        assert_eq!(12, cpu.read_register(10));
+        assert!(cpu.tracer.rows.borrow().is_empty());
+    }
+
+    #[test]
+    fn advice() {
+        let mut cpu = create_cpu();
+        cpu.get_mut_mmu().init_memory(8);
+        cpu.update_pc(DRAM_BASE);
+        cpu.set_advice(&[0x78, 0x56, 0x34, 0x12, 0xff]);
+        // write two "advice a0" instructions
+        for offset in [0, 4] {
+            match cpu.get_mut_mmu().store_word(DRAM_BASE + offset, 0x50b) {
+                Ok(()) => {}
+                Err(_e) => panic!("Failed to store"),
+            };
+        }
+        cpu.tick();
+        assert_eq!(0x12345678, cpu.read_register(10));
+        cpu.tick();
+        assert_eq!(0xff, cpu.read_register(10));
+
+        let rows = cpu.tracer.rows.borrow();
+        assert_eq!(rows[0].instruction.opcode, RV32IM::VIRTUAL_ADVICE);
+        assert_eq!(rows[0].advice_value, Some(0x12345678));
+        assert_eq!(rows[1].register_state.rd_post_val, Some(0xff));
+    }
+
+    #[test]
+    fn hints() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        let mut hints = Hints::new();
+        hints.register(7, move |request| {
+            handler_calls.fetch_add(1, Ordering::Relaxed);
+            request.iter().rev().copied().collect()
+        });
+
+        for _ in 0..2 {
+            let mut cpu = create_cpu();
+            cpu.get_mut_mmu().init_memory(20);
+            cpu.update_pc(DRAM_BASE);
+            cpu.set_advice(&[0xff]);
+            cpu.set_hints(hints.clone());
+            // write a hint request followed by three "advice a0" instructions, and
+            // the request's bytes
+            for (offset, word) in [(0, 0x10000b), (4, 0x50b), (8, 0x50b), (12, 0x50b)] {
+                match cpu.get_mut_mmu().store_word(DRAM_BASE + offset, word) {
+                    Ok(()) => {}
+                    Err(_e) => panic!("Failed to store"),
+                };
+            }
+            match cpu.get_mut_mmu().store_word(DRAM_BASE + 16, 0x030201) {
+                Ok(()) => {}
+                Err(_e) => panic!("Failed to store"),
+            };
+            cpu.write_register(10, (DRAM_BASE + 16) as i64);
+            cpu.write_register(11, 3);
+            cpu.write_register(12, 7);
+
+            cpu.tick();
+            assert_eq!(0, cpu.read_register(0));
+            cpu.tick();
+            assert_eq!(3, cpu.read_register(10));
+            cpu.tick();
+            assert_eq!(0x010203, cpu.read_register(10));
+            cpu.tick();
+            assert_eq!(0xff, cpu.read_register(10));
+            assert_eq!(cpu.tracer.rows.borrow()[0].advice_value, Some(0));
+        }
+        // The second execution replays the recorded response
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(hints.records()[0].response, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn log_message() {
+        let mut cpu = create_cpu();
+        cpu.get_mut_mmu().init_memory(12);
+        cpu.update_pc(DRAM_BASE);
+        let hints = Hints::new();
+        cpu.set_hints(hints.clone());
+        // write a log instruction followed by an "advice a0" instruction, and the
+        // message's bytes
+        for (offset, word) in [(0, 0x20000b), (4, 0x50b), (8, 0x216968)] {
+            match cpu.get_mut_mmu().store_word(DRAM_BASE + offset, word) {
+                Ok(()) => {}
+                Err(_e) => panic!("Failed to store"),
+            };
+        }
+        cpu.write_register(10, (DRAM_BASE + 8) as i64);
+        cpu.write_register(11, 3);
+
+        cpu.tick();
+        assert_eq!(0, cpu.read_register(0));
+        // The log consumes no advice
+        cpu.set_advice(&[0xff]);
+        cpu.tick();
+        assert_eq!(0xff, cpu.read_register(10));
+        assert_eq!(cpu.tracer.rows.borrow()[0].advice_value, Some(0));
+        assert_eq!(
+            hints.logs(),
+            vec![LogRecord {
+                cycle: 0,
+                message: "hi!".to_string()
+            }]
+        );
+
+        // A new execution starts a new log
+        cpu.set_hints(hints.clone());
+        assert!(hints.logs().is_empty());
+    }
+
     #[test]
     fn tick_operate() {
         let mut cpu = create_cpu();
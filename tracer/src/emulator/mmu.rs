@@ -724,6 +724,18 @@ impl Mmu {
         }
     }
 
+    /// Loads `len` bytes from main memory or peripheral devices depending on
+    /// physical address.
+    ///
+    /// # Arguments
+    /// * `p_address` Physical address
+    /// * `len`
+    pub fn load_bytes_raw(&mut self, p_address: u64, len: usize) -> Vec<u8> {
+        (0..len as u64)
+            .map(|offset| self.load_raw(p_address.wrapping_add(offset)))
+            .collect()
+    }
+
     /// Loads four bytes from main memory or peripheral devices depending on
     /// physical address.
     ///
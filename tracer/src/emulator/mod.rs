@@ -17,6 +17,7 @@ pub mod terminal;
 use self::cpu::{Cpu, Xlen};
 use self::elf_analyzer::ElfAnalyzer;
 use self::terminal::Terminal;
+use common::rv_trace::RVTraceRow;
 
 /// RISC-V emulator. It emulates RISC-V CPU and peripheral devices.
 ///
@@ -133,6 +134,14 @@ impl Emulator {
         self.cpu.tick();
     }
 
+    /// Runs a single instruction and returns the trace row it produced, if
+    /// any. Unlike `tick`, the row is removed from the CPU's tracer, so
+    /// stepping indefinitely doesn't accumulate a trace in memory.
+    pub fn step(&mut self) -> Option<RVTraceRow> {
+        self.cpu.tick();
+        self.cpu.tracer.rows.borrow_mut().pop()
+    }
+
     /// Sets up program run by the program. This method analyzes the passed content
     /// and configure CPU properly. If the passed contend doesn't seem ELF file,
     /// it panics. This method is expected to be called only once.
@@ -315,3 +324,29 @@ impl Emulator {
         self.symbol_map.get(s).copied()
     }
 }
+
+#[cfg(test)]
+mod test_emulator {
+    use super::*;
+    use crate::emulator::mmu::DRAM_BASE;
+    use crate::emulator::terminal::DummyTerminal;
+    use common::rv_trace::RV32IM;
+
+    #[test]
+    fn step() {
+        let mut emulator = Emulator::new(Box::new(DummyTerminal::new()));
+        emulator.update_xlen(Xlen::Bit32);
+        let cpu = emulator.get_mut_cpu();
+        cpu.get_mut_mmu().init_memory(4);
+        cpu.update_pc(DRAM_BASE);
+        cpu.write_register(1, 41);
+        // "addi x1, x1, 1"
+        assert!(cpu.get_mut_mmu().store_word(DRAM_BASE, 0x00108093).is_ok());
+
+        let row = emulator.step().unwrap();
+        assert_eq!(row.instruction.opcode, RV32IM::ADDI);
+        assert_eq!(row.register_state.rd_post_val, Some(42));
+        assert_eq!(emulator.get_cpu().read_register(1), 42);
+        assert!(emulator.get_cpu().tracer.rows.borrow().is_empty());
+    }
+}
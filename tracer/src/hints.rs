@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+type Handler = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// A hint requested by the guest, and the host's response
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HintRecord {
+    pub id: u32,
+    pub request: Vec<u8>,
+    pub response: Vec<u8>,
+}
+
+/// A message logged by the guest with `jolt::log!`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    /// The cycle at which the message was logged
+    pub cycle: u64,
+    pub message: String,
+}
+
+/// Host functions the guest calls at runtime with `jolt::hint::request`, e.g. to
+/// look up the Merkle path of a key. The response reaches the guest as advice, so
+/// it is untrusted: the guest must check it.
+///
+/// The responses are recorded, and a later execution of the program making the
+/// same requests (e.g. the one traced for proving) is answered from the record
+/// rather than by calling the handlers again. Handlers therefore need not be
+/// deterministic for the trace to be reproducible. Clones share the record.
+///
+/// The messages the guest logs are collected here as well, since they are sent
+/// to the host the same way.
+#[derive(Clone, Default)]
+pub struct Hints {
+    handlers: HashMap<u32, Handler>,
+    records: Arc<Mutex<Vec<HintRecord>>>,
+    logs: Arc<Mutex<Vec<LogRecord>>>,
+}
+
+impl Hints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers the requests with id `id` by calling `handler`
+    pub fn register(
+        &mut self,
+        id: u32,
+        handler: impl Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.handlers.insert(id, Arc::new(handler));
+        self
+    }
+
+    /// The requests made so far by the last execution, and their responses
+    pub fn records(&self) -> Vec<HintRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// The messages logged by the last execution, in order
+    pub fn logs(&self) -> Vec<LogRecord> {
+        self.logs.lock().unwrap().clone()
+    }
+
+    pub(crate) fn clear_logs(&self) {
+        self.logs.lock().unwrap().clear();
+    }
+
+    pub(crate) fn log(&self, cycle: u64, message: &[u8]) {
+        self.logs.lock().unwrap().push(LogRecord {
+            cycle,
+            message: String::from_utf8_lossy(message).into_owned(),
+        });
+    }
+
+    /// Answers the `index`-th request of an execution. The recorded response is
+    /// replayed if the recorded request is the same; otherwise the handler is
+    /// called, and the record from this request on is replaced by its response.
+    pub(crate) fn respond(&self, index: usize, id: u32, request: &[u8]) -> Vec<u8> {
+        let mut records = self.records.lock().unwrap();
+        if let Some(record) = records.get(index) {
+            if record.id == id && record.request == request {
+                return record.response.clone();
+            }
+        }
+
+        let handler = self
+            .handlers
+            .get(&id)
+            .unwrap_or_else(|| panic!("Guest requested hint {id}, which has no handler"));
+        let response = handler(request);
+        records.truncate(index);
+        records.push(HintRecord {
+            id,
+            request: request.to_vec(),
+            response: response.clone(),
+        });
+        response
+    }
+}
+
+impl fmt::Debug for Hints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ids: Vec<_> = self.handlers.keys().collect();
+        ids.sort();
+        f.debug_struct("Hints").field("handlers", &ids).finish()
+    }
+}
@@ -0,0 +1,197 @@
+use std::ops::Range;
+
+use common::rv_trace::{MemoryState, RVTraceRow};
+
+/// Returned by hook callbacks to either continue execution or stop it after the
+/// current instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Break,
+}
+
+/// State of the emulator after an instruction has executed, as seen by hooks.
+pub struct Step<'a> {
+    /// Index of the instruction in the trace
+    pub index: usize,
+    pub row: &'a RVTraceRow,
+    /// Register file after the instruction executed
+    pub registers: &'a [i64; 32],
+}
+
+impl Step<'_> {
+    /// Address of the memory access performed by this instruction, if any
+    pub fn memory_address(&self) -> Option<u64> {
+        match self.row.memory_state {
+            Some(MemoryState::Read { address, .. }) => Some(address),
+            Some(MemoryState::Write { address, .. }) => Some(address),
+            None => None,
+        }
+    }
+}
+
+type Callback<'a> = Box<dyn FnMut(&Step) -> Control + 'a>;
+type Predicate<'a> = Box<dyn FnMut(&Step) -> bool + 'a>;
+
+/// Callbacks invoked while tracing a program (see `trace_with_hooks`), for
+/// building debuggers, invariant checkers, coverage tools etc. on top of the
+/// emulator. Any callback can stop execution by returning `Control::Break`.
+#[derive(Default)]
+pub struct Hooks<'a> {
+    breakpoints: Vec<(Range<u64>, Callback<'a>)>,
+    watchpoints: Vec<(Range<u64>, Callback<'a>)>,
+    conditions: Vec<(Predicate<'a>, Callback<'a>)>,
+    stopped_at: Option<usize>,
+}
+
+impl<'a> Hooks<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Invokes `callback` after executing any instruction whose address lies in `pc_range`.
+    pub fn on_pc(
+        &mut self,
+        pc_range: Range<u64>,
+        callback: impl FnMut(&Step) -> Control + 'a,
+    ) -> &mut Self {
+        self.breakpoints.push((pc_range, Box::new(callback)));
+        self
+    }
+
+    /// Invokes `callback` after any load or store to an address in `address_range`.
+    pub fn on_memory(
+        &mut self,
+        address_range: Range<u64>,
+        callback: impl FnMut(&Step) -> Control + 'a,
+    ) -> &mut Self {
+        self.watchpoints.push((address_range, Box::new(callback)));
+        self
+    }
+
+    /// Invokes `callback` after any instruction for which `predicate` holds,
+    /// e.g. a condition on register values.
+    pub fn on_condition(
+        &mut self,
+        predicate: impl FnMut(&Step) -> bool + 'a,
+        callback: impl FnMut(&Step) -> Control + 'a,
+    ) -> &mut Self {
+        self.conditions
+            .push((Box::new(predicate), Box::new(callback)));
+        self
+    }
+
+    /// Index of the instruction after which a callback stopped execution, if any.
+    pub fn stopped_at(&self) -> Option<usize> {
+        self.stopped_at
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.breakpoints.is_empty() && self.watchpoints.is_empty() && self.conditions.is_empty()
+    }
+
+    /// Runs all matching callbacks for `step`. Returns `Control::Break` if any
+    /// of them requested to stop.
+    pub(crate) fn dispatch(&mut self, step: &Step) -> Control {
+        let mut control = Control::Continue;
+
+        let pc = step.row.instruction.address;
+        for (range, callback) in self.breakpoints.iter_mut() {
+            if range.contains(&pc) && callback(step) == Control::Break {
+                control = Control::Break;
+            }
+        }
+
+        if let Some(address) = step.memory_address() {
+            for (range, callback) in self.watchpoints.iter_mut() {
+                if range.contains(&address) && callback(step) == Control::Break {
+                    control = Control::Break;
+                }
+            }
+        }
+
+        for (predicate, callback) in self.conditions.iter_mut() {
+            if predicate(step) && callback(step) == Control::Break {
+                control = Control::Break;
+            }
+        }
+
+        if control == Control::Break {
+            self.stopped_at = Some(step.index);
+        }
+        control
+    }
+}
+
+#[cfg(test)]
+mod test_hooks {
+    use super::*;
+    use common::rv_trace::{ELFInstruction, RegisterState, RV32IM};
+
+    fn row(address: u64, memory_state: Option<MemoryState>) -> RVTraceRow {
+        RVTraceRow {
+            instruction: ELFInstruction {
+                address,
+                opcode: RV32IM::SW,
+                rs1: Some(2),
+                rs2: Some(5),
+                rd: None,
+                imm: Some(0),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState::default(),
+            memory_state,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        }
+    }
+
+    #[test]
+    fn dispatch_runs_matching_hooks() {
+        let mut pc_hits = 0;
+        let mut memory_hits = 0;
+        let mut hooks = Hooks::new();
+        hooks
+            .on_pc(0x100..0x108, |_| {
+                pc_hits += 1;
+                Control::Continue
+            })
+            .on_memory(0x2000..0x2004, |_| {
+                memory_hits += 1;
+                Control::Continue
+            })
+            .on_condition(|step| step.registers[5] == 7, |_| Control::Break);
+
+        let registers = [0i64; 32];
+        let write = MemoryState::Write {
+            address: 0x2000,
+            pre_value: 0,
+            post_value: 1,
+        };
+        let rows = [row(0x100, None), row(0x104, Some(write)), row(0x108, None)];
+        for (index, row) in rows.iter().enumerate() {
+            let step = Step {
+                index,
+                row,
+                registers: &registers,
+            };
+            assert_eq!(hooks.dispatch(&step), Control::Continue);
+        }
+        assert_eq!(hooks.stopped_at(), None);
+
+        let mut registers = [0i64; 32];
+        registers[5] = 7;
+        let step = Step {
+            index: 3,
+            row: &rows[2],
+            registers: &registers,
+        };
+        assert_eq!(hooks.dispatch(&step), Control::Break);
+        assert_eq!(hooks.stopped_at(), Some(3));
+
+        drop(hooks);
+        assert_eq!(pc_hits, 2);
+        assert_eq!(memory_hits, 1);
+    }
+}
@@ -3,7 +3,10 @@
 
 use std::{fs::File, io::Read, path::PathBuf};
 
-use common::{self, constants::RAM_START_ADDRESS};
+use common::{
+    self,
+    constants::{RAM_START_ADDRESS, REGISTER_COUNT},
+};
 use emulator::{
     cpu::{self, Xlen},
     default_terminal::DefaultTerminal,
@@ -12,43 +15,226 @@ use emulator::{
 
 use object::{Object, ObjectSection, SectionKind};
 
+mod backtrace;
 mod decode;
+mod diff;
+mod differential;
+#[cfg(feature = "testing")]
+pub mod emulator;
+#[cfg(not(feature = "testing"))]
 mod emulator;
+mod hints;
+mod hooks;
+mod reference;
 mod trace;
+pub mod trace_format;
 
+pub use backtrace::{cycles_by_function, guest_panic, GuestPanic, StackFrame};
 pub use common::rv_trace::{
     ELFInstruction, JoltDevice, MemoryState, RVTraceRow, RegisterState, RV32IM,
 };
+pub use diff::{first_divergence, DivergenceKind, TraceDivergence};
+pub use differential::{Mismatch, ReferenceDivergence};
+pub use hints::{HintRecord, Hints, LogRecord};
+pub use hooks::{Control, Hooks, Step};
+pub use reference::{ReferenceInterpreter, ReferenceModel};
 
 use crate::decode::decode_raw;
 
+/// Traces the program's execution on `inputs`. `advice` is untrusted data handed
+/// to the guest by the `ADVICE` instruction; unlike `inputs`, it is not part of
+/// the resulting `JoltDevice`. `hints` answers the guest's hint requests, also as
+/// advice.
 #[tracing::instrument(skip_all)]
 pub fn trace(
     elf: &PathBuf,
     inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
     input_size: u64,
     output_size: u64,
 ) -> (Vec<RVTraceRow>, JoltDevice) {
-    let term = DefaultTerminal::new();
-    let mut emulator = Emulator::new(Box::new(term));
-    emulator.update_xlen(get_xlen());
+    trace_with_hooks(
+        elf,
+        inputs,
+        advice,
+        hints,
+        input_size,
+        output_size,
+        &mut Hooks::new(),
+    )
+}
 
-    let mut jolt_device = JoltDevice::new(input_size, output_size);
-    jolt_device.inputs = inputs.to_vec();
-    emulator.get_mut_cpu().get_mut_mmu().jolt_device = jolt_device;
+/// Like `trace`, but invokes the given `hooks` after each executed instruction.
+/// If a hook returns `Control::Break`, execution stops and the trace up to and
+/// including that instruction is returned.
+pub fn trace_with_hooks(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+    hooks: &mut Hooks,
+) -> (Vec<RVTraceRow>, JoltDevice) {
+    let emulator = setup_emulator(elf, inputs, advice, hints, input_size, output_size);
+    run_traced(emulator, hooks)
+}
 
-    let mut elf_file = File::open(elf).unwrap();
+/// Like `trace`, but executes each instruction on the bundled
+/// `ReferenceInterpreter` as well, in lockstep, and stops at the first
+/// instruction after which the two disagree on the PC, a register or the word
+/// written to memory. Catches emulator bugs that would otherwise surface as a
+/// proof of the wrong execution.
+pub fn trace_differential(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+) -> Result<(Vec<RVTraceRow>, JoltDevice), ReferenceDivergence> {
+    let elf_contents = std::fs::read(elf).unwrap();
+    let memory_layout = JoltDevice::new(input_size, output_size).memory_layout;
+    let mut reference = ReferenceInterpreter::from_elf(&elf_contents, inputs, &memory_layout);
+    trace_against_reference(
+        elf,
+        inputs,
+        advice,
+        hints,
+        input_size,
+        output_size,
+        &mut reference,
+    )
+}
 
-    let mut elf_contents = Vec::new();
-    elf_file.read_to_end(&mut elf_contents).unwrap();
+/// Like `trace_differential`, but against the given `reference`, which must be
+/// set up with the same program and inputs, e.g. an adapter to Spike.
+pub fn trace_against_reference(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+    reference: &mut dyn ReferenceModel,
+) -> Result<(Vec<RVTraceRow>, JoltDevice), ReferenceDivergence> {
+    let mut divergence = None;
+    let (rows, device) = {
+        let mut hooks = Hooks::new();
+        hooks.on_condition(
+            |_| true,
+            |step| match differential::check_step(reference, step.row, step.registers) {
+                Some(mismatch) => {
+                    divergence = Some(ReferenceDivergence {
+                        step: step.index,
+                        row: step.row.clone(),
+                        mismatch,
+                    });
+                    Control::Break
+                }
+                None => Control::Continue,
+            },
+        );
+        trace_with_hooks(
+            elf,
+            inputs,
+            advice,
+            hints,
+            input_size,
+            output_size,
+            &mut hooks,
+        )
+    };
+    match divergence {
+        Some(divergence) => Err(divergence),
+        None => Ok((rows, device)),
+    }
+}
 
-    emulator.setup_program(elf_contents);
+/// Like `trace`, but starts from the final state of a previous execution rather
+/// than a freshly loaded program. `state` lists the nonzero words of that state as
+/// `(index, word)` pairs, indexed like Jolt's RAM witness: the RISC-V registers,
+/// then the virtual registers, then one word per address from `input_start` (see
+/// `MemoryLayout`). The I/O region is not restored; the program receives the new
+/// `inputs` instead.
+pub fn trace_from_state(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+    state: &[(usize, u32)],
+) -> (Vec<RVTraceRow>, JoltDevice) {
+    let mut emulator = setup_emulator(elf, inputs, advice, hints, input_size, output_size);
+    restore_state(&mut emulator, state);
+    run_traced(emulator, &mut Hooks::new())
+}
 
+/// Like `trace`, but traces lazily, yielding the trace in chunks of
+/// `chunk_size` rows (the last one possibly shorter). Long executions can thus be
+/// processed without holding their whole trace in memory.
+pub fn trace_chunks(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+    chunk_size: usize,
+) -> TraceChunks {
+    assert!(chunk_size > 0);
+    TraceChunks {
+        emulator: setup_emulator(elf, inputs, advice, hints, input_size, output_size),
+        chunk_size,
+        prev_pc: 0,
+        done: false,
+    }
+}
+
+/// Iterator over the chunks of a trace, see `trace_chunks`.
+pub struct TraceChunks {
+    emulator: Emulator,
+    chunk_size: usize,
+    prev_pc: u64,
+    done: bool,
+}
+
+impl TraceChunks {
+    /// The state of the I/O device, final once all chunks have been traced.
+    pub fn device(&mut self) -> &JoltDevice {
+        &self.emulator.get_mut_cpu().get_mut_mmu().jolt_device
+    }
+}
+
+impl Iterator for TraceChunks {
+    type Item = Vec<RVTraceRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        while !self.done && chunk.len() < self.chunk_size {
+            let pc = self.emulator.get_cpu().read_pc();
+            chunk.extend(self.emulator.step());
+            // See `run_traced`
+            self.done = self.prev_pc == pc;
+            self.prev_pc = pc;
+        }
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+fn run_traced(mut emulator: Emulator, hooks: &mut Hooks) -> (Vec<RVTraceRow>, JoltDevice) {
     let mut prev_pc = 0;
+    let mut num_rows = 0;
     loop {
         let pc = emulator.get_cpu().read_pc();
         emulator.tick();
 
+        if !hooks.is_empty() && dispatch_hooks(&emulator, hooks, &mut num_rows) == Control::Break {
+            break;
+        }
+
         // This is a trick to see if the program has terminated by throwing itself
         // into an infinite loop. It seems to be a good heuristic for now but we
         // should eventually migrate to an explicit shutdown signal.
@@ -69,6 +255,111 @@ pub fn trace(
     (output, device)
 }
 
+/// Executes the program as `trace` does but without recording the trace rows.
+/// Returns the number of executed cycles (i.e. the length of the trace `trace`
+/// would produce) and the final state of the I/O device.
+#[tracing::instrument(skip_all)]
+pub fn execute(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+) -> (usize, JoltDevice) {
+    let mut emulator = setup_emulator(elf, inputs, advice, hints, input_size, output_size);
+    emulator.get_mut_cpu().set_tracing(false);
+
+    let mut cycles = 0;
+    let mut prev_pc = 0;
+    loop {
+        let pc = emulator.get_cpu().read_pc();
+        emulator.tick();
+        cycles += 1;
+
+        // See `trace_with_hooks`
+        if prev_pc == pc {
+            break;
+        }
+
+        prev_pc = pc;
+    }
+
+    let device = emulator.get_mut_cpu().get_mut_mmu().jolt_device.clone();
+
+    (cycles, device)
+}
+
+fn setup_emulator(
+    elf: &PathBuf,
+    inputs: &[u8],
+    advice: &[u8],
+    hints: &Hints,
+    input_size: u64,
+    output_size: u64,
+) -> Emulator {
+    let term = DefaultTerminal::new();
+    let mut emulator = Emulator::new(Box::new(term));
+    emulator.update_xlen(get_xlen());
+
+    let mut jolt_device = JoltDevice::new(input_size, output_size);
+    jolt_device.inputs = inputs.to_vec();
+    emulator.get_mut_cpu().get_mut_mmu().jolt_device = jolt_device;
+    emulator.get_mut_cpu().set_advice(advice);
+    emulator.get_mut_cpu().set_hints(hints.clone());
+
+    let mut elf_file = File::open(elf).unwrap();
+
+    let mut elf_contents = Vec::new();
+    elf_file.read_to_end(&mut elf_contents).unwrap();
+
+    emulator.setup_program(elf_contents);
+    emulator
+}
+
+fn restore_state(emulator: &mut Emulator, state: &[(usize, u32)]) {
+    let cpu = emulator.get_mut_cpu();
+    for (reg, value) in state.iter().filter(|(index, _)| *index < 32) {
+        cpu.write_register(*reg as u8, *value as i32 as i64);
+    }
+
+    let mmu = cpu.get_mut_mmu();
+    let input_start = mmu.jolt_device.memory_layout.input_start;
+    for (index, word) in state
+        .iter()
+        .filter(|(index, _)| *index as u64 >= REGISTER_COUNT)
+    {
+        let address = input_start + (*index as u64 - REGISTER_COUNT) * 4;
+        // The I/O region is handled by the `JoltDevice`, and padding words beyond
+        // the end of memory are zero
+        if address < RAM_START_ADDRESS || mmu.validate_address(address) != Ok(true) {
+            continue;
+        }
+        for (offset, byte) in word.to_le_bytes().into_iter().enumerate() {
+            mmu.store_raw(address + offset as u64, byte);
+        }
+    }
+}
+
+/// Runs `hooks` on the rows traced since the last call.
+fn dispatch_hooks(emulator: &Emulator, hooks: &mut Hooks, num_rows: &mut usize) -> Control {
+    let cpu = emulator.get_cpu();
+    let rows = cpu.tracer.rows.borrow();
+    let mut control = Control::Continue;
+    for (index, row) in rows.iter().enumerate().skip(*num_rows) {
+        let step = Step {
+            index,
+            row,
+            registers: &cpu.x,
+        };
+        if hooks.dispatch(&step) == Control::Break {
+            control = Control::Break;
+        }
+    }
+    *num_rows = rows.len();
+    control
+}
+
 #[tracing::instrument(skip_all)]
 pub fn decode(elf: &[u8]) -> (Vec<ELFInstruction>, Vec<(u64, u8)>) {
     let obj = object::File::parse(elf).unwrap();
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use common::constants::RAM_START_ADDRESS;
+use common::rv_trace::MemoryLayout;
+use object::{Object, ObjectSection};
+
+/// An implementation of the RISC-V ISA the emulator is checked against in
+/// lockstep (see `trace_differential`), e.g. the bundled `ReferenceInterpreter`,
+/// or an adapter to an external simulator such as Spike.
+pub trait ReferenceModel {
+    /// Address of the next instruction to execute
+    fn pc(&self) -> u64;
+    /// Value of register `index`, zero-extended to 64 bits
+    fn register(&self, index: usize) -> u64;
+    /// Little-endian word at the (word-aligned) `address`
+    fn load_word(&self, address: u64) -> u64;
+    /// Executes the instruction at `pc()`. `advice` is the value the emulator's
+    /// host supplied to it if it is an `ADVICE` instruction, which the model
+    /// cannot know. Fails with the instruction word if the model does not
+    /// implement the instruction.
+    fn step(&mut self, advice: Option<u64>) -> Result<(), u32>;
+}
+
+/// A deliberately simple interpreter of RV32IM (plus Jolt's `ADVICE`
+/// instruction), written independently of the emulator, for differential
+/// testing. It runs on a flat, sparse memory holding the program and its
+/// inputs, and has no devices, privilege modes or traps.
+pub struct ReferenceInterpreter {
+    pc: u32,
+    x: [u32; 32],
+    memory: HashMap<u32, u8>,
+}
+
+impl ReferenceInterpreter {
+    /// An interpreter starting at `entry`, with the given bytes of memory set
+    /// and all other memory and registers zero.
+    pub fn new(entry: u64, memory: impl IntoIterator<Item = (u64, u8)>) -> Self {
+        Self {
+            pc: entry as u32,
+            x: [0; 32],
+            memory: memory
+                .into_iter()
+                .map(|(address, byte)| (address as u32, byte))
+                .collect(),
+        }
+    }
+
+    /// Loads the program in `elf`, as the emulator does, with `inputs` in the
+    /// input region of `memory_layout`.
+    pub fn from_elf(elf: &[u8], inputs: &[u8], memory_layout: &MemoryLayout) -> Self {
+        let obj = object::File::parse(elf).unwrap();
+        let program = obj
+            .sections()
+            .filter(|section| section.address() >= RAM_START_ADDRESS)
+            .flat_map(|section| {
+                let address = section.address();
+                let data = section.data().unwrap();
+                data.iter()
+                    .enumerate()
+                    .map(move |(offset, byte)| (address + offset as u64, *byte))
+            });
+        let inputs = inputs
+            .iter()
+            .enumerate()
+            .map(|(offset, byte)| (memory_layout.input_start + offset as u64, *byte));
+        Self::new(obj.entry(), program.chain(inputs))
+    }
+
+    fn read(&self, address: u32, bytes: u32) -> u32 {
+        (0..bytes).fold(0, |value, i| {
+            let byte = self.memory.get(&address.wrapping_add(i)).copied();
+            value | ((byte.unwrap_or(0) as u32) << (8 * i))
+        })
+    }
+
+    fn write(&mut self, address: u32, bytes: u32, value: u32) {
+        for i in 0..bytes {
+            self.memory
+                .insert(address.wrapping_add(i), (value >> (8 * i)) as u8);
+        }
+    }
+
+    fn set(&mut self, rd: usize, value: u32) {
+        if rd != 0 {
+            self.x[rd] = value;
+        }
+    }
+}
+
+impl ReferenceModel for ReferenceInterpreter {
+    fn pc(&self) -> u64 {
+        self.pc as u64
+    }
+
+    fn register(&self, index: usize) -> u64 {
+        self.x[index] as u64
+    }
+
+    fn load_word(&self, address: u64) -> u64 {
+        self.read(address as u32, 4) as u64
+    }
+
+    fn step(&mut self, advice: Option<u64>) -> Result<(), u32> {
+        let word = self.read(self.pc, 4);
+        let rd = ((word >> 7) & 0x1f) as usize;
+        let rs1 = self.x[((word >> 15) & 0x1f) as usize];
+        let rs2 = self.x[((word >> 20) & 0x1f) as usize];
+        let funct3 = (word >> 12) & 0x7;
+        let funct7 = word >> 25;
+        let imm_i = (word as i32 >> 20) as u32;
+        let imm_s = ((word as i32 >> 25) << 5) as u32 | ((word >> 7) & 0x1f);
+        let imm_b = ((word as i32 >> 31) << 12) as u32
+            | ((word << 4) & 0x800)
+            | ((word >> 20) & 0x7e0)
+            | ((word >> 7) & 0x1e);
+        let imm_u = word & 0xfffff000;
+        let imm_j = ((word as i32 >> 31) << 20) as u32
+            | (word & 0xff000)
+            | ((word >> 9) & 0x800)
+            | ((word >> 20) & 0x7fe);
+
+        let mut next_pc = self.pc.wrapping_add(4);
+        match word & 0x7f {
+            // LUI
+            0x37 => self.set(rd, imm_u),
+            // AUIPC
+            0x17 => self.set(rd, self.pc.wrapping_add(imm_u)),
+            // JAL
+            0x6f => {
+                self.set(rd, next_pc);
+                next_pc = self.pc.wrapping_add(imm_j);
+            }
+            // JALR
+            0x67 if funct3 == 0 => {
+                self.set(rd, next_pc);
+                next_pc = rs1.wrapping_add(imm_i) & !1;
+            }
+            // Branches
+            0x63 => {
+                let taken = match funct3 {
+                    0 => rs1 == rs2,
+                    1 => rs1 != rs2,
+                    4 => (rs1 as i32) < (rs2 as i32),
+                    5 => (rs1 as i32) >= (rs2 as i32),
+                    6 => rs1 < rs2,
+                    7 => rs1 >= rs2,
+                    _ => return Err(word),
+                };
+                if taken {
+                    next_pc = self.pc.wrapping_add(imm_b);
+                }
+            }
+            // Loads
+            0x03 => {
+                let address = rs1.wrapping_add(imm_i);
+                let value = match funct3 {
+                    0 => self.read(address, 1) as i8 as i32 as u32,
+                    1 => self.read(address, 2) as i16 as i32 as u32,
+                    2 => self.read(address, 4),
+                    4 => self.read(address, 1),
+                    5 => self.read(address, 2),
+                    _ => return Err(word),
+                };
+                self.set(rd, value);
+            }
+            // Stores
+            0x23 => {
+                let address = rs1.wrapping_add(imm_s);
+                match funct3 {
+                    0 => self.write(address, 1, rs2),
+                    1 => self.write(address, 2, rs2),
+                    2 => self.write(address, 4, rs2),
+                    _ => return Err(word),
+                }
+            }
+            // Register-immediate operations
+            0x13 => {
+                let shamt = imm_i & 0x1f;
+                let value = match (funct3, funct7) {
+                    (0, _) => rs1.wrapping_add(imm_i),
+                    (2, _) => ((rs1 as i32) < (imm_i as i32)) as u32,
+                    (3, _) => (rs1 < imm_i) as u32,
+                    (4, _) => rs1 ^ imm_i,
+                    (6, _) => rs1 | imm_i,
+                    (7, _) => rs1 & imm_i,
+                    (1, 0x00) => rs1 << shamt,
+                    (5, 0x00) => rs1 >> shamt,
+                    (5, 0x20) => (rs1 as i32 >> shamt) as u32,
+                    _ => return Err(word),
+                };
+                self.set(rd, value);
+            }
+            // Register-register operations
+            0x33 => {
+                let value = match (funct7, funct3) {
+                    (0x00, 0) => rs1.wrapping_add(rs2),
+                    (0x20, 0) => rs1.wrapping_sub(rs2),
+                    (0x00, 1) => rs1 << (rs2 & 0x1f),
+                    (0x00, 2) => ((rs1 as i32) < (rs2 as i32)) as u32,
+                    (0x00, 3) => (rs1 < rs2) as u32,
+                    (0x00, 4) => rs1 ^ rs2,
+                    (0x00, 5) => rs1 >> (rs2 & 0x1f),
+                    (0x20, 5) => (rs1 as i32 >> (rs2 & 0x1f)) as u32,
+                    (0x00, 6) => rs1 | rs2,
+                    (0x00, 7) => rs1 & rs2,
+                    (0x01, funct3) => multiply_divide(funct3, rs1, rs2),
+                    _ => return Err(word),
+                };
+                self.set(rd, value);
+            }
+            // FENCE
+            0x0f if funct3 == 0 => {}
+            // ADVICE: the value comes from the host
+            0x0b if funct3 == 0 => {
+                self.set(rd, advice.unwrap_or(0) as u32);
+            }
+            _ => return Err(word),
+        }
+        self.pc = next_pc;
+        Ok(())
+    }
+}
+
+/// The M extension, as specified (including division by zero and overflow)
+fn multiply_divide(funct3: u32, rs1: u32, rs2: u32) -> u32 {
+    let (signed1, signed2) = (rs1 as i32 as i64, rs2 as i32 as i64);
+    match funct3 {
+        0 => rs1.wrapping_mul(rs2),
+        1 => ((signed1 * signed2) >> 32) as u32,
+        2 => ((signed1 * rs2 as i64) >> 32) as u32,
+        3 => ((rs1 as u64 * rs2 as u64) >> 32) as u32,
+        4 if rs2 == 0 => u32::MAX,
+        4 => (rs1 as i32).wrapping_div(rs2 as i32) as u32,
+        5 if rs2 == 0 => u32::MAX,
+        5 => rs1 / rs2,
+        6 if rs2 == 0 => rs1,
+        6 => (rs1 as i32).wrapping_rem(rs2 as i32) as u32,
+        7 if rs2 == 0 => rs1,
+        _ => rs1 % rs2,
+    }
+}
+
+#[cfg(test)]
+mod test_reference {
+    use super::*;
+
+    /// An interpreter with `program` at 0x80000000
+    fn interpreter(program: &[u32]) -> ReferenceInterpreter {
+        let bytes = program.iter().flat_map(|word| word.to_le_bytes());
+        ReferenceInterpreter::new(
+            RAM_START_ADDRESS,
+            (RAM_START_ADDRESS..).zip(bytes).collect::<Vec<_>>(),
+        )
+    }
+
+    fn run(interpreter: &mut ReferenceInterpreter, steps: usize) {
+        for _ in 0..steps {
+            interpreter.step(None).unwrap();
+        }
+    }
+
+    #[test]
+    fn arithmetic_and_memory() {
+        let mut interpreter = interpreter(&[
+            0xff900093, // addi x1, x0, -7
+            0x00300113, // addi x2, x0, 3
+            0x0220c1b3, // div x3, x1, x2
+            0x0220e233, // rem x4, x1, x2
+            0x0210b2b3, // mulhu x5, x1, x1
+            0x4010d313, // srai x6, x1, 1
+            0x00000397, // auipc x7, 0
+            0x0013a423, // sw x1, 8(x7)
+            0x0083c403, // lbu x8, 8(x7)
+        ]);
+        run(&mut interpreter, 9);
+        assert_eq!(interpreter.register(3) as i32, -2);
+        assert_eq!(interpreter.register(4) as i32, -1);
+        assert_eq!(interpreter.register(5), 0xfffffff2);
+        assert_eq!(interpreter.register(6) as i32, -4);
+        assert_eq!(interpreter.load_word(RAM_START_ADDRESS + 32), 0xfffffff9);
+        assert_eq!(interpreter.register(8), 0xf9);
+        assert_eq!(interpreter.pc(), RAM_START_ADDRESS + 36);
+    }
+
+    #[test]
+    fn division_edge_cases() {
+        assert_eq!(multiply_divide(4, 7, 0), u32::MAX);
+        assert_eq!(multiply_divide(6, 7, 0), 7);
+        assert_eq!(
+            multiply_divide(4, i32::MIN as u32, -1i32 as u32),
+            i32::MIN as u32
+        );
+        assert_eq!(multiply_divide(6, i32::MIN as u32, -1i32 as u32), 0);
+    }
+
+    #[test]
+    fn jumps_and_branches() {
+        let mut interpreter = interpreter(&[
+            0x00100093, // addi x1, x0, 1
+            0x00108463, // beq x1, x1, 8
+            0x00000013, // nop (skipped)
+            0xff5ff16f, // jal x2, -12
+        ]);
+        run(&mut interpreter, 3);
+        assert_eq!(interpreter.pc(), RAM_START_ADDRESS);
+        assert_eq!(interpreter.register(2), RAM_START_ADDRESS + 16);
+    }
+
+    #[test]
+    fn unsupported_instruction() {
+        let mut interpreter = interpreter(&[0x00000073]); // ecall
+        assert_eq!(interpreter.step(None), Err(0x00000073));
+    }
+}
@@ -71,6 +71,16 @@ impl Tracer {
         }
     }
 
+    pub fn push_advice(&self, value: u64) {
+        if !*self.open.try_borrow().unwrap() {
+            return;
+        }
+
+        if let Some(row) = self.rows.try_borrow_mut().unwrap().last_mut() {
+            row.advice_value = Some(value);
+        }
+    }
+
     pub fn end_instruction(&self) {
         *self.open.try_borrow_mut().unwrap() = false;
     }
@@ -0,0 +1,475 @@
+//! Compact on-disk format for execution traces, so that traces can be stored
+//! or shipped to a remote prover and re-loaded without re-executing the guest.
+//!
+//! Rows are split into columns which are encoded separately: program counters
+//! are delta-encoded, opcodes and register indices are packed into single
+//! bytes, optional fields are tracked by a per-row presence bitmask, and all
+//! remaining values are LEB128 varints. The concatenated columns are then
+//! DEFLATE-compressed.
+
+use std::io::{self, Read, Write};
+
+use common::rv_trace::{
+    ELFInstruction, JoltDevice, MemoryState, RVTraceRow, RegisterState, RV32IM,
+};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+const MAGIC: &[u8; 8] = b"JOLTTRC\0";
+const VERSION: u8 = 1;
+
+/// Marks an absent register index
+const NO_REGISTER: u8 = u8::MAX;
+
+// Presence bits of optional row fields
+const HAS_IMM: u16 = 1 << 0;
+const HAS_SEQUENCE: u16 = 1 << 1;
+const HAS_RS1_VAL: u16 = 1 << 2;
+const HAS_RS2_VAL: u16 = 1 << 3;
+const HAS_RD_POST_VAL: u16 = 1 << 4;
+const HAS_MEMORY_READ: u16 = 1 << 5;
+const HAS_MEMORY_WRITE: u16 = 1 << 6;
+const HAS_ADVICE: u16 = 1 << 7;
+const HAS_PRECOMPILE_INPUT: u16 = 1 << 8;
+const HAS_PRECOMPILE_OUTPUT: u16 = 1 << 9;
+
+#[derive(Default)]
+struct Columns {
+    pc: Vec<u8>,
+    opcode: Vec<u8>,
+    registers: Vec<u8>,
+    flags: Vec<u8>,
+    values: Vec<u8>,
+}
+
+impl Columns {
+    fn as_slices(&self) -> [&Vec<u8>; 5] {
+        [
+            &self.pc,
+            &self.opcode,
+            &self.registers,
+            &self.flags,
+            &self.values,
+        ]
+    }
+}
+
+/// Writes `rows` and the final I/O device state to `writer` in the compressed
+/// trace format.
+pub fn write_trace<W: Write>(
+    writer: W,
+    rows: &[RVTraceRow],
+    device: &JoltDevice,
+) -> io::Result<()> {
+    let mut columns = Columns::default();
+    let mut prev_pc = 0u64;
+    for row in rows {
+        encode_row(&mut columns, row, &mut prev_pc);
+    }
+
+    let mut encoder = DeflateEncoder::new(writer, Compression::default());
+    encoder.write_all(MAGIC)?;
+    encoder.write_all(&[VERSION])?;
+    write_device(&mut encoder, device)?;
+    write_varint(&mut encoder, rows.len() as u64)?;
+    for column in columns.as_slices() {
+        write_varint(&mut encoder, column.len() as u64)?;
+        encoder.write_all(column)?;
+    }
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Reads a trace written by `write_trace`.
+pub fn read_trace<R: Read>(reader: R) -> io::Result<(Vec<RVTraceRow>, JoltDevice)> {
+    let mut decoder = DeflateDecoder::new(reader);
+
+    let mut magic = [0u8; 8];
+    decoder.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid_data("not a Jolt trace file"));
+    }
+    let mut version = [0u8; 1];
+    decoder.read_exact(&mut version)?;
+    if version[0] != VERSION {
+        return Err(invalid_data("unsupported trace format version"));
+    }
+
+    let device = read_device(&mut decoder)?;
+    let num_rows = read_varint(&mut decoder)? as usize;
+
+    let mut read_column = || -> io::Result<Vec<u8>> {
+        let len = read_varint(&mut decoder)?;
+        read_bytes(&mut decoder, len)
+    };
+    let pc = read_column()?;
+    let opcode = read_column()?;
+    let registers = read_column()?;
+    let flags = read_column()?;
+    let values = read_column()?;
+
+    let mut pc = pc.as_slice();
+    let mut registers = registers.as_slice();
+    let mut flags = flags.as_slice();
+    let mut values = values.as_slice();
+
+    // Rows have one opcode each, so the row count, which is read from the file, is
+    // only trusted once it matches the data actually present
+    if opcode.len() != num_rows {
+        return Err(invalid_data("row count does not match the opcode column"));
+    }
+
+    let mut rows = Vec::with_capacity(num_rows);
+    let mut prev_pc = 0u64;
+    for &opcode in &opcode {
+        let opcode = RV32IM::from_repr(opcode).ok_or_else(|| invalid_data("invalid opcode"))?;
+
+        let address = prev_pc.wrapping_add(unzigzag(read_varint(&mut pc)?) as u64);
+        prev_pc = address;
+
+        let mut register = || -> io::Result<Option<u64>> {
+            let index = read_u8(&mut registers)?;
+            Ok((index != NO_REGISTER).then_some(index as u64))
+        };
+        let rs1 = register()?;
+        let rs2 = register()?;
+        let rd = register()?;
+
+        let row_flags = u16::from_le_bytes([read_u8(&mut flags)?, read_u8(&mut flags)?]);
+        let mut optional = |flag: u16| -> io::Result<Option<u64>> {
+            if row_flags & flag != 0 {
+                Ok(Some(read_varint(&mut values)?))
+            } else {
+                Ok(None)
+            }
+        };
+
+        let imm = optional(HAS_IMM)?.map(unzigzag);
+        let virtual_sequence_remaining = optional(HAS_SEQUENCE)?.map(|v| v as usize);
+        let rs1_val = optional(HAS_RS1_VAL)?;
+        let rs2_val = optional(HAS_RS2_VAL)?;
+        let rd_post_val = optional(HAS_RD_POST_VAL)?;
+        let advice_value = optional(HAS_ADVICE)?;
+        let precompile_output_address = optional(HAS_PRECOMPILE_OUTPUT)?;
+
+        let memory_state = if row_flags & HAS_MEMORY_READ != 0 {
+            Some(MemoryState::Read {
+                address: read_varint(&mut values)?,
+                value: read_varint(&mut values)?,
+            })
+        } else if row_flags & HAS_MEMORY_WRITE != 0 {
+            Some(MemoryState::Write {
+                address: read_varint(&mut values)?,
+                pre_value: read_varint(&mut values)?,
+                post_value: read_varint(&mut values)?,
+            })
+        } else {
+            None
+        };
+
+        let precompile_input = if row_flags & HAS_PRECOMPILE_INPUT != 0 {
+            let mut input = [0u32; 16];
+            for word in input.iter_mut() {
+                *word = read_varint(&mut values)? as u32;
+            }
+            Some(input)
+        } else {
+            None
+        };
+
+        rows.push(RVTraceRow {
+            instruction: ELFInstruction {
+                address,
+                opcode,
+                rs1,
+                rs2,
+                rd,
+                imm,
+                virtual_sequence_remaining,
+            },
+            register_state: RegisterState {
+                rs1_val,
+                rs2_val,
+                rd_post_val,
+            },
+            memory_state,
+            advice_value,
+            precompile_input,
+            precompile_output_address,
+        });
+    }
+
+    Ok((rows, device))
+}
+
+fn encode_row(columns: &mut Columns, row: &RVTraceRow, prev_pc: &mut u64) {
+    let instruction = &row.instruction;
+
+    let delta = instruction.address.wrapping_sub(*prev_pc) as i64;
+    write_varint(&mut columns.pc, zigzag(delta)).unwrap();
+    *prev_pc = instruction.address;
+
+    columns.opcode.push(instruction.opcode as u8);
+
+    for register in [instruction.rs1, instruction.rs2, instruction.rd] {
+        columns
+            .registers
+            .push(register.map_or(NO_REGISTER, |index| index as u8));
+    }
+
+    let mut row_flags = 0u16;
+    let values = &mut columns.values;
+    let mut optional = |flag: u16, value: Option<u64>| {
+        if let Some(value) = value {
+            row_flags |= flag;
+            write_varint(&mut *values, value).unwrap();
+        }
+    };
+
+    optional(HAS_IMM, instruction.imm.map(zigzag));
+    optional(
+        HAS_SEQUENCE,
+        instruction.virtual_sequence_remaining.map(|v| v as u64),
+    );
+    optional(HAS_RS1_VAL, row.register_state.rs1_val);
+    optional(HAS_RS2_VAL, row.register_state.rs2_val);
+    optional(HAS_RD_POST_VAL, row.register_state.rd_post_val);
+    optional(HAS_ADVICE, row.advice_value);
+    optional(HAS_PRECOMPILE_OUTPUT, row.precompile_output_address);
+
+    match row.memory_state {
+        Some(MemoryState::Read { address, value }) => {
+            row_flags |= HAS_MEMORY_READ;
+            write_varint(&mut columns.values, address).unwrap();
+            write_varint(&mut columns.values, value).unwrap();
+        }
+        Some(MemoryState::Write {
+            address,
+            pre_value,
+            post_value,
+        }) => {
+            row_flags |= HAS_MEMORY_WRITE;
+            write_varint(&mut columns.values, address).unwrap();
+            write_varint(&mut columns.values, pre_value).unwrap();
+            write_varint(&mut columns.values, post_value).unwrap();
+        }
+        None => {}
+    }
+
+    if let Some(input) = row.precompile_input {
+        row_flags |= HAS_PRECOMPILE_INPUT;
+        for word in input {
+            write_varint(&mut columns.values, word as u64).unwrap();
+        }
+    }
+
+    columns.flags.extend_from_slice(&row_flags.to_le_bytes());
+}
+
+fn write_device<W: Write>(writer: &mut W, device: &JoltDevice) -> io::Result<()> {
+    write_varint(writer, device.memory_layout.max_input_size)?;
+    write_varint(writer, device.memory_layout.max_output_size)?;
+    write_varint(writer, device.inputs.len() as u64)?;
+    writer.write_all(&device.inputs)?;
+    write_varint(writer, device.outputs.len() as u64)?;
+    writer.write_all(&device.outputs)?;
+    writer.write_all(&[device.panic as u8])
+}
+
+fn read_device<R: Read>(reader: &mut R) -> io::Result<JoltDevice> {
+    let max_input_size = read_varint(reader)?;
+    let max_output_size = read_varint(reader)?;
+    let mut device = JoltDevice::new(max_input_size, max_output_size);
+
+    let inputs_len = read_varint(reader)?;
+    device.inputs = read_bytes(reader, inputs_len)?;
+    let outputs_len = read_varint(reader)?;
+    device.outputs = read_bytes(reader, outputs_len)?;
+    device.panic = read_u8(reader)? != 0;
+
+    Ok(device)
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_u8(reader)?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("varint overflow"))
+}
+
+/// Reads `len` bytes, where `len` comes from the file. The buffer only grows as
+/// bytes are actually read, so a corrupt length fails with an error rather than
+/// allocating `len` bytes up front.
+fn read_bytes<R: Read>(reader: &mut R, len: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated trace file",
+        ));
+    }
+    Ok(bytes)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod test_trace_format {
+    use super::*;
+
+    fn rows() -> Vec<RVTraceRow> {
+        let row = |address: u64, opcode: RV32IM, imm: i64, memory_state: Option<MemoryState>| {
+            RVTraceRow {
+                instruction: ELFInstruction {
+                    address,
+                    opcode,
+                    rs1: Some(2),
+                    rs2: None,
+                    rd: Some(10),
+                    imm: Some(imm),
+                    virtual_sequence_remaining: None,
+                },
+                register_state: RegisterState {
+                    rs1_val: Some(0x8000_0000),
+                    rs2_val: None,
+                    rd_post_val: Some(u64::MAX),
+                },
+                memory_state,
+                advice_value: None,
+                precompile_input: None,
+                precompile_output_address: None,
+            }
+        };
+
+        let mut sequence = row(0x8000_0008, RV32IM::VIRTUAL_ADVICE, 0, None);
+        sequence.instruction.rs1 = None;
+        sequence.instruction.virtual_sequence_remaining = Some(3);
+        sequence.advice_value = Some(7);
+
+        vec![
+            row(0x8000_0000, RV32IM::ADDI, -4, None),
+            row(
+                0x8000_0004,
+                RV32IM::LW,
+                8,
+                Some(MemoryState::Read {
+                    address: 0x8000_1000,
+                    value: 42,
+                }),
+            ),
+            sequence,
+            row(
+                0x7fff_fff0,
+                RV32IM::SW,
+                -16,
+                Some(MemoryState::Write {
+                    address: 0x8000_1000,
+                    pre_value: 42,
+                    post_value: 43,
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn round_trip() {
+        let rows = rows();
+        let mut device = JoltDevice::new(64, 64);
+        device.inputs = vec![1, 2, 3];
+        device.outputs = vec![4, 5];
+
+        let mut bytes = Vec::new();
+        write_trace(&mut bytes, &rows, &device).unwrap();
+        let (decoded_rows, decoded_device) = read_trace(bytes.as_slice()).unwrap();
+
+        assert_eq!(decoded_rows, rows);
+        assert_eq!(decoded_device, device);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        let mut bytes = Vec::new();
+        let mut encoder = DeflateEncoder::new(&mut bytes, Compression::default());
+        encoder.write_all(b"not a trace").unwrap();
+        encoder.finish().unwrap();
+        assert!(read_trace(bytes.as_slice()).is_err());
+    }
+
+    /// The compressed trace of `rows()`, with the varint `value` substituted for
+    /// the (single-byte) varint at `offset` of the uncompressed bytes
+    fn with_varint_at(offset: usize, value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_trace(&mut bytes, &rows(), &JoltDevice::new(64, 64)).unwrap();
+        let mut uncompressed = Vec::new();
+        DeflateDecoder::new(bytes.as_slice())
+            .read_to_end(&mut uncompressed)
+            .unwrap();
+        let mut varint = Vec::new();
+        write_varint(&mut varint, value).unwrap();
+        let uncompressed = [
+            &uncompressed[..offset],
+            varint.as_slice(),
+            &uncompressed[offset + 1..],
+        ]
+        .concat();
+
+        let mut bytes = Vec::new();
+        let mut encoder = DeflateEncoder::new(&mut bytes, Compression::default());
+        encoder.write_all(&uncompressed).unwrap();
+        encoder.finish().unwrap();
+        bytes
+    }
+
+    #[test]
+    fn rejects_lengths_beyond_the_data() {
+        // Offsets of the inputs' length, after the magic, the version and the
+        // maximum sizes, of the row count, after the empty inputs and outputs and
+        // the panic flag, and of the PC column's length
+        let inputs_len = MAGIC.len() + 1 + 2;
+        let num_rows = inputs_len + 3;
+        let pc_len = num_rows + 1;
+        for offset in [inputs_len, num_rows, pc_len] {
+            let bytes = with_varint_at(offset, u64::MAX >> 1);
+            assert!(read_trace(bytes.as_slice()).is_err());
+        }
+    }
+
+    #[test]
+    fn zigzag_round_trip() {
+        for value in [0, 1, -1, i64::MIN, i64::MAX] {
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+    }
+}